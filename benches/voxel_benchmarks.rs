@@ -0,0 +1,129 @@
+//! `cargo bench` numbers for the CPU-side octree/chunk operations, so a
+//! `Tree`/`Chunk` change can be checked for a regression instead of only
+//! guessed at. All inputs are built from a fixed seed via
+//! `voxel_adventure::voxel::test_util`, so two runs of this file (before and
+//! after a change) are actually comparable.
+//!
+//! What the request behind this file asked to compare and can't: "palette
+//! vs dense" storage and "morton vs linear" indexing. Neither alternative
+//! exists in this crate -- `Tree` is the only voxel storage, and
+//! `voxel::morton`'s own module doc already explains there's no second flat
+//! index scheme to race it against (nothing here calls `morton3_encode` for
+//! anything but its own micro-benchmark). Likewise there's no
+//! `Tree::set_block_state`, `Tree::from_dense`, `Tree::raycast`, RLE
+//! codec, or `Chunk::fill_region`/`downsample` to benchmark; what's
+//! benchmarked below is every CPU-side operation this crate actually has
+//! that a caller would reasonably want to keep fast: `Tree::set`/`get`,
+//! `Tree::lod`, `Tree::to_gpu_nodes`, `Chunk::collect_emitters`,
+//! `Chunk::fingerprint`, and Morton encode/decode on its own.
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use std::hint::black_box;
+use voxel_adventure::voxel::material::MaterialTable;
+use voxel_adventure::voxel::morton::{morton3_decode, morton3_encode};
+use voxel_adventure::voxel::test_util::{seeded_positions, standard_chunk, Xorshift32};
+use voxel_adventure::voxel::tree::Tree;
+
+const CHUNK_DEPTH: u32 = 5;
+const CHUNK_SIZE: u32 = 1 << CHUNK_DEPTH;
+
+fn tree_set_fill_from_noise(c: &mut Criterion) {
+    c.bench_function("tree_set_fill_32_from_noise", |b| {
+        b.iter_batched(
+            || {
+                let mut rng = Xorshift32(0xC0FF_EE01);
+                (0..20_000)
+                    .map(|_| {
+                        let pos = glam::UVec3::new(
+                            rng.next_below(CHUNK_SIZE),
+                            rng.next_below(CHUNK_SIZE),
+                            rng.next_below(CHUNK_SIZE),
+                        );
+                        (pos, rng.next_below(3))
+                    })
+                    .collect::<Vec<_>>()
+            },
+            |writes| {
+                let mut tree = Tree::new(CHUNK_DEPTH);
+                for (pos, material) in &writes {
+                    tree.set(*pos, *material);
+                }
+                black_box(tree)
+            },
+            BatchSize::SmallInput,
+        )
+    });
+}
+
+fn tree_get_lookup(c: &mut Criterion) {
+    let chunk = standard_chunk();
+    let lookups = seeded_positions(CHUNK_SIZE, 10_000, 0x5EED_0002);
+
+    c.bench_function("tree_get_10k_lookups", |b| {
+        b.iter(|| {
+            let sum: u32 = lookups.iter().map(|&pos| chunk.tree.get(pos)).sum();
+            black_box(sum)
+        })
+    });
+}
+
+fn tree_lod(c: &mut Criterion) {
+    let chunk = standard_chunk();
+    c.bench_function("tree_lod_to_depth_2", |b| b.iter(|| black_box(chunk.tree.lod(2))));
+}
+
+fn tree_to_gpu_nodes(c: &mut Criterion) {
+    let chunk = standard_chunk();
+    c.bench_function("tree_to_gpu_nodes", |b| b.iter(|| black_box(chunk.tree.to_gpu_nodes())));
+}
+
+fn chunk_collect_emitters(c: &mut Criterion) {
+    let chunk = standard_chunk();
+    let materials = MaterialTable::default();
+    c.bench_function("chunk_collect_emitters", |b| b.iter(|| black_box(chunk.collect_emitters(&materials))));
+}
+
+fn chunk_fingerprint(c: &mut Criterion) {
+    let chunk = standard_chunk();
+    c.bench_function("chunk_fingerprint", |b| b.iter(|| black_box(chunk.fingerprint())));
+}
+
+fn morton_encode_decode(c: &mut Criterion) {
+    let coords: Vec<(u32, u32, u32)> = {
+        let mut rng = Xorshift32(0xC0DE_0003);
+        (0..10_000).map(|_| (rng.next_below(2048), rng.next_below(2048), rng.next_below(2048))).collect()
+    };
+
+    c.bench_function("morton3_encode_10k", |b| {
+        b.iter(|| {
+            let sum: u32 = coords.iter().map(|&(x, y, z)| morton3_encode(x, y, z)).fold(0, u32::wrapping_add);
+            black_box(sum)
+        })
+    });
+
+    let codes: Vec<u32> = coords.iter().map(|&(x, y, z)| morton3_encode(x, y, z)).collect();
+    c.bench_function("morton3_decode_10k", |b| {
+        b.iter(|| {
+            let sum: u32 = codes
+                .iter()
+                .map(|&code| {
+                    let (x, y, z) = morton3_decode(code);
+                    x.wrapping_add(y).wrapping_add(z)
+                })
+                .fold(0, u32::wrapping_add);
+            black_box(sum)
+        })
+    });
+}
+
+criterion_group!(
+    benches,
+    tree_set_fill_from_noise,
+    tree_get_lookup,
+    tree_lod,
+    tree_to_gpu_nodes,
+    chunk_collect_emitters,
+    chunk_fingerprint,
+    morton_encode_decode,
+);
+criterion_main!(benches);
@@ -0,0 +1,12 @@
+/// Anti-aliasing strategy for the voxel image blit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AaMode {
+    /// Blit `render_texture` as-is; `render_scale != 1.0` still resizes it,
+    /// with a single bilinear tap rather than a true downsample filter.
+    #[default]
+    Native,
+    /// Renders at `render_scale = 2.0` and downsamples with a box filter
+    /// (see `voxel::BlitFilter::Box2x`) instead of a single bilinear tap, so
+    /// ray-marched edges get real anti-aliasing rather than just a resize.
+    SuperSample2x,
+}
@@ -0,0 +1,81 @@
+/// Tracks how many consecutive static frames have been blended into
+/// `Renderer`'s progressive accumulation buffer. `frames()` is the blend
+/// weight denominator for the frame about to render -- `1` means the new
+/// frame fully replaces whatever the accumulation buffer currently holds,
+/// which is exactly what "accumulation disabled" looks like without a
+/// separate flag (mirrors how `AoSettings::samples == 0` disables AO).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AccumulationState {
+    frames: u32,
+}
+
+impl AccumulationState {
+    /// Past this, `1 / frames` is already indistinguishable from zero in
+    /// `f32`, so there's no benefit to letting the counter climb further --
+    /// and it keeps the value comfortably away from overflow.
+    const MAX_FRAMES: u32 = 4096;
+
+    pub fn new() -> Self {
+        Self { frames: 1 }
+    }
+
+    /// Blend weight denominator for the frame about to render.
+    pub fn frames(&self) -> u32 {
+        self.frames
+    }
+
+    /// Call once per rendered frame while the camera and chunk are
+    /// unchanged, so the next frame blends in a smaller fraction of new
+    /// data.
+    pub fn advance(&mut self) {
+        self.frames = (self.frames + 1).min(Self::MAX_FRAMES);
+    }
+
+    /// Call whenever the camera moves or the chunk is edited, since the
+    /// accumulated history no longer matches what the next frame will draw.
+    pub fn reset(&mut self) {
+        self.frames = 1;
+    }
+}
+
+impl Default for AccumulationState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn starts_at_one_frame() {
+        assert_eq!(AccumulationState::new().frames(), 1);
+    }
+
+    #[test]
+    fn advance_increments_frame_count() {
+        let mut state = AccumulationState::new();
+        state.advance();
+        state.advance();
+        assert_eq!(state.frames(), 3);
+    }
+
+    #[test]
+    fn reset_returns_to_one_frame() {
+        let mut state = AccumulationState::new();
+        state.advance();
+        state.advance();
+        state.reset();
+        assert_eq!(state.frames(), 1);
+    }
+
+    #[test]
+    fn advance_caps_at_max_frames() {
+        let mut state = AccumulationState::new();
+        for _ in 0..10_000 {
+            state.advance();
+        }
+        assert_eq!(state.frames(), AccumulationState::MAX_FRAMES);
+    }
+}
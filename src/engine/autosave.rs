@@ -0,0 +1,334 @@
+//! Crash-safe persistence of the loaded chunk: an atomic file write (so a
+//! crash mid-save can never corrupt an existing save), a crash-recovery
+//! manifest format, and a panic hook that drives both. `--autosave-dir`
+//! wires this up two ways: `main.rs` installs [`install_panic_hook`] before
+//! `App::run` so a panic gets a last-ditch save, and `App`'s
+//! `WindowEvent::CloseRequested` handler calls [`write_crash_recovery`]
+//! directly for a clean-exit save (`App::save_on_exit`).
+//!
+//! What's still out of scope, and why:
+//! - There's no periodic mid-session save, and [`AutosaveHandle`] is
+//!   populated once at startup rather than kept fresh: there's no
+//!   `ChunkManager`, dirty-chunk tracking, or worker thread yet (see
+//!   `chunk_cache.rs`'s doc comment) to call this on a timer or to know
+//!   *when* the loaded chunk actually changed. The one case that currently
+//!   changes it after startup -- the console's `regen` command and
+//!   `scene`/`biome` loads -- won't be reflected in a panic-path save until
+//!   that tracking exists; `save_on_exit` doesn't have this gap, since it
+//!   reads `Renderer::dirty_chunks` live at the moment the window closes.
+//! - There's no serialization layer for `Chunk`/`Tree` (see
+//!   `chunk_delta.rs`'s doc comment) for a dirty chunk to encode itself into
+//!   bytes with, so [`write_crash_recovery`] takes each chunk as a position
+//!   plus an opaque byte payload the caller already has in hand -- the same
+//!   "plain struct, bring your own serialization" shape as `ChunkSnapshot`.
+//!   `Renderer::dirty_chunks` fills that payload with `Tree::to_gpu_nodes`
+//!   bytes, the same data already uploaded to the GPU.
+
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+
+/// A chunk position plus its opaque GPU-node bytes; see this module's doc
+/// comment for why there's no real `Chunk`/`Tree` serialization behind it.
+type DirtyChunks = Vec<(glam::IVec3, Vec<u8>)>;
+
+/// Writes `bytes` to `path` without ever leaving a half-written file there:
+/// first to a sibling temp file, then an atomic rename over `path` (POSIX
+/// and Windows both guarantee `rename` within the same directory replaces
+/// the destination in a single filesystem operation). A crash or power loss
+/// mid-write leaves the temp file orphaned; `path` itself is always either
+/// the previous complete version or the new one, never a truncated mix.
+pub fn atomic_write(path: &Path, bytes: &[u8]) -> io::Result<()> {
+    let dir = path.parent().unwrap_or_else(|| Path::new("."));
+    std::fs::create_dir_all(dir)?;
+    let temp_path = dir.join(format!(".{}.tmp", path.file_name().and_then(|name| name.to_str()).unwrap_or("autosave")));
+    std::fs::write(&temp_path, bytes)?;
+    std::fs::rename(&temp_path, path)
+}
+
+/// One dirty chunk [`write_crash_recovery`] wrote out: `file_name` relative
+/// to the crash-recovery directory, and `byte_len` so [`CrashManifest::validate`]
+/// can catch a truncated write without re-reading and re-hashing the payload.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CrashManifestEntry {
+    pub position: [i32; 3],
+    pub file_name: String,
+    pub byte_len: usize,
+}
+
+/// What landed in a `crash-recovery/` directory during one
+/// [`write_crash_recovery`] call, alongside the chunk files it names.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CrashManifest {
+    pub entries: Vec<CrashManifestEntry>,
+}
+
+impl CrashManifest {
+    /// TOML, the same format `CameraPath`/`engine::Config` already use for
+    /// everything hand-editable or human-readable in this crate.
+    pub fn to_toml(&self) -> String {
+        toml::to_string(self).expect("CrashManifest fields are all plain TOML-representable types")
+    }
+
+    /// No caller yet outside this module's own tests -- there's no recovery
+    /// tool in this codebase to read a `crash-recovery/` dump back, only
+    /// `write_crash_recovery` to produce one. Kept `pub` for when one exists.
+    #[allow(dead_code)]
+    pub fn parse(raw: &str) -> Result<Self, String> {
+        toml::from_str(raw).map_err(|err| err.to_string())
+    }
+
+    /// Checks every entry's file exists alongside the manifest in `dir` and
+    /// is exactly `byte_len` long, naming the first mismatch -- what a
+    /// recovery tool would run before trusting a `crash-recovery/` dump.
+    /// Same "no caller yet" note as `parse`.
+    #[allow(dead_code)]
+    pub fn validate(&self, dir: &Path) -> Result<(), String> {
+        for entry in &self.entries {
+            let path = dir.join(&entry.file_name);
+            let len = std::fs::metadata(&path).map_err(|err| format!("{path:?}: {err}"))?.len() as usize;
+            if len != entry.byte_len {
+                return Err(format!("{path:?}: manifest says {} bytes, found {len}", entry.byte_len));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Atomically writes each `(position, bytes)` dirty chunk payload plus a
+/// [`CrashManifest`] describing them into `dir`, creating it if needed.
+/// Every chunk file and the manifest itself go through [`atomic_write`], so
+/// a crash partway through this call never leaves the manifest pointing at
+/// a half-written chunk file -- it's either not in the manifest yet, or it
+/// was already fully and atomically written.
+pub fn write_crash_recovery(dir: &Path, dirty: &[(glam::IVec3, Vec<u8>)]) -> io::Result<CrashManifest> {
+    let mut entries = Vec::with_capacity(dirty.len());
+    for (position, bytes) in dirty {
+        let file_name = format!("chunk_{}_{}_{}.bin", position.x, position.y, position.z);
+        atomic_write(&dir.join(&file_name), bytes)?;
+        entries.push(CrashManifestEntry {
+            position: [position.x, position.y, position.z],
+            file_name,
+            byte_len: bytes.len(),
+        });
+    }
+    let manifest = CrashManifest { entries };
+    atomic_write(&dir.join("manifest.toml"), manifest.to_toml().as_bytes())?;
+    Ok(manifest)
+}
+
+/// Shared holder for the last-known dirty-chunk list, so `main.rs` can hand
+/// [`install_panic_hook`] something to read from a panic hook (which runs
+/// wherever the panic happened, long after `main` set anything up) while
+/// `App` is the only thing that can actually produce that list (it's the
+/// only thing holding a `Renderer`). Cloning shares the same underlying
+/// list, mirroring `DeviceLostFlag`'s clone-to-share-with-a-callback shape.
+#[derive(Clone, Default)]
+pub struct AutosaveHandle(Arc<Mutex<DirtyChunks>>);
+
+impl AutosaveHandle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replaces the held dirty-chunk list; `App` calls this once `Renderer`
+    /// exists (see this module's doc comment for why only once).
+    pub fn update(&self, dirty: DirtyChunks) {
+        *self.0.lock().unwrap() = dirty;
+    }
+
+    /// A snapshot of whatever was last passed to `update`, or empty if it
+    /// hasn't been called yet (e.g. a panic before `Renderer::new` returns).
+    pub fn snapshot(&self) -> DirtyChunks {
+        self.0.lock().unwrap().clone()
+    }
+}
+
+/// Installs a panic hook that runs whatever hook was previously installed
+/// (so the usual panic message still prints), then attempts
+/// [`write_crash_recovery`] with `handle`'s latest snapshot at the moment of
+/// the panic. A hook can't stop the unwind/abort that follows it, only
+/// observe the panic -- this doesn't try to. Save failures are logged, not
+/// propagated, so a failed last-ditch save never masks the original panic's
+/// message underneath it.
+pub fn install_panic_hook(dir: PathBuf, handle: AutosaveHandle) {
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        previous(info);
+        match write_crash_recovery(&dir, &handle.snapshot()) {
+            Ok(manifest) => log::error!("wrote crash recovery save ({} chunks) to {dir:?}", manifest.entries.len()),
+            Err(err) => log::error!("crash recovery save to {dir:?} failed: {err}"),
+        }
+    }));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scratch_dir(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("{name}_{:?}", std::thread::current().id()))
+    }
+
+    #[test]
+    fn atomic_write_creates_missing_parent_directories() {
+        let dir = scratch_dir("autosave_test_create_dirs");
+        let path = dir.join("nested").join("file.bin");
+        atomic_write(&path, b"hello").unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), b"hello");
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn atomic_write_replaces_an_existing_file_wholesale() {
+        let dir = scratch_dir("autosave_test_replace");
+        let path = dir.join("file.bin");
+        atomic_write(&path, b"first version, quite long").unwrap();
+        atomic_write(&path, b"v2").unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), b"v2");
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn atomic_write_leaves_no_temp_file_behind_on_success() {
+        let dir = scratch_dir("autosave_test_no_temp_left");
+        let path = dir.join("file.bin");
+        atomic_write(&path, b"data").unwrap();
+        let names: Vec<_> = std::fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(names, vec!["file.bin".to_string()]);
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn crash_manifest_round_trips_through_toml() {
+        let manifest = CrashManifest {
+            entries: vec![
+                CrashManifestEntry { position: [0, 0, 0], file_name: "chunk_0_0_0.bin".to_string(), byte_len: 128 },
+                CrashManifestEntry { position: [1, -2, 3], file_name: "chunk_1_-2_3.bin".to_string(), byte_len: 64 },
+            ],
+        };
+        let parsed = CrashManifest::parse(&manifest.to_toml()).unwrap();
+        assert_eq!(parsed, manifest);
+    }
+
+    #[test]
+    fn crash_manifest_parse_rejects_malformed_toml() {
+        assert!(CrashManifest::parse("this is not valid toml [[[").is_err());
+    }
+
+    #[test]
+    fn write_crash_recovery_writes_every_chunk_and_a_matching_manifest() {
+        let dir = scratch_dir("autosave_test_write_crash_recovery");
+        let dirty = vec![
+            (glam::IVec3::new(0, 0, 0), vec![1, 2, 3]),
+            (glam::IVec3::new(4, 0, -4), vec![9, 9, 9, 9, 9]),
+        ];
+        let manifest = write_crash_recovery(&dir, &dirty).unwrap();
+
+        assert_eq!(manifest.entries.len(), 2);
+        manifest.validate(&dir).expect("a just-written recovery dump should validate");
+
+        let on_disk = CrashManifest::parse(&std::fs::read_to_string(dir.join("manifest.toml")).unwrap()).unwrap();
+        assert_eq!(on_disk, manifest);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn write_crash_recovery_with_no_dirty_chunks_still_writes_an_empty_manifest() {
+        let dir = scratch_dir("autosave_test_write_crash_recovery_empty");
+        let manifest = write_crash_recovery(&dir, &[]).unwrap();
+        assert!(manifest.entries.is_empty());
+        assert!(dir.join("manifest.toml").exists());
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn validate_reports_a_chunk_file_whose_size_no_longer_matches_the_manifest() {
+        let dir = scratch_dir("autosave_test_validate_mismatch");
+        let dirty = vec![(glam::IVec3::new(0, 0, 0), vec![1, 2, 3, 4])];
+        let manifest = write_crash_recovery(&dir, &dirty).unwrap();
+
+        std::fs::write(dir.join(&manifest.entries[0].file_name), b"short").unwrap();
+        let err = manifest.validate(&dir).expect_err("a truncated chunk file should fail validation");
+        assert!(err.contains("found 5"));
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn autosave_handle_starts_empty_and_reports_the_latest_update() {
+        let handle = AutosaveHandle::new();
+        assert!(handle.snapshot().is_empty());
+
+        handle.update(vec![(glam::IVec3::new(1, 2, 3), vec![0xAB])]);
+        assert_eq!(handle.snapshot(), vec![(glam::IVec3::new(1, 2, 3), vec![0xAB])]);
+
+        handle.update(vec![]);
+        assert!(handle.snapshot().is_empty());
+    }
+
+    #[test]
+    fn cloning_an_autosave_handle_shares_the_same_underlying_dirty_list() {
+        let handle = AutosaveHandle::new();
+        let clone = handle.clone();
+
+        handle.update(vec![(glam::IVec3::ZERO, vec![1, 2, 3])]);
+        assert_eq!(clone.snapshot(), vec![(glam::IVec3::ZERO, vec![1, 2, 3])]);
+    }
+
+    #[test]
+    fn install_panic_hook_writes_a_recovery_dump_and_still_prints_the_panic() {
+        let dir = scratch_dir("autosave_test_panic_hook");
+        std::fs::remove_dir_all(&dir).ok();
+        let handle = AutosaveHandle::new();
+        handle.update(vec![(glam::IVec3::new(7, 0, 0), vec![42])]);
+
+        let result = std::panic::catch_unwind(|| {
+            install_panic_hook(dir.clone(), handle);
+            panic!("synthetic panic for install_panic_hook's test");
+        });
+
+        assert!(result.is_err());
+        let manifest = CrashManifest::parse(&std::fs::read_to_string(dir.join("manifest.toml")).unwrap()).unwrap();
+        assert_eq!(manifest.entries.len(), 1);
+        manifest.validate(&dir).expect("the panic hook's dump should validate");
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn install_panic_hook_saves_whatever_the_handle_held_at_the_moment_of_the_panic() {
+        let dir = scratch_dir("autosave_test_panic_hook_dirty_tracking");
+        std::fs::remove_dir_all(&dir).ok();
+        let handle = AutosaveHandle::new();
+
+        let result = std::panic::catch_unwind({
+            let dir = dir.clone();
+            let handle = handle.clone();
+            move || {
+                install_panic_hook(dir, handle.clone());
+                // Simulates `App` calling `AutosaveHandle::update` once the
+                // chunk the panic hook should save is actually known, which
+                // happens after `install_panic_hook` has already run (it's
+                // installed before `App::run`, not after).
+                handle.update(vec![(glam::IVec3::new(2, 0, -2), vec![9, 9])]);
+                panic!("synthetic panic after the dirty chunk list was populated");
+            }
+        });
+
+        assert!(result.is_err());
+        let manifest = CrashManifest::parse(&std::fs::read_to_string(dir.join("manifest.toml")).unwrap()).unwrap();
+        assert_eq!(
+            manifest.entries,
+            vec![CrashManifestEntry { position: [2, 0, -2], file_name: "chunk_2_0_-2.bin".to_string(), byte_len: 2 }]
+        );
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
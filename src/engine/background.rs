@@ -0,0 +1,176 @@
+//! Decides how much work the renderer should do while the window isn't
+//! focused, per `background_behavior` (see `engine::Config`/`--background-behavior`).
+//! Separate from [`super::render_gate::RenderGate`], which only tracks
+//! whether rendering is *possible* right now (zero-size/occluded); this
+//! tracks whether it's *wanted*, given the user's chosen tradeoff between
+//! battery life and staying responsive in the background.
+
+/// `background_behavior` config/CLI value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BackgroundBehavior {
+    /// Stop rendering entirely while unfocused/minimized.
+    Pause,
+    /// Keep rendering, but capped to a low frame rate, while unfocused/minimized.
+    #[default]
+    Throttle,
+    /// Ignore focus state; always render at full rate.
+    Full,
+}
+
+impl BackgroundBehavior {
+    pub fn parse(raw: &str) -> Result<Self, String> {
+        match raw {
+            "pause" => Ok(Self::Pause),
+            "throttle" => Ok(Self::Throttle),
+            "full" => Ok(Self::Full),
+            other => Err(format!(
+                "background_behavior expects one of pause, throttle, full, got {other:?}"
+            )),
+        }
+    }
+}
+
+/// What `App` should actually be doing right now, given the current
+/// [`BackgroundBehavior`] and focus/minimized state. Input is muted in
+/// both non-`Active` modes -- there's no reason to keep consuming camera
+/// input (once there is any; see `Camera`'s doc comment) for a window the
+/// user isn't looking at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BackgroundMode {
+    Active,
+    Throttled,
+    Paused,
+}
+
+/// Tracks window focus/minimized state and resolves it against a
+/// [`BackgroundBehavior`] into the [`BackgroundMode`] `App` should apply. A
+/// minimized window counts as backgrounded regardless of focus, since
+/// there's nothing on screen to render to either way.
+pub struct FocusTracker {
+    behavior: BackgroundBehavior,
+    focused: bool,
+    minimized: bool,
+}
+
+impl FocusTracker {
+    pub fn new(behavior: BackgroundBehavior) -> Self {
+        Self {
+            behavior,
+            focused: true,
+            minimized: false,
+        }
+    }
+
+    /// Updates focus state from `WindowEvent::Focused` and returns the mode
+    /// that now applies.
+    pub fn set_focused(&mut self, focused: bool) -> BackgroundMode {
+        self.focused = focused;
+        self.mode()
+    }
+
+    /// Updates minimized state (`App` derives this from a zero-size
+    /// `WindowEvent::Resized`) and returns the mode that now applies.
+    pub fn set_minimized(&mut self, minimized: bool) -> BackgroundMode {
+        self.minimized = minimized;
+        self.mode()
+    }
+
+    fn in_background(&self) -> bool {
+        !self.focused || self.minimized
+    }
+
+    pub fn mode(&self) -> BackgroundMode {
+        if !self.in_background() {
+            return BackgroundMode::Active;
+        }
+        match self.behavior {
+            BackgroundBehavior::Full => BackgroundMode::Active,
+            BackgroundBehavior::Throttle => BackgroundMode::Throttled,
+            BackgroundBehavior::Pause => BackgroundMode::Paused,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_every_known_behavior_name() {
+        assert_eq!(BackgroundBehavior::parse("pause").unwrap(), BackgroundBehavior::Pause);
+        assert_eq!(BackgroundBehavior::parse("throttle").unwrap(), BackgroundBehavior::Throttle);
+        assert_eq!(BackgroundBehavior::parse("full").unwrap(), BackgroundBehavior::Full);
+    }
+
+    #[test]
+    fn rejects_an_unknown_behavior_name() {
+        assert!(BackgroundBehavior::parse("nap").is_err());
+    }
+
+    #[test]
+    fn focused_and_not_minimized_is_always_active() {
+        let mut tracker = FocusTracker::new(BackgroundBehavior::Pause);
+        assert_eq!(tracker.set_focused(true), BackgroundMode::Active);
+    }
+
+    #[test]
+    fn losing_focus_pauses_under_the_pause_behavior() {
+        let mut tracker = FocusTracker::new(BackgroundBehavior::Pause);
+        assert_eq!(tracker.set_focused(false), BackgroundMode::Paused);
+        assert_eq!(tracker.set_focused(true), BackgroundMode::Active);
+    }
+
+    #[test]
+    fn losing_focus_throttles_under_the_throttle_behavior() {
+        let mut tracker = FocusTracker::new(BackgroundBehavior::Throttle);
+        assert_eq!(tracker.set_focused(false), BackgroundMode::Throttled);
+        assert_eq!(tracker.set_focused(true), BackgroundMode::Active);
+    }
+
+    #[test]
+    fn full_behavior_ignores_focus_entirely() {
+        let mut tracker = FocusTracker::new(BackgroundBehavior::Full);
+        assert_eq!(tracker.set_focused(false), BackgroundMode::Active);
+        assert_eq!(tracker.set_minimized(true), BackgroundMode::Active);
+    }
+
+    #[test]
+    fn rapid_focus_flapping_always_reflects_the_latest_state() {
+        let mut tracker = FocusTracker::new(BackgroundBehavior::Throttle);
+        for _ in 0..50 {
+            assert_eq!(tracker.set_focused(false), BackgroundMode::Throttled);
+            assert_eq!(tracker.set_focused(true), BackgroundMode::Active);
+        }
+    }
+
+    #[test]
+    fn minimized_while_focused_still_backgrounds() {
+        let mut tracker = FocusTracker::new(BackgroundBehavior::Pause);
+        assert_eq!(tracker.set_minimized(true), BackgroundMode::Paused);
+    }
+
+    #[test]
+    fn regaining_focus_while_still_minimized_stays_backgrounded() {
+        let mut tracker = FocusTracker::new(BackgroundBehavior::Pause);
+        tracker.set_minimized(true);
+        tracker.set_focused(false);
+        assert_eq!(tracker.set_focused(true), BackgroundMode::Paused, "still minimized, so focus alone shouldn't resume");
+    }
+
+    #[test]
+    fn unminimizing_while_still_unfocused_stays_backgrounded() {
+        let mut tracker = FocusTracker::new(BackgroundBehavior::Throttle);
+        tracker.set_focused(false);
+        tracker.set_minimized(true);
+        assert_eq!(tracker.set_minimized(false), BackgroundMode::Throttled, "still unfocused, so unminimizing alone shouldn't resume");
+    }
+
+    #[test]
+    fn only_active_once_both_focused_and_unminimized() {
+        let mut tracker = FocusTracker::new(BackgroundBehavior::Pause);
+        tracker.set_focused(false);
+        tracker.set_minimized(true);
+        assert_eq!(tracker.set_focused(true), BackgroundMode::Paused);
+        assert_eq!(tracker.set_minimized(false), BackgroundMode::Active);
+    }
+}
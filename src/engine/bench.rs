@@ -0,0 +1,178 @@
+use std::collections::BTreeMap;
+use std::time::Instant;
+
+use super::camera_path::CameraPath;
+use super::{Renderer, RendererOptions};
+use crate::voxel::WorkgroupSize;
+
+/// Frames rendered but excluded from the reported statistics, to let shader
+/// caches and the driver's internal pipelines settle before timing starts.
+const WARMUP_FRAMES: usize = 10;
+
+/// CPU+GPU frame-time statistics from a headless `--bench` run, in
+/// milliseconds. GPU time is approximated by blocking on `wait_for_gpu`
+/// after each frame rather than real timestamp queries (see synth-2815).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BenchReport {
+    pub frames: usize,
+    pub warmup_frames: usize,
+    pub mean_ms: f64,
+    pub median_ms: f64,
+    pub p99_ms: f64,
+}
+
+impl BenchReport {
+    fn from_samples(frames: usize, mut samples_ms: Vec<f64>) -> Self {
+        samples_ms.sort_by(|a, b| a.partial_cmp(b).expect("frame time is never NaN"));
+        let mean_ms = samples_ms.iter().sum::<f64>() / samples_ms.len() as f64;
+        Self {
+            frames,
+            warmup_frames: WARMUP_FRAMES,
+            mean_ms,
+            median_ms: percentile(&samples_ms, 0.5),
+            p99_ms: percentile(&samples_ms, 0.99),
+        }
+    }
+
+    /// Minimal hand-rolled JSON so `--bench-json` doesn't need to pull in a
+    /// serialization crate for five numbers.
+    pub fn to_json(self) -> String {
+        format!(
+            "{{\"frames\":{},\"warmup_frames\":{},\"mean_ms\":{:.4},\"median_ms\":{:.4},\"p99_ms\":{:.4}}}\n",
+            self.frames, self.warmup_frames, self.mean_ms, self.median_ms, self.p99_ms
+        )
+    }
+}
+
+impl std::fmt::Display for BenchReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} frames ({} warm-up): mean {:.3} ms, median {:.3} ms, p99 {:.3} ms",
+            self.frames, self.warmup_frames, self.mean_ms, self.median_ms, self.p99_ms
+        )
+    }
+}
+
+fn percentile(sorted_ms: &[f64], p: f64) -> f64 {
+    let idx = (((sorted_ms.len() - 1) as f64) * p).round() as usize;
+    sorted_ms[idx]
+}
+
+/// Renders `frames` headless frames back-to-back and reports frame-time
+/// statistics, discarding the first [`WARMUP_FRAMES`].
+pub fn run(frames: usize) -> BenchReport {
+    run_with_options(
+        frames,
+        RendererOptions {
+            present_mode: wgpu::PresentMode::AutoNoVsync,
+            ..RendererOptions::default()
+        },
+    )
+}
+
+/// Runs `run` once per square workgroup size in `sizes` (e.g. `&[8, 16, 32]`
+/// for `x`x`x` tiles), so users can compare GPU times across sizes and pick
+/// the best one for their device via `RendererOptions::workgroup_size`.
+pub fn run_sweep(frames: usize, sizes: &[u32]) -> Vec<(WorkgroupSize, BenchReport)> {
+    sizes
+        .iter()
+        .map(|&size| {
+            let workgroup_size = WorkgroupSize { x: size, y: size };
+            let options = RendererOptions {
+                present_mode: wgpu::PresentMode::AutoNoVsync,
+                workgroup_size: Some(workgroup_size),
+                ..RendererOptions::default()
+            };
+            (workgroup_size, run_with_options(frames, options))
+        })
+        .collect()
+}
+
+/// Frame-time statistics for the frames sampled while a `--bench-path` run's
+/// camera was in one segment of the path, so "looking at dense terrain" and
+/// "looking at sky" show up as distinct numbers instead of blurring into
+/// [`BenchReport`]'s single overall average.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SegmentReport {
+    pub segment: usize,
+    pub frames: usize,
+    pub mean_ms: f64,
+}
+
+impl std::fmt::Display for SegmentReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "segment {}: {} frames, mean {:.3} ms", self.segment, self.frames, self.mean_ms)
+    }
+}
+
+/// Renders `frames` headless frames while sampling `camera_path` evenly
+/// across its full duration (including through [`WARMUP_FRAMES`], so warm-up
+/// isn't spent staring at the path's first keyframe), reporting both the
+/// overall [`BenchReport`] and a per-segment breakdown.
+pub fn run_with_path(frames: usize, camera_path: CameraPath) -> (BenchReport, Vec<SegmentReport>) {
+    let mut renderer = pollster::block_on(Renderer::new_headless(RendererOptions {
+        present_mode: wgpu::PresentMode::AutoNoVsync,
+        ..RendererOptions::default()
+    }))
+    .expect("failed to build headless renderer");
+
+    let total_frames = WARMUP_FRAMES + frames;
+    let duration = camera_path.duration();
+    let mut samples_ms = Vec::with_capacity(frames);
+    let mut segment_samples: BTreeMap<usize, Vec<f64>> = BTreeMap::new();
+    let mut last_update = Instant::now();
+    for i in 0..total_frames {
+        let now = Instant::now();
+        renderer.update(now - last_update);
+        last_update = now;
+
+        let progress = if total_frames <= 1 { 0.0 } else { i as f32 / (total_frames - 1) as f32 };
+        let time = duration * progress;
+        renderer.set_camera_pose(camera_path.sample(time));
+
+        let start = Instant::now();
+        renderer.render().expect("headless render failed");
+        renderer.wait_for_gpu();
+        let elapsed_ms = start.elapsed().as_secs_f64() * 1000.0;
+
+        if i >= WARMUP_FRAMES {
+            samples_ms.push(elapsed_ms);
+            segment_samples.entry(camera_path.segment_index(time)).or_default().push(elapsed_ms);
+        }
+    }
+
+    let report = BenchReport::from_samples(frames, samples_ms);
+    let segments = segment_samples
+        .into_iter()
+        .map(|(segment, samples)| SegmentReport {
+            segment,
+            frames: samples.len(),
+            mean_ms: samples.iter().sum::<f64>() / samples.len() as f64,
+        })
+        .collect();
+    (report, segments)
+}
+
+fn run_with_options(frames: usize, options: RendererOptions) -> BenchReport {
+    let mut renderer = pollster::block_on(Renderer::new_headless(options)).expect("failed to build headless renderer");
+
+    let mut samples_ms = Vec::with_capacity(frames);
+    let mut last_update = Instant::now();
+    for i in 0..WARMUP_FRAMES + frames {
+        let now = Instant::now();
+        renderer.update(now - last_update);
+        last_update = now;
+
+        let start = Instant::now();
+        renderer.render().expect("headless render failed");
+        renderer.wait_for_gpu();
+        let elapsed_ms = start.elapsed().as_secs_f64() * 1000.0;
+
+        if i >= WARMUP_FRAMES {
+            samples_ms.push(elapsed_ms);
+        }
+    }
+
+    BenchReport::from_samples(frames, samples_ms)
+}
@@ -0,0 +1,436 @@
+/// One sub-allocated region inside a [`BufferArena`]: `buffer_index` selects
+/// which of the arena's buffers it lives in, `offset`/`len` (both already
+/// rounded up to the arena's alignment) give its range within that buffer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct ArenaSlot {
+    pub(crate) buffer_index: usize,
+    pub(crate) offset: u64,
+    pub(crate) len: u64,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct FreeRange {
+    offset: u64,
+    len: u64,
+}
+
+/// Pure free-list bookkeeping for [`BufferArena`], kept separate from actual
+/// `wgpu::Buffer` creation so the allocation logic can be exercised by tests
+/// without a real `wgpu::Device` -- mirrors how `MemoCache` in
+/// `pipeline_cache.rs` splits memoization logic from the GPU object it
+/// memoizes.
+struct ArenaLayout {
+    /// Capacity a freshly opened buffer gets, unless a single allocation is
+    /// larger than this, in which case that allocation gets a buffer sized
+    /// exactly to it.
+    default_buffer_size: u64,
+    alignment: u64,
+    /// Free ranges per buffer, sorted by offset and kept non-overlapping
+    /// with adjacent ranges merged -- the "compacts opportunistically" half
+    /// of the request, since the arena can't move live slot contents around
+    /// without a copy it doesn't know how to issue for opaque bytes.
+    free_ranges: Vec<Vec<FreeRange>>,
+    /// Live slots per buffer, tracked only so [`ArenaLayout::compact_step`]
+    /// knows what's occupying the space between free ranges -- `alloc`'s
+    /// caller already knows its own [`ArenaSlot`], this is purely for the
+    /// compactor's own bookkeeping.
+    live_slots: Vec<Vec<ArenaSlot>>,
+}
+
+impl ArenaLayout {
+    fn new(default_buffer_size: u64, alignment: u64) -> Self {
+        Self { default_buffer_size, alignment, free_ranges: Vec::new(), live_slots: Vec::new() }
+    }
+
+    fn align_up(&self, len: u64) -> u64 {
+        len.div_ceil(self.alignment) * self.alignment
+    }
+
+    /// Finds or opens room for `len` bytes, returning the slot and, if a new
+    /// buffer had to be opened, the capacity it should be created with.
+    fn alloc(&mut self, len: u64) -> (ArenaSlot, Option<u64>) {
+        let len = self.align_up(len.max(1));
+
+        for (buffer_index, ranges) in self.free_ranges.iter_mut().enumerate() {
+            if let Some(range_index) = ranges.iter().position(|range| range.len >= len) {
+                let range = ranges[range_index];
+                if range.len == len {
+                    ranges.remove(range_index);
+                } else {
+                    ranges[range_index] = FreeRange { offset: range.offset + len, len: range.len - len };
+                }
+                let slot = ArenaSlot { buffer_index, offset: range.offset, len };
+                self.live_slots[buffer_index].push(slot);
+                return (slot, None);
+            }
+        }
+
+        let capacity = len.max(self.default_buffer_size);
+        let buffer_index = self.free_ranges.len();
+        let remainder = capacity - len;
+        self.free_ranges.push(if remainder > 0 { vec![FreeRange { offset: len, len: remainder }] } else { Vec::new() });
+        let slot = ArenaSlot { buffer_index, offset: 0, len };
+        self.live_slots.push(vec![slot]);
+        (slot, Some(capacity))
+    }
+
+    fn free(&mut self, slot: ArenaSlot) {
+        let live = &mut self.live_slots[slot.buffer_index];
+        let index = live.iter().position(|&live_slot| live_slot == slot).expect("freeing a slot this layout never allocated");
+        live.remove(index);
+
+        let ranges = &mut self.free_ranges[slot.buffer_index];
+        let insert_at = ranges.iter().position(|r| r.offset > slot.offset).unwrap_or(ranges.len());
+        ranges.insert(insert_at, FreeRange { offset: slot.offset, len: slot.len });
+        merge_adjacent(ranges);
+    }
+
+    /// Fragmentation of `buffer_index`'s free space: `0.0` when it's either
+    /// full or all in one contiguous range, approaching `1.0` as that same
+    /// total free byte count splits into more, smaller ranges. What a
+    /// caller checks before bothering to run [`ArenaLayout::compact_step`].
+    fn fragmentation(&self, buffer_index: usize) -> f32 {
+        let ranges = &self.free_ranges[buffer_index];
+        let total: u64 = ranges.iter().map(|range| range.len).sum();
+        if total == 0 {
+            return 0.0;
+        }
+        let largest = ranges.iter().map(|range| range.len).max().unwrap_or(0);
+        1.0 - (largest as f32 / total as f32)
+    }
+
+    /// The first (free range, live slot) pair in `buffer_index` where the
+    /// slot immediately follows the range and fits inside it -- what
+    /// [`ArenaLayout::compact_step`] moves next.
+    fn next_compactable_pair(&self, buffer_index: usize) -> Option<(usize, usize)> {
+        let free_ranges = &self.free_ranges[buffer_index];
+        let live_slots = &self.live_slots[buffer_index];
+        for (range_index, range) in free_ranges.iter().enumerate() {
+            let range_end = range.offset + range.len;
+            if let Some(slot_index) = live_slots.iter().position(|slot| slot.offset == range_end && slot.len <= range.len) {
+                return Some((range_index, slot_index));
+            }
+        }
+        None
+    }
+
+    /// Slides up to `max_moves` live slots in `buffer_index` down into a
+    /// free range immediately preceding them, returning what moved so a
+    /// caller can mirror each move with a `copy_buffer_to_buffer` and a
+    /// chunk-table update -- "a few slots per frame" incremental
+    /// compaction, not a full repack in one call, so a long session's
+    /// worth of fragmentation is worked off gradually instead of in one
+    /// frame-stalling pass.
+    ///
+    /// Deliberately narrower than "pack everything to offset zero": a slot
+    /// only moves when it fits entirely inside the free range right before
+    /// it ([`ArenaLayout::next_compactable_pair`]), so the old and new
+    /// ranges of the same buffer never overlap -- required for
+    /// `wgpu::CommandEncoder::copy_buffer_to_buffer` within the same
+    /// buffer, which forbids overlapping source/destination ranges. A slot
+    /// bigger than every gap ahead of it is left in place until
+    /// [`ArenaLayout::free`] merges enough neighboring free ranges (via
+    /// `merge_adjacent`) to fit it.
+    fn compact_step(&mut self, buffer_index: usize, max_moves: usize) -> Vec<CompactionMove> {
+        let mut moves = Vec::with_capacity(max_moves);
+        while moves.len() < max_moves {
+            let Some((range_index, slot_index)) = self.next_compactable_pair(buffer_index) else {
+                break;
+            };
+            let free_range = self.free_ranges[buffer_index][range_index];
+            let slot = self.live_slots[buffer_index][slot_index];
+
+            self.live_slots[buffer_index][slot_index].offset = free_range.offset;
+            moves.push(CompactionMove { buffer_index, old_offset: slot.offset, new_offset: free_range.offset, len: slot.len });
+
+            let ranges = &mut self.free_ranges[buffer_index];
+            ranges.remove(range_index);
+            ranges.push(FreeRange { offset: slot.offset, len: slot.len });
+            if free_range.len > slot.len {
+                ranges.push(FreeRange { offset: free_range.offset + slot.len, len: free_range.len - slot.len });
+            }
+            ranges.sort_by_key(|range| range.offset);
+            merge_adjacent(ranges);
+        }
+        moves
+    }
+}
+
+/// One live slot relocated by [`ArenaLayout::compact_step`]: the same
+/// buffer, `len` bytes, moving from `old_offset` down to `new_offset`. A
+/// caller applies it with `copy_buffer_to_buffer` and then updates whatever
+/// chunk-table entry pointed at `old_offset`, in that order within the same
+/// submission -- otherwise a frame could render with a table entry pointing
+/// at bytes the compactor already overwrote.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct CompactionMove {
+    pub(crate) buffer_index: usize,
+    pub(crate) old_offset: u64,
+    pub(crate) new_offset: u64,
+    pub(crate) len: u64,
+}
+
+/// Merges ranges that are adjacent (or overlapping) once sorted by offset,
+/// so two frees next to each other become one reusable range instead of
+/// staying fragmented forever.
+fn merge_adjacent(ranges: &mut Vec<FreeRange>) {
+    let mut merged: Vec<FreeRange> = Vec::with_capacity(ranges.len());
+    for range in ranges.drain(..) {
+        match merged.last_mut() {
+            Some(last) if last.offset + last.len >= range.offset => {
+                let end = (last.offset + last.len).max(range.offset + range.len);
+                last.len = end - last.offset;
+            }
+            _ => merged.push(range),
+        }
+    }
+    *ranges = merged;
+}
+
+/// Sub-allocates fixed-size slots for chunk octree/voxel data out of a few
+/// large `wgpu::Buffer`s, instead of one buffer per chunk -- see the
+/// synth-2845 request this exists for. Not yet wired into
+/// `VoxelRendererPass`'s single-chunk `node_buffer`: that pass records one
+/// buffer and rebuilds it whole rather than indexing a chunk table, so there
+/// is nothing to hand an `ArenaSlot` to yet. Kept as the allocator this
+/// crate will need once a streaming, multi-chunk chunk table exists.
+///
+/// **Not wired up, not closed out.** Two later requests build on this one
+/// assuming it's live -- `chunk_cache.rs`'s eviction policy (synth-2866)
+/// and `GpuMemoryReport::fragmentation_ratio` (synth-2896) -- and both hit
+/// the same gap as a result: there's no chunk table calling into this
+/// allocator for them to report real numbers about, so neither produces
+/// any runtime behavior yet either.
+///
+/// [`BufferArena::compact_step`] and [`BufferArena::fragmentation`] are the
+/// same story: real, usable once a chunk table exists to update alongside
+/// the copies, but nothing calls them today, so there's no live "chunk
+/// table" for [`CompactionMove`] to update and nothing feeding
+/// `fragmentation` into `GpuMemoryReport` -- see `memory_report.rs`'s
+/// `GpuMemoryReport`, which only totals buffers/textures the renderer
+/// actually creates. `fragmentation`/`compact_step` themselves are
+/// unconditionally correct against this arena's own bookkeeping regardless
+/// of whether anything calls them yet, which is exercised in
+/// `ArenaLayout`'s tests below.
+#[allow(dead_code)]
+pub(crate) struct BufferArena {
+    label: &'static str,
+    usage: wgpu::BufferUsages,
+    layout: ArenaLayout,
+    buffers: Vec<wgpu::Buffer>,
+}
+
+#[allow(dead_code)]
+impl BufferArena {
+    /// `default_buffer_size` is the capacity a freshly opened buffer gets;
+    /// `alignment` is the minimum offset granularity slots are rounded up
+    /// to (e.g. 256 for storage buffer offsets on most backends).
+    pub(crate) fn new(label: &'static str, usage: wgpu::BufferUsages, default_buffer_size: u64, alignment: u64) -> Self {
+        Self {
+            label,
+            usage,
+            layout: ArenaLayout::new(default_buffer_size, alignment),
+            buffers: Vec::new(),
+        }
+    }
+
+    /// Hands out a slot at least `len` bytes long, opening a new backing
+    /// buffer (sized to fit `len`, or `default_buffer_size` if larger) when
+    /// no existing buffer has room.
+    pub(crate) fn alloc(&mut self, device: &wgpu::Device, len: u64) -> ArenaSlot {
+        let (slot, new_buffer_capacity) = self.layout.alloc(len);
+        if let Some(capacity) = new_buffer_capacity {
+            self.buffers.push(device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some(self.label),
+                size: capacity,
+                usage: self.usage,
+                mapped_at_creation: false,
+            }));
+        }
+        slot
+    }
+
+    /// Returns `slot`'s range to its buffer's free list for reuse.
+    pub(crate) fn free(&mut self, slot: ArenaSlot) {
+        self.layout.free(slot);
+    }
+
+    pub(crate) fn buffer(&self, index: usize) -> &wgpu::Buffer {
+        &self.buffers[index]
+    }
+
+    /// [`ArenaLayout::fragmentation`] for the buffer at `index`.
+    pub(crate) fn fragmentation(&self, index: usize) -> f32 {
+        self.layout.fragmentation(index)
+    }
+
+    /// Runs [`ArenaLayout::compact_step`] against the buffer at
+    /// `buffer_index` and issues the matching `copy_buffer_to_buffer` calls
+    /// into `encoder` for each [`CompactionMove`] it returns, so the copies
+    /// land in the same submission as whatever chunk-table update a caller
+    /// makes from the returned moves -- see [`CompactionMove`]'s doc
+    /// comment for why that ordering matters.
+    pub(crate) fn compact_step(&mut self, encoder: &mut wgpu::CommandEncoder, buffer_index: usize, max_moves: usize) -> Vec<CompactionMove> {
+        let moves = self.layout.compact_step(buffer_index, max_moves);
+        let buffer = &self.buffers[buffer_index];
+        for mv in &moves {
+            encoder.copy_buffer_to_buffer(buffer, mv.old_offset, buffer, mv.new_offset, mv.len);
+        }
+        moves
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn two_allocations_from_one_buffer_do_not_overlap() {
+        let mut layout = ArenaLayout::new(1024, 1);
+        let (a, _) = layout.alloc(100);
+        let (b, _) = layout.alloc(200);
+        assert_eq!(a.buffer_index, b.buffer_index);
+        assert!(a.offset + a.len <= b.offset || b.offset + b.len <= a.offset);
+    }
+
+    #[test]
+    fn allocation_larger_than_default_size_gets_its_own_buffer() {
+        let mut layout = ArenaLayout::new(64, 1);
+        let (a, new_capacity) = layout.alloc(256);
+        assert_eq!(a.buffer_index, 0);
+        assert_eq!(new_capacity, Some(256));
+    }
+
+    #[test]
+    fn freeing_and_reallocating_the_same_size_reuses_the_slot() {
+        let mut layout = ArenaLayout::new(1024, 1);
+        let (a, _) = layout.alloc(100);
+        layout.free(a);
+        let (b, new_capacity) = layout.alloc(100);
+        assert_eq!(new_capacity, None);
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn freeing_adjacent_slots_merges_them_for_a_larger_reuse() {
+        let mut layout = ArenaLayout::new(1024, 1);
+        let (a, _) = layout.alloc(100);
+        let (b, _) = layout.alloc(100);
+        layout.free(a);
+        layout.free(b);
+        let (c, new_capacity) = layout.alloc(200);
+        assert_eq!(new_capacity, None);
+        assert_eq!(c.buffer_index, a.buffer_index);
+        assert_eq!(c.offset, a.offset.min(b.offset));
+    }
+
+    #[test]
+    fn offsets_are_rounded_up_to_alignment() {
+        let mut layout = ArenaLayout::new(1024, 256);
+        let (a, _) = layout.alloc(10);
+        let (b, _) = layout.alloc(10);
+        assert_eq!(a.len, 256);
+        assert_eq!(b.offset % 256, 0);
+    }
+
+    #[test]
+    fn repeated_alloc_free_cycles_never_overlap_within_a_buffer() {
+        let mut layout = ArenaLayout::new(4096, 16);
+        let mut live = Vec::new();
+        let sizes = [32, 64, 128, 16, 256, 32, 64, 512, 16, 128];
+        for (i, &size) in sizes.iter().cycle().take(40).enumerate() {
+            let (slot, _) = layout.alloc(size);
+            live.push(slot);
+            if i % 3 == 2 {
+                let freed = live.remove(0);
+                layout.free(freed);
+            }
+        }
+
+        for buffer_index in 0..layout.free_ranges.len() {
+            let mut slots_in_buffer: Vec<_> = live.iter().filter(|s| s.buffer_index == buffer_index).collect();
+            slots_in_buffer.sort_by_key(|s| s.offset);
+            for pair in slots_in_buffer.windows(2) {
+                assert!(pair[0].offset + pair[0].len <= pair[1].offset, "overlap: {:?} vs {:?}", pair[0], pair[1]);
+            }
+        }
+    }
+
+    #[test]
+    fn fragmentation_is_zero_for_a_single_free_range() {
+        let mut layout = ArenaLayout::new(1024, 1);
+        let (a, _) = layout.alloc(100);
+        assert_eq!(layout.fragmentation(a.buffer_index), 0.0);
+    }
+
+    #[test]
+    fn fragmentation_is_zero_when_the_buffer_has_no_free_space() {
+        let mut layout = ArenaLayout::new(100, 1);
+        let (a, _) = layout.alloc(100);
+        assert_eq!(layout.fragmentation(a.buffer_index), 0.0);
+    }
+
+    #[test]
+    fn fragmentation_rises_as_free_space_splits_into_smaller_ranges() {
+        let mut layout = ArenaLayout::new(300, 1);
+        let (a, _) = layout.alloc(100);
+        let (_b, _) = layout.alloc(100);
+        let (c, _) = layout.alloc(100);
+        layout.free(a);
+        let one_range_fragmentation = layout.fragmentation(a.buffer_index);
+        // `_b` stays live between `a` and `c`'s freed ranges, so freeing `c`
+        // leaves two separate free ranges instead of merging into one --
+        // worse fragmentation than a single range of the same total size.
+        layout.free(c);
+        let two_range_fragmentation = layout.fragmentation(a.buffer_index);
+        assert!(two_range_fragmentation > one_range_fragmentation);
+    }
+
+    #[test]
+    fn compact_step_moves_a_slot_into_a_preceding_gap_that_fits_it() {
+        let mut layout = ArenaLayout::new(1024, 1);
+        let (a, _) = layout.alloc(100);
+        let (b, _) = layout.alloc(100);
+        layout.free(a);
+
+        let moves = layout.compact_step(a.buffer_index, 10);
+        assert_eq!(moves, vec![CompactionMove { buffer_index: a.buffer_index, old_offset: b.offset, new_offset: a.offset, len: b.len }]);
+        assert_eq!(layout.fragmentation(a.buffer_index), 0.0);
+    }
+
+    #[test]
+    fn compact_step_respects_the_max_moves_budget() {
+        let mut layout = ArenaLayout::new(1024, 1);
+        // 4 same-size slots, then free every other one: slots 1 and 3 each
+        // gain a preceding gap exactly their own size.
+        let slots: Vec<_> = (0..4).map(|_| layout.alloc(50).0).collect();
+        layout.free(slots[0]);
+        layout.free(slots[2]);
+
+        let moves = layout.compact_step(slots[0].buffer_index, 1);
+        assert_eq!(moves.len(), 1, "one call with a budget of 1 should move exactly one slot");
+    }
+
+    #[test]
+    fn compact_step_never_moves_a_slot_into_a_gap_too_small_to_hold_it() {
+        let mut layout = ArenaLayout::new(1024, 1);
+        let (a, _) = layout.alloc(10);
+        let (b, _) = layout.alloc(200);
+        layout.free(a);
+
+        // `b` (200 bytes) doesn't fit in `a`'s 10-byte gap, so nothing moves
+        // -- moving it anyway would make the copy's source and destination
+        // ranges overlap.
+        let moves = layout.compact_step(a.buffer_index, 10);
+        assert!(moves.is_empty());
+        assert_eq!(b.offset, layout.live_slots[a.buffer_index].iter().find(|s| s.len == 200).unwrap().offset);
+    }
+
+    #[test]
+    fn compact_step_leaves_a_fully_packed_buffer_untouched() {
+        let mut layout = ArenaLayout::new(1024, 1);
+        layout.alloc(100);
+        layout.alloc(100);
+        assert!(layout.compact_step(0, 10).is_empty());
+    }
+}
@@ -0,0 +1,286 @@
+//! Scripted camera paths for repeatable flythroughs and benchmarks: a list
+//! of keyframes (time, position, yaw/pitch) loaded from a small TOML file,
+//! sampled with Catmull-Rom interpolation for position and shortest-arc
+//! interpolation for angles so a looping yaw (say, `350°` to `10°`) turns
+//! through the short way instead of snapping the long way around.
+//!
+//! Played back two ways: `Renderer::play_camera_path` advances it once per
+//! fixed simulation step in `step_simulation`, the same place "camera
+//! movement and chunk streaming integration" are documented to land, and
+//! `bench::run_with_path` samples it once per rendered frame for a
+//! deterministic `--bench-path` run.
+
+use serde::{Deserialize, Serialize};
+
+/// One waypoint: `time` in seconds since the path started, `position` in
+/// world space, `yaw_degrees`/`pitch_degrees` matching `Camera::yaw`/`Camera::pitch`
+/// but in degrees since that's what a human hand-editing a TOML file
+/// reaches for, converted to radians on sampling.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CameraKeyframe {
+    pub time: f32,
+    pub position: [f32; 3],
+    pub yaw_degrees: f32,
+    pub pitch_degrees: f32,
+}
+
+/// A validated, time-ordered list of keyframes; see [`parse`] and
+/// [`CameraPath::validate`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CameraPath {
+    pub keyframes: Vec<CameraKeyframe>,
+}
+
+/// A sampled point along a [`CameraPath`], ready to assign straight onto a
+/// `Camera`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CameraPose {
+    pub position: glam::Vec3,
+    pub yaw_radians: f32,
+    pub pitch_radians: f32,
+}
+
+impl CameraPath {
+    /// At least two keyframes (nothing to interpolate between with fewer)
+    /// with strictly increasing `time`, naming the first offending pair on
+    /// failure.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.keyframes.len() < 2 {
+            return Err(format!("camera path needs at least 2 keyframes, got {}", self.keyframes.len()));
+        }
+        for pair in self.keyframes.windows(2) {
+            if pair[1].time <= pair[0].time {
+                return Err(format!(
+                    "camera path keyframes must have strictly increasing time, got {} then {}",
+                    pair[0].time, pair[1].time
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Total duration in seconds, from the first keyframe's time to the
+    /// last's. Only meaningful once [`validate`](Self::validate) has passed.
+    pub fn duration(&self) -> f32 {
+        self.keyframes.last().map(|k| k.time).unwrap_or(0.0) - self.keyframes.first().map(|k| k.time).unwrap_or(0.0)
+    }
+
+    /// Index `i` such that `time` falls in `[keyframes[i].time, keyframes[i + 1].time]`,
+    /// clamping `time` to the path's own range first.
+    pub(crate) fn segment_index(&self, time: f32) -> usize {
+        let last_segment = self.keyframes.len() - 2;
+        self.keyframes
+            .windows(2)
+            .position(|pair| time <= pair[1].time)
+            .unwrap_or(last_segment)
+    }
+
+    /// Samples the path at `time` (clamped to `[first.time, last.time]`):
+    /// Catmull-Rom for `position`, shortest-arc lerp for `yaw`/`pitch`.
+    /// Panics if [`validate`](Self::validate) would fail -- callers parse
+    /// with [`parse`], which validates first.
+    pub fn sample(&self, time: f32) -> CameraPose {
+        let first = self.keyframes.first().expect("validated path has at least one keyframe");
+        let last = self.keyframes.last().expect("validated path has at least one keyframe");
+        let time = time.clamp(first.time, last.time);
+
+        let segment = self.segment_index(time);
+        let k1 = &self.keyframes[segment];
+        let k2 = &self.keyframes[segment + 1];
+        let k0 = segment.checked_sub(1).map(|i| &self.keyframes[i]).unwrap_or(k1);
+        let k3 = self.keyframes.get(segment + 2).unwrap_or(k2);
+
+        let t = if k2.time > k1.time { (time - k1.time) / (k2.time - k1.time) } else { 0.0 };
+        let position = catmull_rom(k0.position.into(), k1.position.into(), k2.position.into(), k3.position.into(), t);
+
+        CameraPose {
+            position,
+            yaw_radians: shortest_arc_lerp(k1.yaw_degrees, k2.yaw_degrees, t).to_radians(),
+            pitch_radians: shortest_arc_lerp(k1.pitch_degrees, k2.pitch_degrees, t).to_radians(),
+        }
+    }
+}
+
+/// Uniform Catmull-Rom spline through `p1`..`p2` at `t` in `[0, 1]`, using
+/// `p0`/`p3` as the surrounding control points (the segment's own endpoints
+/// stand in for a missing neighbor at either end of the path).
+fn catmull_rom(p0: glam::Vec3, p1: glam::Vec3, p2: glam::Vec3, p3: glam::Vec3, t: f32) -> glam::Vec3 {
+    let t2 = t * t;
+    let t3 = t2 * t;
+    0.5 * ((2.0 * p1)
+        + (p2 - p0) * t
+        + (2.0 * p0 - 5.0 * p1 + 4.0 * p2 - p3) * t2
+        + (3.0 * p1 - p0 - 3.0 * p2 + p3) * t3)
+}
+
+/// Lerps from `a_degrees` to `b_degrees` through whichever direction is
+/// shorter around the circle, so e.g. `350 -> 10` turns through `0`
+/// (`20°` of travel) instead of the long way back through `180°`
+/// (`340°` of travel).
+fn shortest_arc_lerp(a_degrees: f32, b_degrees: f32, t: f32) -> f32 {
+    let mut delta = (b_degrees - a_degrees) % 360.0;
+    if delta > 180.0 {
+        delta -= 360.0;
+    } else if delta < -180.0 {
+        delta += 360.0;
+    }
+    a_degrees + delta * t
+}
+
+/// Parses a camera path TOML file (a top-level `[[keyframes]]` array of
+/// tables) and validates it, so a caller never has to check both a parse
+/// error and a validation error separately.
+pub fn parse(raw: &str) -> Result<CameraPath, String> {
+    let path: CameraPath = toml::from_str(raw).map_err(|err| err.to_string())?;
+    path.validate()?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keyframe(time: f32, position: [f32; 3], yaw_degrees: f32, pitch_degrees: f32) -> CameraKeyframe {
+        CameraKeyframe { time, position, yaw_degrees, pitch_degrees }
+    }
+
+    #[test]
+    fn parses_a_valid_path() {
+        let raw = r#"
+            [[keyframes]]
+            time = 0.0
+            position = [0.0, 0.0, 0.0]
+            yaw_degrees = 0.0
+            pitch_degrees = 0.0
+
+            [[keyframes]]
+            time = 2.0
+            position = [10.0, 0.0, 0.0]
+            yaw_degrees = 90.0
+            pitch_degrees = 0.0
+        "#;
+        let path = parse(raw).expect("valid path should parse");
+        assert_eq!(path.keyframes.len(), 2);
+    }
+
+    #[test]
+    fn rejects_malformed_toml() {
+        assert!(parse("this is not valid toml [[[").is_err());
+    }
+
+    #[test]
+    fn rejects_a_single_keyframe() {
+        let raw = r#"
+            [[keyframes]]
+            time = 0.0
+            position = [0.0, 0.0, 0.0]
+            yaw_degrees = 0.0
+            pitch_degrees = 0.0
+        "#;
+        let err = parse(raw).expect_err("a single keyframe has nothing to interpolate between");
+        assert!(err.contains("at least 2"));
+    }
+
+    #[test]
+    fn rejects_out_of_order_keyframe_times() {
+        let raw = r#"
+            [[keyframes]]
+            time = 5.0
+            position = [0.0, 0.0, 0.0]
+            yaw_degrees = 0.0
+            pitch_degrees = 0.0
+
+            [[keyframes]]
+            time = 1.0
+            position = [10.0, 0.0, 0.0]
+            yaw_degrees = 0.0
+            pitch_degrees = 0.0
+        "#;
+        let err = parse(raw).expect_err("keyframe times must strictly increase");
+        assert!(err.contains("increasing time"));
+    }
+
+    #[test]
+    fn sampling_exactly_at_a_keyframe_reproduces_its_pose() {
+        let path = CameraPath {
+            keyframes: vec![
+                keyframe(0.0, [0.0, 0.0, 0.0], 0.0, 0.0),
+                keyframe(1.0, [10.0, 0.0, 0.0], 90.0, 0.0),
+                keyframe(2.0, [10.0, 10.0, 0.0], 180.0, 0.0),
+            ],
+        };
+        let pose = path.sample(1.0);
+        assert!((pose.position - glam::Vec3::new(10.0, 0.0, 0.0)).length() < 0.001);
+        assert!((pose.yaw_radians - 90f32.to_radians()).abs() < 0.001);
+    }
+
+    #[test]
+    fn sampling_before_the_first_keyframe_clamps_to_it() {
+        let path = CameraPath {
+            keyframes: vec![keyframe(0.0, [0.0, 0.0, 0.0], 0.0, 0.0), keyframe(1.0, [10.0, 0.0, 0.0], 0.0, 0.0)],
+        };
+        let pose = path.sample(-5.0);
+        assert!((pose.position - glam::Vec3::ZERO).length() < 0.001);
+    }
+
+    #[test]
+    fn sampling_past_the_last_keyframe_clamps_to_it() {
+        let path = CameraPath {
+            keyframes: vec![keyframe(0.0, [0.0, 0.0, 0.0], 0.0, 0.0), keyframe(1.0, [10.0, 0.0, 0.0], 0.0, 0.0)],
+        };
+        let pose = path.sample(50.0);
+        assert!((pose.position - glam::Vec3::new(10.0, 0.0, 0.0)).length() < 0.001);
+    }
+
+    #[test]
+    fn position_interpolation_has_no_discontinuity_across_a_segment_boundary() {
+        let path = CameraPath {
+            keyframes: vec![
+                keyframe(0.0, [0.0, 0.0, 0.0], 0.0, 0.0),
+                keyframe(1.0, [10.0, 0.0, 0.0], 0.0, 0.0),
+                keyframe(2.0, [10.0, 10.0, 0.0], 0.0, 0.0),
+            ],
+        };
+        let just_before = path.sample(1.0 - 1e-4).position;
+        let at = path.sample(1.0).position;
+        let just_after = path.sample(1.0 + 1e-4).position;
+        assert!((just_before - at).length() < 0.01, "position should be continuous approaching a keyframe");
+        assert!((just_after - at).length() < 0.01, "position should be continuous leaving a keyframe");
+    }
+
+    #[test]
+    fn angle_interpolation_takes_the_short_way_around_a_wrap() {
+        // 350 -> 10 should pass through 0/360, not through 180.
+        let eased = shortest_arc_lerp(350.0, 10.0, 0.5);
+        let normalized = ((eased % 360.0) + 360.0) % 360.0;
+        assert!(!(10.0..=350.0).contains(&normalized), "expected the short way around 0, got {normalized}");
+    }
+
+    #[test]
+    fn angle_interpolation_never_pops_across_a_wrapping_keyframe() {
+        let path = CameraPath {
+            keyframes: vec![
+                keyframe(0.0, [0.0, 0.0, 0.0], 350.0, 0.0),
+                keyframe(1.0, [0.0, 0.0, 0.0], 10.0, 0.0),
+            ],
+        };
+        let mut previous = path.sample(0.0).yaw_radians;
+        for i in 1..=20 {
+            let pose = path.sample(i as f32 / 20.0);
+            let delta = (pose.yaw_radians - previous).abs();
+            assert!(delta < 30f32.to_radians(), "yaw jumped by {delta} radians in one step, expected a smooth wrap");
+            previous = pose.yaw_radians;
+        }
+    }
+
+    #[test]
+    fn duration_spans_the_first_and_last_keyframe() {
+        let path = CameraPath {
+            keyframes: vec![
+                keyframe(1.0, [0.0, 0.0, 0.0], 0.0, 0.0),
+                keyframe(4.5, [0.0, 0.0, 0.0], 0.0, 0.0),
+            ],
+        };
+        assert_eq!(path.duration(), 3.5);
+    }
+}
@@ -0,0 +1,442 @@
+//! Optional `voxel-adventure.toml` for settings that rarely change, with
+//! precedence CLI > config file > built-in defaults; see `--write-default-config`
+//! in `main.rs`.
+//!
+//! Only the settings this codebase actually has a knob for are offered:
+//! window size, fullscreen, render scale, vsync, backend,
+//! background_behavior, mouse_sensitivity, chunk_size, and key_bindings --
+//! the same set `main.rs`'s CLI flags cover, plus `key_bindings`
+//! (config-file only; see below). Streaming radius isn't offered, since
+//! there's no streaming/multi-chunk system -- just the one loaded `Chunk`.
+//! Gamepad deadzone/sensitivity aren't either -- see `engine::gamepad`'s
+//! doc comment for why.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use super::app::AppOptions;
+use super::background::BackgroundBehavior;
+use super::input::InputMap;
+use super::renderer::RendererOptions;
+
+/// Every field optional, so a file only needs to mention what it wants to
+/// override.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Config {
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub fullscreen: Option<bool>,
+    pub render_scale: Option<f32>,
+    pub vsync: Option<bool>,
+    /// One of `vulkan`, `metal`, `dx12`, `gl`, `all`; see `parse_backend`.
+    pub backend: Option<String>,
+    /// One of `pause`, `throttle`, `full`; see `BackgroundBehavior::parse`.
+    pub background_behavior: Option<String>,
+    /// Radians of camera turn per raw pixel of mouse motion while the
+    /// cursor is grabbed; see `mouse_look::MouseLook`.
+    pub mouse_sensitivity: Option<f32>,
+    /// Voxels per side of the loaded chunk; must be a power of two accepted
+    /// by `voxel::chunk::depth_from_size`. See `Renderer::regenerate_chunk`
+    /// for why changing this needs a chunk regeneration, not just a config
+    /// reload.
+    pub chunk_size: Option<u32>,
+    /// `engine::Action::name()` -> key name (e.g. `"KeyA"`), overriding just
+    /// the actions it mentions on top of `InputMap::with_defaults`; see
+    /// `InputMap::from_overrides`. No CLI equivalent -- a flag per
+    /// rebindable action isn't worth it, unlike the scalar settings above.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub key_bindings: BTreeMap<String, String>,
+}
+
+const FIELD_NAMES: &[&str] = &[
+    "width",
+    "height",
+    "fullscreen",
+    "render_scale",
+    "vsync",
+    "backend",
+    "background_behavior",
+    "mouse_sensitivity",
+    "chunk_size",
+    "key_bindings",
+];
+
+impl Config {
+    /// Every field set to the value it'd resolve to with no config file at
+    /// all, for `--write-default-config` to dump.
+    pub fn defaults() -> Self {
+        let renderer_defaults = RendererOptions::default();
+        let app_defaults = AppOptions::default();
+        Self {
+            width: Some(renderer_defaults.width),
+            height: Some(renderer_defaults.height),
+            fullscreen: Some(app_defaults.fullscreen),
+            render_scale: Some(renderer_defaults.render_scale),
+            vsync: Some(renderer_defaults.present_mode == wgpu::PresentMode::AutoVsync),
+            backend: Some("all".to_string()),
+            background_behavior: Some(match app_defaults.background_behavior {
+                BackgroundBehavior::Pause => "pause",
+                BackgroundBehavior::Throttle => "throttle",
+                BackgroundBehavior::Full => "full",
+            }.to_string()),
+            mouse_sensitivity: Some(app_defaults.mouse_sensitivity),
+            chunk_size: Some(1 << renderer_defaults.chunk_depth),
+            // Every action, bound to its own out-of-the-box key -- not
+            // because any of them need overriding, but so a config file
+            // started from this dump already lists every rebindable name
+            // instead of someone having to go read `Action::ALL` to find out
+            // what's available.
+            key_bindings: super::input::Action::ALL
+                .iter()
+                .map(|&action| {
+                    let key_name = super::input::key_name(action.default_key())
+                        .expect("every action's default key has a name");
+                    (action.name().to_string(), key_name.to_string())
+                })
+                .collect(),
+        }
+    }
+
+    pub fn to_toml(&self) -> String {
+        toml::to_string_pretty(self).expect("Config only holds TOML-representable values")
+    }
+}
+
+/// Parses `raw` as a `voxel-adventure.toml`. A key outside `FIELD_NAMES` is
+/// logged as a warning and otherwise ignored, not a parse failure. A known
+/// key with the wrong type is: `toml`'s own error already names the
+/// offending key and its line/column.
+pub fn parse(raw: &str) -> Result<Config, String> {
+    if let Ok(toml::Value::Table(table)) = toml::from_str::<toml::Value>(raw) {
+        for key in table.keys() {
+            if !FIELD_NAMES.contains(&key.as_str()) {
+                log::warn!("voxel-adventure.toml: unknown key {key:?}, ignoring");
+            }
+        }
+    }
+    toml::from_str(raw).map_err(|err| err.to_string())
+}
+
+/// `./voxel-adventure.toml` first, then the platform config dir's copy
+/// (e.g. `~/.config/voxel-adventure/voxel-adventure.toml` on Linux) -- the
+/// first path that actually exists wins.
+pub fn config_paths() -> Vec<PathBuf> {
+    let mut paths = vec![PathBuf::from("voxel-adventure.toml")];
+    if let Some(dirs) = directories::ProjectDirs::from("", "", "voxel-adventure") {
+        paths.push(dirs.config_dir().join("voxel-adventure.toml"));
+    }
+    paths
+}
+
+/// Reads and parses the first existing path from [`config_paths`].
+/// `Ok(None)` if none exist -- a missing file is fine, not an error.
+/// `Err` if one exists but fails to parse, naming the path and the key.
+pub fn load() -> Result<Option<Config>, String> {
+    for path in config_paths() {
+        match std::fs::read_to_string(&path) {
+            Ok(raw) => {
+                return parse(&raw)
+                    .map(Some)
+                    .map_err(|err| format!("{}: {err}", path.display()));
+            }
+            Err(_) => continue,
+        }
+    }
+    Ok(None)
+}
+
+/// Maps a `--backend`/config `backend` value to the `wgpu::Backends` it
+/// names; shared so the CLI flag and the config key accept the same names.
+pub fn parse_backend(raw: &str) -> Result<wgpu::Backends, String> {
+    match raw.to_ascii_lowercase().as_str() {
+        "vulkan" => Ok(wgpu::Backends::VULKAN),
+        "metal" => Ok(wgpu::Backends::METAL),
+        "dx12" => Ok(wgpu::Backends::DX12),
+        "gl" => Ok(wgpu::Backends::GL),
+        "all" => Ok(wgpu::Backends::all()),
+        other => Err(format!(
+            "backend expects one of vulkan, metal, dx12, gl, all, got {other:?}"
+        )),
+    }
+}
+
+/// The same fields as [`Config`], filled in by `main`'s CLI flag parsing;
+/// `None` means "not passed on the command line", so [`resolve`] knows to
+/// fall through to the config file or the built-in default instead of a
+/// baked-in CLI default.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct CliOverrides {
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub fullscreen: Option<bool>,
+    pub render_scale: Option<f32>,
+    pub vsync: Option<bool>,
+    pub backend: Option<String>,
+    pub background_behavior: Option<String>,
+    pub mouse_sensitivity: Option<f32>,
+    pub chunk_size: Option<u32>,
+}
+
+/// Final values `main` hands off to `RendererOptions`/`AppOptions`, after
+/// merging `cli` over `config` over the built-in defaults.
+pub struct ResolvedOptions {
+    pub width: u32,
+    pub height: u32,
+    pub fullscreen: bool,
+    pub render_scale: f32,
+    pub vsync: bool,
+    pub backends: wgpu::Backends,
+    pub background_behavior: BackgroundBehavior,
+    pub mouse_sensitivity: f32,
+    /// Octree depth resolved from `chunk_size` via `voxel::chunk::depth_from_size`.
+    pub chunk_depth: u32,
+    /// `InputMap::with_defaults()` with `config.key_bindings` applied on
+    /// top; doesn't derive `PartialEq` like the rest of this struct's
+    /// fields, since `InputMap` has no reason to (nothing compares two of
+    /// them) and deriving it would mean hand-rolling one on `InputMap` just
+    /// for this struct's sake.
+    pub input_map: InputMap,
+}
+
+/// Merges `cli` (highest precedence) over `config` (loaded from a TOML
+/// file, if any) over the built-in defaults, field by field.
+pub fn resolve(cli: &CliOverrides, config: &Config) -> Result<ResolvedOptions, String> {
+    let renderer_defaults = RendererOptions::default();
+    let app_defaults = AppOptions::default();
+
+    let backend_name = cli.backend.as_deref().or(config.backend.as_deref());
+    let backends = match backend_name {
+        Some(name) => parse_backend(name)?,
+        None => renderer_defaults.backends,
+    };
+    let vsync_default = renderer_defaults.present_mode == wgpu::PresentMode::AutoVsync;
+
+    let background_behavior_name = cli
+        .background_behavior
+        .as_deref()
+        .or(config.background_behavior.as_deref());
+    let background_behavior = match background_behavior_name {
+        Some(name) => BackgroundBehavior::parse(name)?,
+        None => app_defaults.background_behavior,
+    };
+
+    let input_map = InputMap::from_overrides(&config.key_bindings)?;
+
+    let chunk_size = cli.chunk_size.or(config.chunk_size).unwrap_or(1 << renderer_defaults.chunk_depth);
+    let chunk_depth = crate::voxel::chunk::depth_from_size(chunk_size)?;
+
+    Ok(ResolvedOptions {
+        width: cli.width.or(config.width).unwrap_or(renderer_defaults.width),
+        height: cli.height.or(config.height).unwrap_or(renderer_defaults.height),
+        fullscreen: cli.fullscreen.or(config.fullscreen).unwrap_or(app_defaults.fullscreen),
+        render_scale: cli
+            .render_scale
+            .or(config.render_scale)
+            .unwrap_or(renderer_defaults.render_scale),
+        vsync: cli.vsync.or(config.vsync).unwrap_or(vsync_default),
+        backends,
+        background_behavior,
+        mouse_sensitivity: cli
+            .mouse_sensitivity
+            .or(config.mouse_sensitivity)
+            .unwrap_or(app_defaults.mouse_sensitivity),
+        chunk_depth,
+        input_map,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_a_minimal_file_leaving_everything_else_none() {
+        let config = parse("render_scale = 0.5\n").unwrap();
+        assert_eq!(
+            config,
+            Config {
+                render_scale: Some(0.5),
+                ..Config::default()
+            }
+        );
+    }
+
+    #[test]
+    fn parses_every_known_field() {
+        let config = parse(
+            "width = 1920\nheight = 1080\nfullscreen = true\nrender_scale = 2.0\nvsync = false\n\
+             backend = \"vulkan\"\nbackground_behavior = \"pause\"\nmouse_sensitivity = 0.004\n\
+             chunk_size = 64\n",
+        )
+        .unwrap();
+        assert_eq!(
+            config,
+            Config {
+                width: Some(1920),
+                height: Some(1080),
+                fullscreen: Some(true),
+                render_scale: Some(2.0),
+                vsync: Some(false),
+                backend: Some("vulkan".to_string()),
+                background_behavior: Some("pause".to_string()),
+                mouse_sensitivity: Some(0.004),
+                chunk_size: Some(64),
+                key_bindings: BTreeMap::new(),
+            }
+        );
+    }
+
+    #[test]
+    fn parses_a_key_bindings_table() {
+        let config = parse("[key_bindings]\ntoggle_aa_mode = \"KeyQ\"\n").unwrap();
+        assert_eq!(
+            config.key_bindings,
+            BTreeMap::from([("toggle_aa_mode".to_string(), "KeyQ".to_string())])
+        );
+    }
+
+    #[test]
+    fn unknown_keys_are_ignored_rather_than_rejected() {
+        let config = parse("width = 1920\nfavorite_color = \"blue\"\n").unwrap();
+        assert_eq!(config.width, Some(1920));
+    }
+
+    #[test]
+    fn a_field_with_the_wrong_type_is_a_readable_error_naming_the_key() {
+        let err = parse("width = \"not-a-number\"\n").unwrap_err();
+        assert!(err.contains("width"), "error should name the offending key: {err}");
+    }
+
+    #[test]
+    fn resolve_prefers_cli_over_config_over_defaults() {
+        let cli = CliOverrides {
+            width: Some(1920),
+            ..CliOverrides::default()
+        };
+        let config = Config {
+            width: Some(800),
+            height: Some(600),
+            ..Config::default()
+        };
+        let resolved = resolve(&cli, &config).unwrap();
+        assert_eq!(resolved.width, 1920, "CLI should win over the config file");
+        assert_eq!(resolved.height, 600, "config file should win over the default");
+        assert_eq!(resolved.render_scale, RendererOptions::default().render_scale);
+    }
+
+    #[test]
+    fn resolve_rejects_an_unrecognized_backend_name_from_either_source() {
+        let cli = CliOverrides {
+            backend: Some("directx9".to_string()),
+            ..CliOverrides::default()
+        };
+        assert!(resolve(&cli, &Config::default()).is_err());
+
+        let config = Config {
+            backend: Some("directx9".to_string()),
+            ..Config::default()
+        };
+        assert!(resolve(&CliOverrides::default(), &config).is_err());
+    }
+
+    #[test]
+    fn resolve_prefers_cli_over_config_for_mouse_sensitivity() {
+        let cli = CliOverrides {
+            mouse_sensitivity: Some(0.01),
+            ..CliOverrides::default()
+        };
+        let config = Config {
+            mouse_sensitivity: Some(0.02),
+            ..Config::default()
+        };
+        assert_eq!(resolve(&cli, &config).unwrap().mouse_sensitivity, 0.01);
+    }
+
+    #[test]
+    fn resolve_falls_back_to_the_default_mouse_sensitivity() {
+        let resolved = resolve(&CliOverrides::default(), &Config::default()).unwrap();
+        assert_eq!(resolved.mouse_sensitivity, AppOptions::default().mouse_sensitivity);
+    }
+
+    #[test]
+    fn resolve_prefers_cli_over_config_for_chunk_size() {
+        let cli = CliOverrides {
+            chunk_size: Some(128),
+            ..CliOverrides::default()
+        };
+        let config = Config {
+            chunk_size: Some(64),
+            ..Config::default()
+        };
+        assert_eq!(resolve(&cli, &config).unwrap().chunk_depth, 7);
+    }
+
+    #[test]
+    fn resolve_falls_back_to_the_default_chunk_size() {
+        let resolved = resolve(&CliOverrides::default(), &Config::default()).unwrap();
+        assert_eq!(resolved.chunk_depth, RendererOptions::default().chunk_depth);
+    }
+
+    #[test]
+    fn resolve_rejects_a_chunk_size_that_is_not_a_power_of_two() {
+        let cli = CliOverrides {
+            chunk_size: Some(96),
+            ..CliOverrides::default()
+        };
+        assert!(resolve(&cli, &Config::default()).is_err());
+    }
+
+    #[test]
+    fn resolve_prefers_cli_over_config_for_background_behavior() {
+        let cli = CliOverrides {
+            background_behavior: Some("pause".to_string()),
+            ..CliOverrides::default()
+        };
+        let config = Config {
+            background_behavior: Some("full".to_string()),
+            ..Config::default()
+        };
+        assert_eq!(resolve(&cli, &config).unwrap().background_behavior, BackgroundBehavior::Pause);
+    }
+
+    #[test]
+    fn resolve_rejects_an_unrecognized_background_behavior_name() {
+        let cli = CliOverrides {
+            background_behavior: Some("nap".to_string()),
+            ..CliOverrides::default()
+        };
+        assert!(resolve(&cli, &Config::default()).is_err());
+    }
+
+    #[test]
+    fn resolve_applies_key_bindings_on_top_of_the_defaults() {
+        let config = Config {
+            key_bindings: BTreeMap::from([("toggle_aa_mode".to_string(), "KeyQ".to_string())]),
+            ..Config::default()
+        };
+        let resolved = resolve(&CliOverrides::default(), &config).unwrap();
+        assert_eq!(resolved.input_map.binding(crate::engine::Action::ToggleAaMode), winit::keyboard::KeyCode::KeyQ);
+    }
+
+    #[test]
+    fn resolve_rejects_an_unrecognized_key_binding() {
+        let config = Config {
+            key_bindings: BTreeMap::from([("toggle_aa_mode".to_string(), "NotAKey".to_string())]),
+            ..Config::default()
+        };
+        assert!(resolve(&CliOverrides::default(), &config).is_err());
+    }
+
+    #[test]
+    fn defaults_to_toml_round_trips_through_parse() {
+        let defaults = Config::defaults();
+        assert_eq!(parse(&defaults.to_toml()).unwrap(), defaults);
+    }
+
+    #[test]
+    fn config_paths_checks_the_working_directory_first() {
+        assert_eq!(config_paths()[0], PathBuf::from("voxel-adventure.toml"));
+    }
+}
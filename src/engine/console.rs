@@ -0,0 +1,611 @@
+//! Line-oriented developer console, read from the process's stdin rather
+//! than typed into the window -- this codebase has no text-input widget
+//! (the debug overlay is buttons/sliders, see `engine::overlay`), and stdin
+//! is already right there in a terminal the same way `println!` debug
+//! output already is. A background thread blocks on `stdin().lines()` and
+//! forwards each one through an `mpsc` channel, same shape as
+//! `ShaderWatcher`'s file-event thread; `App::redraw` calls [`StdinConsole::poll`]
+//! once per frame, never blocking the render loop on a terminal that may
+//! have nothing typed into it.
+//!
+//! Parsing ([`tokenize`]) and dispatch ([`CommandRegistry`]) don't touch GPU
+//! or window state, so they're plain data and exercised directly in tests.
+//! Side effects run through [`ConsoleContext`], implemented directly on
+//! `Renderer` below, so a handler never depends on `App` and tests can
+//! dispatch against a fake instead of a real GPU-backed `Renderer`.
+//!
+//! `tp`, `regen`, `vsync`, `debugview`, `time`, `daynight`, `flypath`,
+//! `scene`, and `biome` are registered: they're the commands in the
+//! prompt's example list (`seed`, `save`, `screenshot`) plus later
+//! additions that something real in this codebase already does.
+//! `Chunk::filled_test_pattern_with_water` has no seed to set (see
+//! `main.rs`'s `Cli` doc comment), there's no save/serialization system,
+//! and no screenshot capture path -- typing any of those just gets the
+//! same "unknown command" message as a typo would.
+
+use std::collections::BTreeMap;
+use std::io::BufRead;
+use std::sync::mpsc::Receiver;
+
+use super::camera_path;
+use super::renderer::Renderer;
+use super::scene::{self, SceneDescription};
+use crate::voxel::DebugView;
+
+/// What a console command can do to the running app. A trait (rather than
+/// dispatching straight against `Renderer`) so handlers and their tests
+/// don't need a real GPU-backed `Renderer` to run against.
+pub trait ConsoleContext {
+    fn set_vsync(&mut self, enabled: bool);
+    fn regenerate_chunk(&mut self);
+    fn set_debug_view(&mut self, view: DebugView);
+    fn teleport(&mut self, position: glam::Vec3);
+    fn set_time_of_day(&mut self, seconds: f32);
+    fn set_day_night_paused(&mut self, paused: bool);
+    fn play_camera_path(&mut self, path: camera_path::CameraPath);
+    fn load_scene(&mut self, scene: &SceneDescription) -> Result<(), String>;
+}
+
+impl ConsoleContext for Renderer {
+    fn set_vsync(&mut self, enabled: bool) {
+        self.set_present_mode(if enabled {
+            wgpu::PresentMode::AutoVsync
+        } else {
+            wgpu::PresentMode::AutoNoVsync
+        });
+    }
+
+    fn regenerate_chunk(&mut self) {
+        Renderer::regenerate_chunk(self);
+    }
+
+    fn set_debug_view(&mut self, view: DebugView) {
+        Renderer::set_debug_view(self, view);
+    }
+
+    fn teleport(&mut self, position: glam::Vec3) {
+        Renderer::teleport(self, position);
+    }
+
+    fn set_time_of_day(&mut self, seconds: f32) {
+        Renderer::set_time_of_day(self, seconds);
+    }
+
+    fn set_day_night_paused(&mut self, paused: bool) {
+        Renderer::set_day_night_paused(self, paused);
+    }
+
+    fn play_camera_path(&mut self, path: camera_path::CameraPath) {
+        Renderer::play_camera_path(self, path);
+    }
+
+    fn load_scene(&mut self, scene: &SceneDescription) -> Result<(), String> {
+        Renderer::load_scene(self, scene)
+    }
+}
+
+/// A command's implementation: validates `args`' arity/types itself (there's
+/// no shared arity-checking machinery -- with four commands it'd be more
+/// code than the checks it replaces) and returns either a confirmation
+/// message or an error naming what was wrong.
+type Handler = fn(&[String], &mut dyn ConsoleContext) -> Result<String, String>;
+
+/// Splits a console line into arguments on whitespace, honoring `"..."`
+/// quoting so an argument containing spaces survives as one token. An
+/// unterminated quote is an error naming the line, rather than silently
+/// swallowing the rest of it.
+pub fn tokenize(line: &str) -> Result<Vec<String>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = line.chars().peekable();
+    loop {
+        while matches!(chars.peek(), Some(c) if c.is_whitespace()) {
+            chars.next();
+        }
+        if chars.peek().is_none() {
+            break;
+        }
+        let mut token = String::new();
+        if chars.peek() == Some(&'"') {
+            chars.next();
+            loop {
+                match chars.next() {
+                    Some('"') => break,
+                    Some(c) => token.push(c),
+                    None => return Err(format!("unterminated quote in {line:?}")),
+                }
+            }
+        } else {
+            while matches!(chars.peek(), Some(c) if !c.is_whitespace()) {
+                token.push(chars.next().unwrap());
+            }
+        }
+        tokens.push(token);
+    }
+    Ok(tokens)
+}
+
+fn cmd_tp(args: &[String], ctx: &mut dyn ConsoleContext) -> Result<String, String> {
+    let [x, y, z] = args else {
+        return Err("usage: tp <x> <y> <z>".to_string());
+    };
+    let parse_coord = |raw: &str| raw.parse::<f32>().map_err(|_| format!("tp expects numbers, got {raw:?}"));
+    let position = glam::Vec3::new(parse_coord(x)?, parse_coord(y)?, parse_coord(z)?);
+    ctx.teleport(position);
+    Ok(format!("teleported to ({:.1}, {:.1}, {:.1})", position.x, position.y, position.z))
+}
+
+fn cmd_regen(args: &[String], ctx: &mut dyn ConsoleContext) -> Result<String, String> {
+    if !args.is_empty() {
+        return Err("usage: regen (takes no arguments)".to_string());
+    }
+    ctx.regenerate_chunk();
+    Ok("chunk regenerated".to_string())
+}
+
+fn cmd_vsync(args: &[String], ctx: &mut dyn ConsoleContext) -> Result<String, String> {
+    let [value] = args else {
+        return Err("usage: vsync <on|off>".to_string());
+    };
+    match value.as_str() {
+        "on" => {
+            ctx.set_vsync(true);
+            Ok("vsync on".to_string())
+        }
+        "off" => {
+            ctx.set_vsync(false);
+            Ok("vsync off".to_string())
+        }
+        other => Err(format!("vsync expects on or off, got {other:?}")),
+    }
+}
+
+fn cmd_debugview(args: &[String], ctx: &mut dyn ConsoleContext) -> Result<String, String> {
+    let [name] = args else {
+        return Err("usage: debugview <none|normals|depth|steps|octree_level>".to_string());
+    };
+    let view = DebugView::parse(name)?;
+    ctx.set_debug_view(view);
+    Ok(format!("debug view -> {view:?}"))
+}
+
+fn cmd_time(args: &[String], ctx: &mut dyn ConsoleContext) -> Result<String, String> {
+    let [seconds] = args else {
+        return Err("usage: time <seconds since midnight>".to_string());
+    };
+    let seconds: f32 = seconds.parse().map_err(|_| format!("time expects a number, got {seconds:?}"))?;
+    ctx.set_time_of_day(seconds);
+    Ok(format!("time of day -> {seconds:.1}s"))
+}
+
+fn cmd_flypath(args: &[String], ctx: &mut dyn ConsoleContext) -> Result<String, String> {
+    let [path] = args else {
+        return Err("usage: flypath <path.toml>".to_string());
+    };
+    let raw = std::fs::read_to_string(path).map_err(|err| format!("flypath: couldn't read {path:?}: {err}"))?;
+    let camera_path = camera_path::parse(&raw)?;
+    let keyframes = camera_path.keyframes.len();
+    ctx.play_camera_path(camera_path);
+    Ok(format!("flying {path} ({keyframes} keyframes)"))
+}
+
+fn cmd_scene(args: &[String], ctx: &mut dyn ConsoleContext) -> Result<String, String> {
+    let [path] = args else {
+        return Err("usage: scene <scene.toml>".to_string());
+    };
+    let description = scene::load(std::path::Path::new(path)).map_err(|err| format!("scene: {err}"))?;
+    ctx.load_scene(&description).map_err(|err| format!("scene: {err}"))?;
+    Ok(format!("loaded scene {path}"))
+}
+
+/// Reports the [`crate::voxel::Biome`] a world-space column would generate
+/// with, using [`crate::voxel::BiomeMap::default`] -- there's no seed stored
+/// on `Renderer` to look one up from yet (chunk generation still only goes
+/// through `filled_test_pattern_with_water`, see `regenerate_chunk`'s doc
+/// comment), so this reports what a default-seeded world would place there
+/// rather than the currently loaded chunk's own biome.
+fn cmd_biome(args: &[String], _ctx: &mut dyn ConsoleContext) -> Result<String, String> {
+    let [sub, x, z] = args else {
+        return Err("usage: biome at <x> <z>".to_string());
+    };
+    if sub != "at" {
+        return Err(format!("usage: biome at <x> <z> (got {sub:?})"));
+    }
+    let parse_coord = |raw: &str| raw.parse::<f32>().map_err(|_| format!("biome expects numbers, got {raw:?}"));
+    let (x, z) = (parse_coord(x)?, parse_coord(z)?);
+    let biome = crate::voxel::BiomeMap::default().biome_at(x, z);
+    Ok(format!("biome at ({x:.1}, {z:.1}) -> {}", biome.name))
+}
+
+fn cmd_daynight(args: &[String], ctx: &mut dyn ConsoleContext) -> Result<String, String> {
+    let [value] = args else {
+        return Err("usage: daynight <pause|resume>".to_string());
+    };
+    match value.as_str() {
+        "pause" => {
+            ctx.set_day_night_paused(true);
+            Ok("day/night cycle paused".to_string())
+        }
+        "resume" => {
+            ctx.set_day_night_paused(false);
+            Ok("day/night cycle resumed".to_string())
+        }
+        other => Err(format!("daynight expects pause or resume, got {other:?}")),
+    }
+}
+
+/// Maps command names to their [`Handler`]; owned by [`StdinConsole`], but
+/// kept separate so dispatch is testable without also dragging in the
+/// stdin thread.
+pub struct CommandRegistry {
+    handlers: BTreeMap<String, Handler>,
+}
+
+impl CommandRegistry {
+    pub fn new() -> Self {
+        Self { handlers: BTreeMap::new() }
+    }
+
+    /// Registers `name`, overwriting any previous handler under that name.
+    pub fn register(&mut self, name: &str, handler: Handler) {
+        self.handlers.insert(name.to_string(), handler);
+    }
+
+    /// Runs `name` with `args` against `ctx` and returns its message, or an
+    /// "unknown command" message naming what *is* registered if there's no
+    /// handler for `name`.
+    pub fn dispatch(&self, name: &str, args: &[String], ctx: &mut dyn ConsoleContext) -> String {
+        match self.handlers.get(name) {
+            Some(handler) => handler(args, ctx).unwrap_or_else(|err| err),
+            None => {
+                let known = self.handlers.keys().cloned().collect::<Vec<_>>().join(", ");
+                format!("unknown command {name:?} (known commands: {known})")
+            }
+        }
+    }
+}
+
+impl Default for CommandRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Registers `tp`/`regen`/`vsync`/`debugview`/`time`/`daynight`/`flypath`/`scene`/`biome`;
+/// see the module doc comment for why those and not the prompt's full
+/// wishlist.
+fn default_commands() -> CommandRegistry {
+    let mut registry = CommandRegistry::new();
+    registry.register("tp", cmd_tp);
+    registry.register("regen", cmd_regen);
+    registry.register("vsync", cmd_vsync);
+    registry.register("debugview", cmd_debugview);
+    registry.register("time", cmd_time);
+    registry.register("daynight", cmd_daynight);
+    registry.register("flypath", cmd_flypath);
+    registry.register("scene", cmd_scene);
+    registry.register("biome", cmd_biome);
+    registry
+}
+
+/// Reads lines from stdin on a background thread and dispatches each one
+/// against a [`ConsoleContext`] once per frame. Never blocks `App::redraw`:
+/// the read side only drains whatever's already arrived, same as
+/// `ShaderWatcher::poll_changed`.
+pub struct StdinConsole {
+    registry: CommandRegistry,
+    lines: Receiver<String>,
+}
+
+impl StdinConsole {
+    /// Spawns the reader thread and registers the default commands. The
+    /// thread exits on its own once stdin closes (EOF or the terminal going
+    /// away); there's nothing to join on shutdown since `App::run` never
+    /// returns short of the event loop exiting the whole process.
+    pub fn new() -> Self {
+        let (tx, lines) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            let stdin = std::io::stdin();
+            for line in stdin.lock().lines() {
+                match line {
+                    Ok(line) => {
+                        if tx.send(line).is_err() {
+                            break;
+                        }
+                    }
+                    Err(err) => {
+                        log::warn!("console stdin read error: {err}");
+                        break;
+                    }
+                }
+            }
+        });
+        Self { registry: default_commands(), lines }
+    }
+
+    /// Dispatches every line received since the last call against `ctx`,
+    /// printing the echoed line and its output the same way the rest of
+    /// `App`'s debug toggles print theirs. Never blocks.
+    pub fn poll(&self, ctx: &mut dyn ConsoleContext) {
+        while let Ok(line) = self.lines.try_recv() {
+            self.dispatch_line(&line, ctx);
+        }
+    }
+
+    fn dispatch_line(&self, line: &str, ctx: &mut dyn ConsoleContext) {
+        if line.trim().is_empty() {
+            return;
+        }
+        let output = match tokenize(line) {
+            Ok(tokens) => match tokens.split_first() {
+                Some((name, args)) => self.registry.dispatch(name, args, ctx),
+                None => return,
+            },
+            Err(err) => err,
+        };
+        println!("{output}");
+    }
+}
+
+impl Default for StdinConsole {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct FakeContext {
+        vsync: Option<bool>,
+        regenerated: bool,
+        debug_view: Option<DebugView>,
+        teleported_to: Option<glam::Vec3>,
+        time_of_day: Option<f32>,
+        day_night_paused: Option<bool>,
+        played_camera_path: Option<camera_path::CameraPath>,
+        loaded_scene: Option<SceneDescription>,
+    }
+
+    impl ConsoleContext for FakeContext {
+        fn set_vsync(&mut self, enabled: bool) {
+            self.vsync = Some(enabled);
+        }
+        fn regenerate_chunk(&mut self) {
+            self.regenerated = true;
+        }
+        fn set_debug_view(&mut self, view: DebugView) {
+            self.debug_view = Some(view);
+        }
+        fn teleport(&mut self, position: glam::Vec3) {
+            self.teleported_to = Some(position);
+        }
+        fn set_time_of_day(&mut self, seconds: f32) {
+            self.time_of_day = Some(seconds);
+        }
+        fn set_day_night_paused(&mut self, paused: bool) {
+            self.day_night_paused = Some(paused);
+        }
+        fn play_camera_path(&mut self, path: camera_path::CameraPath) {
+            self.played_camera_path = Some(path);
+        }
+        fn load_scene(&mut self, scene: &SceneDescription) -> Result<(), String> {
+            self.loaded_scene = Some(scene.clone());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn tokenize_splits_on_whitespace() {
+        assert_eq!(tokenize("tp 10 40 10").unwrap(), vec!["tp", "10", "40", "10"]);
+    }
+
+    #[test]
+    fn tokenize_keeps_a_quoted_argument_as_one_token() {
+        assert_eq!(tokenize(r#"save "world one""#).unwrap(), vec!["save", "world one"]);
+    }
+
+    #[test]
+    fn tokenize_rejects_an_unterminated_quote() {
+        assert!(tokenize(r#"save "world"#).is_err());
+    }
+
+    #[test]
+    fn tokenize_an_all_whitespace_line_yields_no_tokens() {
+        assert_eq!(tokenize("   ").unwrap(), Vec::<String>::new());
+    }
+
+    #[test]
+    fn tp_requires_exactly_three_arguments() {
+        let mut ctx = FakeContext::default();
+        assert!(cmd_tp(&[], &mut ctx).is_err());
+        assert!(cmd_tp(&["1".to_string(), "2".to_string()], &mut ctx).is_err());
+        assert!(ctx.teleported_to.is_none());
+    }
+
+    #[test]
+    fn tp_rejects_a_non_numeric_coordinate() {
+        let mut ctx = FakeContext::default();
+        let args = vec!["1".to_string(), "sideways".to_string(), "3".to_string()];
+        assert!(cmd_tp(&args, &mut ctx).is_err());
+    }
+
+    #[test]
+    fn vsync_rejects_a_non_boolean_argument() {
+        let mut ctx = FakeContext::default();
+        assert!(cmd_vsync(&["sideways".to_string()], &mut ctx).is_err());
+        assert!(ctx.vsync.is_none());
+    }
+
+    #[test]
+    fn debugview_rejects_an_unknown_view_name() {
+        let mut ctx = FakeContext::default();
+        assert!(cmd_debugview(&["wireframe".to_string()], &mut ctx).is_err());
+    }
+
+    #[test]
+    fn registry_dispatches_a_known_command_and_runs_its_effect() {
+        let mut registry = CommandRegistry::new();
+        registry.register("tp", cmd_tp);
+        let mut ctx = FakeContext::default();
+        let args = vec!["10".to_string(), "40".to_string(), "10".to_string()];
+        let message = registry.dispatch("tp", &args, &mut ctx);
+        assert_eq!(ctx.teleported_to, Some(glam::Vec3::new(10.0, 40.0, 10.0)));
+        assert!(message.contains("teleported"));
+    }
+
+    #[test]
+    fn unknown_command_names_itself_and_what_is_registered() {
+        let mut registry = CommandRegistry::new();
+        registry.register("regen", cmd_regen);
+        let message = registry.dispatch("nope", &[], &mut FakeContext::default());
+        assert!(message.contains("nope"));
+        assert!(message.contains("regen"));
+    }
+
+    #[test]
+    fn default_commands_registers_tp_regen_vsync_and_debugview() {
+        let registry = default_commands();
+        let mut ctx = FakeContext::default();
+        assert!(registry.dispatch("regen", &[], &mut ctx).contains("regenerated"));
+        assert!(!registry.dispatch("nope", &[], &mut ctx).contains("regenerated"));
+    }
+
+    #[test]
+    fn time_sets_the_day_night_clock() {
+        let mut ctx = FakeContext::default();
+        let message = cmd_time(&["3600".to_string()], &mut ctx).unwrap();
+        assert_eq!(ctx.time_of_day, Some(3600.0));
+        assert!(message.contains("3600"));
+    }
+
+    #[test]
+    fn time_rejects_a_non_numeric_argument() {
+        let mut ctx = FakeContext::default();
+        assert!(cmd_time(&["noon".to_string()], &mut ctx).is_err());
+        assert!(ctx.time_of_day.is_none());
+    }
+
+    #[test]
+    fn daynight_pause_and_resume_set_the_paused_flag() {
+        let mut ctx = FakeContext::default();
+        assert!(cmd_daynight(&["pause".to_string()], &mut ctx).is_ok());
+        assert_eq!(ctx.day_night_paused, Some(true));
+        assert!(cmd_daynight(&["resume".to_string()], &mut ctx).is_ok());
+        assert_eq!(ctx.day_night_paused, Some(false));
+    }
+
+    #[test]
+    fn daynight_rejects_an_unknown_argument() {
+        let mut ctx = FakeContext::default();
+        assert!(cmd_daynight(&["stop".to_string()], &mut ctx).is_err());
+        assert!(ctx.day_night_paused.is_none());
+    }
+
+    #[test]
+    fn flypath_requires_exactly_one_argument() {
+        let mut ctx = FakeContext::default();
+        assert!(cmd_flypath(&[], &mut ctx).is_err());
+        assert!(ctx.played_camera_path.is_none());
+    }
+
+    #[test]
+    fn flypath_reports_a_missing_file() {
+        let mut ctx = FakeContext::default();
+        let err = cmd_flypath(&["/no/such/path.toml".to_string()], &mut ctx).unwrap_err();
+        assert!(err.contains("no/such/path.toml"));
+    }
+
+    #[test]
+    fn flypath_loads_and_plays_a_valid_file() {
+        let dir = std::env::temp_dir();
+        let path = dir.join(format!("flypath_test_{:?}.toml", std::thread::current().id()));
+        std::fs::write(
+            &path,
+            r#"
+                [[keyframes]]
+                time = 0.0
+                position = [0.0, 0.0, 0.0]
+                yaw_degrees = 0.0
+                pitch_degrees = 0.0
+
+                [[keyframes]]
+                time = 1.0
+                position = [1.0, 0.0, 0.0]
+                yaw_degrees = 90.0
+                pitch_degrees = 0.0
+            "#,
+        )
+        .unwrap();
+
+        let mut ctx = FakeContext::default();
+        let message = cmd_flypath(&[path.to_string_lossy().to_string()], &mut ctx).unwrap();
+        assert!(message.contains("2 keyframes"));
+        assert_eq!(ctx.played_camera_path.unwrap().keyframes.len(), 2);
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn scene_requires_exactly_one_argument() {
+        let mut ctx = FakeContext::default();
+        assert!(cmd_scene(&[], &mut ctx).is_err());
+        assert!(ctx.loaded_scene.is_none());
+    }
+
+    #[test]
+    fn scene_reports_a_missing_file() {
+        let mut ctx = FakeContext::default();
+        let err = cmd_scene(&["/no/such/scene.toml".to_string()], &mut ctx).unwrap_err();
+        assert!(err.contains("no/such/scene.toml"));
+    }
+
+    #[test]
+    fn scene_loads_a_valid_file() {
+        let dir = std::env::temp_dir();
+        let path = dir.join(format!("scene_test_{:?}.toml", std::thread::current().id()));
+        std::fs::write(
+            &path,
+            r#"
+                [camera]
+                position = [0.0, 20.0, 0.0]
+                yaw_degrees = 0.0
+                pitch_degrees = -45.0
+
+                [chunk]
+                position = [0, 0, 0]
+                generator = { kind = "test_pattern_with_water", water_depth = 4 }
+            "#,
+        )
+        .unwrap();
+
+        let mut ctx = FakeContext::default();
+        let message = cmd_scene(&[path.to_string_lossy().to_string()], &mut ctx).unwrap();
+        assert!(message.contains(&path.to_string_lossy().to_string()));
+        assert!(ctx.loaded_scene.is_some());
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn biome_requires_the_at_subcommand() {
+        let mut ctx = FakeContext::default();
+        let args = vec!["near".to_string(), "10".to_string(), "20".to_string()];
+        assert!(cmd_biome(&args, &mut ctx).is_err());
+    }
+
+    #[test]
+    fn biome_rejects_a_non_numeric_coordinate() {
+        let mut ctx = FakeContext::default();
+        let args = vec!["at".to_string(), "sideways".to_string(), "20".to_string()];
+        assert!(cmd_biome(&args, &mut ctx).is_err());
+    }
+
+    #[test]
+    fn biome_at_reports_the_classified_biome_by_name() {
+        let mut ctx = FakeContext::default();
+        let args = vec!["at".to_string(), "10".to_string(), "20".to_string()];
+        let message = cmd_biome(&args, &mut ctx).unwrap();
+        let expected = crate::voxel::BiomeMap::default().biome_at(10.0, 20.0).name;
+        assert!(message.contains(expected));
+    }
+}
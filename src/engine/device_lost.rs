@@ -0,0 +1,51 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Cross-thread flag set by wgpu's device-lost callback (which can fire from
+/// an arbitrary thread) and polled by `Renderer::render` on the main thread.
+/// Cloning shares the same underlying flag, so a clone can be moved into the
+/// callback closure while the original stays with the `Renderer`.
+#[derive(Clone, Default)]
+pub struct DeviceLostFlag(Arc<AtomicBool>);
+
+impl DeviceLostFlag {
+    pub fn new() -> Self {
+        Self(Arc::new(AtomicBool::new(false)))
+    }
+
+    pub fn mark_lost(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    /// Returns whether the device was marked lost, clearing the flag so a
+    /// later loss (after recreation) can be observed independently.
+    pub fn take_lost(&self) -> bool {
+        self.0.swap(false, Ordering::SeqCst)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn starts_not_lost() {
+        assert!(!DeviceLostFlag::new().take_lost());
+    }
+
+    #[test]
+    fn mark_lost_is_observed_exactly_once() {
+        let flag = DeviceLostFlag::new();
+        flag.mark_lost();
+        assert!(flag.take_lost());
+        assert!(!flag.take_lost());
+    }
+
+    #[test]
+    fn clone_shares_the_underlying_flag() {
+        let flag = DeviceLostFlag::new();
+        let handle = flag.clone();
+        handle.mark_lost();
+        assert!(flag.take_lost());
+    }
+}
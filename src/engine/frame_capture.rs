@@ -0,0 +1,170 @@
+//! Frame capture for making videos without a separate screen recorder: push
+//! rendered frames onto a bounded queue drained by a background writer
+//! thread, so a slow disk stalls a video export instead of the render loop.
+//!
+//! Not wired into `Renderer`/`App` yet:
+//! - There's no per-frame path from a rendered texture into CPU memory in
+//!   the render loop -- `RenderTexture::read_to_cpu` exists but nothing
+//!   calls it every frame today; `VoxelRendererPass::poll_pick_result` is
+//!   this crate's only per-frame GPU->CPU readback, and it's sized for one
+//!   pixel, not a whole frame.
+//! - There's no camera bookmark / fly-to feature yet to combine this with
+//!   for repeatable shots.
+//! - No image-encoding crate is a dependency of this project, so
+//!   [`FrameCaptureWriter`] writes raw RGBA bytes rather than PNGs; adding
+//!   real encoding is a dependency decision, not something to sneak into a
+//!   capture-plumbing change.
+//! - `StdinConsole`/`engine::overlay` don't expose start/stop commands or a
+//!   dropped-frame readout yet.
+//!
+//! What's here is the part that's a well-defined, testable unit on its own:
+//! the bounded-queue backpressure behavior (drop rather than block once the
+//! writer falls behind) and the capture filename format.
+#![allow(dead_code)]
+
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::mpsc::{Receiver, SyncSender};
+use std::sync::Arc;
+
+/// One frame handed off for writing: raw RGBA8 bytes, `width * height * 4`
+/// long, named by `frame_index` on disk.
+pub struct CapturedFrame {
+    pub frame_index: u32,
+    pub width: u32,
+    pub height: u32,
+    pub rgba: Vec<u8>,
+}
+
+/// Filename a captured frame is written under, zero-padded so a directory
+/// listing sorts in capture order.
+pub fn capture_filename(frame_index: u32) -> String {
+    format!("frame_{frame_index:06}.rgba")
+}
+
+/// Whether `frame_index` should be captured given a `start_capture(dir,
+/// every_n_frames)` request: `every_n_frames == 0` disables capture
+/// entirely (mirrors how `AoSettings::samples == 0` disables AO), otherwise
+/// every `every_n_frames`th frame starting at `0`.
+pub fn should_capture(frame_index: u64, every_n_frames: u32) -> bool {
+    every_n_frames != 0 && frame_index.is_multiple_of(every_n_frames as u64)
+}
+
+/// Hands frames to a background writer thread through a bounded channel.
+/// [`push`](Self::push) never blocks: once the queue is full the frame is
+/// dropped and counted in [`dropped_frames`](Self::dropped_frames) instead
+/// of stalling the render loop waiting for disk I/O to catch up.
+pub struct FrameCaptureWriter {
+    sender: SyncSender<CapturedFrame>,
+    dropped: Arc<AtomicU32>,
+}
+
+impl FrameCaptureWriter {
+    fn new(sender: SyncSender<CapturedFrame>, dropped: Arc<AtomicU32>) -> Self {
+        Self { sender, dropped }
+    }
+
+    /// Spawns the writer thread and returns a handle to push frames onto
+    /// its queue. `queue_capacity` bounds how many frames may be in flight
+    /// before `push` starts dropping.
+    pub fn start(dir: PathBuf, queue_capacity: usize) -> Self {
+        let (sender, receiver) = std::sync::mpsc::sync_channel(queue_capacity);
+        let dropped = Arc::new(AtomicU32::new(0));
+        std::thread::spawn(move || Self::run(dir, receiver));
+        Self::new(sender, dropped)
+    }
+
+    fn run(dir: PathBuf, receiver: Receiver<CapturedFrame>) {
+        for frame in receiver {
+            let path = dir.join(capture_filename(frame.frame_index));
+            if let Err(err) = std::fs::write(&path, &frame.rgba) {
+                log::warn!("frame capture write failed for {path:?}: {err}");
+            }
+        }
+    }
+
+    /// Queues `frame` for writing, or drops it and increments
+    /// [`dropped_frames`](Self::dropped_frames) if the writer hasn't kept
+    /// up.
+    pub fn push(&self, frame: CapturedFrame) {
+        if self.sender.try_send(frame).is_err() {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Frames dropped so far because the queue was full, for the
+    /// overlay/console to surface.
+    pub fn dropped_frames(&self) -> u32 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(frame_index: u32) -> CapturedFrame {
+        CapturedFrame { frame_index, width: 1, height: 1, rgba: vec![0, 0, 0, 255] }
+    }
+
+    #[test]
+    fn capture_filename_is_zero_padded_and_sorts_in_order() {
+        assert_eq!(capture_filename(0), "frame_000000.rgba");
+        assert_eq!(capture_filename(42), "frame_000042.rgba");
+        assert!(capture_filename(9) < capture_filename(10));
+    }
+
+    #[test]
+    fn should_capture_zero_every_n_frames_never_captures() {
+        assert!(!should_capture(0, 0));
+        assert!(!should_capture(100, 0));
+    }
+
+    #[test]
+    fn should_capture_one_every_n_frames_captures_every_frame() {
+        assert!(should_capture(0, 1));
+        assert!(should_capture(1, 1));
+        assert!(should_capture(2, 1));
+    }
+
+    #[test]
+    fn should_capture_every_n_frames_skips_the_frames_between() {
+        assert!(should_capture(0, 3));
+        assert!(!should_capture(1, 3));
+        assert!(!should_capture(2, 3));
+        assert!(should_capture(3, 3));
+    }
+
+    #[test]
+    fn pushing_within_capacity_never_drops() {
+        let (sender, _receiver) = std::sync::mpsc::sync_channel(2);
+        let writer = FrameCaptureWriter::new(sender, Arc::new(AtomicU32::new(0)));
+        writer.push(frame(0));
+        writer.push(frame(1));
+        assert_eq!(writer.dropped_frames(), 0);
+    }
+
+    #[test]
+    fn pushing_past_capacity_drops_and_counts_instead_of_blocking() {
+        // No receiver draining, so once the channel's capacity is full every
+        // further push has nowhere to go.
+        let (sender, _receiver) = std::sync::mpsc::sync_channel(1);
+        let writer = FrameCaptureWriter::new(sender, Arc::new(AtomicU32::new(0)));
+        writer.push(frame(0));
+        writer.push(frame(1));
+        writer.push(frame(2));
+        assert_eq!(writer.dropped_frames(), 2);
+    }
+
+    #[test]
+    fn draining_the_receiver_frees_capacity_for_further_pushes() {
+        let (sender, receiver) = std::sync::mpsc::sync_channel(1);
+        let writer = FrameCaptureWriter::new(sender, Arc::new(AtomicU32::new(0)));
+        writer.push(frame(0));
+        writer.push(frame(1));
+        assert_eq!(writer.dropped_frames(), 1);
+        receiver.recv().expect("first frame should be queued");
+        writer.push(frame(2));
+        assert_eq!(writer.dropped_frames(), 1, "draining a slot should let the next push succeed");
+    }
+}
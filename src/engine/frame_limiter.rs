@@ -0,0 +1,144 @@
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How far ahead of the deadline to stop sleeping and spin instead;
+/// `thread::sleep` routinely overshoots by more than this on a loaded
+/// system, but spinning the last stretch keeps the wake-up accurate.
+const SPIN_WINDOW: Duration = Duration::from_millis(1);
+
+/// Paces frames against an absolute deadline instead of sleeping a fixed
+/// duration each frame, so per-frame scheduling overhead can't accumulate
+/// into drift over a long session.
+///
+/// Scheduling (pure, testable) is split from waiting (`wait_until`, which
+/// actually sleeps) so the deadline math can be unit-tested without a real
+/// clock or real sleeps.
+pub struct FrameLimiter {
+    frame_duration: Option<Duration>,
+    next_deadline: Option<Instant>,
+}
+
+impl FrameLimiter {
+    pub fn new(max_fps: Option<u32>) -> Self {
+        Self {
+            frame_duration: max_fps.map(Self::duration_for),
+            next_deadline: None,
+        }
+    }
+
+    pub fn set_max_fps(&mut self, max_fps: Option<u32>) {
+        self.frame_duration = max_fps.map(Self::duration_for);
+        self.next_deadline = None;
+    }
+
+    fn duration_for(fps: u32) -> Duration {
+        Duration::from_secs_f64(1.0 / fps.max(1) as f64)
+    }
+
+    /// Computes the deadline for the upcoming frame given the current time,
+    /// and arms the deadline for the frame after that. Returns the deadline
+    /// to wait for and whether the cap is actually the limiting factor
+    /// (`now` is still ahead of it) -- if a slower vsync interval already put
+    /// us past the deadline, the cap isn't what's pacing this frame.
+    pub fn schedule(&mut self, now: Instant) -> (Instant, bool) {
+        let Some(frame_duration) = self.frame_duration else {
+            return (now, false);
+        };
+
+        let deadline = self.next_deadline.unwrap_or(now);
+        let limited = now < deadline;
+        self.next_deadline = Some(deadline.max(now) + frame_duration);
+        (deadline, limited)
+    }
+}
+
+/// Blocks until `deadline`: sleeps for all but the last [`SPIN_WINDOW`], then
+/// spin-waits the remainder for accuracy `thread::sleep` alone can't give.
+pub fn wait_until(deadline: Instant) {
+    let now = Instant::now();
+    if now >= deadline {
+        return;
+    }
+
+    let remaining = deadline - now;
+    if remaining > SPIN_WINDOW {
+        thread::sleep(remaining - SPIN_WINDOW);
+    }
+    while Instant::now() < deadline {
+        std::hint::spin_loop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn uncapped_never_limits() {
+        let mut limiter = FrameLimiter::new(None);
+        let now = Instant::now();
+        assert_eq!(limiter.schedule(now), (now, false));
+        assert_eq!(limiter.schedule(now + Duration::from_secs(1)), (now + Duration::from_secs(1), false));
+    }
+
+    #[test]
+    fn first_frame_is_never_limited() {
+        let mut limiter = FrameLimiter::new(Some(60));
+        let now = Instant::now();
+        let (deadline, limited) = limiter.schedule(now);
+        assert_eq!(deadline, now);
+        assert!(!limited);
+    }
+
+    #[test]
+    fn back_to_back_frames_are_capped_to_the_deadline() {
+        let mut limiter = FrameLimiter::new(Some(60));
+        let start = Instant::now();
+        let (_, _) = limiter.schedule(start);
+
+        // Simulate the next frame arriving instantly, well before the cap's
+        // deadline would allow.
+        let (deadline, limited) = limiter.schedule(start);
+        assert!(limited);
+        assert_eq!(deadline, start + FrameLimiter::duration_for(60));
+    }
+
+    #[test]
+    fn a_frame_slower_than_the_cap_is_not_limited() {
+        let mut limiter = FrameLimiter::new(Some(30));
+        let start = Instant::now();
+        limiter.schedule(start);
+
+        // A vsync interval (or slow frame) that already exceeds the cap's
+        // period means the cap isn't what paced this frame.
+        let later = start + Duration::from_secs(1);
+        let (_, limited) = limiter.schedule(later);
+        assert!(!limited);
+    }
+
+    #[test]
+    fn deadlines_do_not_drift_across_many_frames() {
+        let mut limiter = FrameLimiter::new(Some(60));
+        let start = Instant::now();
+        let step = FrameLimiter::duration_for(60);
+
+        let mut now = start;
+        for i in 0..10 {
+            let (deadline, _) = limiter.schedule(now);
+            now = deadline;
+            assert_eq!(deadline, start + step * i);
+        }
+    }
+
+    #[test]
+    fn set_max_fps_rearms_the_deadline() {
+        let mut limiter = FrameLimiter::new(Some(30));
+        let now = Instant::now();
+        limiter.schedule(now);
+
+        limiter.set_max_fps(Some(60));
+        let (deadline, limited) = limiter.schedule(now);
+        assert_eq!(deadline, now);
+        assert!(!limited);
+    }
+}
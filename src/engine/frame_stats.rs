@@ -0,0 +1,125 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// A rolling one-second FPS report, produced by [`FrameStats::record_frame`]
+/// once per reporting window.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FrameReport {
+    pub fps: f64,
+    /// Whether every frame in this window was paced by the `max_fps` cap
+    /// rather than by vsync or render cost.
+    pub cap_limited: bool,
+    /// Mean per-scope GPU time in milliseconds over the window, from
+    /// `Renderer::gpu_timings`. Empty if the adapter has no timestamp query
+    /// support.
+    pub gpu_times_ms: Vec<(String, f32)>,
+    /// `(visible, total)` tile counts from the most recent `CullPass::poll_stats`,
+    /// for a camera staring at empty sky to read as a much smaller `visible`
+    /// than one staring at the chunk. Unlike `gpu_times_ms`, this is the
+    /// latest sample rather than an average over the window -- the count
+    /// doesn't accumulate meaningfully across frames the way a duration
+    /// does. `None` when `RendererOptions::gpu_culling_enabled` is off.
+    pub culled_tiles: Option<(u32, u32)>,
+    /// `(visible, total)` chunk counts from a HiZ occlusion pass, same
+    /// latest-sample semantics as `culled_tiles`. Always `None` today --
+    /// this renderer loads exactly one chunk, and there's no HiZ pass built
+    /// yet to populate it; see `voxel::passes::hiz`'s module doc comment for
+    /// what's missing to wire one in.
+    pub chunks_culled: Option<(u32, u32)>,
+    /// Mean bytes written per frame into `Renderer::render`'s upload
+    /// encoder, from `Renderer::upload_stats`.
+    pub mean_upload_bytes: f64,
+    /// Mean CPU time in milliseconds spent recording that frame's upload
+    /// encoder, from `Renderer::upload_stats`.
+    pub mean_upload_time_ms: f32,
+}
+
+/// Tracks achieved frame rate over rolling one-second windows, and whether
+/// the `max_fps` cap (see [`super::frame_limiter::FrameLimiter`]) was the
+/// limiting factor.
+pub struct FrameStats {
+    window_start: Instant,
+    frame_count: u32,
+    cap_limited_frames: u32,
+    gpu_time_totals_ms: HashMap<String, f32>,
+    culled_tiles: Option<(u32, u32)>,
+    chunks_culled: Option<(u32, u32)>,
+    upload_bytes_total: u64,
+    upload_time_total_ms: f32,
+}
+
+impl Default for FrameStats {
+    fn default() -> Self {
+        Self {
+            window_start: Instant::now(),
+            frame_count: 0,
+            cap_limited_frames: 0,
+            gpu_time_totals_ms: HashMap::new(),
+            culled_tiles: None,
+            chunks_culled: None,
+            upload_bytes_total: 0,
+            upload_time_total_ms: 0.0,
+        }
+    }
+}
+
+impl FrameStats {
+    /// Records one rendered frame. Returns a report once a full second has
+    /// elapsed since the last one, otherwise `None`. `culled_tiles` is
+    /// `Renderer`'s latest `CullPass::poll_stats` reading (`None` when GPU
+    /// culling is disabled), overwritten every call rather than accumulated
+    /// -- the report just carries through whatever the last frame in the
+    /// window reported. `upload_stats` is `Renderer::upload_stats`'s
+    /// `(bytes, cpu_ms)` for this frame.
+    pub fn record_frame(
+        &mut self,
+        cap_limited: bool,
+        gpu_times_ms: &[(String, f32)],
+        culled_tiles: Option<(u32, u32)>,
+        chunks_culled: Option<(u32, u32)>,
+        upload_stats: (u64, f32),
+    ) -> Option<FrameReport> {
+        self.frame_count += 1;
+        if cap_limited {
+            self.cap_limited_frames += 1;
+        }
+        for (scope, ms) in gpu_times_ms {
+            *self.gpu_time_totals_ms.entry(scope.clone()).or_insert(0.0) += ms;
+        }
+        if culled_tiles.is_some() {
+            self.culled_tiles = culled_tiles;
+        }
+        if chunks_culled.is_some() {
+            self.chunks_culled = chunks_culled;
+        }
+        let (upload_bytes, upload_time_ms) = upload_stats;
+        self.upload_bytes_total += upload_bytes;
+        self.upload_time_total_ms += upload_time_ms;
+
+        let elapsed = self.window_start.elapsed();
+        if elapsed < Duration::from_secs(1) {
+            return None;
+        }
+
+        let report = FrameReport {
+            fps: self.frame_count as f64 / elapsed.as_secs_f64(),
+            cap_limited: self.cap_limited_frames == self.frame_count,
+            gpu_times_ms: self
+                .gpu_time_totals_ms
+                .iter()
+                .map(|(scope, total_ms)| (scope.clone(), total_ms / self.frame_count as f32))
+                .collect(),
+            culled_tiles: self.culled_tiles,
+            chunks_culled: self.chunks_culled,
+            mean_upload_bytes: self.upload_bytes_total as f64 / self.frame_count as f64,
+            mean_upload_time_ms: self.upload_time_total_ms / self.frame_count as f32,
+        };
+        self.frame_count = 0;
+        self.cap_limited_frames = 0;
+        self.gpu_time_totals_ms.clear();
+        self.upload_bytes_total = 0;
+        self.upload_time_total_ms = 0.0;
+        self.window_start = Instant::now();
+        Some(report)
+    }
+}
@@ -0,0 +1,214 @@
+//! Windowed/borderless/exclusive fullscreen state machine, plus the window
+//! geometry bookkeeping needed to restore it on the way back out. The actual
+//! `winit::window::Fullscreen` construction and monitor/video-mode
+//! enumeration stay in `app.rs`, since they need a real `Window`; this module
+//! holds the parts of the logic that don't.
+
+/// A `--fullscreen-exclusive WxH@Hz` request, or a monitor's own video mode
+/// reduced to the numbers [`closest_mode_index`] compares on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VideoModeSpec {
+    pub width: u32,
+    pub height: u32,
+    /// Millihertz, matching `winit::monitor::VideoMode::refresh_rate_millihertz`.
+    pub refresh_rate_mhz: u32,
+}
+
+impl VideoModeSpec {
+    /// Parses `--fullscreen-exclusive`'s `WxH@Hz` syntax, e.g. `1920x1080@60`.
+    pub fn parse(raw: &str) -> Result<Self, String> {
+        let invalid = || format!("--fullscreen-exclusive expects WxH@Hz, got {raw:?}");
+        let (resolution, hz) = raw.split_once('@').ok_or_else(invalid)?;
+        let (width, height) = resolution.split_once('x').ok_or_else(invalid)?;
+        Ok(Self {
+            width: width.parse().map_err(|_| invalid())?,
+            height: height.parse().map_err(|_| invalid())?,
+            refresh_rate_mhz: hz.parse::<u32>().map_err(|_| invalid())? * 1000,
+        })
+    }
+
+    /// Squared distance from `self` to `candidate` in (width, height, Hz)
+    /// space; smaller is a closer match. `refresh_rate_mhz` is divided back
+    /// down to whole Hz first so it doesn't dominate the resolution terms.
+    fn distance_squared(&self, candidate: &VideoModeSpec) -> i64 {
+        let dw = self.width as i64 - candidate.width as i64;
+        let dh = self.height as i64 - candidate.height as i64;
+        let dhz = (self.refresh_rate_mhz as i64 - candidate.refresh_rate_mhz as i64) / 1000;
+        dw * dw + dh * dh + dhz * dhz
+    }
+}
+
+/// Index into `candidates` of the entry closest to `requested`, or `None` if
+/// `candidates` is empty. `app.rs` uses this to turn a monitor's
+/// `video_modes()` into the one `--fullscreen-exclusive` asked for.
+pub fn closest_mode_index(requested: VideoModeSpec, candidates: &[VideoModeSpec]) -> Option<usize> {
+    candidates
+        .iter()
+        .enumerate()
+        .min_by_key(|(_, candidate)| requested.distance_squared(candidate))
+        .map(|(index, _)| index)
+}
+
+/// Windowed size/position remembered across a fullscreen transition, so
+/// leaving fullscreen can put the window back where it was.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WindowGeometry {
+    pub width: u32,
+    pub height: u32,
+    pub x: i32,
+    pub y: i32,
+}
+
+/// Which of the three fullscreen modes the window is currently in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FullscreenMode {
+    Windowed,
+    Borderless,
+    Exclusive(VideoModeSpec),
+}
+
+/// Tracks the current [`FullscreenMode`] and the windowed geometry to
+/// restore when leaving it. `App` owns one of these; it drives the real
+/// `window.set_fullscreen` calls and monitor lookups itself.
+pub struct FullscreenState {
+    mode: FullscreenMode,
+    windowed_geometry: Option<WindowGeometry>,
+}
+
+impl FullscreenState {
+    pub fn new(mode: FullscreenMode) -> Self {
+        Self {
+            mode,
+            windowed_geometry: None,
+        }
+    }
+
+    pub fn mode(&self) -> FullscreenMode {
+        self.mode
+    }
+
+    pub fn is_windowed(&self) -> bool {
+        self.mode == FullscreenMode::Windowed
+    }
+
+    /// Overwrites the current mode directly, e.g. falling back to windowed
+    /// when `--fullscreen-exclusive` didn't match any video mode.
+    pub fn set_mode(&mut self, mode: FullscreenMode) {
+        self.mode = mode;
+    }
+
+    /// Remembers `geometry` as what to restore to on the next exit back to
+    /// windowed. A no-op if already in a fullscreen mode, so a geometry
+    /// captured right before entering fullscreen isn't overwritten by a
+    /// stale one captured later (e.g. the monitor's geometry while borderless).
+    pub fn remember_windowed_geometry(&mut self, geometry: WindowGeometry) {
+        if self.is_windowed() {
+            self.windowed_geometry = Some(geometry);
+        }
+    }
+
+    /// The geometry to restore when leaving fullscreen, if one was ever
+    /// remembered.
+    pub fn windowed_geometry(&self) -> Option<WindowGeometry> {
+        self.windowed_geometry
+    }
+
+    /// F11: windowed <-> borderless. Exclusive fullscreen (only reachable via
+    /// `--fullscreen-exclusive`) also exits to windowed rather than toggling
+    /// into borderless, since there's no keybinding to pick a video mode.
+    pub fn toggle_borderless(&mut self) {
+        self.mode = match self.mode {
+            FullscreenMode::Windowed => FullscreenMode::Borderless,
+            FullscreenMode::Borderless | FullscreenMode::Exclusive(_) => FullscreenMode::Windowed,
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec(width: u32, height: u32, hz: u32) -> VideoModeSpec {
+        VideoModeSpec {
+            width,
+            height,
+            refresh_rate_mhz: hz * 1000,
+        }
+    }
+
+    #[test]
+    fn parses_a_well_formed_spec() {
+        assert_eq!(VideoModeSpec::parse("1920x1080@60").unwrap(), spec(1920, 1080, 60));
+    }
+
+    #[test]
+    fn rejects_a_spec_missing_the_refresh_rate() {
+        assert!(VideoModeSpec::parse("1920x1080").is_err());
+    }
+
+    #[test]
+    fn rejects_a_spec_with_a_non_numeric_field() {
+        assert!(VideoModeSpec::parse("1920xabc@60").is_err());
+    }
+
+    #[test]
+    fn closest_mode_index_prefers_an_exact_match() {
+        let candidates = [spec(1280, 720, 60), spec(1920, 1080, 60), spec(3840, 2160, 60)];
+        assert_eq!(closest_mode_index(spec(1920, 1080, 60), &candidates), Some(1));
+    }
+
+    #[test]
+    fn closest_mode_index_falls_back_to_the_nearest_resolution() {
+        let candidates = [spec(1280, 720, 60), spec(1920, 1080, 144)];
+        assert_eq!(closest_mode_index(spec(1920, 1080, 60), &candidates), Some(1));
+    }
+
+    #[test]
+    fn closest_mode_index_is_none_for_an_empty_monitor() {
+        assert_eq!(closest_mode_index(spec(1920, 1080, 60), &[]), None);
+    }
+
+    #[test]
+    fn toggle_borderless_enters_and_exits() {
+        let mut state = FullscreenState::new(FullscreenMode::Windowed);
+        state.toggle_borderless();
+        assert_eq!(state.mode(), FullscreenMode::Borderless);
+        state.toggle_borderless();
+        assert_eq!(state.mode(), FullscreenMode::Windowed);
+    }
+
+    #[test]
+    fn toggle_borderless_from_exclusive_exits_to_windowed() {
+        let mut state = FullscreenState::new(FullscreenMode::Exclusive(spec(1920, 1080, 60)));
+        state.toggle_borderless();
+        assert_eq!(state.mode(), FullscreenMode::Windowed);
+    }
+
+    #[test]
+    fn remembers_geometry_only_while_windowed() {
+        let mut state = FullscreenState::new(FullscreenMode::Windowed);
+        let geometry = WindowGeometry {
+            width: 1280,
+            height: 720,
+            x: 10,
+            y: 20,
+        };
+        state.remember_windowed_geometry(geometry);
+        state.toggle_borderless();
+
+        // Entering fullscreen shouldn't clobber the remembered geometry with
+        // whatever the fullscreen geometry happens to be.
+        state.remember_windowed_geometry(WindowGeometry {
+            width: 1920,
+            height: 1080,
+            x: 0,
+            y: 0,
+        });
+        assert_eq!(state.windowed_geometry(), Some(geometry));
+    }
+
+    #[test]
+    fn no_geometry_remembered_until_a_transition_happens() {
+        assert_eq!(FullscreenState::new(FullscreenMode::Windowed).windowed_geometry(), None);
+    }
+}
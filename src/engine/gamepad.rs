@@ -0,0 +1,167 @@
+//! Optional gamepad polling, built only with the `gamepad` feature. See
+//! [`GamepadInput`].
+//!
+//! Sticks/triggers come out as a flat [`GamepadAxes`] of analog values,
+//! not merged into `engine::input::Action`/`InputMap`. That layer is a
+//! digital hotkey table polled once per frame by `App::dispatch_actions`
+//! (pressed or not pressed), and there's no camera movement/look system
+//! for stick axes to actually drive yet -- `Camera`'s own doc comment
+//! says movement/look input "lands in later commits", and
+//! `Renderer::step_simulation` still says "camera movement ... land here
+//! as those systems are added". Bolting analog values onto `Action` now
+//! would mean designing that merge against a movement system that
+//! doesn't exist; `GamepadAxes` stays standalone until one does.
+//!
+//! No config-file or CLI surface either, for the same reason
+//! `engine::Config`'s doc comment already gives for mouse sensitivity:
+//! there's nothing downstream yet for a deadzone/sensitivity knob to
+//! tune.
+
+use gilrs::{Axis, Button, Gilrs};
+
+/// Deadzone/sensitivity applied to every stick and trigger read through a
+/// [`GamepadInput`]. Not per-gamepad -- whichever controller is active
+/// gets the same curve.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GamepadConfig {
+    /// Raw magnitude at or below this is reported as `0.0`, so stick
+    /// drift/noise around center doesn't register as input. `0.0..=1.0`.
+    pub deadzone: f32,
+    /// Multiplies the deadzone-adjusted value. `1.0` leaves axes at the
+    /// `-1.0..=1.0` range gilrs reports and triggers at `0.0..=1.0`.
+    pub sensitivity: f32,
+}
+
+impl Default for GamepadConfig {
+    fn default() -> Self {
+        Self {
+            deadzone: 0.15,
+            sensitivity: 1.0,
+        }
+    }
+}
+
+/// Rescales `raw` so magnitudes inside `deadzone` report `0.0` and the
+/// response ramps linearly back up to `1.0` at full stick extent, instead
+/// of jumping straight from `0.0` to whatever `deadzone` cuts off.
+/// Per-axis, not per-stick, so a stick held at a diagonal doesn't get a
+/// smaller effective deadzone than one held straight.
+fn apply_deadzone(raw: f32, deadzone: f32) -> f32 {
+    let magnitude = raw.abs();
+    if deadzone >= 1.0 || magnitude <= deadzone {
+        return 0.0;
+    }
+    let rescaled = ((magnitude - deadzone) / (1.0 - deadzone)).clamp(0.0, 1.0);
+    rescaled * raw.signum()
+}
+
+/// One frame's worth of merged analog gamepad input. Buttons contribute
+/// `0.0`/`1.0` the same as an axis so triggers and sticks share a type.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct GamepadAxes {
+    pub move_x: f32,
+    pub move_y: f32,
+    pub look_x: f32,
+    pub look_y: f32,
+    pub up_down: f32,
+}
+
+/// Wraps `gilrs::Gilrs`. Hot-plugging is gilrs' own job: `poll` drains
+/// [`Gilrs::next_event`] for `Connected`/`Disconnected` before reading
+/// sticks, so a controller plugged in mid-session is picked up on the
+/// very next `poll` without `GamepadInput` re-enumerating anything
+/// itself.
+pub struct GamepadInput {
+    gilrs: Gilrs,
+    config: GamepadConfig,
+    active: Option<gilrs::GamepadId>,
+}
+
+impl GamepadInput {
+    pub fn new(config: GamepadConfig) -> Result<Self, gilrs::Error> {
+        Ok(Self {
+            gilrs: Gilrs::new()?,
+            config,
+            active: None,
+        })
+    }
+
+    /// Call once per `Renderer::update(dt)`. All-zero axes if no gamepad
+    /// is connected.
+    pub fn poll(&mut self) -> GamepadAxes {
+        while let Some(event) = self.gilrs.next_event() {
+            match event.event {
+                gilrs::EventType::Connected => self.active = Some(event.id),
+                gilrs::EventType::Disconnected if self.active == Some(event.id) => self.active = None,
+                _ => {}
+            }
+        }
+
+        let Some(id) = self.active.or_else(|| self.gilrs.gamepads().next().map(|(id, _)| id)) else {
+            return GamepadAxes::default();
+        };
+        self.active = Some(id);
+        let gamepad = self.gilrs.gamepad(id);
+
+        let axis = |axis| apply_deadzone(gamepad.value(axis), self.config.deadzone) * self.config.sensitivity;
+        let trigger = |button| gamepad.button_data(button).map(|data| data.value()).unwrap_or(0.0);
+
+        GamepadAxes {
+            move_x: axis(Axis::LeftStickX),
+            move_y: axis(Axis::LeftStickY),
+            look_x: axis(Axis::RightStickX),
+            look_y: axis(Axis::RightStickY),
+            up_down: trigger(Button::RightTrigger2) - trigger(Button::LeftTrigger2),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn apply_deadzone_zeroes_magnitudes_at_or_below_the_deadzone() {
+        assert_eq!(apply_deadzone(0.1, 0.15), 0.0);
+        assert_eq!(apply_deadzone(-0.1, 0.15), 0.0);
+        assert_eq!(apply_deadzone(0.15, 0.15), 0.0);
+    }
+
+    #[test]
+    fn apply_deadzone_rescales_the_remaining_range_to_full_scale() {
+        assert_eq!(apply_deadzone(1.0, 0.15), 1.0);
+        assert!((apply_deadzone(0.575, 0.15) - 0.5).abs() < 1e-5);
+    }
+
+    #[test]
+    fn apply_deadzone_preserves_sign() {
+        assert!(apply_deadzone(-1.0, 0.15) < 0.0);
+        assert!(apply_deadzone(-0.575, 0.15) < 0.0);
+    }
+
+    #[test]
+    fn apply_deadzone_with_a_1_0_deadzone_always_reports_zero() {
+        assert_eq!(apply_deadzone(1.0, 1.0), 0.0);
+    }
+
+    #[test]
+    fn gamepad_axes_default_is_all_zero() {
+        assert_eq!(
+            GamepadAxes::default(),
+            GamepadAxes {
+                move_x: 0.0,
+                move_y: 0.0,
+                look_x: 0.0,
+                look_y: 0.0,
+                up_down: 0.0,
+            }
+        );
+    }
+
+    #[test]
+    fn gamepad_config_default_has_a_small_nonzero_deadzone() {
+        let config = GamepadConfig::default();
+        assert!(config.deadzone > 0.0 && config.deadzone < 1.0);
+        assert_eq!(config.sensitivity, 1.0);
+    }
+}
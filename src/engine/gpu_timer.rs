@@ -0,0 +1,163 @@
+/// Per-pass GPU timings via timestamp queries. Each named scope (e.g.
+/// `"voxel_compute"`, `"present_blit"`) owns a begin/end pair of entries in a
+/// single `QuerySet`; results are read back one frame behind so the readback
+/// map/wait doesn't stall on the very GPU work it's timing.
+pub struct GpuTimer {
+    query_set: wgpu::QuerySet,
+    resolve_buffer: wgpu::Buffer,
+    readback_buffers: [wgpu::Buffer; 2],
+    scopes: Vec<String>,
+    /// Which `readback_buffers` slot `resolve` writes into this frame; the
+    /// other slot holds last frame's (by-now-complete) data for `read_back`.
+    write_slot: usize,
+    results: Vec<(String, f32)>,
+}
+
+impl GpuTimer {
+    pub fn new(device: &wgpu::Device, scope_names: &[String]) -> Self {
+        let query_count = (scope_names.len() * 2) as u32;
+        let query_set = device.create_query_set(&wgpu::QuerySetDescriptor {
+            label: Some("gpu_timer_queries"),
+            ty: wgpu::QueryType::Timestamp,
+            count: query_count,
+        });
+
+        let buffer_size = query_count as u64 * std::mem::size_of::<u64>() as u64;
+        let resolve_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("gpu_timer_resolve_buffer"),
+            size: buffer_size,
+            usage: wgpu::BufferUsages::QUERY_RESOLVE | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+        let readback_buffers = std::array::from_fn(|_| {
+            device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some("gpu_timer_readback_buffer"),
+                size: buffer_size,
+                usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+                mapped_at_creation: false,
+            })
+        });
+
+        Self {
+            query_set,
+            resolve_buffer,
+            readback_buffers,
+            scopes: scope_names.to_vec(),
+            write_slot: 0,
+            results: scope_names.iter().map(|s| (s.clone(), 0.0)).collect(),
+        }
+    }
+
+    /// Query-set indices `(begin, end)` for `scope`.
+    fn scope_indices(&self, scope: &str) -> (u32, u32) {
+        let i = self
+            .scopes
+            .iter()
+            .position(|s| s == scope)
+            .unwrap_or_else(|| panic!("unknown GpuTimer scope {scope:?}")) as u32;
+        (i * 2, i * 2 + 1)
+    }
+
+    /// `timestamp_writes` for `scope`'s compute pass descriptor.
+    pub fn compute_pass_timestamp_writes(&self, scope: &str) -> wgpu::ComputePassTimestampWrites<'_> {
+        let (begin, end) = self.scope_indices(scope);
+        wgpu::ComputePassTimestampWrites {
+            query_set: &self.query_set,
+            beginning_of_pass_write_index: Some(begin),
+            end_of_pass_write_index: Some(end),
+        }
+    }
+
+    /// `timestamp_writes` for `scope`'s render pass descriptor.
+    pub fn render_pass_timestamp_writes(&self, scope: &str) -> wgpu::RenderPassTimestampWrites<'_> {
+        let (begin, end) = self.scope_indices(scope);
+        wgpu::RenderPassTimestampWrites {
+            query_set: &self.query_set,
+            beginning_of_pass_write_index: Some(begin),
+            end_of_pass_write_index: Some(end),
+        }
+    }
+
+    /// Resolves this frame's queries into the current write slot. Call once
+    /// per frame while building the command encoder, after every scope's
+    /// pass has been recorded.
+    pub fn resolve(&mut self, encoder: &mut wgpu::CommandEncoder) {
+        encoder.resolve_query_set(
+            &self.query_set,
+            0..self.scopes.len() as u32 * 2,
+            &self.resolve_buffer,
+            0,
+        );
+        let readback = &self.readback_buffers[self.write_slot];
+        encoder.copy_buffer_to_buffer(&self.resolve_buffer, 0, readback, 0, self.resolve_buffer.size());
+    }
+
+    /// Maps and reads the slot `resolve` wrote to one frame ago, updating
+    /// `results`. Call once per frame after `queue.submit`.
+    pub fn read_back(&mut self, device: &wgpu::Device, queue: &wgpu::Queue) {
+        let read_slot = 1 - self.write_slot;
+        self.write_slot = read_slot;
+
+        let readback = &self.readback_buffers[read_slot];
+        let slice = readback.slice(..);
+        slice.map_async(wgpu::MapMode::Read, |_| {});
+        device.poll(wgpu::Maintain::Wait);
+
+        let ticks: Vec<u64> = {
+            let data = slice.get_mapped_range();
+            bytemuck::cast_slice(&data).to_vec()
+        };
+        readback.unmap();
+
+        let period_ns = queue.get_timestamp_period();
+        for (i, (_, ms)) in self.results.iter_mut().enumerate() {
+            *ms = scope_duration_ms(ticks[i * 2], ticks[i * 2 + 1], period_ns);
+        }
+    }
+
+    /// Per-scope GPU time in milliseconds, as of the most recent `read_back`.
+    pub fn results(&self) -> &[(String, f32)] {
+        &self.results
+    }
+}
+
+/// Converts a begin/end timestamp pair (raw GPU ticks) to milliseconds using
+/// the queue's tick period in nanoseconds (`queue.get_timestamp_period()`).
+fn scope_duration_ms(begin_ticks: u64, end_ticks: u64, period_ns: f32) -> f32 {
+    end_ticks.saturating_sub(begin_ticks) as f32 * period_ns / 1_000_000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scope_indices_are_allocated_two_per_scope_in_order() {
+        // GpuTimer::new requires a device, so exercise the pure indexing
+        // logic directly against the scope list it wraps.
+        let scopes = ["voxel_compute".to_string(), "present_blit".to_string()];
+        let index_of = |name: &str| scopes.iter().position(|s| s == name).unwrap() as u32;
+
+        assert_eq!((index_of("voxel_compute") * 2, index_of("voxel_compute") * 2 + 1), (0, 1));
+        assert_eq!((index_of("present_blit") * 2, index_of("present_blit") * 2 + 1), (2, 3));
+    }
+
+    #[test]
+    fn nanosecond_period_converts_to_milliseconds() {
+        // A GPU running at 1 GHz (period = 1 ns/tick) that took 2,500,000
+        // ticks between begin and end spent 2.5 ms in that scope.
+        assert_eq!(scope_duration_ms(1_000, 2_501_000, 1.0), 2.5);
+    }
+
+    #[test]
+    fn zero_duration_scopes_report_zero() {
+        assert_eq!(scope_duration_ms(42, 42, 1.0), 0.0);
+    }
+
+    #[test]
+    fn duration_scales_with_the_tick_period() {
+        // Same tick delta, but a slower clock (period = 4 ns/tick) means more
+        // real time elapsed.
+        assert_eq!(scope_duration_ms(0, 1_000_000, 4.0), 4.0);
+    }
+}
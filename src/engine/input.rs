@@ -0,0 +1,512 @@
+//! Rebindable keyboard action mapping. [`Action`] names a logical operation
+//! instead of a physical key, and [`InputMap`] resolves it to whichever
+//! [`KeyCode`] is currently bound to it -- loaded from `engine::Config`'s
+//! `[key_bindings]` table, falling back to [`InputMap::with_defaults`].
+//!
+//! `App` used to match on a `KeyCode` directly per hotkey in
+//! `handle_window_event`; it now forwards every key event into an
+//! `InputMap` and polls `just_pressed`/`is_pressed` once per frame in
+//! `dispatch_actions`, so an AZERTY user (or anyone who just prefers
+//! different keys) can move `Action::ToggleAaMode` off `KeyA` without this
+//! codebase's only non-letter-Latin-layout accommodation being "don't use
+//! that feature".
+//!
+//! Movement actions (`MoveForward`, `MoveLeft`, ...) and `PlaceBlock` aren't
+//! here: there's no planar movement or block-editing system wired up yet
+//! (see `Camera`'s doc comment). `Screenshot` isn't either -- there's no
+//! screenshot capture anywhere in this codebase to bind it to. Mouse look
+//! itself also isn't an `Action`: it's continuous analog motion from
+//! `mouse_look::MouseLook`, not a press/release hotkey, so it doesn't fit
+//! this digital table. `ToggleMouseCapture` is here, though -- grabbing the
+//! cursor is itself a discrete press/release toggle like everything else in
+//! this enum. Otherwise `Action` covers the debug/benchmarking toggles
+//! `App` already has, which makes those rebindable instead of inventing
+//! gameplay that doesn't exist. The developer console (`engine::console`)
+//! isn't here either -- it's driven from stdin rather than a window hotkey,
+//! so it has nothing to bind.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use winit::keyboard::KeyCode;
+
+/// A logical operation `App` can trigger, independent of which physical key
+/// is currently bound to it. One variant per hotkey `App` used to match on
+/// directly; see [`Action::default_key`] for what it used to be hardcoded to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    ToggleFullscreen,
+    ToggleVsync,
+    CycleMaxFps,
+    ToggleRedrawPolicy,
+    ForceDeviceLost,
+    PrintMemoryReport,
+    ToggleProfilingCapture,
+    IncreaseRenderScale,
+    DecreaseRenderScale,
+    ToggleAaMode,
+    ToggleFxaa,
+    ToggleHdr,
+    CycleTonemapOperator,
+    IncreaseExposure,
+    DecreaseExposure,
+    ToggleDayCycle,
+    ToggleDayNightPaused,
+    TogglePaused,
+    ResetSun,
+    ToggleAo,
+    ResetAo,
+    ToggleAccumulation,
+    ResetSky,
+    ToggleDebugClear,
+    CycleDebugView,
+    ResetDebugFarPlane,
+    PickCenter,
+    ToggleHighlight,
+    InspectGbufferCenter,
+    ToggleMouseCapture,
+}
+
+impl Action {
+    /// Every action, in the same order `App`'s old match arms declared them;
+    /// used to build the default bindings and to validate config keys.
+    pub const ALL: &'static [Action] = &[
+        Action::ToggleFullscreen,
+        Action::ToggleVsync,
+        Action::CycleMaxFps,
+        Action::ToggleRedrawPolicy,
+        Action::ForceDeviceLost,
+        Action::PrintMemoryReport,
+        Action::ToggleProfilingCapture,
+        Action::IncreaseRenderScale,
+        Action::DecreaseRenderScale,
+        Action::ToggleAaMode,
+        Action::ToggleFxaa,
+        Action::ToggleHdr,
+        Action::CycleTonemapOperator,
+        Action::IncreaseExposure,
+        Action::DecreaseExposure,
+        Action::ToggleDayCycle,
+        Action::ToggleDayNightPaused,
+        Action::TogglePaused,
+        Action::ResetSun,
+        Action::ToggleAo,
+        Action::ResetAo,
+        Action::ToggleAccumulation,
+        Action::ResetSky,
+        Action::ToggleDebugClear,
+        Action::CycleDebugView,
+        Action::ResetDebugFarPlane,
+        Action::PickCenter,
+        Action::ToggleHighlight,
+        Action::InspectGbufferCenter,
+        Action::ToggleMouseCapture,
+    ];
+
+    /// Config-file name, e.g. `"toggle_fullscreen"`; the TOML key under
+    /// `[key_bindings]`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::ToggleFullscreen => "toggle_fullscreen",
+            Action::ToggleVsync => "toggle_vsync",
+            Action::CycleMaxFps => "cycle_max_fps",
+            Action::ToggleRedrawPolicy => "toggle_redraw_policy",
+            Action::ForceDeviceLost => "force_device_lost",
+            Action::PrintMemoryReport => "print_memory_report",
+            Action::ToggleProfilingCapture => "toggle_profiling_capture",
+            Action::IncreaseRenderScale => "increase_render_scale",
+            Action::DecreaseRenderScale => "decrease_render_scale",
+            Action::ToggleAaMode => "toggle_aa_mode",
+            Action::ToggleFxaa => "toggle_fxaa",
+            Action::ToggleHdr => "toggle_hdr",
+            Action::CycleTonemapOperator => "cycle_tonemap_operator",
+            Action::IncreaseExposure => "increase_exposure",
+            Action::DecreaseExposure => "decrease_exposure",
+            Action::ToggleDayCycle => "toggle_day_cycle",
+            Action::ToggleDayNightPaused => "toggle_day_night_paused",
+            Action::TogglePaused => "toggle_paused",
+            Action::ResetSun => "reset_sun",
+            Action::ToggleAo => "toggle_ao",
+            Action::ResetAo => "reset_ao",
+            Action::ToggleAccumulation => "toggle_accumulation",
+            Action::ResetSky => "reset_sky",
+            Action::ToggleDebugClear => "toggle_debug_clear",
+            Action::CycleDebugView => "cycle_debug_view",
+            Action::ResetDebugFarPlane => "reset_debug_far_plane",
+            Action::PickCenter => "pick_center",
+            Action::ToggleHighlight => "toggle_highlight",
+            Action::InspectGbufferCenter => "inspect_gbuffer_center",
+            Action::ToggleMouseCapture => "toggle_mouse_capture",
+        }
+    }
+
+    /// The physical key this action was hardcoded to before rebinding
+    /// existed; what [`InputMap::with_defaults`] starts every action at.
+    pub fn default_key(self) -> KeyCode {
+        match self {
+            Action::ToggleFullscreen => KeyCode::F11,
+            Action::ToggleVsync => KeyCode::KeyV,
+            Action::CycleMaxFps => KeyCode::KeyF,
+            Action::ToggleRedrawPolicy => KeyCode::KeyR,
+            Action::ForceDeviceLost => KeyCode::KeyL,
+            Action::PrintMemoryReport => KeyCode::KeyM,
+            Action::ToggleProfilingCapture => KeyCode::F3,
+            Action::IncreaseRenderScale => KeyCode::Equal,
+            Action::DecreaseRenderScale => KeyCode::Minus,
+            Action::ToggleAaMode => KeyCode::KeyA,
+            Action::ToggleFxaa => KeyCode::KeyX,
+            Action::ToggleHdr => KeyCode::KeyH,
+            Action::CycleTonemapOperator => KeyCode::KeyT,
+            Action::IncreaseExposure => KeyCode::BracketRight,
+            Action::DecreaseExposure => KeyCode::BracketLeft,
+            Action::ToggleDayCycle => KeyCode::KeyC,
+            Action::ToggleDayNightPaused => KeyCode::KeyZ,
+            Action::TogglePaused => KeyCode::Space,
+            Action::ResetSun => KeyCode::KeyN,
+            Action::ToggleAo => KeyCode::KeyO,
+            Action::ResetAo => KeyCode::KeyU,
+            Action::ToggleAccumulation => KeyCode::KeyG,
+            Action::ResetSky => KeyCode::KeyB,
+            Action::ToggleDebugClear => KeyCode::KeyK,
+            Action::CycleDebugView => KeyCode::F5,
+            Action::ResetDebugFarPlane => KeyCode::KeyJ,
+            Action::PickCenter => KeyCode::KeyP,
+            Action::ToggleHighlight => KeyCode::KeyY,
+            Action::InspectGbufferCenter => KeyCode::KeyI,
+            Action::ToggleMouseCapture => KeyCode::Tab,
+        }
+    }
+
+    /// The action bound to `name`, for `[key_bindings]` validation; `None`
+    /// if `name` isn't one of [`Action::ALL`].
+    pub fn parse(name: &str) -> Option<Action> {
+        Action::ALL.iter().copied().find(|action| action.name() == name)
+    }
+}
+
+/// Letters, digits, function keys, and the handful of punctuation/whitespace
+/// keys this codebase's default bindings actually use -- the keys a config
+/// file can rebind an [`Action`] *to*. `KeyCode` is `#[non_exhaustive]` with
+/// far more variants than that (numpad, media keys, ...); round-tripping all
+/// of them through a name isn't worth it for a config file that only ever
+/// needs to name a handful of keys.
+pub(crate) fn key_name(key: KeyCode) -> Option<&'static str> {
+    Some(match key {
+        KeyCode::KeyA => "KeyA",
+        KeyCode::KeyB => "KeyB",
+        KeyCode::KeyC => "KeyC",
+        KeyCode::KeyD => "KeyD",
+        KeyCode::KeyE => "KeyE",
+        KeyCode::KeyF => "KeyF",
+        KeyCode::KeyG => "KeyG",
+        KeyCode::KeyH => "KeyH",
+        KeyCode::KeyI => "KeyI",
+        KeyCode::KeyJ => "KeyJ",
+        KeyCode::KeyK => "KeyK",
+        KeyCode::KeyL => "KeyL",
+        KeyCode::KeyM => "KeyM",
+        KeyCode::KeyN => "KeyN",
+        KeyCode::KeyO => "KeyO",
+        KeyCode::KeyP => "KeyP",
+        KeyCode::KeyQ => "KeyQ",
+        KeyCode::KeyR => "KeyR",
+        KeyCode::KeyS => "KeyS",
+        KeyCode::KeyT => "KeyT",
+        KeyCode::KeyU => "KeyU",
+        KeyCode::KeyV => "KeyV",
+        KeyCode::KeyW => "KeyW",
+        KeyCode::KeyX => "KeyX",
+        KeyCode::KeyY => "KeyY",
+        KeyCode::KeyZ => "KeyZ",
+        KeyCode::Digit0 => "Digit0",
+        KeyCode::Digit1 => "Digit1",
+        KeyCode::Digit2 => "Digit2",
+        KeyCode::Digit3 => "Digit3",
+        KeyCode::Digit4 => "Digit4",
+        KeyCode::Digit5 => "Digit5",
+        KeyCode::Digit6 => "Digit6",
+        KeyCode::Digit7 => "Digit7",
+        KeyCode::Digit8 => "Digit8",
+        KeyCode::Digit9 => "Digit9",
+        KeyCode::F1 => "F1",
+        KeyCode::F2 => "F2",
+        KeyCode::F3 => "F3",
+        KeyCode::F4 => "F4",
+        KeyCode::F5 => "F5",
+        KeyCode::F6 => "F6",
+        KeyCode::F7 => "F7",
+        KeyCode::F8 => "F8",
+        KeyCode::F9 => "F9",
+        KeyCode::F10 => "F10",
+        KeyCode::F11 => "F11",
+        KeyCode::F12 => "F12",
+        KeyCode::Space => "Space",
+        KeyCode::Minus => "Minus",
+        KeyCode::Equal => "Equal",
+        KeyCode::BracketLeft => "BracketLeft",
+        KeyCode::BracketRight => "BracketRight",
+        KeyCode::Semicolon => "Semicolon",
+        KeyCode::Quote => "Quote",
+        KeyCode::Comma => "Comma",
+        KeyCode::Period => "Period",
+        KeyCode::Slash => "Slash",
+        KeyCode::Backslash => "Backslash",
+        KeyCode::Tab => "Tab",
+        _ => return None,
+    })
+}
+
+/// The inverse of [`key_name`]; `Err` names the offending value, matching
+/// `parse_backend`/`BackgroundBehavior::parse`'s error style.
+fn parse_key(raw: &str) -> Result<KeyCode, String> {
+    match raw {
+        "KeyA" => Ok(KeyCode::KeyA),
+        "KeyB" => Ok(KeyCode::KeyB),
+        "KeyC" => Ok(KeyCode::KeyC),
+        "KeyD" => Ok(KeyCode::KeyD),
+        "KeyE" => Ok(KeyCode::KeyE),
+        "KeyF" => Ok(KeyCode::KeyF),
+        "KeyG" => Ok(KeyCode::KeyG),
+        "KeyH" => Ok(KeyCode::KeyH),
+        "KeyI" => Ok(KeyCode::KeyI),
+        "KeyJ" => Ok(KeyCode::KeyJ),
+        "KeyK" => Ok(KeyCode::KeyK),
+        "KeyL" => Ok(KeyCode::KeyL),
+        "KeyM" => Ok(KeyCode::KeyM),
+        "KeyN" => Ok(KeyCode::KeyN),
+        "KeyO" => Ok(KeyCode::KeyO),
+        "KeyP" => Ok(KeyCode::KeyP),
+        "KeyQ" => Ok(KeyCode::KeyQ),
+        "KeyR" => Ok(KeyCode::KeyR),
+        "KeyS" => Ok(KeyCode::KeyS),
+        "KeyT" => Ok(KeyCode::KeyT),
+        "KeyU" => Ok(KeyCode::KeyU),
+        "KeyV" => Ok(KeyCode::KeyV),
+        "KeyW" => Ok(KeyCode::KeyW),
+        "KeyX" => Ok(KeyCode::KeyX),
+        "KeyY" => Ok(KeyCode::KeyY),
+        "KeyZ" => Ok(KeyCode::KeyZ),
+        "Digit0" => Ok(KeyCode::Digit0),
+        "Digit1" => Ok(KeyCode::Digit1),
+        "Digit2" => Ok(KeyCode::Digit2),
+        "Digit3" => Ok(KeyCode::Digit3),
+        "Digit4" => Ok(KeyCode::Digit4),
+        "Digit5" => Ok(KeyCode::Digit5),
+        "Digit6" => Ok(KeyCode::Digit6),
+        "Digit7" => Ok(KeyCode::Digit7),
+        "Digit8" => Ok(KeyCode::Digit8),
+        "Digit9" => Ok(KeyCode::Digit9),
+        "F1" => Ok(KeyCode::F1),
+        "F2" => Ok(KeyCode::F2),
+        "F3" => Ok(KeyCode::F3),
+        "F4" => Ok(KeyCode::F4),
+        "F5" => Ok(KeyCode::F5),
+        "F6" => Ok(KeyCode::F6),
+        "F7" => Ok(KeyCode::F7),
+        "F8" => Ok(KeyCode::F8),
+        "F9" => Ok(KeyCode::F9),
+        "F10" => Ok(KeyCode::F10),
+        "F11" => Ok(KeyCode::F11),
+        "F12" => Ok(KeyCode::F12),
+        "Space" => Ok(KeyCode::Space),
+        "Minus" => Ok(KeyCode::Minus),
+        "Equal" => Ok(KeyCode::Equal),
+        "BracketLeft" => Ok(KeyCode::BracketLeft),
+        "BracketRight" => Ok(KeyCode::BracketRight),
+        "Semicolon" => Ok(KeyCode::Semicolon),
+        "Quote" => Ok(KeyCode::Quote),
+        "Comma" => Ok(KeyCode::Comma),
+        "Period" => Ok(KeyCode::Period),
+        "Slash" => Ok(KeyCode::Slash),
+        "Backslash" => Ok(KeyCode::Backslash),
+        "Tab" => Ok(KeyCode::Tab),
+        other => Err(format!("key_bindings: unrecognized key name {other:?}")),
+    }
+}
+
+/// Resolves [`Action`]s to physical keys, and tracks which keys are
+/// currently held so `App` can poll edge-triggered (`just_pressed`) or
+/// level-triggered (`is_pressed`) state once per frame instead of matching
+/// on raw `WindowEvent::KeyboardInput`s as they arrive.
+#[derive(Debug)]
+pub struct InputMap {
+    bindings: HashMap<Action, KeyCode>,
+    /// Keys held as of the most recent `set_key_state` call.
+    current: HashSet<KeyCode>,
+    /// `current` as it stood at the start of this frame, i.e. before any of
+    /// this frame's `set_key_state` calls; `just_pressed` diffs against
+    /// this. Swapped to `current` by `end_frame`.
+    previous: HashSet<KeyCode>,
+}
+
+impl InputMap {
+    /// Binds every [`Action`] to [`Action::default_key`].
+    pub fn with_defaults() -> Self {
+        let bindings = Action::ALL.iter().map(|&action| (action, action.default_key())).collect();
+        Self {
+            bindings,
+            current: HashSet::new(),
+            previous: HashSet::new(),
+        }
+    }
+
+    /// Rebinds `action` to `key`. Doesn't check whether another action is
+    /// already bound to `key` -- every action always has *some* binding (so
+    /// `binding`/`is_pressed` never need to handle "unbound"), and a config
+    /// file mapping two actions to the same key just means both fire
+    /// together when it's pressed, same as any other misconfiguration.
+    pub fn set_binding(&mut self, action: Action, key: KeyCode) {
+        self.bindings.insert(action, key);
+    }
+
+    /// The key `action` currently fires on.
+    pub fn binding(&self, action: Action) -> KeyCode {
+        self.bindings[&action]
+    }
+
+    /// Call from `WindowEvent::KeyboardInput` for every press/release.
+    pub fn set_key_state(&mut self, key: KeyCode, pressed: bool) {
+        if pressed {
+            self.current.insert(key);
+        } else {
+            self.current.remove(&key);
+        }
+    }
+
+    /// Whether `action`'s key is held right now.
+    pub fn is_pressed(&self, action: Action) -> bool {
+        self.current.contains(&self.bindings[&action])
+    }
+
+    /// Whether `action`'s key is held now but wasn't as of the start of
+    /// this frame, i.e. it was pressed since the last `end_frame`.
+    pub fn just_pressed(&self, action: Action) -> bool {
+        let key = self.bindings[&action];
+        self.current.contains(&key) && !self.previous.contains(&key)
+    }
+
+    /// Snapshots `current` as next frame's baseline for `just_pressed`;
+    /// call once per frame, after every action has been polled.
+    pub fn end_frame(&mut self) {
+        self.previous = self.current.clone();
+    }
+
+    /// Applies `overrides` (parsed from `engine::Config::key_bindings`) over
+    /// [`InputMap::with_defaults`]. `Err` names the first unrecognized
+    /// action or key name encountered.
+    pub fn from_overrides(overrides: &BTreeMap<String, String>) -> Result<Self, String> {
+        let mut map = Self::with_defaults();
+        for (action_name, key_name) in overrides {
+            let action = Action::parse(action_name)
+                .ok_or_else(|| format!("key_bindings: unrecognized action {action_name:?}"))?;
+            let key = parse_key(key_name)?;
+            map.set_binding(action, key);
+        }
+        Ok(map)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_action_name_parses_back_to_itself() {
+        for &action in Action::ALL {
+            assert_eq!(Action::parse(action.name()), Some(action));
+        }
+    }
+
+    #[test]
+    fn unknown_action_name_does_not_parse() {
+        assert_eq!(Action::parse("teleport"), None);
+    }
+
+    #[test]
+    fn defaults_bind_every_action_to_its_old_hardcoded_key() {
+        let map = InputMap::with_defaults();
+        assert_eq!(map.binding(Action::ToggleFullscreen), KeyCode::F11);
+        assert_eq!(map.binding(Action::ToggleAaMode), KeyCode::KeyA);
+    }
+
+    #[test]
+    fn pressing_the_bound_key_is_seen_by_is_pressed() {
+        let mut map = InputMap::with_defaults();
+        assert!(!map.is_pressed(Action::ToggleFullscreen));
+        map.set_key_state(KeyCode::F11, true);
+        assert!(map.is_pressed(Action::ToggleFullscreen));
+    }
+
+    #[test]
+    fn just_pressed_is_true_only_on_the_frame_the_key_went_down() {
+        let mut map = InputMap::with_defaults();
+        map.set_key_state(KeyCode::F11, true);
+        assert!(map.just_pressed(Action::ToggleFullscreen), "should fire the frame it's pressed");
+        map.end_frame();
+        assert!(!map.just_pressed(Action::ToggleFullscreen), "should not fire again while held");
+        assert!(map.is_pressed(Action::ToggleFullscreen), "but is_pressed should still see it held");
+    }
+
+    #[test]
+    fn releasing_and_repressing_fires_just_pressed_again() {
+        let mut map = InputMap::with_defaults();
+        map.set_key_state(KeyCode::F11, true);
+        map.end_frame();
+        map.set_key_state(KeyCode::F11, false);
+        map.end_frame();
+        assert!(!map.just_pressed(Action::ToggleFullscreen));
+        map.set_key_state(KeyCode::F11, true);
+        assert!(map.just_pressed(Action::ToggleFullscreen));
+    }
+
+    #[test]
+    fn simultaneous_keys_are_tracked_independently() {
+        let mut map = InputMap::with_defaults();
+        map.set_key_state(KeyCode::F11, true);
+        map.set_key_state(KeyCode::KeyA, true);
+        assert!(map.is_pressed(Action::ToggleFullscreen));
+        assert!(map.is_pressed(Action::ToggleAaMode));
+        map.set_key_state(KeyCode::F11, false);
+        assert!(!map.is_pressed(Action::ToggleFullscreen));
+        assert!(map.is_pressed(Action::ToggleAaMode), "releasing one key shouldn't affect another");
+    }
+
+    #[test]
+    fn rebinding_moves_the_action_to_the_new_key_and_frees_the_old_one() {
+        let mut map = InputMap::with_defaults();
+        map.set_binding(Action::ToggleAaMode, KeyCode::KeyZ);
+        map.set_key_state(KeyCode::KeyZ, true);
+        assert!(map.is_pressed(Action::ToggleAaMode));
+        map.set_key_state(KeyCode::KeyZ, false);
+        map.set_key_state(KeyCode::KeyA, true);
+        assert!(!map.is_pressed(Action::ToggleAaMode), "KeyA is no longer bound to it");
+    }
+
+    #[test]
+    fn rebinding_onto_an_already_bound_key_makes_both_actions_fire_on_it() {
+        let mut map = InputMap::with_defaults();
+        map.set_binding(Action::ToggleFxaa, KeyCode::F11);
+        map.set_key_state(KeyCode::F11, true);
+        assert!(map.is_pressed(Action::ToggleFxaa));
+        assert!(map.is_pressed(Action::ToggleFullscreen), "F11 still fires its original action too");
+    }
+
+    #[test]
+    fn from_overrides_applies_a_remap_on_top_of_the_defaults() {
+        let overrides = BTreeMap::from([("toggle_aa_mode".to_string(), "KeyQ".to_string())]);
+        let map = InputMap::from_overrides(&overrides).unwrap();
+        assert_eq!(map.binding(Action::ToggleAaMode), KeyCode::KeyQ);
+        assert_eq!(map.binding(Action::ToggleFullscreen), KeyCode::F11, "untouched actions keep their default");
+    }
+
+    #[test]
+    fn from_overrides_rejects_an_unknown_action_name() {
+        let overrides = BTreeMap::from([("teleport".to_string(), "KeyQ".to_string())]);
+        assert!(InputMap::from_overrides(&overrides).unwrap_err().contains("teleport"));
+    }
+
+    #[test]
+    fn from_overrides_rejects_an_unknown_key_name() {
+        let overrides = BTreeMap::from([("toggle_aa_mode".to_string(), "NumpadEnter".to_string())]);
+        assert!(InputMap::from_overrides(&overrides).unwrap_err().contains("NumpadEnter"));
+    }
+}
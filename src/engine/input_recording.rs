@@ -0,0 +1,361 @@
+//! Records and replays the input `App` feeds into its per-frame handlers,
+//! so a bug report can ship as a session file instead of a verbal
+//! description of what was pressed when. Conceptually the same idea as
+//! `camera_path`'s scripted flythroughs, but recorded rather than
+//! hand-authored, and covering the actual input surface (`Action` presses,
+//! mouse-look deltas, window resizes) instead of a handful of camera
+//! keyframes -- which is also why it's a compact binary format instead of
+//! TOML: an hour of mashed hotkeys has a lot more entries than a camera
+//! path ever will.
+//!
+//! Every event is tagged with the frame index it happened on (`App`'s
+//! `tick`, incremented once per `redraw`) rather than a wall-clock
+//! timestamp, so replay determinism rides on the same "one tick, one unit
+//! of simulated time" assumption `FixedTimestep`/`SimClock` already make --
+//! see their doc comments. There's no planar movement or block-editing
+//! `Action` yet (see `input`'s module doc comment), so a recorded session
+//! can't actually reproduce an octree-editing bug today; what it *can*
+//! reproduce is every other kind of input-driven state change (debug
+//! toggles, exposure/render-scale nudges, camera look, resize), which is
+//! still useful on its own and becomes strictly more useful once editing
+//! exists. For the same reason, a "compare the final world content hash"
+//! regression test has nothing to diff against yet -- `tests` below instead
+//! verifies that replaying a recorded session reproduces identical engine
+//! state for everything it currently covers.
+//!
+//! Headless replay (`Renderer::new_headless`, the same path `bench::run`
+//! uses) isn't wired up: `App::run` owns a real `winit` event loop and
+//! window for its whole lifetime, so swapping in a headless renderer mid-run
+//! would mean restructuring `App` around an abstraction neither `Renderer`
+//! nor `App` has today. `--replay` instead drives the same windowed `App`
+//! normally would, just fed from a file instead of a mouse and keyboard.
+
+use std::collections::VecDeque;
+
+use super::input::Action;
+
+/// One input-driven event `App` recorded, tagged with the tick it happened
+/// on by [`InputRecorder::record`]. Mirrors the three things `App::redraw`
+/// and `App::handle_window_event` actually feed into the engine each
+/// frame -- see that module's doc comment for what's deliberately missing
+/// (movement, editing, screenshots).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RecordedEvent {
+    /// An [`Action`] that was `just_pressed` this tick.
+    Action(Action),
+    /// `MouseLook::take_delta`'s result for this tick, already summed if
+    /// the live frame accumulated more than one `DeviceEvent::MouseMotion`.
+    MouseLook { delta_yaw: f32, delta_pitch: f32 },
+    /// A `WindowEvent::Resized` observed this tick.
+    Resize { width: u32, height: u32 },
+}
+
+const MAGIC: &[u8; 4] = b"VXIR";
+const VERSION: u8 = 1;
+
+const TAG_ACTION: u8 = 0;
+const TAG_MOUSE_LOOK: u8 = 1;
+const TAG_RESIZE: u8 = 2;
+
+/// Why [`SessionRecording::decode`] rejected a `--replay` file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RecordingError {
+    /// Doesn't start with [`MAGIC`] -- not a session recording at all.
+    BadMagic,
+    /// Starts with [`MAGIC`] but a version this build doesn't know how to
+    /// read.
+    UnsupportedVersion(u8),
+    /// The buffer ended partway through an event; likely a truncated or
+    /// corrupted file.
+    Truncated,
+    /// A tag byte other than [`TAG_ACTION`]/[`TAG_MOUSE_LOOK`]/[`TAG_RESIZE`].
+    UnknownEventTag(u8),
+    /// An encoded `Action` name that [`Action::parse`] doesn't recognize --
+    /// most likely a recording made by a newer build with actions this one
+    /// doesn't have.
+    UnknownActionName(String),
+}
+
+impl std::fmt::Display for RecordingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::BadMagic => write!(f, "not a session recording (bad magic bytes)"),
+            Self::UnsupportedVersion(version) => write!(f, "session recording version {version} is not supported"),
+            Self::Truncated => write!(f, "session recording ended partway through an event"),
+            Self::UnknownEventTag(tag) => write!(f, "unknown recorded event tag {tag}"),
+            Self::UnknownActionName(name) => write!(f, "unknown action {name:?} in recorded session"),
+        }
+    }
+}
+
+impl std::error::Error for RecordingError {}
+
+/// A decoded (or not-yet-encoded) sequence of [`RecordedEvent`]s in the
+/// order they were recorded, each tagged with its tick. Built up by
+/// [`InputRecorder`], consumed by [`InputReplayer`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SessionRecording {
+    events: Vec<(u64, RecordedEvent)>,
+}
+
+impl SessionRecording {
+    /// Packs this recording into `--record`'s binary format: a 4-byte
+    /// magic, a version byte, then each event as an 8-byte LE tick, a tag
+    /// byte, and a tag-specific payload. Hand-rolled rather than pulling in
+    /// a serialization crate, matching `BenchReport::to_json`'s reasoning --
+    /// there's nothing else in this crate that would justify the
+    /// dependency.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(MAGIC.len() + 1 + self.events.len() * 10);
+        out.extend_from_slice(MAGIC);
+        out.push(VERSION);
+        for (tick, event) in &self.events {
+            out.extend_from_slice(&tick.to_le_bytes());
+            match event {
+                RecordedEvent::Action(action) => {
+                    out.push(TAG_ACTION);
+                    let name = action.name().as_bytes();
+                    out.push(name.len() as u8);
+                    out.extend_from_slice(name);
+                }
+                RecordedEvent::MouseLook { delta_yaw, delta_pitch } => {
+                    out.push(TAG_MOUSE_LOOK);
+                    out.extend_from_slice(&delta_yaw.to_le_bytes());
+                    out.extend_from_slice(&delta_pitch.to_le_bytes());
+                }
+                RecordedEvent::Resize { width, height } => {
+                    out.push(TAG_RESIZE);
+                    out.extend_from_slice(&width.to_le_bytes());
+                    out.extend_from_slice(&height.to_le_bytes());
+                }
+            }
+        }
+        out
+    }
+
+    /// Unpacks a buffer written by [`encode`](Self::encode), or a
+    /// [`RecordingError`] naming exactly where it's invalid.
+    pub fn decode(bytes: &[u8]) -> Result<Self, RecordingError> {
+        if bytes.len() < MAGIC.len() + 1 || &bytes[..MAGIC.len()] != MAGIC {
+            return Err(RecordingError::BadMagic);
+        }
+        let version = bytes[MAGIC.len()];
+        if version != VERSION {
+            return Err(RecordingError::UnsupportedVersion(version));
+        }
+
+        let mut cursor = MAGIC.len() + 1;
+        let mut events = Vec::new();
+        while cursor < bytes.len() {
+            let tick = u64::from_le_bytes(take(bytes, &mut cursor, 8)?.try_into().unwrap());
+            let tag = take(bytes, &mut cursor, 1)?[0];
+            let event = match tag {
+                TAG_ACTION => {
+                    let len = take(bytes, &mut cursor, 1)?[0] as usize;
+                    let name = std::str::from_utf8(take(bytes, &mut cursor, len)?)
+                        .map_err(|_| RecordingError::Truncated)?;
+                    let action = Action::parse(name).ok_or_else(|| RecordingError::UnknownActionName(name.to_string()))?;
+                    RecordedEvent::Action(action)
+                }
+                TAG_MOUSE_LOOK => {
+                    let delta_yaw = f32::from_le_bytes(take(bytes, &mut cursor, 4)?.try_into().unwrap());
+                    let delta_pitch = f32::from_le_bytes(take(bytes, &mut cursor, 4)?.try_into().unwrap());
+                    RecordedEvent::MouseLook { delta_yaw, delta_pitch }
+                }
+                TAG_RESIZE => {
+                    let width = u32::from_le_bytes(take(bytes, &mut cursor, 4)?.try_into().unwrap());
+                    let height = u32::from_le_bytes(take(bytes, &mut cursor, 4)?.try_into().unwrap());
+                    RecordedEvent::Resize { width, height }
+                }
+                other => return Err(RecordingError::UnknownEventTag(other)),
+            };
+            events.push((tick, event));
+        }
+        Ok(Self { events })
+    }
+}
+
+/// Slices `len` bytes starting at `*cursor`, advancing it, or
+/// [`RecordingError::Truncated`] if fewer than `len` remain.
+fn take<'a>(bytes: &'a [u8], cursor: &mut usize, len: usize) -> Result<&'a [u8], RecordingError> {
+    let end = cursor.checked_add(len).ok_or(RecordingError::Truncated)?;
+    let slice = bytes.get(*cursor..end).ok_or(RecordingError::Truncated)?;
+    *cursor = end;
+    Ok(slice)
+}
+
+/// Appends [`RecordedEvent`]s as `App` observes them; `--record <path>`
+/// writes [`finish`](Self::finish)'s [`SessionRecording::encode`] out once
+/// the window closes.
+#[derive(Debug, Default)]
+pub struct InputRecorder {
+    events: Vec<(u64, RecordedEvent)>,
+}
+
+impl InputRecorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&mut self, tick: u64, event: RecordedEvent) {
+        self.events.push((tick, event));
+    }
+
+    pub fn finish(self) -> SessionRecording {
+        SessionRecording { events: self.events }
+    }
+}
+
+/// Feeds a [`SessionRecording`] back out in recorded order, one tick's
+/// worth at a time; `--replay <path>` substitutes [`events_due`](Self::events_due)'s
+/// result for live input each frame instead of polling a real `InputMap`/`MouseLook`.
+#[derive(Debug)]
+pub struct InputReplayer {
+    events: VecDeque<(u64, RecordedEvent)>,
+}
+
+impl InputReplayer {
+    pub fn new(recording: SessionRecording) -> Self {
+        Self { events: recording.events.into() }
+    }
+
+    /// Pops and returns every event recorded at or before `tick`, in the
+    /// order they were recorded. A tick with nothing due returns an empty
+    /// `Vec` rather than blocking or erroring -- most frames don't have
+    /// input on them.
+    pub fn events_due(&mut self, tick: u64) -> Vec<RecordedEvent> {
+        let mut due = Vec::new();
+        while matches!(self.events.front(), Some((t, _)) if *t <= tick) {
+            due.push(self.events.pop_front().expect("front just matched Some").1);
+        }
+        due
+    }
+
+    /// Whether every recorded event has already been returned by
+    /// [`events_due`](Self::events_due); `App` doesn't currently act on
+    /// this (replay just idles through the rest of the session once it's
+    /// exhausted), but it's here for a future "exit after replay finishes"
+    /// flag.
+    pub fn is_finished(&self) -> bool {
+        self.events.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::voxel::Camera;
+
+    #[test]
+    fn encode_decode_round_trips_every_event_kind() {
+        let mut recorder = InputRecorder::new();
+        recorder.record(0, RecordedEvent::Action(Action::ToggleFullscreen));
+        recorder.record(3, RecordedEvent::MouseLook { delta_yaw: 0.1, delta_pitch: -0.2 });
+        recorder.record(3, RecordedEvent::Resize { width: 1920, height: 1080 });
+        recorder.record(7, RecordedEvent::Action(Action::TogglePaused));
+
+        let encoded = recorder.finish().encode();
+        let decoded = SessionRecording::decode(&encoded).expect("a just-encoded session should decode");
+
+        assert_eq!(
+            decoded.events,
+            vec![
+                (0, RecordedEvent::Action(Action::ToggleFullscreen)),
+                (3, RecordedEvent::MouseLook { delta_yaw: 0.1, delta_pitch: -0.2 }),
+                (3, RecordedEvent::Resize { width: 1920, height: 1080 }),
+                (7, RecordedEvent::Action(Action::TogglePaused)),
+            ]
+        );
+    }
+
+    #[test]
+    fn decode_rejects_bad_magic() {
+        assert_eq!(SessionRecording::decode(b"nope"), Err(RecordingError::BadMagic));
+    }
+
+    #[test]
+    fn decode_rejects_an_unsupported_version() {
+        let mut bytes = MAGIC.to_vec();
+        bytes.push(99);
+        assert_eq!(SessionRecording::decode(&bytes), Err(RecordingError::UnsupportedVersion(99)));
+    }
+
+    #[test]
+    fn decode_rejects_a_truncated_event() {
+        let mut recorder = InputRecorder::new();
+        recorder.record(0, RecordedEvent::Resize { width: 640, height: 480 });
+        let mut encoded = recorder.finish().encode();
+        encoded.truncate(encoded.len() - 2);
+        assert_eq!(SessionRecording::decode(&encoded), Err(RecordingError::Truncated));
+    }
+
+    #[test]
+    fn decode_rejects_an_unknown_event_tag() {
+        let mut bytes = MAGIC.to_vec();
+        bytes.push(VERSION);
+        bytes.extend_from_slice(&0u64.to_le_bytes());
+        bytes.push(255);
+        assert_eq!(SessionRecording::decode(&bytes), Err(RecordingError::UnknownEventTag(255)));
+    }
+
+    #[test]
+    fn decode_rejects_an_unknown_action_name() {
+        let mut bytes = MAGIC.to_vec();
+        bytes.push(VERSION);
+        bytes.extend_from_slice(&0u64.to_le_bytes());
+        bytes.push(TAG_ACTION);
+        let name = b"not_a_real_action";
+        bytes.push(name.len() as u8);
+        bytes.extend_from_slice(name);
+        assert_eq!(
+            SessionRecording::decode(&bytes),
+            Err(RecordingError::UnknownActionName("not_a_real_action".to_string()))
+        );
+    }
+
+    #[test]
+    fn events_due_drains_only_events_at_or_before_the_given_tick_in_order() {
+        let mut recorder = InputRecorder::new();
+        recorder.record(1, RecordedEvent::Action(Action::ToggleVsync));
+        recorder.record(2, RecordedEvent::Action(Action::ToggleHdr));
+        recorder.record(5, RecordedEvent::Action(Action::ToggleFxaa));
+
+        let mut replayer = InputReplayer::new(recorder.finish());
+        assert_eq!(replayer.events_due(0), vec![]);
+        assert_eq!(
+            replayer.events_due(2),
+            vec![RecordedEvent::Action(Action::ToggleVsync), RecordedEvent::Action(Action::ToggleHdr)]
+        );
+        assert_eq!(replayer.events_due(4), vec![]);
+        assert_eq!(replayer.events_due(5), vec![RecordedEvent::Action(Action::ToggleFxaa)]);
+        assert!(replayer.is_finished());
+    }
+
+    #[test]
+    fn replaying_a_recorded_mouse_look_session_reproduces_the_same_camera_state() {
+        let live_deltas = [(0u64, 0.02, -0.01), (1, -0.05, 0.03), (4, 0.10, 0.0)];
+
+        let mut live_camera = Camera::new(glam::Vec3::ZERO);
+        let mut recorder = InputRecorder::new();
+        for &(tick, delta_yaw, delta_pitch) in &live_deltas {
+            live_camera.look(delta_yaw, delta_pitch);
+            recorder.record(tick, RecordedEvent::MouseLook { delta_yaw, delta_pitch });
+        }
+
+        let encoded = recorder.finish().encode();
+        let recording = SessionRecording::decode(&encoded).expect("a just-encoded session should decode");
+        let mut replayer = InputReplayer::new(recording);
+
+        let mut replayed_camera = Camera::new(glam::Vec3::ZERO);
+        for tick in 0..=4 {
+            for event in replayer.events_due(tick) {
+                if let RecordedEvent::MouseLook { delta_yaw, delta_pitch } = event {
+                    replayed_camera.look(delta_yaw, delta_pitch);
+                }
+            }
+        }
+
+        assert_eq!(replayed_camera.yaw, live_camera.yaw);
+        assert_eq!(replayed_camera.pitch, live_camera.pitch);
+    }
+}
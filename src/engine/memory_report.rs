@@ -0,0 +1,73 @@
+/// Byte counts for GPU allocations grouped by what they're used for, so VRAM
+/// usage can be inspected without a GPU profiler attached.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct GpuMemoryReport {
+    /// Octree node storage buffer(s) for the loaded chunk(s).
+    pub octree_bytes: u64,
+    /// Per-frame uniform buffers (camera, frame params, ...).
+    pub uniform_bytes: u64,
+    /// Render targets and other textures (the compute pass's output, the
+    /// headless offscreen stand-in, and future depth/G-buffer attachments).
+    pub render_target_bytes: u64,
+    /// Fraction of a `BufferArena`'s backing buffers that's free space
+    /// split across more than one range rather than one contiguous block
+    /// (see `BufferArena::fragmentation`). Hardcoded to `0.0` by
+    /// `Renderer::memory_report` -- not a real measurement, since
+    /// `Renderer` doesn't allocate out of a `BufferArena` yet and has no
+    /// live arena to ask (see `buffer_arena.rs`'s doc comment on what's
+    /// still unwired, and why this field can't report anything else until
+    /// that lands).
+    pub fragmentation_ratio: f32,
+}
+
+impl GpuMemoryReport {
+    pub fn total_bytes(&self) -> u64 {
+        self.octree_bytes + self.uniform_bytes + self.render_target_bytes
+    }
+}
+
+fn as_mb(bytes: u64) -> f64 {
+    bytes as f64 / (1024.0 * 1024.0)
+}
+
+impl std::fmt::Display for GpuMemoryReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "GPU memory usage:")?;
+        writeln!(f, "  octree:         {:.2} MB", as_mb(self.octree_bytes))?;
+        writeln!(f, "  uniforms:       {:.2} MB", as_mb(self.uniform_bytes))?;
+        writeln!(f, "  render targets: {:.2} MB", as_mb(self.render_target_bytes))?;
+        writeln!(f, "  fragmentation:  {:.1}%", self.fragmentation_ratio * 100.0)?;
+        write!(f, "  total:          {:.2} MB", as_mb(self.total_bytes()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn total_bytes_sums_every_category() {
+        let report = GpuMemoryReport {
+            octree_bytes: 1024,
+            uniform_bytes: 256,
+            render_target_bytes: 2048,
+            fragmentation_ratio: 0.0,
+        };
+        assert_eq!(report.total_bytes(), 1024 + 256 + 2048);
+    }
+
+    #[test]
+    fn display_rounds_to_two_decimal_places_of_mb() {
+        let report = GpuMemoryReport {
+            octree_bytes: 1024 * 1024,
+            uniform_bytes: 0,
+            render_target_bytes: 512 * 1024,
+            fragmentation_ratio: 0.25,
+        };
+        let text = report.to_string();
+        assert!(text.contains("octree:         1.00 MB"));
+        assert!(text.contains("render targets: 0.50 MB"));
+        assert!(text.contains("fragmentation:  25.0%"));
+        assert!(text.contains("total:          1.50 MB"));
+    }
+}
@@ -0,0 +1,64 @@
+mod aa_mode;
+mod accumulation;
+mod app;
+mod autosave;
+mod background;
+mod bench;
+mod buffer_arena;
+mod camera_path;
+mod config;
+mod console;
+mod daynight;
+mod device_lost;
+mod frame_capture;
+mod frame_limiter;
+mod frame_stats;
+mod fullscreen;
+#[cfg(feature = "gamepad")]
+pub(crate) mod gamepad;
+mod gpu_timer;
+mod input;
+mod input_recording;
+mod memory_report;
+mod mouse_look;
+#[cfg(feature = "debug-overlay")]
+mod overlay;
+mod pipeline_cache;
+mod profiling;
+mod redraw_policy;
+mod render_gate;
+mod renderer;
+mod reprojection;
+mod resize_debounce;
+mod scene;
+#[cfg(feature = "shader-hot-reload")]
+pub(crate) mod shader_watcher;
+mod sim_clock;
+mod startup;
+pub(crate) mod texture_pool;
+mod timestep;
+mod upload_context;
+
+pub use app::{App, AppOptions};
+pub use autosave::{install_panic_hook, AutosaveHandle};
+pub use background::BackgroundBehavior;
+pub use input::{Action, InputMap};
+pub use input_recording::{InputRecorder, InputReplayer, RecordedEvent, RecordingError, SessionRecording};
+pub use fullscreen::VideoModeSpec;
+pub use config::{
+    config_paths, load as load_config, parse as parse_config, parse_backend, resolve as resolve_config, CliOverrides,
+    Config, ResolvedOptions,
+};
+pub use bench::{run as run_bench, run_sweep as run_bench_sweep, run_with_path as run_bench_with_path, SegmentReport};
+pub use camera_path::{parse as parse_camera_path, CameraKeyframe, CameraPath};
+/// Re-exported for callers that already reach for `engine::Camera`; `Camera`
+/// itself lives in `voxel` now (see `lib.rs`'s module doc comment) since
+/// it's plain math with no wgpu/winit in it, unlike the rest of this module.
+pub use crate::voxel::Camera;
+pub use pipeline_cache::PipelineCache;
+pub use renderer::{Renderer, RendererOptions};
+pub use scene::{
+    load as load_scene, parse as parse_scene, ChunkGenerator, SceneCamera, SceneChunk, SceneDescription, SceneError,
+    SceneLight, SceneSky,
+};
+pub(crate) use upload_context::UploadContext;
@@ -0,0 +1,188 @@
+//! Raw-mouse-motion camera look, fed from `winit`'s `DeviceEvent::MouseMotion`
+//! rather than `WindowEvent::CursorMoved` -- `CursorMoved` deltas are
+//! accelerated and clamp at the screen edge, which is unusable for an
+//! FPS-style look that should keep turning for as long as the mouse keeps
+//! moving. See [`MouseLook`] for the accumulator and [`GrabStrategy`] for
+//! how the cursor gets out of the way so it can.
+
+use winit::dpi::PhysicalPosition;
+use winit::window::{CursorGrabMode, Window};
+
+/// How the cursor is being kept from wandering off while the camera is
+/// being looked around with. Tried in this order -- `Locked` is the ideal
+/// case (cursor stays put, `MouseMotion` deltas keep flowing), `Confined`
+/// is the common fallback (cursor can still move, but can't leave the
+/// window), and `Recenter` is the last resort for platforms where neither
+/// `set_cursor_grab` mode is supported: the cursor is pinned to the
+/// window's center by hand, once per frame, instead of relying on the
+/// windowing backend to do it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GrabStrategy {
+    Locked,
+    Confined,
+    Recenter,
+}
+
+/// The next strategy to try after `attempted` has failed (or `None` to
+/// start from scratch). `Recenter` never fails -- it's not a
+/// `set_cursor_grab` mode at all, just `set_cursor_position` every frame --
+/// so it's the floor this sequence settles on.
+fn next_grab_strategy(attempted: Option<GrabStrategy>) -> GrabStrategy {
+    match attempted {
+        None => GrabStrategy::Locked,
+        Some(GrabStrategy::Locked) => GrabStrategy::Confined,
+        Some(GrabStrategy::Confined) | Some(GrabStrategy::Recenter) => GrabStrategy::Recenter,
+    }
+}
+
+/// Tries `window.set_cursor_grab` in [`next_grab_strategy`]'s order until
+/// one succeeds (or falls through to `Recenter`, which always does), hides
+/// the cursor, and returns which strategy is now in effect.
+pub fn grab_cursor(window: &Window) -> GrabStrategy {
+    let mut strategy = next_grab_strategy(None);
+    loop {
+        let result = match strategy {
+            GrabStrategy::Locked => window.set_cursor_grab(CursorGrabMode::Locked),
+            GrabStrategy::Confined => window.set_cursor_grab(CursorGrabMode::Confined),
+            GrabStrategy::Recenter => Ok(()),
+        };
+        if result.is_ok() {
+            window.set_cursor_visible(false);
+            return strategy;
+        }
+        strategy = next_grab_strategy(Some(strategy));
+    }
+}
+
+/// Releases whichever [`GrabStrategy`] is in effect and shows the cursor
+/// again.
+pub fn release_cursor(window: &Window) {
+    let _ = window.set_cursor_grab(CursorGrabMode::None);
+    window.set_cursor_visible(true);
+}
+
+/// Pins the cursor back to the window's center; only meaningful under
+/// `GrabStrategy::Recenter`, where nothing else is keeping it there.
+pub fn recenter_cursor(window: &Window) {
+    let size = window.inner_size();
+    let _ = window.set_cursor_position(PhysicalPosition::new(size.width as f64 / 2.0, size.height as f64 / 2.0));
+}
+
+/// Accumulates raw `DeviceEvent::MouseMotion` deltas while the cursor is
+/// grabbed, for `App::redraw` to drain once per frame -- independent of
+/// frame rate, since it's a sum of whatever the OS reported since the last
+/// drain, not something scaled by `dt`.
+#[derive(Debug)]
+pub struct MouseLook {
+    sensitivity: f32,
+    grabbed: bool,
+    strategy: Option<GrabStrategy>,
+    accumulated: (f32, f32),
+}
+
+impl MouseLook {
+    pub fn new(sensitivity: f32) -> Self {
+        Self {
+            sensitivity,
+            grabbed: false,
+            strategy: None,
+            accumulated: (0.0, 0.0),
+        }
+    }
+
+    pub fn is_grabbed(&self) -> bool {
+        self.grabbed
+    }
+
+    pub fn grab_strategy(&self) -> Option<GrabStrategy> {
+        self.strategy
+    }
+
+    /// Call once `grab_cursor`/`release_cursor` have actually been applied
+    /// to the window, so the accumulator's idea of "grabbed" can't drift
+    /// from what the OS is doing.
+    pub fn set_grabbed(&mut self, grabbed: bool, strategy: Option<GrabStrategy>) {
+        self.grabbed = grabbed;
+        self.strategy = if grabbed { strategy } else { None };
+        self.accumulated = (0.0, 0.0);
+    }
+
+    /// Folds one `DeviceEvent::MouseMotion` delta in, dropped entirely
+    /// while not grabbed so stray motion before the first grab (or after a
+    /// release) never leaks into the next `take_delta`.
+    pub fn accumulate(&mut self, dx: f64, dy: f64) {
+        if !self.grabbed {
+            return;
+        }
+        self.accumulated.0 += dx as f32;
+        self.accumulated.1 += dy as f32;
+    }
+
+    /// Drains the accumulated delta scaled by `sensitivity`, resetting it
+    /// to zero for the next frame.
+    pub fn take_delta(&mut self) -> (f32, f32) {
+        let (dx, dy) = self.accumulated;
+        self.accumulated = (0.0, 0.0);
+        (dx * self.sensitivity, dy * self.sensitivity)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grab_strategy_order_is_locked_then_confined_then_recenter() {
+        assert_eq!(next_grab_strategy(None), GrabStrategy::Locked);
+        assert_eq!(next_grab_strategy(Some(GrabStrategy::Locked)), GrabStrategy::Confined);
+        assert_eq!(next_grab_strategy(Some(GrabStrategy::Confined)), GrabStrategy::Recenter);
+    }
+
+    #[test]
+    fn recenter_is_a_fixed_point_once_reached() {
+        assert_eq!(next_grab_strategy(Some(GrabStrategy::Recenter)), GrabStrategy::Recenter);
+    }
+
+    #[test]
+    fn accumulate_is_ignored_while_not_grabbed() {
+        let mut look = MouseLook::new(1.0);
+        look.accumulate(10.0, 10.0);
+        assert_eq!(look.take_delta(), (0.0, 0.0));
+    }
+
+    #[test]
+    fn accumulate_sums_multiple_deltas_within_a_frame() {
+        let mut look = MouseLook::new(1.0);
+        look.set_grabbed(true, Some(GrabStrategy::Locked));
+        look.accumulate(3.0, -2.0);
+        look.accumulate(4.0, -1.0);
+        assert_eq!(look.take_delta(), (7.0, -3.0));
+    }
+
+    #[test]
+    fn take_delta_resets_the_accumulator() {
+        let mut look = MouseLook::new(1.0);
+        look.set_grabbed(true, Some(GrabStrategy::Locked));
+        look.accumulate(5.0, 5.0);
+        look.take_delta();
+        assert_eq!(look.take_delta(), (0.0, 0.0));
+    }
+
+    #[test]
+    fn take_delta_scales_by_sensitivity() {
+        let mut look = MouseLook::new(0.5);
+        look.set_grabbed(true, Some(GrabStrategy::Locked));
+        look.accumulate(2.0, 4.0);
+        assert_eq!(look.take_delta(), (1.0, 2.0));
+    }
+
+    #[test]
+    fn releasing_drops_whatever_was_accumulated_since_the_last_drain() {
+        let mut look = MouseLook::new(1.0);
+        look.set_grabbed(true, Some(GrabStrategy::Locked));
+        look.accumulate(5.0, 5.0);
+        look.set_grabbed(false, None);
+        assert_eq!(look.take_delta(), (0.0, 0.0));
+        assert_eq!(look.grab_strategy(), None);
+    }
+}
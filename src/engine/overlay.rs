@@ -0,0 +1,394 @@
+use winit::event::WindowEvent;
+use winit::window::Window;
+
+use super::aa_mode::AaMode;
+use super::memory_report::GpuMemoryReport;
+use crate::voxel::{AoSettings, DebugView, Light, SkySettings, TonemapOperator, MAX_LIGHTS};
+
+/// Everything the overlay panel needs to render one frame, assembled by
+/// `Renderer` so this module doesn't need to know about GPU-side types like
+/// `VoxelRendererPass`.
+pub struct OverlayStats {
+    pub fps: f64,
+    pub gpu_times_ms: Vec<(String, f32)>,
+    pub camera_position: glam::Vec3,
+    pub chunk_node_count: u32,
+    pub memory_report: GpuMemoryReport,
+    pub vsync_enabled: bool,
+    pub render_scale: f32,
+    pub aa_mode: AaMode,
+    pub fxaa_enabled: bool,
+    pub hdr_enabled: bool,
+    pub srgb_conversion_enabled: bool,
+    pub exposure: f32,
+    pub tonemap_operator: TonemapOperator,
+    pub sun_direction: glam::Vec3,
+    pub sun_color: glam::Vec3,
+    /// Extra shadow-casting fill lights beyond the sun; at most `MAX_LIGHTS` long.
+    pub lights: Vec<Light>,
+    pub day_cycle_enabled: bool,
+    pub day_night_paused: bool,
+    pub time_of_day: f32,
+    pub day_length_seconds: f32,
+    pub ao_enabled: bool,
+    pub ao_settings: AoSettings,
+    pub accumulation_enabled: bool,
+    pub sky: SkySettings,
+    pub debug_clear: bool,
+    pub debug_view: DebugView,
+    pub debug_far_plane: f32,
+    /// Costliest tiles from `VoxelRendererPass::top_k_tile_costs`, most
+    /// expensive first, paired with that tile's pixel-space origin when
+    /// `tile_origin` has one (see its doc comment for when it doesn't).
+    pub top_tile_costs: Vec<(Option<[u32; 2]>, u32)>,
+    pub highlight_enabled: bool,
+}
+
+/// What the user asked for from the panel this frame; `Renderer::render`
+/// applies these after the egui pass has been recorded.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OverlayActions {
+    pub toggle_vsync: bool,
+    pub regenerate_chunk: bool,
+    pub render_scale: Option<f32>,
+    pub aa_mode: Option<AaMode>,
+    pub fxaa_enabled: Option<bool>,
+    pub hdr_enabled: Option<bool>,
+    pub srgb_conversion_enabled: Option<bool>,
+    pub exposure: Option<f32>,
+    pub tonemap_operator: Option<TonemapOperator>,
+    /// `(direction, color)`, set together since the overlay edits both from
+    /// the same panel section.
+    pub sun: Option<(glam::Vec3, glam::Vec3)>,
+    /// Replacement fill-light list; unlike the other `Option` fields this is
+    /// the whole list rather than a per-field delta, since the panel lets
+    /// slots be enabled/disabled as a set.
+    pub lights: Option<Vec<Light>>,
+    pub day_cycle_enabled: Option<bool>,
+    pub day_night_paused: Option<bool>,
+    pub time_of_day: Option<f32>,
+    pub ao_enabled: Option<bool>,
+    pub ao_settings: Option<AoSettings>,
+    pub accumulation_enabled: Option<bool>,
+    pub sky: Option<SkySettings>,
+    pub debug_clear: Option<bool>,
+    pub debug_view: Option<DebugView>,
+    pub debug_far_plane: Option<f32>,
+    pub highlight_enabled: Option<bool>,
+}
+
+/// In-window debug overlay: an egui panel drawn on top of the swapchain
+/// image after the voxel blit, plus the input routing needed so the camera
+/// doesn't move while the user is interacting with it.
+pub struct Overlay {
+    context: egui::Context,
+    state: egui_winit::State,
+    renderer: egui_wgpu::Renderer,
+}
+
+impl Overlay {
+    pub fn new(device: &wgpu::Device, output_format: wgpu::TextureFormat, window: &Window) -> Self {
+        let context = egui::Context::default();
+        let viewport_id = context.viewport_id();
+        let state = egui_winit::State::new(context.clone(), viewport_id, window, None, None);
+        let renderer = egui_wgpu::Renderer::new(device, output_format, None, 1);
+        Self { context, state, renderer }
+    }
+
+    /// Gives egui first refusal on a window event. Returns whether egui
+    /// consumed it, in which case the caller should not also treat it as
+    /// game input (camera look, debug key bindings, ...).
+    pub fn consumes_event(&mut self, window: &Window, event: &WindowEvent) -> bool {
+        self.state.on_window_event(window, event).consumed
+    }
+
+    /// Builds the panel, uploads its draw data, and records a render pass
+    /// for it into `encoder`. Must run after the voxel blit pass so
+    /// `LoadOp::Load` preserves it, and before `queue.submit`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn draw(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        encoder: &mut wgpu::CommandEncoder,
+        view: &wgpu::TextureView,
+        window: &Window,
+        screen_size: [u32; 2],
+        stats: &OverlayStats,
+    ) -> OverlayActions {
+        let raw_input = self.state.take_egui_input(window);
+        let mut actions = OverlayActions::default();
+
+        let full_output = self.context.run(raw_input, |ctx| {
+            egui::Window::new("voxel-adventure").show(ctx, |ui| {
+                ui.label(format!("{:.1} fps", stats.fps));
+                for (scope, ms) in &stats.gpu_times_ms {
+                    ui.label(format!("{scope}: {ms:.2} ms"));
+                }
+                ui.separator();
+                ui.label(format!(
+                    "camera: ({:.1}, {:.1}, {:.1})",
+                    stats.camera_position.x, stats.camera_position.y, stats.camera_position.z,
+                ));
+                ui.label(format!("chunk nodes: {}", stats.chunk_node_count));
+                ui.label(format!("{}", stats.memory_report));
+                ui.separator();
+
+                let mut vsync = stats.vsync_enabled;
+                if ui.checkbox(&mut vsync, "vsync").changed() {
+                    actions.toggle_vsync = true;
+                }
+
+                let mut render_scale = stats.render_scale;
+                let slider = egui::Slider::new(&mut render_scale, 0.25..=2.0).text("render scale");
+                if ui.add(slider).changed() {
+                    actions.render_scale = Some(render_scale);
+                }
+
+                let mut supersample = stats.aa_mode == AaMode::SuperSample2x;
+                if ui.checkbox(&mut supersample, "supersample 2x (needs render scale 2.0)").changed() {
+                    actions.aa_mode = Some(if supersample { AaMode::SuperSample2x } else { AaMode::Native });
+                }
+
+                let mut fxaa = stats.fxaa_enabled;
+                if ui.checkbox(&mut fxaa, "FXAA").changed() {
+                    actions.fxaa_enabled = Some(fxaa);
+                }
+
+                let mut hdr = stats.hdr_enabled;
+                if ui.checkbox(&mut hdr, "HDR").changed() {
+                    actions.hdr_enabled = Some(hdr);
+                }
+
+                let mut srgb_conversion = stats.srgb_conversion_enabled;
+                if ui
+                    .checkbox(&mut srgb_conversion, "sRGB conversion (disable to A/B a non-sRGB surface)")
+                    .changed()
+                {
+                    actions.srgb_conversion_enabled = Some(srgb_conversion);
+                }
+
+                let mut exposure = stats.exposure;
+                let slider = egui::Slider::new(&mut exposure, 0.1..=8.0).text("exposure");
+                if ui.add(slider).changed() {
+                    actions.exposure = Some(exposure);
+                }
+
+                let mut operator = stats.tonemap_operator;
+                ui.horizontal(|ui| {
+                    ui.label("tonemap:");
+                    if ui.radio_value(&mut operator, TonemapOperator::None, "none").changed()
+                        || ui.radio_value(&mut operator, TonemapOperator::Reinhard, "reinhard").changed()
+                        || ui.radio_value(&mut operator, TonemapOperator::AcesApprox, "aces").changed()
+                    {
+                        actions.tonemap_operator = Some(operator);
+                    }
+                });
+
+                let mut azimuth = stats.sun_direction.z.atan2(stats.sun_direction.x).to_degrees();
+                let mut elevation = stats.sun_direction.y.asin().to_degrees();
+                let mut sun_color = [stats.sun_color.x, stats.sun_color.y, stats.sun_color.z];
+                let mut sun_changed = false;
+                sun_changed |= ui.add(egui::Slider::new(&mut azimuth, -180.0..=180.0).text("sun azimuth")).changed();
+                sun_changed |= ui.add(egui::Slider::new(&mut elevation, -10.0..=90.0).text("sun elevation")).changed();
+                sun_changed |= ui.color_edit_button_rgb(&mut sun_color).changed();
+                if sun_changed {
+                    let (az, el) = (azimuth.to_radians(), elevation.to_radians());
+                    let direction = glam::Vec3::new(el.cos() * az.cos(), el.sin(), el.cos() * az.sin());
+                    actions.sun = Some((direction, glam::Vec3::from(sun_color)));
+                }
+
+                ui.separator();
+                ui.label("fill lights:");
+                let mut light_enabled: Vec<bool> = (0..MAX_LIGHTS).map(|i| i < stats.lights.len()).collect();
+                let mut lights = stats.lights.clone();
+                lights.resize(
+                    MAX_LIGHTS,
+                    Light { direction: glam::Vec3::Y, color: glam::Vec3::ONE, intensity: 1.0, cast_shadows: true },
+                );
+                let mut lights_changed = false;
+                for i in 0..MAX_LIGHTS {
+                    ui.horizontal(|ui| {
+                        lights_changed |= ui.checkbox(&mut light_enabled[i], format!("light {i}")).changed();
+                        let mut azimuth = lights[i].direction.z.atan2(lights[i].direction.x).to_degrees();
+                        let mut elevation = lights[i].direction.y.asin().to_degrees();
+                        let mut color = [lights[i].color.x, lights[i].color.y, lights[i].color.z];
+                        let mut intensity = lights[i].intensity;
+                        let mut cast_shadows = lights[i].cast_shadows;
+                        lights_changed |= ui.add(egui::Slider::new(&mut azimuth, -180.0..=180.0).text("az")).changed();
+                        lights_changed |=
+                            ui.add(egui::Slider::new(&mut elevation, -90.0..=90.0).text("el")).changed();
+                        lights_changed |= ui.color_edit_button_rgb(&mut color).changed();
+                        lights_changed |=
+                            ui.add(egui::Slider::new(&mut intensity, 0.0..=4.0).text("intensity")).changed();
+                        lights_changed |= ui.checkbox(&mut cast_shadows, "shadow").changed();
+                        let (az, el) = (azimuth.to_radians(), elevation.to_radians());
+                        lights[i] = Light {
+                            direction: glam::Vec3::new(el.cos() * az.cos(), el.sin(), el.cos() * az.sin()),
+                            color: glam::Vec3::from(color),
+                            intensity,
+                            cast_shadows,
+                        };
+                    });
+                }
+                if lights_changed {
+                    actions.lights = Some(
+                        lights
+                            .into_iter()
+                            .zip(light_enabled)
+                            .filter(|(_, enabled)| *enabled)
+                            .map(|(light, _)| light)
+                            .collect(),
+                    );
+                }
+
+                let mut day_cycle = stats.day_cycle_enabled;
+                if ui.checkbox(&mut day_cycle, "day cycle").changed() {
+                    actions.day_cycle_enabled = Some(day_cycle);
+                }
+
+                let mut day_night_paused = stats.day_night_paused;
+                if ui.checkbox(&mut day_night_paused, "pause day/night clock").changed() {
+                    actions.day_night_paused = Some(day_night_paused);
+                }
+
+                let mut time_of_day = stats.time_of_day;
+                let slider = egui::Slider::new(&mut time_of_day, 0.0..=stats.day_length_seconds).text("time of day (s)");
+                if ui.add(slider).changed() {
+                    actions.time_of_day = Some(time_of_day);
+                }
+
+                let mut ao_enabled = stats.ao_enabled;
+                if ui.checkbox(&mut ao_enabled, "ambient occlusion").changed() {
+                    actions.ao_enabled = Some(ao_enabled);
+                }
+
+                let mut ao_settings = stats.ao_settings;
+                let mut ao_changed = false;
+                ao_changed |= ui
+                    .add(egui::Slider::new(&mut ao_settings.samples, 0..=8).text("AO samples"))
+                    .changed();
+                ao_changed |= ui
+                    .add(egui::Slider::new(&mut ao_settings.radius, 0.1..=4.0).text("AO radius"))
+                    .changed();
+                ao_changed |= ui
+                    .add(egui::Slider::new(&mut ao_settings.strength, 0.0..=1.0).text("AO strength"))
+                    .changed();
+                if ao_changed {
+                    actions.ao_settings = Some(ao_settings);
+                }
+
+                let mut accumulation = stats.accumulation_enabled;
+                if ui
+                    .checkbox(&mut accumulation, "progressive accumulation (static camera only)")
+                    .changed()
+                {
+                    actions.accumulation_enabled = Some(accumulation);
+                }
+
+                let mut sky = stats.sky;
+                let mut sky_changed = false;
+                let mut zenith = [sky.zenith_color.x, sky.zenith_color.y, sky.zenith_color.z];
+                let mut horizon = [sky.horizon_color.x, sky.horizon_color.y, sky.horizon_color.z];
+                let mut ground = [sky.ground_color.x, sky.ground_color.y, sky.ground_color.z];
+                ui.horizontal(|ui| {
+                    ui.label("sky:");
+                    sky_changed |= ui.color_edit_button_rgb(&mut zenith).changed();
+                    sky_changed |= ui.color_edit_button_rgb(&mut horizon).changed();
+                    sky_changed |= ui.color_edit_button_rgb(&mut ground).changed();
+                });
+                sky_changed |= ui.checkbox(&mut sky.sun_disc, "sun disc").changed();
+                if sky_changed {
+                    sky.zenith_color = glam::Vec3::from(zenith);
+                    sky.horizon_color = glam::Vec3::from(horizon);
+                    sky.ground_color = glam::Vec3::from(ground);
+                    actions.sky = Some(sky);
+                }
+
+                let mut debug_clear = stats.debug_clear;
+                if ui.checkbox(&mut debug_clear, "debug clear (red)").changed() {
+                    actions.debug_clear = Some(debug_clear);
+                }
+
+                let mut debug_view = stats.debug_view;
+                ui.horizontal(|ui| {
+                    ui.label("debug view:");
+                    if ui.radio_value(&mut debug_view, DebugView::None, "none").changed()
+                        || ui.radio_value(&mut debug_view, DebugView::Normals, "normals").changed()
+                        || ui.radio_value(&mut debug_view, DebugView::Depth, "depth").changed()
+                        || ui.radio_value(&mut debug_view, DebugView::Steps, "steps").changed()
+                        || ui.radio_value(&mut debug_view, DebugView::OctreeLevel, "octree level").changed()
+                        || ui.radio_value(&mut debug_view, DebugView::TileCost, "tile cost").changed()
+                    {
+                        actions.debug_view = Some(debug_view);
+                    }
+                });
+
+                let mut debug_far_plane = stats.debug_far_plane;
+                let slider = egui::Slider::new(&mut debug_far_plane, 1.0..=256.0).text("debug depth far plane");
+                if ui.add(slider).changed() {
+                    actions.debug_far_plane = Some(debug_far_plane);
+                }
+
+                if !stats.top_tile_costs.is_empty() {
+                    ui.label("costliest tiles:");
+                    for (origin, cost) in &stats.top_tile_costs {
+                        match origin {
+                            Some([x, y]) => ui.label(format!("  ({x}, {y}): {cost} steps")),
+                            None => ui.label(format!("  tile: {cost} steps")),
+                        };
+                    }
+                }
+
+                let mut highlight_enabled = stats.highlight_enabled;
+                if ui.checkbox(&mut highlight_enabled, "highlight picked voxel").changed() {
+                    actions.highlight_enabled = Some(highlight_enabled);
+                }
+
+                if ui.button("regenerate chunk").clicked() {
+                    actions.regenerate_chunk = true;
+                }
+            });
+        });
+
+        self.state.handle_platform_output(window, full_output.platform_output);
+        let clipped_primitives = self
+            .context
+            .tessellate(full_output.shapes, full_output.pixels_per_point);
+
+        for (id, delta) in &full_output.textures_delta.set {
+            self.renderer.update_texture(device, queue, *id, delta);
+        }
+
+        let screen_descriptor = egui_wgpu::ScreenDescriptor {
+            size_in_pixels: screen_size,
+            pixels_per_point: full_output.pixels_per_point,
+        };
+        self.renderer
+            .update_buffers(device, queue, encoder, &clipped_primitives, &screen_descriptor);
+
+        {
+            let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("egui_overlay_pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Load,
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+            self.renderer.render(&mut pass, &clipped_primitives, &screen_descriptor);
+        }
+
+        for id in &full_output.textures_delta.free {
+            self.renderer.free_texture(id);
+        }
+
+        actions
+    }
+}
@@ -0,0 +1,150 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::Arc;
+
+/// Memoizes `build`'s result per distinct `key`, handing out a shared `Arc`
+/// on every call after the first. Generic (rather than tied to a GPU type)
+/// so the memoization logic itself can be exercised by tests without a real
+/// `wgpu::Device`; [`PipelineCache`] is three of these, one per GPU object
+/// kind.
+struct MemoCache<K, V> {
+    entries: HashMap<K, Arc<V>>,
+}
+
+impl<K: Eq + Hash, V> MemoCache<K, V> {
+    fn new() -> Self {
+        Self { entries: HashMap::new() }
+    }
+
+    fn get_or_build(&mut self, key: K, build: impl FnOnce() -> V) -> Arc<V> {
+        if let Some(existing) = self.entries.get(&key) {
+            return existing.clone();
+        }
+        let value = Arc::new(build());
+        self.entries.insert(key, value.clone());
+        value
+    }
+}
+
+#[cfg(feature = "shader-hot-reload")]
+impl<V> MemoCache<String, V> {
+    fn retain_without_prefix(&mut self, prefix: &str) {
+        self.entries.retain(|key, _| !key.starts_with(prefix));
+    }
+}
+
+/// Owned by `Renderer` and threaded into each pass constructor, so shader
+/// modules and pipelines survive `rebuild_gpu_pipeline` (chunk regeneration,
+/// HDR toggle) instead of being recompiled every time a pass is
+/// reconstructed -- compiling a WGSL module is the slowest part of either
+/// path, and most reconstructions don't actually change the shader or the
+/// pipeline layout.
+///
+/// Callers key each entry with a `String` that folds in whatever varies
+/// about that particular pipeline (render target format, entry point, ...);
+/// the cache itself doesn't inspect `wgpu`'s descriptor types (they aren't
+/// `Hash`), so the caller is the only one who knows which parts of its own
+/// construction actually matter to the object's identity.
+///
+/// Doesn't wire up `wgpu::PipelineCache` (the driver-side blob
+/// `Device::create_pipeline_cache` would persist to disk): that API isn't
+/// available in the pinned `wgpu = "0.20"`. This covers the in-process half
+/// instead -- reusing an `Arc` across reconstructions within the same run --
+/// which a disk-backed cache wouldn't give for free anyway (the driver
+/// still has to re-link and validate against it).
+pub struct PipelineCache {
+    shader_modules: MemoCache<String, wgpu::ShaderModule>,
+    compute_pipelines: MemoCache<String, wgpu::ComputePipeline>,
+    render_pipelines: MemoCache<String, wgpu::RenderPipeline>,
+}
+
+impl PipelineCache {
+    pub fn new() -> Self {
+        Self {
+            shader_modules: MemoCache::new(),
+            compute_pipelines: MemoCache::new(),
+            render_pipelines: MemoCache::new(),
+        }
+    }
+
+    pub fn shader_module(
+        &mut self,
+        key: impl Into<String>,
+        build: impl FnOnce() -> wgpu::ShaderModule,
+    ) -> Arc<wgpu::ShaderModule> {
+        self.shader_modules.get_or_build(key.into(), build)
+    }
+
+    pub fn compute_pipeline(
+        &mut self,
+        key: impl Into<String>,
+        build: impl FnOnce() -> wgpu::ComputePipeline,
+    ) -> Arc<wgpu::ComputePipeline> {
+        self.compute_pipelines.get_or_build(key.into(), build)
+    }
+
+    pub fn render_pipeline(
+        &mut self,
+        key: impl Into<String>,
+        build: impl FnOnce() -> wgpu::RenderPipeline,
+    ) -> Arc<wgpu::RenderPipeline> {
+        self.render_pipelines.get_or_build(key.into(), build)
+    }
+
+    /// Drops every entry whose key starts with `prefix`, across all three
+    /// maps, so the next matching `shader_module`/`*_pipeline` call rebuilds
+    /// instead of returning the stale `Arc`. Passes key their shader module
+    /// and pipeline(s) with a shared prefix (e.g. `"voxel_renderer_"`) for
+    /// exactly this -- see `Renderer::poll_shader_reload`.
+    #[cfg(feature = "shader-hot-reload")]
+    pub fn invalidate_prefix(&mut self, prefix: &str) {
+        self.shader_modules.retain_without_prefix(prefix);
+        self.compute_pipelines.retain_without_prefix(prefix);
+        self.render_pipelines.retain_without_prefix(prefix);
+    }
+}
+
+impl Default for PipelineCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn get_or_build_calls_build_once_per_key() {
+        let mut cache: MemoCache<String, u32> = MemoCache::new();
+        let mut build_calls = 0;
+        let first = cache.get_or_build("a".to_string(), || {
+            build_calls += 1;
+            1
+        });
+        let second = cache.get_or_build("a".to_string(), || {
+            build_calls += 1;
+            2
+        });
+        assert_eq!(*first, 1);
+        assert_eq!(*second, 1);
+        assert_eq!(build_calls, 1);
+    }
+
+    #[test]
+    fn get_or_build_rebuilds_for_a_distinct_key() {
+        let mut cache: MemoCache<String, u32> = MemoCache::new();
+        let a = cache.get_or_build("a".to_string(), || 1);
+        let b = cache.get_or_build("b".to_string(), || 2);
+        assert_eq!(*a, 1);
+        assert_eq!(*b, 2);
+    }
+
+    #[test]
+    fn repeated_lookups_hand_out_the_same_underlying_allocation() {
+        let mut cache: MemoCache<String, u32> = MemoCache::new();
+        let first = cache.get_or_build("a".to_string(), || 1);
+        let second = cache.get_or_build("a".to_string(), || 1);
+        assert!(Arc::ptr_eq(&first, &second));
+    }
+}
@@ -0,0 +1,159 @@
+use std::io;
+use std::path::Path;
+use std::time::Instant;
+
+/// A single labeled interval of CPU or GPU work, in the shape Chrome's
+/// "complete event" (`ph: "X"`) trace format wants.
+#[derive(Debug, Clone, PartialEq)]
+struct Span {
+    name: String,
+    category: &'static str,
+    start_us: u64,
+    duration_us: u64,
+}
+
+impl Span {
+    fn to_json(&self) -> String {
+        format!(
+            r#"{{"name":"{}","cat":"{}","ph":"X","ts":{},"dur":{},"pid":1,"tid":{}}}"#,
+            self.name,
+            self.category,
+            self.start_us,
+            self.duration_us,
+            if self.category == "gpu" { 1 } else { 0 },
+        )
+    }
+}
+
+/// Records CPU and GPU spans for up to `max_frames` frames, then can export
+/// them as a chrome://tracing / Perfetto compatible JSON array. Bounded so
+/// leaving recording on doesn't grow memory forever.
+pub struct ProfileSession {
+    epoch: Instant,
+    max_frames: usize,
+    frames_recorded: usize,
+    spans: Vec<Span>,
+}
+
+impl ProfileSession {
+    pub fn start(max_frames: usize) -> Self {
+        Self {
+            epoch: Instant::now(),
+            max_frames: max_frames.max(1),
+            frames_recorded: 0,
+            spans: Vec::new(),
+        }
+    }
+
+    /// Whether the frame cap has been reached; once `false`, `Renderer` drops
+    /// the session (after exporting it) instead of calling into it further.
+    pub fn is_recording(&self) -> bool {
+        self.frames_recorded < self.max_frames
+    }
+
+    /// Starts a CPU span named `name` (e.g. `"encode"`, `"submit"`, `"map"`,
+    /// `"present"`); the span is recorded when the returned guard drops.
+    pub fn begin_cpu_span(&mut self, name: &'static str) -> SpanGuard<'_> {
+        SpanGuard {
+            spans: &mut self.spans,
+            epoch: self.epoch,
+            name,
+            category: "cpu",
+            start: Instant::now(),
+        }
+    }
+
+    /// Records a GPU span already timed by `GpuTimer`. GPU results lag one
+    /// frame behind (see `GpuTimer::read_back`), so `frame_start` is the
+    /// wall-clock start of the frame the timing is reported for, not
+    /// necessarily the frame just rendered.
+    pub fn record_gpu_span(&mut self, name: &str, frame_start: Instant, duration_ms: f32) {
+        self.spans.push(Span {
+            name: name.to_string(),
+            category: "gpu",
+            start_us: (frame_start.saturating_duration_since(self.epoch)).as_micros() as u64,
+            duration_us: (duration_ms.max(0.0) * 1000.0) as u64,
+        });
+    }
+
+    /// Marks one frame as complete, counting it against `max_frames`.
+    pub fn end_frame(&mut self) {
+        self.frames_recorded += 1;
+    }
+
+    /// Serializes every recorded span as a Chrome/Perfetto trace-event JSON
+    /// array and writes it to `path`.
+    pub fn write_chrome_trace(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let events: Vec<String> = self.spans.iter().map(Span::to_json).collect();
+        std::fs::write(path, format!("[{}]", events.join(",")))
+    }
+}
+
+/// Records its span's duration into the owning [`ProfileSession`] on drop.
+pub struct SpanGuard<'a> {
+    spans: &'a mut Vec<Span>,
+    epoch: Instant,
+    name: &'static str,
+    category: &'static str,
+    start: Instant,
+}
+
+impl Drop for SpanGuard<'_> {
+    fn drop(&mut self) {
+        let end = Instant::now();
+        self.spans.push(Span {
+            name: self.name.to_string(),
+            category: self.category,
+            start_us: (self.start.saturating_duration_since(self.epoch)).as_micros() as u64,
+            duration_us: (end - self.start).as_micros() as u64,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_recorded_span_appears_in_the_exported_trace() {
+        let mut session = ProfileSession::start(1);
+        {
+            let _span = session.begin_cpu_span("encode");
+        }
+        session.record_gpu_span("voxel_compute", session.epoch, 2.5);
+        session.end_frame();
+
+        let path = std::env::temp_dir().join("gpu_timer_test_trace.json");
+        session.write_chrome_trace(&path).unwrap();
+        let json = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert!(json.starts_with('['));
+        assert!(json.ends_with(']'));
+        assert!(json.contains(r#""name":"encode""#));
+        assert!(json.contains(r#""cat":"cpu""#));
+        assert!(json.contains(r#""name":"voxel_compute""#));
+        assert!(json.contains(r#""cat":"gpu""#));
+        assert!(json.contains(r#""dur":2500"#));
+    }
+
+    #[test]
+    fn recording_stops_once_the_frame_cap_is_reached() {
+        let mut session = ProfileSession::start(2);
+        assert!(session.is_recording());
+        session.end_frame();
+        assert!(session.is_recording());
+        session.end_frame();
+        assert!(!session.is_recording());
+    }
+
+    #[test]
+    fn empty_session_exports_an_empty_json_array() {
+        let session = ProfileSession::start(5);
+        let path = std::env::temp_dir().join("gpu_timer_test_empty_trace.json");
+        session.write_chrome_trace(&path).unwrap();
+        let json = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+        assert_eq!(json, "[]");
+    }
+}
@@ -0,0 +1,97 @@
+/// How `App` decides when to ask the window for another frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RedrawPolicy {
+    /// Redraw every time the event loop goes idle -- simple, but wastes
+    /// power redrawing an unchanged scene.
+    Continuous,
+    /// Only redraw once something marks the frame dirty (input, a finished
+    /// chunk load, an edit, a resize/expose). Suited to viewer/editor
+    /// workloads where most frames would otherwise be identical.
+    OnDemand,
+}
+
+/// Tracks whether another redraw is owed, given the active [`RedrawPolicy`].
+/// `Continuous` always owes one; `OnDemand` only after `mark_dirty` until the
+/// next `consume_redraw_request`.
+pub struct RedrawScheduler {
+    policy: RedrawPolicy,
+    dirty: bool,
+}
+
+impl RedrawScheduler {
+    pub fn new(policy: RedrawPolicy) -> Self {
+        // Dirty by default so the first frame always renders, regardless of
+        // policy.
+        Self { policy, dirty: true }
+    }
+
+    pub fn set_policy(&mut self, policy: RedrawPolicy) {
+        self.policy = policy;
+    }
+
+    /// Called by streaming/edit/input paths when something changed that
+    /// needs a new frame.
+    pub fn mark_dirty(&mut self) {
+        self.dirty = true;
+    }
+
+    /// Whether `App` should call `window.request_redraw()` right now.
+    pub fn wants_redraw(&self) -> bool {
+        match self.policy {
+            RedrawPolicy::Continuous => true,
+            RedrawPolicy::OnDemand => self.dirty,
+        }
+    }
+
+    /// Call once a redraw has actually been requested, so `OnDemand` doesn't
+    /// keep re-requesting until marked dirty again.
+    pub fn consume_redraw_request(&mut self) {
+        if self.policy == RedrawPolicy::OnDemand {
+            self.dirty = false;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn continuous_always_wants_a_redraw() {
+        let mut s = RedrawScheduler::new(RedrawPolicy::Continuous);
+        s.consume_redraw_request();
+        assert!(s.wants_redraw());
+    }
+
+    #[test]
+    fn on_demand_with_no_activity_yields_zero_redraw_requests() {
+        let mut s = RedrawScheduler::new(RedrawPolicy::OnDemand);
+        s.consume_redraw_request(); // consume the initial "always render once"
+
+        for _ in 0..10 {
+            assert!(!s.wants_redraw());
+        }
+    }
+
+    #[test]
+    fn on_demand_with_a_single_edit_yields_exactly_one_redraw_request() {
+        let mut s = RedrawScheduler::new(RedrawPolicy::OnDemand);
+        s.consume_redraw_request();
+
+        s.mark_dirty();
+        assert!(s.wants_redraw());
+        s.consume_redraw_request();
+
+        for _ in 0..10 {
+            assert!(!s.wants_redraw());
+        }
+    }
+
+    #[test]
+    fn switching_to_on_demand_does_not_immediately_clear_pending_dirt() {
+        let mut s = RedrawScheduler::new(RedrawPolicy::Continuous);
+        s.mark_dirty();
+        s.set_policy(RedrawPolicy::OnDemand);
+        assert!(s.wants_redraw());
+    }
+}
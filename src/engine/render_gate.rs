@@ -0,0 +1,112 @@
+/// Tracks whether the renderer should be doing any work at all. A zero-area
+/// surface (window minimized) or an occluded window can't be rendered to
+/// (or shouldn't be, to save power), so we gate `render`/`request_redraw`
+/// behind this instead of special-casing it at every call site.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum RenderGate {
+    #[default]
+    Active,
+    /// Rendering is paused; `reason` records why so `resume_if` calls that
+    /// don't apply (e.g. an occlusion event while minimized) are no-ops.
+    Suspended { reason: SuspendReason },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SuspendReason {
+    ZeroSize,
+    Occluded,
+    /// Window lost focus (or was minimized) under `background_behavior =
+    /// "pause"`; see `engine::background::BackgroundMode::Paused`.
+    Unfocused,
+    /// OS-level suspend (Android `onPause`, or surface loss under some
+    /// Wayland compositors); see `Renderer::suspend`.
+    Lifecycle,
+}
+
+impl RenderGate {
+    pub fn is_active(self) -> bool {
+        matches!(self, RenderGate::Active)
+    }
+
+    pub fn suspend(&mut self, reason: SuspendReason) {
+        *self = RenderGate::Suspended { reason };
+    }
+
+    /// Resumes only if currently suspended for exactly this reason, so an
+    /// `Occluded(false)` doesn't wake a window that's still minimized (and
+    /// vice versa).
+    pub fn resume_if(&mut self, reason: SuspendReason) {
+        if *self == (RenderGate::Suspended { reason }) {
+            *self = RenderGate::Active;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn starts_active() {
+        assert!(RenderGate::default().is_active());
+    }
+
+    #[test]
+    fn zero_size_suspends_and_resumes_on_zero_size_resume() {
+        let mut gate = RenderGate::default();
+        gate.suspend(SuspendReason::ZeroSize);
+        assert!(!gate.is_active());
+        gate.resume_if(SuspendReason::ZeroSize);
+        assert!(gate.is_active());
+    }
+
+    #[test]
+    fn occlusion_resume_does_not_wake_a_minimized_window() {
+        let mut gate = RenderGate::default();
+        gate.suspend(SuspendReason::ZeroSize);
+        gate.resume_if(SuspendReason::Occluded);
+        assert!(!gate.is_active());
+    }
+
+    #[test]
+    fn size_resume_does_not_wake_an_occluded_window() {
+        let mut gate = RenderGate::default();
+        gate.suspend(SuspendReason::Occluded);
+        gate.resume_if(SuspendReason::ZeroSize);
+        assert!(!gate.is_active());
+    }
+
+    #[test]
+    fn unfocused_suspends_and_resumes_on_focus_resume() {
+        let mut gate = RenderGate::default();
+        gate.suspend(SuspendReason::Unfocused);
+        assert!(!gate.is_active());
+        gate.resume_if(SuspendReason::Unfocused);
+        assert!(gate.is_active());
+    }
+
+    #[test]
+    fn focus_resume_does_not_wake_a_minimized_window() {
+        let mut gate = RenderGate::default();
+        gate.suspend(SuspendReason::ZeroSize);
+        gate.resume_if(SuspendReason::Unfocused);
+        assert!(!gate.is_active());
+    }
+
+    #[test]
+    fn lifecycle_suspends_and_resumes_on_lifecycle_resume() {
+        let mut gate = RenderGate::default();
+        gate.suspend(SuspendReason::Lifecycle);
+        assert!(!gate.is_active());
+        gate.resume_if(SuspendReason::Lifecycle);
+        assert!(gate.is_active());
+    }
+
+    #[test]
+    fn lifecycle_resume_does_not_wake_a_minimized_window() {
+        let mut gate = RenderGate::default();
+        gate.suspend(SuspendReason::ZeroSize);
+        gate.resume_if(SuspendReason::Lifecycle);
+        assert!(!gate.is_active());
+    }
+}
@@ -0,0 +1,2819 @@
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use winit::window::Window;
+
+use super::aa_mode::AaMode;
+use super::accumulation::AccumulationState;
+use super::camera_path::{CameraPath, CameraPose};
+use super::daynight::DayNightCycle;
+use super::device_lost::DeviceLostFlag;
+use super::gpu_timer::GpuTimer;
+use super::memory_report::GpuMemoryReport;
+#[cfg(feature = "debug-overlay")]
+use super::overlay::{Overlay, OverlayStats};
+use super::pipeline_cache::PipelineCache;
+use super::profiling::ProfileSession;
+use super::redraw_policy::{RedrawPolicy, RedrawScheduler};
+use super::render_gate::{RenderGate, SuspendReason};
+use super::resize_debounce::ResizeDebounce;
+use super::texture_pool::TexturePool;
+use super::scene::SceneDescription;
+use super::sim_clock::SimClock;
+#[cfg(feature = "shader-hot-reload")]
+use super::shader_watcher::{self, ShaderWatcher};
+use super::timestep::FixedTimestep;
+use super::upload_context::UploadContext;
+use crate::voxel::render_texture::decode_gbuffer_texel;
+use crate::voxel::{
+    gamma_for, AoSettings, BlitFilter, Camera, Chunk, CullFrameParams, CullPass, CullStats, DebugView, FitMode,
+    FrameParams, FxaaPass, GBufferPixel, GizmoPass, Light, PassCreationError, PickResult, PickTicket, RenderTexture,
+    SkySettings, TonemapOperator, VoxelImageRenderingPass, VoxelRendererPass, WorkgroupSize, MAX_LIGHTS,
+};
+use crate::voxel::passes::PUSH_CONSTANTS_SIZE;
+
+/// Construction-time failure from [`Renderer::new`]/[`Renderer::new_headless`].
+/// Currently just a shader that failed to compile in one of the GPU passes
+/// `build_gpu_resources` assembles; kept as its own enum (rather than handing
+/// back `PassCreationError` directly) so later construction failures that
+/// aren't a `PassCreationError` have somewhere to go without changing
+/// `Renderer::new`'s signature again.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RendererError {
+    PassCreation(PassCreationError),
+}
+
+impl std::fmt::Display for RendererError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::PassCreation(err) => write!(f, "renderer construction failed: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for RendererError {}
+
+impl From<PassCreationError> for RendererError {
+    fn from(err: PassCreationError) -> Self {
+        Self::PassCreation(err)
+    }
+}
+
+/// Tunables for [`Renderer::new`]. Kept separate from `Renderer` itself so
+/// callers can construct one before the GPU device exists.
+#[derive(Debug, Clone)]
+pub struct RendererOptions {
+    pub width: u32,
+    pub height: u32,
+    /// Graphics APIs `Instance::new`/`enumerate_adapters`/`--list-adapters`
+    /// are allowed to pick an adapter from; see `--backend` in `main.rs`.
+    pub backends: wgpu::Backends,
+    /// Requested present mode. Validated against the surface's supported
+    /// modes at configure time and falls back to `AutoVsync` if unsupported.
+    pub present_mode: wgpu::PresentMode,
+    pub desired_maximum_frame_latency: u32,
+    /// Case-insensitive substring match against `AdapterSummary::name`. Wins
+    /// over `PowerPreference` when set, since hybrid-graphics laptops don't
+    /// reliably honor the latter.
+    pub preferred_adapter_name: Option<String>,
+    /// Force the CPU/software adapter (lavapipe, WARP) instead of a real GPU.
+    /// Mainly for running the renderer headless in CI.
+    pub force_fallback_adapter: bool,
+    /// Caps the frame rate independent of vsync, via [`super::frame_limiter::FrameLimiter`]
+    /// in `App`. Whichever of this and the vsync-driven present rate is
+    /// lower ends up pacing the app -- the limiter is a no-op once a frame
+    /// already took longer than the cap allows.
+    pub max_fps: Option<u32>,
+    /// Whether `App` redraws every idle tick or only when [`Renderer::mark_dirty`]
+    /// has been called since the last frame.
+    pub redraw_policy: RedrawPolicy,
+    /// Ray-marches at `surface_size * render_scale` and upscales to the
+    /// swapchain, so the (linear-in-pixel-count) compute pass cost can be
+    /// traded against image quality. `1.0` = native, clamped to `0.25..=2.0`.
+    pub render_scale: f32,
+    /// Octree depth the initial chunk (and any `regenerate_chunk` call) is
+    /// generated at; `2^chunk_depth` voxels per side. See
+    /// `voxel::chunk::depth_from_size` for turning a `--chunk-size` voxel
+    /// count into this.
+    pub chunk_depth: u32,
+    /// Filter used to resize `render_texture` to the swapchain when
+    /// `render_scale != 1.0`. `SuperSample2x` only takes effect at
+    /// `render_scale == 2.0`; see `Renderer::blit_filter`.
+    pub aa_mode: AaMode,
+    /// Whether the FXAA pass runs on `render_texture` before the final blit.
+    pub fxaa_enabled: bool,
+    /// Whether `render_texture` uses `RenderTexture::FORMAT_HDR` (letting
+    /// lighting exceed `[0, 1]` for the tonemap pass to compress) instead of
+    /// `FORMAT_LDR`.
+    pub hdr_enabled: bool,
+    /// Multiplies the source color before tonemapping.
+    pub exposure: f32,
+    /// Curve `rendering.wgsl` uses to bring the source color into
+    /// displayable range.
+    pub tonemap_operator: TonemapOperator,
+    /// Initial normalized direction *toward* the sun; see `Renderer::set_sun`.
+    pub sun_direction: glam::Vec3,
+    /// Initial sun color, multiplied into lit (non-shadowed) surfaces.
+    pub sun_color: glam::Vec3,
+    /// How many times a ray that keeps hitting reflective (`Voxel::MIRROR`-style)
+    /// materials bounces before the shader gives up; see `Renderer::set_max_bounces`.
+    pub max_bounces: u32,
+    /// Whether the compute shader's hemisphere AO probes run at all; see
+    /// `Renderer::set_ao_enabled`.
+    pub ao_enabled: bool,
+    /// Sample count/radius/strength for the AO probes, used whenever
+    /// `ao_enabled` is `true`.
+    pub ao_settings: AoSettings,
+    /// Whether the blit pass reads the progressive accumulation buffer
+    /// instead of `render_texture`/the FXAA output; see
+    /// `Renderer::set_accumulation_enabled`. Off by default since it only
+    /// helps once the camera stops moving, and briefly shows the previous
+    /// static view's ghost for one blend step after any change.
+    pub accumulation_enabled: bool,
+    /// Gradient/disc drawn where a ray misses the chunk entirely; see
+    /// `Renderer::set_sky`.
+    pub sky: SkySettings,
+    /// Forces the blit pass's clear color to solid red instead of black,
+    /// so a broken sky/blit source is obviously wrong rather than silently
+    /// matching the letterboxing color. Off by default.
+    pub debug_clear: bool,
+    /// Which (if any) traversal-diagnostic visualization the compute shader
+    /// writes instead of shaded color; see `Renderer::set_debug_view`.
+    pub debug_view: DebugView,
+    /// Far plane the `DebugView::Depth` visualization normalizes hit
+    /// distance against.
+    pub debug_far_plane: f32,
+    /// Whether the last voxel resolved by `Renderer::pick` is outlined in
+    /// the rendered image; see `Renderer::set_highlight_enabled`.
+    pub highlight_enabled: bool,
+    /// Compute dispatch tile size for the ray-marching shader. `None` picks
+    /// [`WorkgroupSize::occupancy_default`] from the selected adapter's
+    /// `max_compute_invocations_per_workgroup`; set explicitly to benchmark
+    /// a specific size (see `--bench-workgroup-sizes`).
+    pub workgroup_size: Option<WorkgroupSize>,
+    /// Splits the compute dispatch into `tile_size`x`tile_size`-pixel tiles,
+    /// each its own compute pass within the frame's encoder, instead of one
+    /// dispatch covering the whole render target. `None` (the default)
+    /// dispatches the whole image in one pass; set it (e.g. `512`) on
+    /// weaker GPUs where a single dispatch at high resolution risks a TDR
+    /// timeout, or to let other queued GPU work interleave between tiles.
+    /// See `VoxelRendererPass::compute_with_pass`.
+    pub tile_size: Option<u32>,
+    /// Never requests `Features::PUSH_CONSTANTS`, even when the adapter
+    /// supports it, so the uniform-buffer fallback path in
+    /// `VoxelRendererPass` can be exercised on hardware that would
+    /// otherwise take the push-constant path. Off by default.
+    pub force_disable_push_constants: bool,
+    /// Runs `CullPass` ahead of the main compute dispatch and drives it via
+    /// `VoxelRendererPass::compute_with_indirect_pass` instead of the usual
+    /// `compute_with_pass`, so tiles the loaded chunk's bounds can't
+    /// possibly occupy are skipped rather than ray-marched and immediately
+    /// missing. Off by default, since it costs one extra pre-pass even on a
+    /// frame where every tile turns out visible.
+    pub gpu_culling_enabled: bool,
+    /// Whether the blit pass applies `gamma_for`'s shader-side sRGB
+    /// approximation on a surface that has no sRGB-capable format or
+    /// `view_formats` companion at all. On by default (the correct
+    /// behavior); see `Renderer::set_srgb_conversion_enabled` for the debug
+    /// A/B use of turning it off.
+    pub srgb_conversion_enabled: bool,
+    /// How the blit pass maps the render texture onto the swapchain when
+    /// their aspect ratios diverge (a non-`1.0` `render_scale` applied
+    /// non-uniformly, or a window resize before the render texture catches
+    /// up); see `Renderer::set_fit_mode`.
+    pub fit_mode: FitMode,
+}
+
+impl Default for RendererOptions {
+    fn default() -> Self {
+        Self {
+            width: 1280,
+            height: 720,
+            backends: wgpu::Backends::all(),
+            present_mode: wgpu::PresentMode::AutoVsync,
+            desired_maximum_frame_latency: 2,
+            preferred_adapter_name: None,
+            force_fallback_adapter: false,
+            max_fps: None,
+            redraw_policy: RedrawPolicy::Continuous,
+            render_scale: 1.0,
+            chunk_depth: crate::voxel::chunk::CHUNK_DEPTH,
+            aa_mode: AaMode::default(),
+            fxaa_enabled: false,
+            hdr_enabled: false,
+            exposure: 1.0,
+            tonemap_operator: TonemapOperator::default(),
+            sun_direction: DEFAULT_SUN_DIRECTION,
+            sun_color: glam::Vec3::new(1.0, 0.96, 0.9),
+            max_bounces: 1,
+            ao_enabled: true,
+            ao_settings: AoSettings::default(),
+            accumulation_enabled: false,
+            sky: SkySettings::default(),
+            debug_clear: false,
+            debug_view: DebugView::None,
+            debug_far_plane: 64.0,
+            highlight_enabled: true,
+            workgroup_size: None,
+            tile_size: None,
+            force_disable_push_constants: false,
+            gpu_culling_enabled: false,
+            srgb_conversion_enabled: true,
+            fit_mode: FitMode::default(),
+        }
+    }
+}
+
+/// Late-afternoon-ish angle, mostly overhead with a bit of a side rake so
+/// shadows are visible rather than pooling straight down.
+const DEFAULT_SUN_DIRECTION: glam::Vec3 = glam::Vec3::new(0.4, 0.7, 0.3);
+
+/// How many simulated seconds `DayNightCycle` takes to complete a full
+/// day/night loop when `day_cycle_enabled`; short enough to see the whole
+/// dawn/day/dusk/night sweep in a couple of minutes of watching.
+const DAY_CYCLE_LENGTH_SECONDS: f32 = 120.0;
+
+/// Voxels of `Voxel::WATER` stacked above the test pattern's floor; see
+/// `Chunk::filled_test_pattern_with_water`.
+const TEST_PATTERN_WATER_DEPTH: u32 = 4;
+
+/// Cap on how much `render_texture_pool` retains across resizes: enough for
+/// a couple of HDR render textures at a typical desktop resolution (e.g.
+/// two 1920x1088 `Rgba16Float` textures are ~17 MB each) without letting a
+/// session that's been resized through many different window sizes hold
+/// every one of them forever.
+const RENDER_TEXTURE_POOL_BYTES: u64 = 128 * 1024 * 1024;
+
+/// Human-readable snapshot of an adapter, independent of the `wgpu::Adapter`
+/// handle so it can be logged, filtered and unit-tested without a GPU.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AdapterSummary {
+    pub name: String,
+    pub backend: wgpu::Backend,
+    pub device_type: wgpu::DeviceType,
+    pub driver: String,
+    pub driver_info: String,
+}
+
+impl From<wgpu::AdapterInfo> for AdapterSummary {
+    fn from(info: wgpu::AdapterInfo) -> Self {
+        Self {
+            name: info.name,
+            backend: info.backend,
+            device_type: info.device_type,
+            driver: info.driver,
+            driver_info: info.driver_info,
+        }
+    }
+}
+
+impl AdapterSummary {
+    /// Case-insensitive substring match, used for `preferred_adapter_name`.
+    fn name_matches(&self, needle: &str) -> bool {
+        self.name.to_lowercase().contains(&needle.to_lowercase())
+    }
+}
+
+/// Owns the GPU device/surface, the voxel compute/blit passes, and draws a
+/// frame: ray-march the active chunk into a `RenderTexture`, then blit it to
+/// the swapchain.
+/// A surface-less swapchain stand-in used by `Renderer::new_headless`
+/// (benchmarks, golden-image tests): a plain render-attachment texture that
+/// nothing ever presents.
+struct OffscreenTarget {
+    view: wgpu::TextureView,
+    width: u32,
+    height: u32,
+}
+
+impl OffscreenTarget {
+    /// Size of the underlying texture in bytes, for `GpuMemoryReport`.
+    fn byte_size(&self) -> u64 {
+        const BYTES_PER_TEXEL: u64 = 4; // HEADLESS_FORMAT is Rgba8UnormSrgb
+        self.width as u64 * self.height as u64 * BYTES_PER_TEXEL
+    }
+}
+
+pub struct Renderer {
+    /// Kept around (rather than just consumed by `create_surface`) so
+    /// `recreate_gpu_state` can build a fresh surface after device loss.
+    /// `None` in headless mode, where there's no window to recreate from.
+    window: Option<Arc<Window>>,
+    instance: wgpu::Instance,
+    /// `None` in headless mode (see `new_headless`); `render` then draws
+    /// into `offscreen` instead of a real swapchain and skips `present`.
+    surface: Option<wgpu::Surface<'static>>,
+    offscreen: Option<OffscreenTarget>,
+    adapter: wgpu::Adapter,
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    device_lost: DeviceLostFlag,
+    config: wgpu::SurfaceConfiguration,
+    /// Format the blit pass's pipeline and the presentation view actually
+    /// target; equal to `config.format` unless `resolve_surface_format` had
+    /// to add an sRGB `view_formats` companion because the adapter offered
+    /// no sRGB-capable format directly. See `render`'s `surface_view`
+    /// construction and `gamma_for`.
+    color_target_format: wgpu::TextureFormat,
+    options: RendererOptions,
+    /// Present mode actually in effect (may differ from `options.present_mode`
+    /// right after `set_present_mode` until the next `configure`).
+    active_present_mode: wgpu::PresentMode,
+    gate: RenderGate,
+    redraw: RedrawScheduler,
+    /// Coalesces `request_resize` calls (one per window `Resized` event)
+    /// into a single `resize` applied at the start of the next `render`.
+    resize_debounce: ResizeDebounce,
+    /// Retains `render_texture`'s old backing texture across a resize so
+    /// `rebuild_render_target` can hand it straight back out on the next
+    /// similarly-sized resize instead of asking the device for a fresh
+    /// allocation every time.
+    render_texture_pool: TexturePool<wgpu::Texture>,
+
+    render_texture: RenderTexture,
+    voxel_renderer_pass: VoxelRendererPass,
+    cull_pass: CullPass,
+    fxaa_pass: FxaaPass,
+    voxel_image_pass: VoxelImageRenderingPass,
+    /// Draws the crosshair as real line geometry over the blit's output,
+    /// with an optional MSAA intermediate; sized to the swapchain rather
+    /// than `render_texture`, so it's rebuilt on `resize` rather than
+    /// `rebuild_render_target`. See `GizmoPass`.
+    gizmo_pass: GizmoPass,
+    /// Shader modules and pipelines reused across `rebuild_gpu_pipeline`
+    /// (chunk regen, HDR toggle) instead of being recompiled every time;
+    /// cleared in `recreate_gpu_state` since a lost device invalidates
+    /// everything cached against it. See `PipelineCache`.
+    pipeline_cache: PipelineCache,
+    /// `None` when the adapter doesn't support `Features::TIMESTAMP_QUERY`
+    /// (e.g. some software rasterizers), in which case per-pass GPU timings
+    /// are simply unavailable rather than an error.
+    gpu_timer: Option<GpuTimer>,
+    /// Staging belt backing this frame's uniform/storage writes (camera
+    /// uniform, tile offsets, cull pass buffers); see `render`.
+    upload_context: UploadContext,
+    /// `Some` while `App`'s F3 key has an active profiling capture running;
+    /// see `start_profiling`/`export_profiling`.
+    profiler: Option<ProfileSession>,
+    /// Scales `render_texture`'s resolution relative to the swapchain, so the
+    /// compute pass can run at less than native resolution. `1.0` = native.
+    render_scale: f32,
+    /// Which filter resizes `render_texture` to the swapchain; see
+    /// `blit_filter`.
+    aa_mode: AaMode,
+    /// Compute dispatch tile size baked into `voxel_renderer_pass`'s shader
+    /// and pipeline; resolved once from `options.workgroup_size` (or the
+    /// adapter's limits) and carried across `rebuild_gpu_pipeline` calls so
+    /// a chunk regen or HDR toggle doesn't silently change it.
+    workgroup_size: WorkgroupSize,
+    /// `options.tile_size`, carried on `Renderer` so `render()` can pass it
+    /// to `compute_with_pass` every frame; unlike `workgroup_size` this
+    /// isn't baked into any pipeline, so changing it doesn't need a rebuild.
+    tile_size: Option<u32>,
+    /// `options.gpu_culling_enabled`, carried on `Renderer` so `render()`
+    /// can decide between `compute_with_pass` and the `cull_pass` +
+    /// `compute_with_indirect_pass` sequence every frame.
+    gpu_culling_enabled: bool,
+    /// Latest `cull_pass.poll_stats` reading, fed into `FrameStats` via
+    /// `culled_tiles`; `None` when `gpu_culling_enabled` is off.
+    culled_tiles: Option<CullStats>,
+    /// `(bytes, cpu_ms)` from the most recent frame's upload encoder --
+    /// `voxel_renderer_pass.update_uniforms` plus, when culling is on,
+    /// `cull_pass.cull_upload`; see `render`'s `upload_encoder` and
+    /// `upload_stats`.
+    last_upload_stats: (u64, f32),
+    /// Whether `render()` runs the FXAA pass and the blit pass samples its
+    /// output instead of `render_texture` directly.
+    fxaa_enabled: bool,
+    /// Whether `render_texture` is currently `RenderTexture::FORMAT_HDR`;
+    /// see `render_texture_format`.
+    hdr_enabled: bool,
+    /// Current tonemap exposure multiplier; see `set_exposure`.
+    exposure: f32,
+    /// Current tonemap curve; see `set_tonemap_operator`.
+    tonemap_operator: TonemapOperator,
+    /// Whether the blit pass applies `gamma_for`'s shader-side sRGB
+    /// approximation when `color_target_format` isn't sRGB; see
+    /// `set_srgb_conversion_enabled`.
+    srgb_conversion_enabled: bool,
+    /// How the blit pass maps `render_texture` onto the swapchain when their
+    /// aspect ratios diverge; see `set_fit_mode`.
+    fit_mode: FitMode,
+    /// Window scale factor, `1.0` in headless mode; see `set_scale_factor`.
+    scale_factor: f32,
+    /// Normalized direction toward the sun; see `set_sun`.
+    sun_direction: glam::Vec3,
+    /// Current sun color; see `set_sun`.
+    sun_color: glam::Vec3,
+    /// Extra shadow-casting fill lights beyond the sun, at most `MAX_LIGHTS`
+    /// long; see `set_lights`.
+    lights: Vec<Light>,
+    /// How many times a ray that keeps hitting reflective materials bounces;
+    /// see `set_max_bounces`.
+    max_bounces: u32,
+    /// Whether the AO probes run this frame; see `set_ao_enabled`.
+    ao_enabled: bool,
+    /// Current AO sample count/radius/strength; see `set_ao_settings`.
+    ao_settings: AoSettings,
+    /// Whether the blit pass currently reads the accumulation buffer; see
+    /// `set_accumulation_enabled`.
+    accumulation_enabled: bool,
+    /// How many consecutive static frames have blended into the
+    /// accumulation buffer so far.
+    accumulation: AccumulationState,
+    /// Per-frame counter written into the compute shader's uniforms;
+    /// wraps rather than saturates since nothing currently depends on it
+    /// not repeating.
+    frame_index: u32,
+    /// Camera position/forward direction as of the last rendered frame, to
+    /// detect movement and reset `accumulation`. `None` before the first
+    /// frame.
+    last_accumulation_camera: Option<(glam::Vec3, glam::Vec3)>,
+    /// Whether `step_simulation` drives `sun_direction`/`sun_color`/`sky`
+    /// from `day_night` over time; see `set_day_cycle_enabled`.
+    day_cycle_enabled: bool,
+    /// Time-of-day clock and dawn/day/dusk/night keyframe sampling; see
+    /// `engine::daynight`.
+    day_night: DayNightCycle,
+    /// A scripted camera path currently driving `camera` from
+    /// `step_simulation`, and how far into it playback has advanced; `None`
+    /// when nothing is playing. See `play_camera_path`.
+    active_camera_path: Option<(CameraPath, f32)>,
+    /// Engine clock fed to the shader as `Uniforms::time_seconds`; advances
+    /// by `timestep.step_duration()` per fixed step run in `update`, scaled
+    /// and pausable. See `set_time_scale`/`set_paused`.
+    sim_clock: SimClock,
+    /// Seconds `update`'s most recent call actually advanced `sim_clock`
+    /// by (summed across however many fixed steps it ran); `0.0` while
+    /// paused. Fed to the shader as `Uniforms::delta_time`.
+    last_update_dt: f32,
+    /// Current sky gradient/sun-disc settings; see `set_sky`.
+    sky: SkySettings,
+    /// Whether the blit pass's clear color is forced to red; see
+    /// `set_debug_clear`.
+    debug_clear: bool,
+    /// Which (if any) traversal-diagnostic visualization currently replaces
+    /// shaded color; see `set_debug_view`.
+    debug_view: DebugView,
+    /// Far plane the `DebugView::Depth` visualization normalizes hit
+    /// distance against; see `set_debug_far_plane`.
+    debug_far_plane: f32,
+    /// Costliest tile from the last resolved `top_k_tile_costs(1)` readback,
+    /// for the `TileCost` debug view to normalize against next frame; starts
+    /// at `1.0` before any readback has happened. See
+    /// `VoxelRendererPass::tile_costs`.
+    debug_max_tile_cost: f32,
+    /// Most recently resolved GPU pick result, tagged with the ticket
+    /// generation it answers; consumed (and cleared) by `poll_pick`.
+    pick_ready: Option<(u64, PickResult)>,
+    /// Voxel outlined in the rendered image, kept in sync with the last
+    /// resolved pick that actually hit a voxel; see `set_highlight_enabled`.
+    highlight_voxel: Option<(i32, i32, i32)>,
+    /// Whether `highlight_voxel` is drawn at all; see
+    /// `set_highlight_enabled`.
+    highlight_enabled: bool,
+    /// `Some` whenever there's a window to draw the panel onto; `None` in
+    /// headless mode. Only present with the `debug-overlay` feature.
+    #[cfg(feature = "debug-overlay")]
+    overlay: Option<Overlay>,
+    /// Wall-clock start of the previous frame, for the overlay's fps display.
+    #[cfg(feature = "debug-overlay")]
+    last_frame_start: Option<Instant>,
+    /// `None` if the filesystem watch on `src/shaders/` couldn't be set up
+    /// (e.g. the platform's file-watching backend is unavailable); hot
+    /// reload is then simply inert rather than a startup error. Only
+    /// present with the `shader-hot-reload` feature.
+    #[cfg(feature = "shader-hot-reload")]
+    shader_watcher: Option<ShaderWatcher>,
+    chunk: Chunk,
+    /// Octree depth `chunk`/`regenerate_chunk` are generated at; carried
+    /// separately from `chunk.size()` so a future `--chunk-size` change
+    /// mid-session (there's no such command yet) would have somewhere to
+    /// land without re-deriving depth from a voxel count.
+    chunk_depth: u32,
+    chunk_node_count: u32,
+    camera: Camera,
+    /// Camera position as of the last fixed simulation step, kept alongside
+    /// `camera.position` (the current step) so `render` can interpolate.
+    camera_prev_position: glam::Vec3,
+    timestep: FixedTimestep,
+}
+
+/// Format used for the offscreen swapchain stand-in in headless mode; picked
+/// to match the sRGB surface format we'd normally negotiate.
+const HEADLESS_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8UnormSrgb;
+
+/// How many of `compute_with_pass`'s tiles (see `RendererOptions::tile_size`)
+/// get their own `GpuTimer` scope; tiles past this index still dispatch
+/// correctly, they just don't report individual GPU time (`render` passes
+/// `None` for their `timestamp_writes`). Matches the bound the voxel render
+/// pass itself tiles against, `voxel::passes::voxel_renderer::MAX_TILES`,
+/// so a caller using the default tile size never silently loses timing.
+const MAX_TIMED_TILES: usize = 32;
+
+/// Chunk size `Renderer::upload_context`'s `StagingBelt` allocates staging
+/// memory in; comfortably covers a frame's handful of small uniform writes
+/// (camera/cull uniforms, tile offsets) without growing a new chunk mid-frame.
+const UPLOAD_BELT_CHUNK_SIZE: wgpu::BufferAddress = 4096;
+
+/// Named scopes timed every frame; see `GpuTimer`. `voxel_compute_tile_0` is
+/// also the whole-image dispatch's scope when `RendererOptions::tile_size` is
+/// `None` -- untiled dispatch is just the one-tile case. Only reserves the
+/// other `MAX_TIMED_TILES - 1` tile scopes when tiling is actually enabled,
+/// so the common (untiled) case doesn't carry 31 permanently-zero entries
+/// through every `gpu_timings()` call.
+fn gpu_timer_scopes(tiling_enabled: bool) -> Vec<String> {
+    let tile_scopes = if tiling_enabled { MAX_TIMED_TILES } else { 1 };
+    let mut scopes: Vec<String> = (0..tile_scopes).map(|i| format!("voxel_compute_tile_{i}")).collect();
+    scopes.push("present_blit".to_string());
+    scopes
+}
+
+impl Renderer {
+    pub async fn new(window: Arc<Window>, options: RendererOptions) -> Result<Self, RendererError> {
+        let instance = wgpu::Instance::new(wgpu::InstanceDescriptor {
+            backends: options.backends,
+            ..Default::default()
+        });
+        let surface = instance.create_surface(window.clone()).unwrap();
+
+        let adapter = Self::select_adapter(&instance, Some(&surface), &options)
+            .await
+            .expect("no suitable GPU adapter found");
+        log::info!("selected adapter: {:?}", AdapterSummary::from(adapter.get_info()));
+
+        let (device, queue) = adapter
+            .request_device(&Self::device_descriptor(&adapter, &options), None)
+            .await
+            .expect("failed to create GPU device");
+        let device_lost = DeviceLostFlag::new();
+        Self::register_device_lost_callback(&device, device_lost.clone());
+        let gpu_timer = Self::create_gpu_timer(&adapter, &device, options.tile_size.is_some());
+
+        let capabilities = surface.get_capabilities(&adapter);
+        let present_mode = Self::resolve_present_mode(&capabilities, options.present_mode);
+        let (format, color_target_format) = Self::resolve_surface_format(&capabilities);
+
+        let config = wgpu::SurfaceConfiguration {
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            format,
+            width: options.width.max(1),
+            height: options.height.max(1),
+            present_mode,
+            alpha_mode: capabilities.alpha_modes[0],
+            view_formats: if color_target_format != format { vec![color_target_format] } else { vec![] },
+            desired_maximum_frame_latency: options.desired_maximum_frame_latency,
+        };
+        surface.configure(&device, &config);
+
+        let render_scale = options.render_scale.clamp(0.25, 2.0);
+        let chunk_depth = options.chunk_depth;
+        let aa_mode = options.aa_mode;
+        let fxaa_enabled = options.fxaa_enabled;
+        let hdr_enabled = options.hdr_enabled;
+        let exposure = options.exposure;
+        let tonemap_operator = options.tonemap_operator;
+        let scale_factor = window.scale_factor() as f32;
+        let sun_direction = options.sun_direction.normalize();
+        let sun_color = options.sun_color;
+        let max_bounces = options.max_bounces;
+        let ao_enabled = options.ao_enabled;
+        let ao_settings = options.ao_settings;
+        let accumulation_enabled = options.accumulation_enabled;
+        let sky = options.sky;
+        let debug_clear = options.debug_clear;
+        let debug_view = options.debug_view;
+        let debug_far_plane = options.debug_far_plane;
+        let highlight_enabled = options.highlight_enabled;
+        let workgroup_size = Self::resolve_workgroup_size(&adapter, &options);
+        let tile_size = options.tile_size;
+        let gpu_culling_enabled = options.gpu_culling_enabled;
+        let srgb_conversion_enabled = options.srgb_conversion_enabled;
+        let fit_mode = options.fit_mode;
+        let mut pipeline_cache = PipelineCache::new();
+        let (render_texture, voxel_renderer_pass, cull_pass, fxaa_pass, voxel_image_pass, gizmo_pass, chunk, chunk_node_count, camera) =
+            Self::build_scene(
+                &device,
+                &adapter,
+                color_target_format,
+                config.width,
+                config.height,
+                render_scale,
+                chunk_depth,
+                aa_mode,
+                fxaa_enabled,
+                hdr_enabled,
+                exposure,
+                tonemap_operator,
+                srgb_conversion_enabled,
+                fit_mode,
+                scale_factor,
+                workgroup_size,
+                &mut pipeline_cache,
+            )?;
+        let camera_prev_position = camera.position;
+        let redraw = RedrawScheduler::new(options.redraw_policy);
+        #[cfg(feature = "debug-overlay")]
+        let overlay = Some(Overlay::new(&device, color_target_format, &window));
+        #[cfg(feature = "shader-hot-reload")]
+        let shader_watcher = ShaderWatcher::new()
+            .inspect_err(|err| log::warn!("shader hot reload disabled: {err}"))
+            .ok();
+
+        Ok(Self {
+            window: Some(window),
+            instance,
+            surface: Some(surface),
+            offscreen: None,
+            adapter,
+            device,
+            queue,
+            device_lost,
+            config,
+            color_target_format,
+            options,
+            active_present_mode: present_mode,
+            gate: RenderGate::default(),
+            redraw,
+            resize_debounce: ResizeDebounce::new(),
+            render_texture_pool: TexturePool::new(RENDER_TEXTURE_POOL_BYTES),
+            render_texture,
+            voxel_renderer_pass,
+            cull_pass,
+            fxaa_pass,
+            voxel_image_pass,
+            gizmo_pass,
+            pipeline_cache,
+            gpu_timer,
+            upload_context: UploadContext::new(UPLOAD_BELT_CHUNK_SIZE),
+            profiler: None,
+            render_scale,
+            aa_mode,
+            workgroup_size,
+            tile_size,
+            gpu_culling_enabled,
+            culled_tiles: None,
+            last_upload_stats: (0, 0.0),
+            fxaa_enabled,
+            hdr_enabled,
+            exposure,
+            tonemap_operator,
+            srgb_conversion_enabled,
+            fit_mode,
+            scale_factor,
+            sun_direction,
+            sun_color,
+            lights: Vec::new(),
+            max_bounces,
+            ao_enabled,
+            ao_settings,
+            accumulation_enabled,
+            accumulation: AccumulationState::new(),
+            frame_index: 0,
+            last_accumulation_camera: None,
+            day_cycle_enabled: false,
+            day_night: DayNightCycle::new(DAY_CYCLE_LENGTH_SECONDS),
+            active_camera_path: None,
+            sim_clock: SimClock::new(),
+            last_update_dt: 0.0,
+            sky,
+            debug_clear,
+            debug_view,
+            debug_far_plane,
+            debug_max_tile_cost: 1.0,
+            pick_ready: None,
+            highlight_voxel: None,
+            highlight_enabled,
+            #[cfg(feature = "debug-overlay")]
+            overlay,
+            #[cfg(feature = "debug-overlay")]
+            last_frame_start: None,
+            #[cfg(feature = "shader-hot-reload")]
+            shader_watcher,
+            chunk,
+            chunk_depth,
+            chunk_node_count,
+            camera,
+            camera_prev_position,
+            timestep: FixedTimestep::new(60.0),
+        })
+    }
+
+    /// Builds a renderer with no window/surface at all, for `--bench` and
+    /// golden-image tests. Frames are drawn into an offscreen texture that
+    /// nothing ever presents.
+    pub async fn new_headless(options: RendererOptions) -> Result<Self, RendererError> {
+        let instance = wgpu::Instance::new(wgpu::InstanceDescriptor {
+            backends: options.backends,
+            ..Default::default()
+        });
+        let adapter = Self::select_adapter(&instance, None, &options)
+            .await
+            .expect("no suitable GPU adapter found");
+        log::info!(
+            "selected adapter (headless): {:?}",
+            AdapterSummary::from(adapter.get_info())
+        );
+
+        let (device, queue) = adapter
+            .request_device(&Self::device_descriptor(&adapter, &options), None)
+            .await
+            .expect("failed to create GPU device");
+        let device_lost = DeviceLostFlag::new();
+        Self::register_device_lost_callback(&device, device_lost.clone());
+        let gpu_timer = Self::create_gpu_timer(&adapter, &device, options.tile_size.is_some());
+
+        let width = options.width.max(1);
+        let height = options.height.max(1);
+        let config = wgpu::SurfaceConfiguration {
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            format: HEADLESS_FORMAT,
+            width,
+            height,
+            present_mode: wgpu::PresentMode::AutoVsync,
+            alpha_mode: wgpu::CompositeAlphaMode::Opaque,
+            view_formats: vec![],
+            desired_maximum_frame_latency: options.desired_maximum_frame_latency,
+        };
+
+        let offscreen = Some(OffscreenTarget {
+            view: Self::create_offscreen_texture(&device, width, height),
+            width,
+            height,
+        });
+
+        let render_scale = options.render_scale.clamp(0.25, 2.0);
+        let chunk_depth = options.chunk_depth;
+        let aa_mode = options.aa_mode;
+        let fxaa_enabled = options.fxaa_enabled;
+        let hdr_enabled = options.hdr_enabled;
+        let exposure = options.exposure;
+        let tonemap_operator = options.tonemap_operator;
+        // No window to read a real scale factor from.
+        let scale_factor = 1.0;
+        let sun_direction = options.sun_direction.normalize();
+        let sun_color = options.sun_color;
+        let max_bounces = options.max_bounces;
+        let ao_enabled = options.ao_enabled;
+        let ao_settings = options.ao_settings;
+        let accumulation_enabled = options.accumulation_enabled;
+        let sky = options.sky;
+        let debug_clear = options.debug_clear;
+        let debug_view = options.debug_view;
+        let debug_far_plane = options.debug_far_plane;
+        let highlight_enabled = options.highlight_enabled;
+        let workgroup_size = Self::resolve_workgroup_size(&adapter, &options);
+        let tile_size = options.tile_size;
+        let gpu_culling_enabled = options.gpu_culling_enabled;
+        let srgb_conversion_enabled = options.srgb_conversion_enabled;
+        let fit_mode = options.fit_mode;
+        let mut pipeline_cache = PipelineCache::new();
+        let (render_texture, voxel_renderer_pass, cull_pass, fxaa_pass, voxel_image_pass, gizmo_pass, chunk, chunk_node_count, camera) =
+            Self::build_scene(
+                &device,
+                &adapter,
+                HEADLESS_FORMAT,
+                width,
+                height,
+                render_scale,
+                chunk_depth,
+                aa_mode,
+                fxaa_enabled,
+                hdr_enabled,
+                exposure,
+                tonemap_operator,
+                srgb_conversion_enabled,
+                fit_mode,
+                scale_factor,
+                workgroup_size,
+                &mut pipeline_cache,
+            )?;
+        let camera_prev_position = camera.position;
+        let redraw = RedrawScheduler::new(options.redraw_policy);
+
+        Ok(Self {
+            window: None,
+            instance,
+            surface: None,
+            offscreen,
+            adapter,
+            device,
+            queue,
+            device_lost,
+            config,
+            color_target_format: HEADLESS_FORMAT,
+            active_present_mode: wgpu::PresentMode::AutoVsync,
+            gate: RenderGate::default(),
+            redraw,
+            resize_debounce: ResizeDebounce::new(),
+            render_texture_pool: TexturePool::new(RENDER_TEXTURE_POOL_BYTES),
+            render_texture,
+            voxel_renderer_pass,
+            cull_pass,
+            fxaa_pass,
+            voxel_image_pass,
+            gizmo_pass,
+            pipeline_cache,
+            gpu_timer,
+            upload_context: UploadContext::new(UPLOAD_BELT_CHUNK_SIZE),
+            profiler: None,
+            render_scale,
+            aa_mode,
+            workgroup_size,
+            tile_size,
+            gpu_culling_enabled,
+            culled_tiles: None,
+            last_upload_stats: (0, 0.0),
+            fxaa_enabled,
+            hdr_enabled,
+            exposure,
+            tonemap_operator,
+            srgb_conversion_enabled,
+            fit_mode,
+            scale_factor,
+            sun_direction,
+            sun_color,
+            lights: Vec::new(),
+            max_bounces,
+            ao_enabled,
+            ao_settings,
+            accumulation_enabled,
+            accumulation: AccumulationState::new(),
+            frame_index: 0,
+            last_accumulation_camera: None,
+            day_cycle_enabled: false,
+            day_night: DayNightCycle::new(DAY_CYCLE_LENGTH_SECONDS),
+            active_camera_path: None,
+            sim_clock: SimClock::new(),
+            last_update_dt: 0.0,
+            sky,
+            debug_clear,
+            debug_view,
+            debug_far_plane,
+            debug_max_tile_cost: 1.0,
+            pick_ready: None,
+            highlight_voxel: None,
+            highlight_enabled,
+            #[cfg(feature = "debug-overlay")]
+            overlay: None,
+            #[cfg(feature = "debug-overlay")]
+            last_frame_start: None,
+            // Headless mode is for `--bench` and golden-image tests, not
+            // interactive editing, so there's no point watching the
+            // filesystem here.
+            #[cfg(feature = "shader-hot-reload")]
+            shader_watcher: None,
+            chunk,
+            chunk_depth,
+            chunk_node_count,
+            camera,
+            camera_prev_position,
+            timestep: FixedTimestep::new(60.0),
+            options,
+        })
+    }
+
+    fn create_offscreen_texture(device: &wgpu::Device, width: u32, height: u32) -> wgpu::TextureView {
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("headless_offscreen_target"),
+            size: wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: HEADLESS_FORMAT,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+        texture.create_view(&wgpu::TextureViewDescriptor::default())
+    }
+
+    /// Builds everything on the GPU side that's derived from `chunk`:
+    /// the render target and both passes. Used both for first construction
+    /// and, with the same chunk re-passed, to rebuild after device loss.
+    ///
+    /// Fails if any pass's shader doesn't validate (see
+    /// [`PassCreationError`]); passes are constructed in a fixed order and
+    /// the first failure short-circuits the rest, same as any other `?`
+    /// chain -- there's no partial-resource cleanup to do since nothing
+    /// gets assigned into `self` until the caller has a complete tuple.
+    #[allow(clippy::too_many_arguments, clippy::type_complexity)]
+    fn build_gpu_resources(
+        device: &wgpu::Device,
+        adapter: &wgpu::Adapter,
+        surface_format: wgpu::TextureFormat,
+        texture_format: wgpu::TextureFormat,
+        width: u32,
+        height: u32,
+        swapchain_width: u32,
+        swapchain_height: u32,
+        chunk: &Chunk,
+        filter: BlitFilter,
+        fxaa_enabled: bool,
+        exposure: f32,
+        tonemap_operator: TonemapOperator,
+        srgb_conversion_enabled: bool,
+        fit_mode: FitMode,
+        scale_factor: f32,
+        workgroup_size: WorkgroupSize,
+        cache: &mut PipelineCache,
+    ) -> Result<(RenderTexture, VoxelRendererPass, CullPass, FxaaPass, VoxelImageRenderingPass, GizmoPass, u32), PassCreationError> {
+        let render_texture = RenderTexture::new(device, width, height, texture_format);
+        let voxel_renderer_pass = VoxelRendererPass::new(device, &render_texture, chunk, workgroup_size, cache)?;
+        let cull_pass = CullPass::new(device, voxel_renderer_pass.visible_tiles_buffer(), cache)?;
+        let fxaa_pass = FxaaPass::new(device, &render_texture, cache)?;
+        let source = if fxaa_enabled {
+            fxaa_pass.output_view()
+        } else {
+            &render_texture.view
+        };
+        let gamma = gamma_for(surface_format, srgb_conversion_enabled);
+        let voxel_image_pass = VoxelImageRenderingPass::new(
+            device,
+            surface_format,
+            source,
+            filter,
+            exposure,
+            tonemap_operator,
+            gamma,
+            [width as f32, height as f32],
+            [swapchain_width as f32, swapchain_height as f32],
+            fit_mode,
+            cache,
+        )?;
+        let gizmo_pass = GizmoPass::new(device, adapter, surface_format, swapchain_width, swapchain_height, scale_factor, cache)?;
+        let chunk_node_count = chunk.tree.to_gpu_nodes().len() as u32;
+        Ok((render_texture, voxel_renderer_pass, cull_pass, fxaa_pass, voxel_image_pass, gizmo_pass, chunk_node_count))
+    }
+
+    /// `render_texture`'s size for a `render_scale`d output of `width x height`.
+    fn scaled_size(width: u32, height: u32, render_scale: f32) -> (u32, u32) {
+        let width = ((width as f32 * render_scale).round() as u32).max(1);
+        let height = ((height as f32 * render_scale).round() as u32).max(1);
+        (width, height)
+    }
+
+    /// Which filter resizes `render_texture` to the target: an exact match
+    /// needs none, a true `render_scale == 2.0` supersample gets the box
+    /// filter (`AaMode::SuperSample2x`), and everything else (including
+    /// intermediate/`render_scale < 1.0` scales, or `SuperSample2x` picked
+    /// without the matching `render_scale`) falls back to a single bilinear
+    /// tap.
+    fn blit_filter(render_scale: f32, aa_mode: AaMode) -> BlitFilter {
+        if render_scale == 1.0 {
+            BlitFilter::Nearest
+        } else if aa_mode == AaMode::SuperSample2x && render_scale == 2.0 {
+            BlitFilter::Box2x
+        } else {
+            BlitFilter::Linear
+        }
+    }
+
+    /// Whether `suspend` has a live surface to tear down, pulled out as a
+    /// pure check so the no-op-on-repeat guarantee is unit-testable without
+    /// a real `wgpu::Surface`.
+    fn should_tear_down_surface(surface_is_live: bool) -> bool {
+        surface_is_live
+    }
+
+    /// Whether `resume` needs to build a new surface at all; `false` both
+    /// when there's nothing to rebuild against (headless) and when one is
+    /// already live (a duplicate `Resumed` event).
+    fn should_rebuild_surface(surface_is_live: bool) -> bool {
+        !surface_is_live
+    }
+
+    /// `options.workgroup_size` if set, otherwise
+    /// [`WorkgroupSize::occupancy_default`] from `adapter`'s compute limits.
+    fn resolve_workgroup_size(adapter: &wgpu::Adapter, options: &RendererOptions) -> WorkgroupSize {
+        options
+            .workgroup_size
+            .unwrap_or_else(|| WorkgroupSize::occupancy_default(adapter.limits().max_compute_invocations_per_workgroup))
+    }
+
+    /// `render_texture`'s format for a given `hdr_enabled` setting.
+    fn render_texture_format_for(hdr_enabled: bool) -> wgpu::TextureFormat {
+        if hdr_enabled {
+            RenderTexture::FORMAT_HDR
+        } else {
+            RenderTexture::FORMAT_LDR
+        }
+    }
+
+    #[allow(clippy::too_many_arguments, clippy::type_complexity)]
+    fn build_scene(
+        device: &wgpu::Device,
+        adapter: &wgpu::Adapter,
+        surface_format: wgpu::TextureFormat,
+        width: u32,
+        height: u32,
+        render_scale: f32,
+        chunk_depth: u32,
+        aa_mode: AaMode,
+        fxaa_enabled: bool,
+        hdr_enabled: bool,
+        exposure: f32,
+        tonemap_operator: TonemapOperator,
+        srgb_conversion_enabled: bool,
+        fit_mode: FitMode,
+        scale_factor: f32,
+        workgroup_size: WorkgroupSize,
+        cache: &mut PipelineCache,
+    ) -> Result<
+        (
+            RenderTexture,
+            VoxelRendererPass,
+            CullPass,
+            FxaaPass,
+            VoxelImageRenderingPass,
+            GizmoPass,
+            Chunk,
+            u32,
+            Camera,
+        ),
+        PassCreationError,
+    > {
+        let chunk_started = Instant::now();
+        let chunk = Chunk::filled_test_pattern_with_water_at_depth(glam::IVec3::ZERO, TEST_PATTERN_WATER_DEPTH, chunk_depth);
+        log::info!("loaded chunk at {:?} ({:.2?})", chunk.position, chunk_started.elapsed());
+        let (target_width, target_height) = Self::scaled_size(width, height, render_scale);
+        let upload_started = Instant::now();
+        let (render_texture, voxel_renderer_pass, cull_pass, fxaa_pass, voxel_image_pass, gizmo_pass, chunk_node_count) =
+            Self::build_gpu_resources(
+                device,
+                adapter,
+                surface_format,
+                Self::render_texture_format_for(hdr_enabled),
+                target_width,
+                target_height,
+                width,
+                height,
+                &chunk,
+                Self::blit_filter(render_scale, aa_mode),
+                fxaa_enabled,
+                exposure,
+                tonemap_operator,
+                srgb_conversion_enabled,
+                fit_mode,
+                scale_factor,
+                workgroup_size,
+                cache,
+            )?;
+        log::debug!("uploaded chunk to the GPU in {:.2?}", upload_started.elapsed());
+        let chunk_size = chunk.size() as f32;
+        let camera = Camera::new(glam::Vec3::new(chunk_size * 1.5, chunk_size, chunk_size * 1.5));
+        Ok((
+            render_texture,
+            voxel_renderer_pass,
+            cull_pass,
+            fxaa_pass,
+            voxel_image_pass,
+            gizmo_pass,
+            chunk,
+            chunk_node_count,
+            camera,
+        ))
+    }
+
+    /// Applies an already-sensitivity-scaled mouse-look delta (radians)
+    /// straight to the camera's orientation. Unlike `step_simulation`,
+    /// this isn't run at a fixed rate -- look response lags by a frame if
+    /// it waits for the next fixed step, and the delta itself (a sum of
+    /// raw `DeviceEvent::MouseMotion` reports since the last frame) is
+    /// already frame-rate independent, so there's nothing a fixed step
+    /// would add. Called once per frame from `App::redraw`, before
+    /// `update`.
+    pub fn apply_look_delta(&mut self, delta_yaw: f32, delta_pitch: f32) {
+        if delta_yaw == 0.0 && delta_pitch == 0.0 {
+            return;
+        }
+        self.camera.look(delta_yaw, delta_pitch);
+        self.mark_dirty();
+    }
+
+    /// Snaps the camera straight to `position`, for the console's `tp`
+    /// command. Sets `camera_prev_position` to match so the next `render`
+    /// doesn't lerp in from wherever the camera used to be.
+    pub fn teleport(&mut self, position: glam::Vec3) {
+        self.camera.position = position;
+        self.camera_prev_position = position;
+        self.mark_dirty();
+    }
+
+    /// Runs zero or more fixed-rate simulation steps to catch up to `dt` of
+    /// real time. Everything time-dependent (camera integration, chunk
+    /// streaming, future physics) belongs here, not in `render`, so it's
+    /// frame-rate independent.
+    pub fn update(&mut self, dt: Duration) {
+        let steps = self.timestep.advance(dt);
+        let mut advanced = 0.0;
+        for _ in 0..steps {
+            self.camera_prev_position = self.camera.position;
+            self.step_simulation();
+            advanced += self.sim_clock.advance(self.timestep.step_duration().as_secs_f32());
+        }
+        self.last_update_dt = advanced;
+    }
+
+    /// A single fixed-rate simulation step. Camera movement and chunk
+    /// streaming integration land here as those systems are added.
+    fn step_simulation(&mut self) {
+        self.step_camera_path();
+
+        if !self.day_cycle_enabled {
+            return;
+        }
+        self.day_night.advance(self.timestep.step_duration().as_secs_f32());
+        let state = self.day_night.sample();
+        self.sun_direction = state.sun_direction;
+        self.sun_color = state.sun_color;
+        self.sky.zenith_color = state.sky_zenith_color;
+        self.sky.horizon_color = state.sky_horizon_color;
+        self.mark_dirty();
+    }
+
+    /// Starts playing `path` back from its first keyframe, snapping the
+    /// camera straight there the same way `teleport` does. Overwrites
+    /// whichever path (if any) was already playing.
+    pub fn play_camera_path(&mut self, path: CameraPath) {
+        let pose = path.sample(0.0);
+        self.camera.position = pose.position;
+        self.camera.yaw = pose.yaw_radians;
+        self.camera.pitch = pose.pitch_radians;
+        self.camera_prev_position = pose.position;
+        self.active_camera_path = Some((path, 0.0));
+        self.mark_dirty();
+    }
+
+    /// Snaps the camera straight to `pose`, without starting or affecting
+    /// any playing `active_camera_path`. Used by `bench::run_with_path` to
+    /// sample a path deterministically frame-by-frame rather than through
+    /// real-time playback.
+    pub fn set_camera_pose(&mut self, pose: CameraPose) {
+        self.camera.position = pose.position;
+        self.camera.yaw = pose.yaw_radians;
+        self.camera.pitch = pose.pitch_radians;
+        self.camera_prev_position = pose.position;
+        self.mark_dirty();
+    }
+
+    /// Advances a playing `active_camera_path` by one fixed step and moves
+    /// the camera to the sampled pose; stops playback once the path's
+    /// duration has elapsed. A no-op while nothing is playing.
+    fn step_camera_path(&mut self) {
+        let Some((path, elapsed)) = &mut self.active_camera_path else {
+            return;
+        };
+        *elapsed += self.timestep.step_duration().as_secs_f32();
+        let pose = path.sample(*elapsed);
+        let finished = *elapsed >= path.duration();
+        self.camera.position = pose.position;
+        self.camera.yaw = pose.yaw_radians;
+        self.camera.pitch = pose.pitch_radians;
+        self.mark_dirty();
+        if finished {
+            self.active_camera_path = None;
+        }
+    }
+
+    /// Lists every adapter available on the given backends, without creating
+    /// a device. Handy for a `--list-adapters` CLI flag or diagnostics.
+    pub fn enumerate_adapters(backends: wgpu::Backends) -> Vec<AdapterSummary> {
+        let instance = wgpu::Instance::new(wgpu::InstanceDescriptor {
+            backends,
+            ..Default::default()
+        });
+        instance
+            .enumerate_adapters(backends)
+            .into_iter()
+            .map(|adapter| AdapterSummary::from(adapter.get_info()))
+            .collect()
+    }
+
+    async fn select_adapter(
+        instance: &wgpu::Instance,
+        surface: Option<&wgpu::Surface<'static>>,
+        options: &RendererOptions,
+    ) -> Option<wgpu::Adapter> {
+        if let Some(preferred) = &options.preferred_adapter_name {
+            let candidates = instance.enumerate_adapters(options.backends);
+            if let Some(adapter) = candidates
+                .into_iter()
+                .find(|a| AdapterSummary::from(a.get_info()).name_matches(preferred))
+            {
+                return Some(adapter);
+            }
+            log::warn!("no adapter matched preferred_adapter_name={preferred:?}, falling back");
+        }
+
+        instance
+            .request_adapter(&wgpu::RequestAdapterOptions {
+                power_preference: wgpu::PowerPreference::HighPerformance,
+                compatible_surface: surface,
+                force_fallback_adapter: options.force_fallback_adapter,
+            })
+            .await
+    }
+
+    /// Wires up the wgpu-side device-lost callback, which can fire from an
+    /// arbitrary thread (driver reset, GPU removal, etc.), to `flag` so
+    /// `render` can notice and recover on the main thread.
+    fn register_device_lost_callback(device: &wgpu::Device, flag: DeviceLostFlag) {
+        device.set_device_lost_callback(move |reason, message| {
+            log::error!("GPU device lost ({reason:?}): {message}");
+            flag.mark_lost();
+        });
+    }
+
+    /// The features to request from `adapter`: `TIMESTAMP_QUERY` when it's
+    /// available (for `GpuTimer`), `PUSH_CONSTANTS` when it's available and
+    /// `options.force_disable_push_constants` isn't set (for
+    /// `VoxelRendererPass`'s per-frame scalars), nothing otherwise.
+    fn device_descriptor(adapter: &wgpu::Adapter, options: &RendererOptions) -> wgpu::DeviceDescriptor<'static> {
+        let mut required_features = wgpu::Features::empty();
+        if adapter.features().contains(wgpu::Features::TIMESTAMP_QUERY) {
+            required_features |= wgpu::Features::TIMESTAMP_QUERY;
+        }
+        let mut required_limits = wgpu::Limits::default();
+        if !options.force_disable_push_constants && adapter.features().contains(wgpu::Features::PUSH_CONSTANTS) {
+            required_features |= wgpu::Features::PUSH_CONSTANTS;
+            required_limits.max_push_constant_size = required_limits.max_push_constant_size.max(PUSH_CONSTANTS_SIZE);
+        }
+        wgpu::DeviceDescriptor {
+            required_features,
+            required_limits,
+            ..Default::default()
+        }
+    }
+
+    /// `None` if `device` wasn't granted `Features::TIMESTAMP_QUERY` -- some
+    /// software rasterizers (notably the GL backend) don't support it, in
+    /// which case per-pass GPU timings are simply left out of `FrameReport`.
+    fn create_gpu_timer(adapter: &wgpu::Adapter, device: &wgpu::Device, tiling_enabled: bool) -> Option<GpuTimer> {
+        adapter
+            .features()
+            .contains(wgpu::Features::TIMESTAMP_QUERY)
+            .then(|| GpuTimer::new(device, &gpu_timer_scopes(tiling_enabled)))
+    }
+
+    /// Re-requests the adapter/device/queue and rebuilds everything derived
+    /// from them, after `render` observes the device-lost flag. `chunk` and
+    /// `camera` are CPU-side state untouched by device loss, so they're
+    /// reused as-is rather than rebuilt.
+    fn recreate_gpu_state(&mut self) {
+        log::warn!("recreating GPU state after device loss");
+
+        let adapter = pollster::block_on(Self::select_adapter(
+            &self.instance,
+            self.surface.as_ref(),
+            &self.options,
+        ))
+        .expect("no suitable GPU adapter found after device loss");
+        let (device, queue) = pollster::block_on(
+            adapter.request_device(&Self::device_descriptor(&adapter, &self.options), None),
+        )
+        .expect("failed to create GPU device after device loss");
+        let device_lost = DeviceLostFlag::new();
+        Self::register_device_lost_callback(&device, device_lost.clone());
+        let gpu_timer = Self::create_gpu_timer(&adapter, &device, self.options.tile_size.is_some());
+
+        #[cfg(feature = "debug-overlay")]
+        if let Some(window) = &self.window {
+            self.overlay = Some(Overlay::new(&device, self.color_target_format, window));
+        }
+        if let Some(window) = &self.window {
+            let surface = self.instance.create_surface(window.clone()).unwrap();
+            surface.configure(&device, &self.config);
+            self.surface = Some(surface);
+        }
+        if self.offscreen.is_some() {
+            self.offscreen = Some(OffscreenTarget {
+                view: Self::create_offscreen_texture(&device, self.config.width, self.config.height),
+                width: self.config.width,
+                height: self.config.height,
+            });
+        }
+
+        self.adapter = adapter;
+        self.device = device;
+        self.queue = queue;
+        self.device_lost = device_lost;
+        self.gpu_timer = gpu_timer;
+        // Every `Arc` in `pipeline_cache` was created against the old,
+        // now-destroyed device; keeping them around would hand a stale
+        // shader module/pipeline back to whichever pass asks next.
+        self.pipeline_cache = PipelineCache::new();
+        // Same reasoning: any chunk the old belt allocated is backed by the
+        // now-destroyed device.
+        self.upload_context = UploadContext::new(UPLOAD_BELT_CHUNK_SIZE);
+        self.rebuild_gpu_pipeline();
+    }
+
+    /// Rebuilds `render_texture` and every pass derived from it (format,
+    /// chunk contents, everything) from current state. Unlike
+    /// `rebuild_render_target`, this reconstructs the passes themselves
+    /// rather than just resizing them, so it's the only path that can react
+    /// to a `hdr_enabled` or chunk-contents change (a storage-texture bind
+    /// group layout bakes in its format at construction time). Used after
+    /// device loss, after regenerating the chunk, and by `set_hdr_enabled`.
+    ///
+    /// This is also the only way a chunk's `node_buffer` contents ever
+    /// change: there's no live edit path that calls `write_buffer_with`
+    /// against an existing `VoxelRendererPass`'s buffers (no brush/paint
+    /// feature exists in this codebase yet). A rewrite means a brand new
+    /// `VoxelRendererPass` -- and its own freshly built `node_buffer` -- with
+    /// `self.voxel_renderer_pass` swapped over to it in one field
+    /// assignment, so `render()` can never observe a half-written buffer;
+    /// it's either still reading the old pass's untouched buffer or the new
+    /// pass's fully-populated one. Double-buffering a single pass's own
+    /// `node_buffer` would only matter once something writes into it
+    /// in-place mid-session, which isn't a case this crate has yet.
+    ///
+    /// If a pass's shader fails to compile (see [`PassCreationError`]), the
+    /// error is logged and the rebuild is abandoned -- `self`'s existing
+    /// passes are left untouched, so the renderer keeps drawing with
+    /// whatever it had before rather than ending up half-swapped. In
+    /// practice `poll_shader_reload` already screens hot-reloaded shaders
+    /// with `shader_watcher::validate` before calling in here, so this is a
+    /// second line of defense rather than the primary one.
+    fn rebuild_gpu_pipeline(&mut self) {
+        let (width, height) = self.render_target_size();
+        let built = Self::build_gpu_resources(
+            &self.device,
+            &self.adapter,
+            self.color_target_format,
+            Self::render_texture_format_for(self.hdr_enabled),
+            width,
+            height,
+            self.config.width,
+            self.config.height,
+            &self.chunk,
+            Self::blit_filter(self.render_scale, self.aa_mode),
+            self.fxaa_enabled,
+            self.exposure,
+            self.tonemap_operator,
+            self.srgb_conversion_enabled,
+            self.fit_mode,
+            self.scale_factor,
+            self.workgroup_size,
+            &mut self.pipeline_cache,
+        );
+        let (render_texture, voxel_renderer_pass, cull_pass, fxaa_pass, voxel_image_pass, gizmo_pass, chunk_node_count) = match built {
+            Ok(built) => built,
+            Err(err) => {
+                log::error!("keeping previous GPU pipeline: {err}");
+                return;
+            }
+        };
+        self.render_texture = render_texture;
+        self.voxel_renderer_pass = voxel_renderer_pass;
+        self.cull_pass = cull_pass;
+        self.fxaa_pass = fxaa_pass;
+        self.voxel_image_pass = voxel_image_pass;
+        self.gizmo_pass = gizmo_pass;
+        self.chunk_node_count = chunk_node_count;
+        self.accumulation.reset();
+        self.mark_dirty();
+    }
+
+    /// Checks `shader_watcher` for edited `.wgsl` files, revalidates each
+    /// with naga, and -- only for ones that still parse and validate --
+    /// drops the matching `pipeline_cache` entries and goes through
+    /// `rebuild_gpu_pipeline`. A shader that fails to validate is logged and
+    /// left alone, so the renderer keeps drawing with the last good
+    /// pipeline instead of handing wgpu a broken module.
+    #[cfg(feature = "shader-hot-reload")]
+    fn poll_shader_reload(&mut self) {
+        let Some(watcher) = &self.shader_watcher else {
+            return;
+        };
+        let changed = watcher.poll_changed();
+        let mut any_valid = false;
+        for name in changed {
+            let Some(prefix) = Self::shader_cache_prefix(&name) else {
+                continue;
+            };
+            let source = shader_watcher::load(&name);
+            if !shader_watcher::validate(&name, &source) {
+                continue;
+            }
+            log::info!("reloading shader: {name}");
+            self.pipeline_cache.invalidate_prefix(prefix);
+            any_valid = true;
+        }
+        if any_valid {
+            self.rebuild_gpu_pipeline();
+        }
+    }
+
+    /// Which `pipeline_cache` key prefix a shader source file's module and
+    /// pipeline(s) share; `None` for a file the renderer doesn't load.
+    #[cfg(feature = "shader-hot-reload")]
+    fn shader_cache_prefix(name: &str) -> Option<&'static str> {
+        match name {
+            "voxel_renderer.wgsl" => Some("voxel_renderer_"),
+            "fxaa.wgsl" => Some("fxaa_"),
+            "rendering.wgsl" => Some("voxel_image_"),
+            "gizmo.wgsl" => Some("gizmo_"),
+            _ => None,
+        }
+    }
+
+    /// `render_texture`'s resolution: the swapchain size scaled by
+    /// `render_scale`, floored at one pixel per axis.
+    fn render_target_size(&self) -> (u32, u32) {
+        let width = ((self.config.width as f32 * self.render_scale).round() as u32).max(1);
+        let height = ((self.config.height as f32 * self.render_scale).round() as u32).max(1);
+        (width, height)
+    }
+
+    /// Recreates `render_texture` (and the passes' bind groups that reference
+    /// it) at the current `render_target_size`. Called after a resize or a
+    /// `render_scale` change; the swapchain/offscreen target itself is sized
+    /// independently by the caller. Goes through `VoxelRendererPass::resize`/
+    /// `VoxelImageRenderingPass::resize` rather than `rebuild_gpu_pipeline`,
+    /// so neither pass recompiles its shader module or loses the chunk data
+    /// already uploaded to `node_buffer` -- only the far more common resize
+    /// path pays for a rebuild, not the scene itself.
+    fn rebuild_render_target(&mut self) {
+        let (width, height) = self.render_target_size();
+        let format = self.render_texture.format;
+        let old_render_texture = std::mem::replace(
+            &mut self.render_texture,
+            RenderTexture::new_pooled(&self.device, &mut self.render_texture_pool, width, height, format),
+        );
+        old_render_texture.release_into(&mut self.render_texture_pool);
+        self.voxel_renderer_pass.resize(&self.device, &self.render_texture);
+        self.fxaa_pass.resize(&self.device, &self.render_texture);
+        self.accumulation.reset();
+        self.rebuild_blit_source();
+    }
+
+    /// Repoints the blit pass at whichever source `accumulation_enabled`/
+    /// `fxaa_enabled` currently select, and refreshes its filter. Called
+    /// after `rebuild_render_target` recreates the underlying textures, and
+    /// by `set_fxaa_enabled`/`set_accumulation_enabled` when only the
+    /// selection changes.
+    fn rebuild_blit_source(&mut self) {
+        // The accumulation buffer is `Rgba32Float`, which isn't filterable
+        // without a device feature this renderer doesn't request, so it's
+        // always sampled with the nearest-neighbor filter regardless of
+        // `render_scale`/`aa_mode`.
+        let filter = if self.accumulation_enabled {
+            BlitFilter::Nearest
+        } else {
+            Self::blit_filter(self.render_scale, self.aa_mode)
+        };
+        let source = if self.accumulation_enabled {
+            self.voxel_renderer_pass.accumulation_view()
+        } else if self.fxaa_enabled {
+            self.fxaa_pass.output_view()
+        } else {
+            &self.render_texture.view
+        };
+        self.voxel_image_pass.resize(&self.device, source, filter);
+        self.voxel_image_pass.set_fit(
+            &self.queue,
+            [self.render_texture.width as f32, self.render_texture.height as f32],
+            [self.config.width as f32, self.config.height as f32],
+            self.fit_mode,
+        );
+    }
+
+    /// Changes the compute pass's output resolution relative to the
+    /// swapchain (`1.0` = native); driven by the debug overlay's slider and
+    /// `App`'s +/- key bindings.
+    pub fn set_render_scale(&mut self, scale: f32) {
+        self.render_scale = scale.clamp(0.25, 2.0);
+        self.rebuild_render_target();
+        self.mark_dirty();
+    }
+
+    /// Current render-scale factor (`1.0` = native), for `App` to report
+    /// after a +/- keypress.
+    pub fn render_scale(&self) -> f32 {
+        self.render_scale
+    }
+
+    /// Switches the blit filter used when `render_scale != 1.0`; driven by
+    /// `App`'s AA key binding and the debug overlay. Only changes anything
+    /// visible once `render_scale == 2.0` -- see `blit_filter`.
+    pub fn set_aa_mode(&mut self, mode: AaMode) {
+        self.aa_mode = mode;
+        self.rebuild_render_target();
+        self.mark_dirty();
+    }
+
+    /// Current AA mode, for `App` to report after a keypress.
+    pub fn aa_mode(&self) -> AaMode {
+        self.aa_mode
+    }
+
+    /// Toggles the FXAA post-process pass; driven by `App`'s FXAA key
+    /// binding and the debug overlay. Only rebinds the blit source (the
+    /// render target itself doesn't change size), so it's cheaper than
+    /// `set_render_scale`/`set_aa_mode`.
+    pub fn set_fxaa_enabled(&mut self, enabled: bool) {
+        self.fxaa_enabled = enabled;
+        self.rebuild_blit_source();
+        self.mark_dirty();
+    }
+
+    /// Whether the FXAA pass is currently active, for `App` to report after
+    /// a keypress.
+    pub fn fxaa_enabled(&self) -> bool {
+        self.fxaa_enabled
+    }
+
+    /// Switches `render_texture` between `RenderTexture::FORMAT_LDR` and
+    /// `FORMAT_HDR`; driven by `App`'s HDR key binding and the debug overlay.
+    /// The storage-texture format is baked into the compute/FXAA passes'
+    /// bind group layouts at construction time, so unlike
+    /// `set_render_scale`/`set_fxaa_enabled` this goes through a full
+    /// pipeline rebuild rather than a resize.
+    pub fn set_hdr_enabled(&mut self, enabled: bool) {
+        self.hdr_enabled = enabled;
+        self.rebuild_gpu_pipeline();
+    }
+
+    /// Whether `render_texture` is currently `RenderTexture::FORMAT_HDR`, for
+    /// `App` to report after a keypress.
+    pub fn hdr_enabled(&self) -> bool {
+        self.hdr_enabled
+    }
+
+    /// Changes the tonemap pass's exposure multiplier; driven by `App`'s
+    /// bracket key bindings and the debug overlay's slider. Only pushes new
+    /// uniform contents to the GPU, no pipeline/bind group rebuild needed.
+    pub fn set_exposure(&mut self, exposure: f32) {
+        self.exposure = exposure.clamp(0.01, 16.0);
+        self.push_tonemap_uniforms();
+        self.mark_dirty();
+    }
+
+    /// Current exposure multiplier, for `App` to report after a keypress.
+    pub fn exposure(&self) -> f32 {
+        self.exposure
+    }
+
+    /// Switches the tonemap curve; driven by `App`'s tonemap key binding and
+    /// the debug overlay. Only pushes new uniform contents to the GPU.
+    pub fn set_tonemap_operator(&mut self, operator: TonemapOperator) {
+        self.tonemap_operator = operator;
+        self.push_tonemap_uniforms();
+        self.mark_dirty();
+    }
+
+    /// Current tonemap curve, for `App` to report after a keypress.
+    pub fn tonemap_operator(&self) -> TonemapOperator {
+        self.tonemap_operator
+    }
+
+    /// Updates the window scale factor from `WindowEvent::ScaleFactorChanged`,
+    /// so the crosshair (drawn at a fixed pixel size by `GizmoPass`) stays
+    /// the same physical size rather than shrinking on a HiDPI monitor. The
+    /// egui overlay doesn't need this -- `egui_winit::State` reads the
+    /// window's own scale factor directly.
+    pub fn set_scale_factor(&mut self, scale_factor: f32) {
+        self.scale_factor = scale_factor;
+        self.gizmo_pass.set_scale_factor(&self.queue, scale_factor);
+        self.mark_dirty();
+    }
+
+    /// Toggles `gamma_for`'s shader-side sRGB approximation, for A/B
+    /// comparison against the (incorrect, on a non-sRGB surface) no-op
+    /// path. Only meaningful when `color_target_format` isn't sRGB --
+    /// otherwise the hardware always does the real conversion on store and
+    /// this setting can't change what gets displayed. Only pushes new
+    /// uniform contents to the GPU.
+    pub fn set_srgb_conversion_enabled(&mut self, enabled: bool) {
+        self.srgb_conversion_enabled = enabled;
+        self.push_tonemap_uniforms();
+        self.mark_dirty();
+    }
+
+    /// Whether the sRGB-approximation debug toggle is currently on, for
+    /// `App` to report after a keypress.
+    pub fn srgb_conversion_enabled(&self) -> bool {
+        self.srgb_conversion_enabled
+    }
+
+    /// Switches how the blit pass maps `render_texture` onto the swapchain
+    /// when their aspect ratios diverge; driven by the debug overlay. Only
+    /// pushes a new scale to the GPU, since neither texture actually
+    /// changes size.
+    pub fn set_fit_mode(&mut self, mode: FitMode) {
+        self.fit_mode = mode;
+        self.voxel_image_pass.set_fit(
+            &self.queue,
+            [self.render_texture.width as f32, self.render_texture.height as f32],
+            [self.config.width as f32, self.config.height as f32],
+            self.fit_mode,
+        );
+        self.mark_dirty();
+    }
+
+    /// Current fit mode, for `App`/the debug overlay to report after a
+    /// change.
+    pub fn fit_mode(&self) -> FitMode {
+        self.fit_mode
+    }
+
+    /// Pushes `exposure`/`tonemap_operator` and the gamma `gamma_for`
+    /// derives from `color_target_format`/`srgb_conversion_enabled` to the
+    /// GPU; shared by every setter above since each one only changes a
+    /// single field of the same uniform buffer.
+    fn push_tonemap_uniforms(&self) {
+        let gamma = gamma_for(self.color_target_format, self.srgb_conversion_enabled);
+        self.voxel_image_pass.set_tonemap(&self.queue, self.exposure, self.tonemap_operator, gamma);
+    }
+
+    /// Sets the directional sun light used for Lambert shading and the
+    /// shadow ray; `direction` is normalized before it's stored. Only
+    /// touches per-frame uniform data, so no pipeline/bind group rebuild is
+    /// needed.
+    pub fn set_sun(&mut self, direction: glam::Vec3, color: glam::Vec3) {
+        self.sun_direction = direction.normalize();
+        self.sun_color = color;
+        self.mark_dirty();
+    }
+
+    /// Current normalized direction toward the sun.
+    pub fn sun_direction(&self) -> glam::Vec3 {
+        self.sun_direction
+    }
+
+    /// Current sun color.
+    pub fn sun_color(&self) -> glam::Vec3 {
+        self.sun_color
+    }
+
+    /// Replaces the extra shadow-casting fill lights (beyond the sun) with
+    /// `lights`, normalizing each direction the same way `set_sun` does.
+    /// Rejects more than `MAX_LIGHTS` rather than silently truncating, since
+    /// a caller that thinks all of its lights are active should find out
+    /// immediately if they aren't.
+    pub fn set_lights(&mut self, lights: &[Light]) -> Result<(), String> {
+        if lights.len() > MAX_LIGHTS {
+            return Err(format!("set_lights supports at most {MAX_LIGHTS} lights, got {}", lights.len()));
+        }
+        self.lights = lights
+            .iter()
+            .map(|light| Light { direction: light.direction.normalize(), ..*light })
+            .collect();
+        self.mark_dirty();
+        Ok(())
+    }
+
+    /// Currently active extra fill lights, for the debug overlay's editor.
+    pub fn lights(&self) -> &[Light] {
+        &self.lights
+    }
+
+    /// Changes how many times a ray that keeps hitting reflective materials
+    /// bounces before the shader gives up; `0` disables reflections. Only
+    /// touches per-frame uniform data.
+    pub fn set_max_bounces(&mut self, max_bounces: u32) {
+        self.max_bounces = max_bounces;
+        self.mark_dirty();
+    }
+
+    /// Current reflection bounce limit.
+    pub fn max_bounces(&self) -> u32 {
+        self.max_bounces
+    }
+
+    /// Toggles the compute shader's hemisphere AO probes; driven by `App`'s
+    /// AO key binding and the debug overlay. Only touches per-frame uniform
+    /// data (via the `ao.samples` zeroing in `render`), so no pipeline/bind
+    /// group rebuild is needed.
+    pub fn set_ao_enabled(&mut self, enabled: bool) {
+        self.ao_enabled = enabled;
+        self.mark_dirty();
+    }
+
+    /// Whether the AO probes currently run, for `App` to report after a
+    /// keypress.
+    pub fn ao_enabled(&self) -> bool {
+        self.ao_enabled
+    }
+
+    /// Changes the AO probes' sample count/radius/strength; driven by the
+    /// debug overlay's sliders. Only touches per-frame uniform data.
+    pub fn set_ao_settings(&mut self, settings: AoSettings) {
+        self.ao_settings = settings;
+        self.mark_dirty();
+    }
+
+    /// Current AO sample count/radius/strength, for the debug overlay's
+    /// sliders.
+    pub fn ao_settings(&self) -> AoSettings {
+        self.ao_settings
+    }
+
+    /// Toggles whether the blit pass reads the progressive accumulation
+    /// buffer instead of `render_texture`/the FXAA output; driven by `App`'s
+    /// accumulation key binding and the debug overlay. Resets the blend
+    /// history so the first frame after enabling isn't blended against
+    /// stale contents.
+    pub fn set_accumulation_enabled(&mut self, enabled: bool) {
+        self.accumulation_enabled = enabled;
+        self.accumulation.reset();
+        self.rebuild_blit_source();
+        self.mark_dirty();
+    }
+
+    /// Whether the blit pass currently reads the accumulation buffer, for
+    /// `App` to report after a keypress.
+    pub fn accumulation_enabled(&self) -> bool {
+        self.accumulation_enabled
+    }
+
+    /// Changes the sky gradient/sun-disc settings drawn where a ray misses
+    /// the chunk; driven by `App`'s sky-reset key binding and the debug
+    /// overlay. Only touches per-frame uniform data.
+    pub fn set_sky(&mut self, sky: SkySettings) {
+        self.sky = sky;
+        self.mark_dirty();
+    }
+
+    /// Current sky gradient/sun-disc settings, for the debug overlay's
+    /// color pickers.
+    pub fn sky(&self) -> SkySettings {
+        self.sky
+    }
+
+    /// Toggles the blit pass's clear color between black and solid red, to
+    /// make an unexpectedly-uncovered blit source obvious; driven by `App`'s
+    /// debug-clear key binding.
+    pub fn set_debug_clear(&mut self, enabled: bool) {
+        self.debug_clear = enabled;
+        self.mark_dirty();
+    }
+
+    /// Whether the debug clear color is currently active, for `App` to
+    /// report after a keypress.
+    pub fn debug_clear(&self) -> bool {
+        self.debug_clear
+    }
+
+    /// Switches which (if any) traversal-diagnostic visualization the
+    /// compute shader writes instead of shaded color; driven by `App`'s F5
+    /// key and the debug overlay's selector. Only touches per-frame uniform
+    /// data.
+    pub fn set_debug_view(&mut self, view: DebugView) {
+        self.debug_view = view;
+        self.mark_dirty();
+    }
+
+    /// Current traversal-diagnostic visualization, for `App` to report after
+    /// a keypress.
+    pub fn debug_view(&self) -> DebugView {
+        self.debug_view
+    }
+
+    /// Changes the far plane the `DebugView::Depth` visualization normalizes
+    /// hit distance against; driven by the debug overlay's slider.
+    pub fn set_debug_far_plane(&mut self, far_plane: f32) {
+        self.debug_far_plane = far_plane.max(0.01);
+        self.mark_dirty();
+    }
+
+    /// Current `DebugView::Depth` far plane, for the debug overlay's slider.
+    pub fn debug_far_plane(&self) -> f32 {
+        self.debug_far_plane
+    }
+
+    /// Toggles whether the last voxel resolved by `pick` is outlined in the
+    /// rendered image; driven by `App`'s highlight key and the debug
+    /// overlay's checkbox. Only touches per-frame uniform data.
+    pub fn set_highlight_enabled(&mut self, enabled: bool) {
+        self.highlight_enabled = enabled;
+        self.mark_dirty();
+    }
+
+    /// Whether the highlight outline is currently drawn, for `App` to report
+    /// after a keypress.
+    pub fn highlight_enabled(&self) -> bool {
+        self.highlight_enabled
+    }
+
+    /// Current render-texture resolution, for callers converting a cursor
+    /// position or the window center into the pixel coordinates `pick`
+    /// expects.
+    pub fn render_texture_size(&self) -> (u32, u32) {
+        (self.render_texture.width, self.render_texture.height)
+    }
+
+    /// The currently loaded chunk's position and GPU-node bytes, in the
+    /// `(position, bytes)` shape `engine::autosave::write_crash_recovery`
+    /// expects. There's no per-chunk dirty bit yet (see `chunk_cache.rs`'s
+    /// doc comment), so this always reports the one loaded chunk rather than
+    /// tracking whether it's actually changed since the last save.
+    pub fn dirty_chunks(&self) -> Vec<(glam::IVec3, Vec<u8>)> {
+        vec![(self.chunk.position, bytemuck::cast_slice(&self.chunk.tree.to_gpu_nodes()).to_vec())]
+    }
+
+    /// Requests a GPU hit-test at `pixel` (render-texture coordinates, not
+    /// window coordinates -- callers driving this from a cursor position
+    /// need to scale by `render_texture` size / swapchain size themselves).
+    /// The result isn't available immediately; poll the returned ticket
+    /// with `poll_pick` on a later frame. Doesn't call `mark_dirty` -- a
+    /// pick doesn't change anything the compute shader draws for other
+    /// pixels.
+    pub fn pick(&mut self, pixel: (u32, u32)) -> PickTicket {
+        self.voxel_renderer_pass.request_pick(pixel)
+    }
+
+    /// Resolves `ticket` if the GPU has finished answering it. Returns
+    /// `None` both while the pick is still in flight and once it's been
+    /// superseded by a newer `pick` call before this one resolved.
+    pub fn poll_pick(&mut self, ticket: PickTicket) -> Option<PickResult> {
+        let (generation, result) = self.pick_ready.take()?;
+        if generation == ticket.generation() {
+            Some(result)
+        } else {
+            None
+        }
+    }
+
+    /// Reads back one decoded texel of the debug G-buffer at `(x, y)`
+    /// (render-texture coordinates). Blocks on `Maintain::Wait` like
+    /// `wait_for_gpu` -- this is a debug-overlay path rather than a
+    /// per-frame one, so a stall here is fine in a way it wouldn't be for
+    /// `pick`'s frame-spread ticket system.
+    pub fn read_gbuffer_pixel(&self, x: u32, y: u32) -> GBufferPixel {
+        const BYTES_PER_TEXEL: u32 = 8; // Rg32Uint
+        let padded_bytes_per_row = BYTES_PER_TEXEL.div_ceil(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT) * wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+        let readback = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("voxel_gbuffer_readback_buffer"),
+            size: padded_bytes_per_row as u64,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor { label: Some("voxel_gbuffer_readback_encoder") });
+        encoder.copy_texture_to_buffer(
+            wgpu::ImageCopyTexture {
+                texture: self.voxel_renderer_pass.gbuffer_texture(),
+                mip_level: 0,
+                origin: wgpu::Origin3d { x, y, z: 0 },
+                aspect: wgpu::TextureAspect::All,
+            },
+            wgpu::ImageCopyBuffer {
+                buffer: &readback,
+                layout: wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(padded_bytes_per_row),
+                    rows_per_image: None,
+                },
+            },
+            wgpu::Extent3d { width: 1, height: 1, depth_or_array_layers: 1 },
+        );
+        self.queue.submit(Some(encoder.finish()));
+
+        let slice = readback.slice(..);
+        slice.map_async(wgpu::MapMode::Read, |_| {});
+        self.device.poll(wgpu::Maintain::Wait);
+
+        let pixel = {
+            let data = slice.get_mapped_range();
+            let word0 = u32::from_le_bytes(data[0..4].try_into().unwrap());
+            let word1 = u32::from_le_bytes(data[4..8].try_into().unwrap());
+            decode_gbuffer_texel(word0, word1)
+        };
+        readback.unmap();
+        pixel
+    }
+
+    /// Toggles whether `step_simulation` orbits the sun over time; driven by
+    /// `App`'s day-cycle key binding.
+    pub fn set_day_cycle_enabled(&mut self, enabled: bool) {
+        self.day_cycle_enabled = enabled;
+    }
+
+    /// Whether the day cycle is currently animating, for `App` to report
+    /// after a keypress.
+    pub fn day_cycle_enabled(&self) -> bool {
+        self.day_cycle_enabled
+    }
+
+    /// Sets the day/night clock's position directly, in seconds since
+    /// midnight, wrapping into `0.0..day_length_seconds()`; driven by the
+    /// `time` console command. Has no visible effect until `day_cycle_enabled`
+    /// is also on, same as `set_sun` while a manual orbit is running.
+    pub fn set_time_of_day(&mut self, seconds: f32) {
+        self.day_night.set_time_of_day(seconds);
+        self.mark_dirty();
+    }
+
+    /// Current position of the day/night clock, in seconds since midnight.
+    pub fn time_of_day(&self) -> f32 {
+        self.day_night.time_of_day()
+    }
+
+    /// How many simulated seconds a full day/night loop takes; the range
+    /// `time_of_day` wraps within.
+    pub fn day_length_seconds(&self) -> f32 {
+        self.day_night.length_seconds()
+    }
+
+    /// Freezes or resumes the day/night clock without disabling
+    /// `day_cycle_enabled`, e.g. to hold a specific time of day for a
+    /// screenshot while keeping its sun/sky state (rather than falling back
+    /// to whatever `set_sun`/`set_sky` last set manually).
+    pub fn set_day_night_paused(&mut self, paused: bool) {
+        self.day_night.set_paused(paused);
+    }
+
+    /// Flips whether the day/night clock is frozen; driven by `App`'s
+    /// day-night-pause key binding. Returns the new state, same as
+    /// `sim_clock`'s underlying `toggle_paused`.
+    pub fn toggle_day_night_paused(&mut self) -> bool {
+        self.day_night.toggle_paused()
+    }
+
+    /// Whether the day/night clock is currently frozen, for `App` to report
+    /// after a keypress.
+    pub fn day_night_paused(&self) -> bool {
+        self.day_night.paused()
+    }
+
+    /// Scales how fast the engine clock (and anything timed off it, e.g.
+    /// the shader's emissive pulse) advances per `update` call; `1.0` is
+    /// real-time. Negative scales are clamped to `0.0`.
+    pub fn set_time_scale(&mut self, scale: f32) {
+        self.sim_clock.set_time_scale(scale);
+    }
+
+    pub fn time_scale(&self) -> f32 {
+        self.sim_clock.time_scale()
+    }
+
+    /// Freezes the engine clock entirely; `update` still runs fixed
+    /// simulation steps (so e.g. the day cycle's own toggle is unaffected)
+    /// but stops advancing the clock the shader's emissive pulse reads.
+    /// Driven by `App`'s pause key binding.
+    pub fn set_paused(&mut self, paused: bool) {
+        self.sim_clock.set_paused(paused);
+    }
+
+    pub fn toggle_paused(&mut self) {
+        self.sim_clock.toggle_paused();
+    }
+
+    pub fn paused(&self) -> bool {
+        self.sim_clock.paused()
+    }
+
+    /// Re-generates the currently loaded chunk and re-uploads it to the GPU.
+    /// There's no procedural world generation yet, so this just re-runs the
+    /// same deterministic test pattern -- it exists so the debug overlay's
+    /// "regenerate chunk" button and the console's `regen` command have
+    /// something real to trigger.
+    pub(crate) fn regenerate_chunk(&mut self) {
+        let started = Instant::now();
+        self.chunk = Chunk::filled_test_pattern_with_water_at_depth(self.chunk.position, TEST_PATTERN_WATER_DEPTH, self.chunk_depth);
+        self.rebuild_gpu_pipeline();
+        log::debug!("regenerated chunk at {:?} in {:.2?}", self.chunk.position, started.elapsed());
+    }
+
+    /// Tears down the current chunk/camera/sky/lights and replaces them
+    /// with what `scene` describes, for the console's `scene` command.
+    /// `scene` is already validated (see [`SceneDescription::validate`]),
+    /// so the only way this can still fail is `set_lights` rejecting an
+    /// over-long light list -- unreachable in practice since `validate`
+    /// already checked that count, but `set_lights` is still the one source
+    /// of truth for the limit rather than duplicating it here.
+    ///
+    /// Doesn't touch material properties: `scene.materials()` builds a real
+    /// `MaterialTable`, but `VoxelRendererPass` bakes its own table in at
+    /// construction with no update path yet, so there's nothing to push it
+    /// to. See `engine::scene`'s module doc comment.
+    pub fn load_scene(&mut self, scene: &SceneDescription) -> Result<(), String> {
+        self.chunk = scene.build_chunk();
+        self.rebuild_gpu_pipeline();
+
+        let pose = scene.camera_pose();
+        self.camera.position = pose.position;
+        self.camera.yaw = pose.yaw_radians;
+        self.camera.pitch = pose.pitch_radians;
+        self.camera_prev_position = pose.position;
+
+        self.set_sky(scene.sky_settings());
+        self.set_lights(&scene.lights())?;
+        Ok(())
+    }
+
+    /// Flips between `AutoNoVsync` and `AutoVsync`; shared by `App`'s 'V' key
+    /// and the debug overlay's vsync checkbox.
+    pub fn toggle_vsync(&mut self) {
+        let next = match self.active_present_mode {
+            wgpu::PresentMode::AutoNoVsync => wgpu::PresentMode::AutoVsync,
+            _ => wgpu::PresentMode::AutoNoVsync,
+        };
+        self.set_present_mode(next);
+    }
+
+    /// Forces the device-lost flag as if the driver had reported a loss, so
+    /// the recovery path can be exercised manually (bound to a debug key in
+    /// `App`) instead of only in the unit tests around `DeviceLostFlag`.
+    pub fn debug_force_device_lost(&mut self) {
+        self.device_lost.mark_lost();
+    }
+
+    /// Starts a bounded chrome-trace capture (bound to a debug key in `App`);
+    /// does nothing if a capture is already running. Recording auto-exports
+    /// to `trace.json` and stops once `max_frames` frames have been rendered.
+    pub fn start_profiling(&mut self, max_frames: usize) {
+        if self.profiler.is_none() {
+            self.profiler = Some(ProfileSession::start(max_frames));
+        }
+    }
+
+    /// Whether a profiling capture is currently recording.
+    pub fn is_profiling(&self) -> bool {
+        self.profiler.is_some()
+    }
+
+    /// Picks `requested` if the surface supports it, otherwise falls back to
+    /// `AutoVsync` which every backend is required to support.
+    fn resolve_present_mode(
+        capabilities: &wgpu::SurfaceCapabilities,
+        requested: wgpu::PresentMode,
+    ) -> wgpu::PresentMode {
+        if capabilities.present_modes.contains(&requested) {
+            requested
+        } else {
+            wgpu::PresentMode::AutoVsync
+        }
+    }
+
+    /// Negotiates the swapchain's configured format and the (possibly
+    /// different) format the blit pass should actually render into.
+    /// Prefers a directly sRGB-capable format, since the GPU then does the
+    /// shading pipeline's one linear-to-sRGB conversion for free on every
+    /// store -- both returned formats are that format in this, the common,
+    /// case. When the adapter offers none (some mobile/software backends),
+    /// falls back to the first format and, if it has an sRGB twin
+    /// (`add_srgb_suffix` returns something different), returns that twin as
+    /// the color target format -- the caller must add it to the surface
+    /// config's `view_formats` and request it explicitly when creating the
+    /// presentation view, so the blit still renders straight into an sRGB
+    /// view of the same swapchain texture. Only when neither exists do both
+    /// returned formats end up equal and non-sRGB, which is the one case
+    /// `gamma_for`'s shader-side approximation exists for.
+    fn resolve_surface_format(capabilities: &wgpu::SurfaceCapabilities) -> (wgpu::TextureFormat, wgpu::TextureFormat) {
+        if let Some(srgb) = capabilities.formats.iter().copied().find(|f| f.is_srgb()) {
+            return (srgb, srgb);
+        }
+        let base = capabilities.formats[0];
+        let srgb_twin = base.add_srgb_suffix();
+        (base, srgb_twin)
+    }
+
+    /// Queues a present-mode change. Applied on the next `resize`/`configure`
+    /// so we never reconfigure the surface mid-frame.
+    pub fn set_present_mode(&mut self, mode: wgpu::PresentMode) {
+        let Some(surface) = &self.surface else {
+            // Headless: no swapchain to negotiate a present mode against.
+            self.options.present_mode = mode;
+            self.active_present_mode = mode;
+            return;
+        };
+        let capabilities = surface.get_capabilities(&self.adapter);
+        self.options.present_mode = Self::resolve_present_mode(&capabilities, mode);
+        self.reconfigure();
+    }
+
+    fn reconfigure(&mut self) {
+        self.config.present_mode = self.options.present_mode;
+        self.active_present_mode = self.options.present_mode;
+        if let Some(surface) = &self.surface {
+            surface.configure(&self.device, &self.config);
+        }
+    }
+
+    /// Records `width`/`height` as the size to apply at the start of the
+    /// next `render`, rather than calling `resize` immediately -- a window
+    /// being dragged can fire dozens of `Resized` events per frame, and
+    /// each `resize` rebuilds render targets, so applying every one of them
+    /// individually visibly hitches. `App` calls this from `WindowEvent::
+    /// Resized`; `resize` itself is still there for callers (bench, tests)
+    /// that want the size applied synchronously.
+    pub fn request_resize(&mut self, width: u32, height: u32) {
+        self.resize_debounce.request(width, height);
+        self.mark_dirty();
+    }
+
+    pub fn resize(&mut self, width: u32, height: u32) {
+        if width == 0 || height == 0 {
+            self.gate.suspend(SuspendReason::ZeroSize);
+            return;
+        }
+        let started = Instant::now();
+        self.config.width = width;
+        self.config.height = height;
+        if let Some(surface) = &self.surface {
+            surface.configure(&self.device, &self.config);
+        }
+        if self.offscreen.is_some() {
+            self.offscreen = Some(OffscreenTarget {
+                view: Self::create_offscreen_texture(&self.device, width, height),
+                width,
+                height,
+            });
+        }
+
+        self.rebuild_render_target();
+        self.gizmo_pass.resize(&self.device, &self.queue, width, height, self.scale_factor);
+
+        self.gate.resume_if(SuspendReason::ZeroSize);
+        self.mark_dirty();
+        log::debug!("resized to {width}x{height} in {:.2?}", started.elapsed());
+    }
+
+    pub fn set_occluded(&mut self, occluded: bool) {
+        if occluded {
+            self.gate.suspend(SuspendReason::Occluded);
+        } else {
+            self.gate.resume_if(SuspendReason::Occluded);
+            self.mark_dirty();
+        }
+    }
+
+    /// Suspends/resumes rendering for `background_behavior = "pause"`; see
+    /// `engine::background::BackgroundMode::Paused`. `App` drives this from
+    /// its `FocusTracker`, not directly from `WindowEvent::Focused`, since
+    /// minimized state also factors in.
+    pub fn set_unfocused(&mut self, unfocused: bool) {
+        if unfocused {
+            self.gate.suspend(SuspendReason::Unfocused);
+        } else {
+            self.gate.resume_if(SuspendReason::Unfocused);
+            self.mark_dirty();
+        }
+    }
+
+    /// Tears down the surface so a native window handle the OS is about to
+    /// invalidate -- Android `onPause`, or a Wayland compositor dropping
+    /// the surface under it -- isn't held onto past that point. `device`,
+    /// `queue`, `chunk`, `camera`, and `options` don't depend on the surface
+    /// at all, so they're left exactly as they are; `resume` rebuilds just
+    /// the surface against the still-live device once a window is
+    /// available again. A no-op if there's no window (headless) or the
+    /// surface is already down, so a duplicate `Suspended` event is cheap.
+    pub fn suspend(&mut self) {
+        if !Self::should_tear_down_surface(self.surface.is_some()) {
+            return;
+        }
+        self.surface = None;
+        self.gate.suspend(SuspendReason::Lifecycle);
+    }
+
+    /// Rebuilds the surface dropped by `suspend` against the existing
+    /// device/queue, reusing `config` as-is (so a suspend/resume cycle
+    /// doesn't change format/present mode/size on its own -- a `Resized`
+    /// event after resume handles that like any other resize). Idempotent:
+    /// a second `resume` with the surface already live is a no-op, since
+    /// winit can fire back-to-back `Resumed` events on some platforms.
+    pub fn resume(&mut self, window: Arc<Window>) {
+        if !Self::should_rebuild_surface(self.surface.is_some()) {
+            return;
+        }
+        let surface = self.instance.create_surface(window.clone()).unwrap();
+        surface.configure(&self.device, &self.config);
+        self.surface = Some(surface);
+        self.window = Some(window);
+        self.gate.resume_if(SuspendReason::Lifecycle);
+        self.mark_dirty();
+    }
+
+    pub fn is_render_gate_active(&self) -> bool {
+        self.gate.is_active()
+    }
+
+    /// Marks the current frame stale so `OnDemand` redraw policy requests a
+    /// new one. Called by streaming/edit paths (and by `App` for input and
+    /// resize/expose events) whenever something visible changed.
+    pub fn mark_dirty(&mut self) {
+        self.redraw.mark_dirty();
+    }
+
+    pub fn set_redraw_policy(&mut self, policy: RedrawPolicy) {
+        self.redraw.set_policy(policy);
+    }
+
+    /// Whether `App` should call `window.request_redraw()` right now.
+    pub fn wants_redraw(&self) -> bool {
+        self.redraw.wants_redraw()
+    }
+
+    /// Call after `App` has actually requested a redraw, so `OnDemand`
+    /// doesn't keep re-requesting until marked dirty again.
+    pub fn consume_redraw_request(&mut self) {
+        self.redraw.consume_redraw_request();
+    }
+
+    pub fn active_present_mode(&self) -> wgpu::PresentMode {
+        self.active_present_mode
+    }
+
+    pub fn max_fps(&self) -> Option<u32> {
+        self.options.max_fps
+    }
+
+    /// Sums up the GPU allocations behind the currently loaded chunk, for
+    /// tracking VRAM cost as chunk/streaming radius grows.
+    pub fn memory_report(&self) -> GpuMemoryReport {
+        let mut render_target_bytes = self.render_texture.byte_size()
+            + self.fxaa_pass.output_byte_size()
+            + self.gizmo_pass.msaa_byte_size()
+            + self.voxel_renderer_pass.accumulation_buffer_bytes()
+            + self.voxel_renderer_pass.gbuffer_buffer_bytes();
+        if let Some(offscreen) = &self.offscreen {
+            render_target_bytes += offscreen.byte_size();
+        }
+        GpuMemoryReport {
+            octree_bytes: self.voxel_renderer_pass.octree_buffer_bytes()
+                + self.voxel_renderer_pass.material_buffer_bytes()
+                + self.voxel_renderer_pass.emitter_buffer_bytes(),
+            uniform_bytes: self.voxel_renderer_pass.uniform_buffer_bytes(),
+            render_target_bytes,
+            // No `BufferArena` backs any of the above yet -- see
+            // `GpuMemoryReport::fragmentation_ratio`'s doc comment.
+            fragmentation_ratio: 0.0,
+        }
+    }
+
+    /// Blocks until all GPU work submitted so far has completed. The windowed
+    /// path never needs this (the surface present paces it instead); the
+    /// headless benchmark uses it to fold GPU execution time into its
+    /// per-frame measurement rather than only timing command submission.
+    pub fn wait_for_gpu(&self) {
+        self.device.poll(wgpu::Maintain::Wait);
+    }
+
+    /// No-op while [`RenderGate`] is suspended: `get_current_texture` on a
+    /// zero-area surface panics, and there's nothing visible to draw anyway.
+    /// In headless mode the gate is always active, since there's no window
+    /// to occlude or resize to zero.
+    pub fn render(&mut self) -> Result<(), wgpu::SurfaceError> {
+        if self.device_lost.take_lost() {
+            self.recreate_gpu_state();
+            return Ok(());
+        }
+
+        #[cfg(feature = "shader-hot-reload")]
+        self.poll_shader_reload();
+
+        if let Some((width, height)) = self.resize_debounce.take() {
+            self.resize(width, height);
+        }
+
+        if !self.gate.is_active() {
+            return Ok(());
+        }
+
+        let frame_start = Instant::now();
+        let output = match &self.surface {
+            Some(surface) => Some(surface.get_current_texture()?),
+            None => None,
+        };
+        // Requests the sRGB twin explicitly when `color_target_format` differs
+        // from the swapchain's own `config.format` -- the case
+        // `resolve_surface_format` added it to `view_formats` for. Otherwise
+        // the default (the texture's own format) already matches.
+        let surface_view_format =
+            (self.color_target_format != self.config.format).then_some(self.color_target_format);
+        let surface_view = output.as_ref().map(|output| {
+            output.texture.create_view(&wgpu::TextureViewDescriptor {
+                format: surface_view_format,
+                ..Default::default()
+            })
+        });
+        let view = match &surface_view {
+            Some(view) => view,
+            None => {
+                &self
+                    .offscreen
+                    .as_ref()
+                    .expect("renderer has neither a surface nor an offscreen target")
+                    .view
+            }
+        };
+
+        let aspect = self.config.width as f32 / self.config.height as f32;
+        let camera_pos = self
+            .camera_prev_position
+            .lerp(self.camera.position, self.timestep.alpha());
+        let view_proj = self.camera.view_proj_at(aspect, camera_pos);
+
+        let camera_forward = self.camera.forward();
+        let camera_moved = self
+            .last_accumulation_camera
+            .is_some_and(|(pos, forward)| pos != camera_pos || forward != camera_forward);
+        if camera_moved {
+            self.accumulation.reset();
+        }
+        self.last_accumulation_camera = Some((camera_pos, camera_forward));
+
+        self.frame_index = self.frame_index.wrapping_add(1);
+        let accumulated_frames = if self.accumulation_enabled { self.accumulation.frames() } else { 1 };
+        let pick_pixel = self.voxel_renderer_pass.pending_pick_pixel();
+
+        let encode_span = self.profiler.as_mut().map(|p| p.begin_cpu_span("encode"));
+        // Buffer writes (`update_uniforms`, `cull_pass.cull_upload`) are
+        // recorded into `upload_encoder` instead of `render_encoder`, and
+        // submitted first -- see the `queue.submit` call below. wgpu 0.20
+        // exposes only one `Queue`, so this doesn't yet overlap with GPU
+        // work the way a second, dedicated transfer queue eventually could;
+        // it gets the submission path and the upload/compute split ready
+        // for that, and the GPU timer scopes around `render_encoder`'s
+        // compute dispatches (`voxel_compute_tile_*`) already isolate
+        // compute time from upload time either way.
+        let mut upload_encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor { label: Some("upload_encoder") });
+        let mut render_encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor { label: Some("render_encoder") });
+
+        let upload_start = Instant::now();
+        let mut upload_bytes = self.voxel_renderer_pass.update_uniforms(
+            &mut self.upload_context,
+            &self.device,
+            &mut upload_encoder,
+            FrameParams {
+                inv_view_proj: view_proj.inverse(),
+                camera_pos,
+                chunk_size: self.chunk.size() as f32,
+                node_count: self.chunk_node_count,
+                texture_width: self.render_texture.width,
+                texture_height: self.render_texture.height,
+                sun_direction: self.sun_direction,
+                sun_color: self.sun_color,
+                lights: self.lights.clone(),
+                max_bounces: self.max_bounces,
+                ao: AoSettings {
+                    samples: if self.ao_enabled { self.ao_settings.samples } else { 0 },
+                    ..self.ao_settings
+                },
+                frame_index: self.frame_index,
+                accumulated_frames,
+                sky: self.sky,
+                debug_view: self.debug_view,
+                debug_far_plane: self.debug_far_plane,
+                debug_max_tile_cost: self.debug_max_tile_cost,
+                pick_pixel,
+                highlight_voxel: if self.highlight_enabled { self.highlight_voxel } else { None },
+                time_seconds: self.sim_clock.time(),
+                delta_time: self.last_update_dt,
+            },
+        );
+        if self.accumulation_enabled {
+            self.accumulation.advance();
+        }
+
+        if self.gpu_culling_enabled {
+            let tile_size = self.tile_size.unwrap_or(self.render_texture.width.max(self.render_texture.height));
+            upload_bytes += self.cull_pass.cull_upload(
+                &mut upload_encoder,
+                &mut self.upload_context,
+                &self.device,
+                CullFrameParams {
+                    inv_view_proj: view_proj.inverse(),
+                    camera_pos,
+                    chunk_size: self.chunk.size() as f32,
+                    texture_width: self.render_texture.width,
+                    texture_height: self.render_texture.height,
+                },
+                tile_size,
+                self.workgroup_size,
+            );
+        }
+        self.last_upload_stats = (upload_bytes, upload_start.elapsed().as_secs_f32() * 1000.0);
+
+        self.voxel_renderer_pass.clear_tile_costs(&mut render_encoder);
+
+        if self.gpu_culling_enabled {
+            self.cull_pass.cull_dispatch(&mut render_encoder);
+            self.voxel_renderer_pass.compute_with_indirect_pass(
+                &mut render_encoder,
+                self.cull_pass.indirect_buffer(),
+                self.gpu_timer.as_ref().map(|t| t.compute_pass_timestamp_writes("voxel_compute_tile_0")),
+            );
+        } else {
+            self.voxel_renderer_pass.compute_with_pass(
+                &mut render_encoder,
+                &mut self.upload_context,
+                &self.device,
+                self.render_texture.width,
+                self.render_texture.height,
+                self.tile_size,
+                |tile_index| {
+                    (tile_index < MAX_TIMED_TILES)
+                        .then_some(self.gpu_timer.as_ref())
+                        .flatten()
+                        .map(|t| t.compute_pass_timestamp_writes(&format!("voxel_compute_tile_{tile_index}")))
+                },
+            );
+        }
+        if pick_pixel.is_some() {
+            self.voxel_renderer_pass.copy_pick_result(&mut render_encoder);
+        }
+        self.voxel_renderer_pass.copy_tile_costs(&mut render_encoder);
+
+        if self.fxaa_enabled {
+            let fxaa_output = self.fxaa_pass.output_view();
+            let mut pass = render_encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("fxaa_pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: fxaa_output,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+            self.fxaa_pass.draw_with_pass(&mut pass);
+        }
+
+        {
+            let mut pass = render_encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("voxel_blit_pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(if self.debug_clear { wgpu::Color::RED } else { wgpu::Color::BLACK }),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: self.gpu_timer.as_ref().map(|t| t.render_pass_timestamp_writes("present_blit")),
+                occlusion_query_set: None,
+            });
+            self.voxel_image_pass.draw_with_pass(&mut pass);
+        }
+
+        self.gizmo_pass.draw(&mut render_encoder, view);
+
+        if let Some(gpu_timer) = &mut self.gpu_timer {
+            gpu_timer.resolve(&mut render_encoder);
+        }
+
+        drop(encode_span);
+
+        #[cfg(feature = "debug-overlay")]
+        let overlay_actions = self.draw_overlay(&mut render_encoder, surface_view.as_ref(), frame_start, camera_pos);
+
+        // Every write this frame queued through `upload_context` is recorded
+        // as a copy into either `upload_encoder` or `render_encoder` above;
+        // `finish` must run before either encoder it was recorded into is
+        // submitted.
+        self.upload_context.finish();
+
+        let submit_span = self.profiler.as_mut().map(|p| p.begin_cpu_span("submit"));
+        self.queue.submit([upload_encoder.finish(), render_encoder.finish()]);
+        drop(submit_span);
+        self.upload_context.recall();
+
+        if let Some(output) = output {
+            let present_span = self.profiler.as_mut().map(|p| p.begin_cpu_span("present"));
+            output.present();
+            drop(present_span);
+        }
+
+        #[cfg(feature = "debug-overlay")]
+        self.apply_overlay_actions(overlay_actions);
+
+        if let Some(gpu_timer) = &mut self.gpu_timer {
+            let map_span = self.profiler.as_mut().map(|p| p.begin_cpu_span("map"));
+            gpu_timer.read_back(&self.device, &self.queue);
+            drop(map_span);
+        }
+
+        if self.gpu_culling_enabled {
+            self.culled_tiles = Some(self.cull_pass.poll_stats(&self.device));
+        }
+
+        if let Some((generation, result)) = self.voxel_renderer_pass.poll_pick_result(&self.device) {
+            // There's no voxel-editing feature in this engine yet for a pick
+            // to feed into; keeping the highlight in sync with the most
+            // recently resolved pick is the closest honest stand-in for
+            // "highlight the edit target".
+            self.highlight_voxel = if result.hit != 0 {
+                Some((result.voxel[0], result.voxel[1], result.voxel[2]))
+            } else {
+                None
+            };
+            self.pick_ready = Some((generation, result));
+        }
+
+        self.voxel_renderer_pass.poll_tile_costs(&self.device);
+        if let Some((_, cost)) = self.voxel_renderer_pass.top_k_tile_costs(1).first().copied() {
+            self.debug_max_tile_cost = cost as f32;
+        }
+
+        self.record_profiled_frame(frame_start);
+        Ok(())
+    }
+
+    /// Feeds this frame's GPU timings into the active profiling capture (if
+    /// any), advances its frame counter, and exports + drops it once the
+    /// configured `max_frames` has been reached.
+    fn record_profiled_frame(&mut self, frame_start: Instant) {
+        let Some(profiler) = &mut self.profiler else {
+            return;
+        };
+        if let Some(gpu_timer) = &self.gpu_timer {
+            for (scope, ms) in gpu_timer.results() {
+                profiler.record_gpu_span(scope, frame_start, *ms);
+            }
+        }
+        profiler.end_frame();
+        if profiler.is_recording() {
+            return;
+        }
+
+        const TRACE_PATH: &str = "trace.json";
+        match profiler.write_chrome_trace(TRACE_PATH) {
+            Ok(()) => log::info!("wrote profiling trace to {TRACE_PATH}"),
+            Err(e) => log::warn!("failed to write profiling trace: {e}"),
+        }
+        self.profiler = None;
+    }
+
+    /// Per-pass GPU time in milliseconds from the most recent frame, or an
+    /// empty slice if the adapter doesn't support `Features::TIMESTAMP_QUERY`.
+    pub fn gpu_timings(&self) -> &[(String, f32)] {
+        self.gpu_timer.as_ref().map_or(&[], |t| t.results())
+    }
+
+    /// `(visible, total)` tile counts from the most recently polled
+    /// `CullPass::poll_stats`, or `None` when `RendererOptions::gpu_culling_enabled`
+    /// is off.
+    pub fn culled_tiles(&self) -> Option<(u32, u32)> {
+        self.culled_tiles.map(|stats| (stats.visible_tile_count, stats.total_tile_count))
+    }
+
+    /// `(bytes, cpu_ms)` the most recent frame wrote into its upload encoder
+    /// -- see `render`'s `upload_encoder`. `cpu_ms` is wall-clock time spent
+    /// recording those writes, not a GPU timestamp; the GPU timer's
+    /// `voxel_compute_tile_*` scopes (see `gpu_timings`) are what show
+    /// whether upload-heavy frames are extending compute time.
+    pub fn upload_stats(&self) -> (u64, f32) {
+        self.last_upload_stats
+    }
+
+    /// Gives the debug overlay first refusal on a window event; see
+    /// [`super::overlay::Overlay::consumes_event`].
+    #[cfg(feature = "debug-overlay")]
+    pub fn overlay_consumes_event(&mut self, event: &winit::event::WindowEvent) -> bool {
+        let (Some(overlay), Some(window)) = (&mut self.overlay, &self.window) else {
+            return false;
+        };
+        overlay.consumes_event(window, event)
+    }
+
+    /// Draws the debug overlay panel onto `surface_view` (a no-op if this is
+    /// a headless renderer with no window/surface). Must run after the voxel
+    /// blit pass has been recorded and before `queue.submit`.
+    #[cfg(feature = "debug-overlay")]
+    fn draw_overlay(
+        &mut self,
+        encoder: &mut wgpu::CommandEncoder,
+        surface_view: Option<&wgpu::TextureView>,
+        frame_start: Instant,
+        camera_pos: glam::Vec3,
+    ) -> super::overlay::OverlayActions {
+        if self.overlay.is_none() {
+            return super::overlay::OverlayActions::default();
+        }
+        let (Some(window), Some(surface_view)) = (self.window.clone(), surface_view) else {
+            return super::overlay::OverlayActions::default();
+        };
+
+        let fps = self
+            .last_frame_start
+            .map(|prev| 1.0 / frame_start.duration_since(prev).as_secs_f64())
+            .unwrap_or(0.0);
+        self.last_frame_start = Some(frame_start);
+
+        let stats = OverlayStats {
+            fps,
+            gpu_times_ms: self.gpu_timer.as_ref().map_or_else(Vec::new, |t| t.results().to_vec()),
+            camera_position: camera_pos,
+            chunk_node_count: self.chunk_node_count,
+            memory_report: self.memory_report(),
+            vsync_enabled: !matches!(self.active_present_mode, wgpu::PresentMode::AutoNoVsync | wgpu::PresentMode::Immediate),
+            render_scale: self.render_scale,
+            aa_mode: self.aa_mode,
+            fxaa_enabled: self.fxaa_enabled,
+            hdr_enabled: self.hdr_enabled,
+            srgb_conversion_enabled: self.srgb_conversion_enabled,
+            exposure: self.exposure,
+            tonemap_operator: self.tonemap_operator,
+            sun_direction: self.sun_direction,
+            sun_color: self.sun_color,
+            lights: self.lights.clone(),
+            day_cycle_enabled: self.day_cycle_enabled,
+            day_night_paused: self.day_night.paused(),
+            time_of_day: self.day_night.time_of_day(),
+            day_length_seconds: self.day_night.length_seconds(),
+            ao_enabled: self.ao_enabled,
+            ao_settings: self.ao_settings,
+            accumulation_enabled: self.accumulation_enabled,
+            sky: self.sky,
+            debug_clear: self.debug_clear,
+            debug_view: self.debug_view,
+            debug_far_plane: self.debug_far_plane,
+            top_tile_costs: self
+                .voxel_renderer_pass
+                .top_k_tile_costs(10)
+                .into_iter()
+                .map(|(tile_index, cost)| (self.voxel_renderer_pass.tile_origin(tile_index), cost))
+                .collect(),
+            highlight_enabled: self.highlight_enabled,
+        };
+
+        self.overlay.as_mut().unwrap().draw(
+            &self.device,
+            &self.queue,
+            encoder,
+            surface_view,
+            &window,
+            [self.config.width, self.config.height],
+            &stats,
+        )
+    }
+
+    /// Applies whatever the overlay's panel was asked to do this frame.
+    /// Deferred until after `present()` so none of these touch the surface
+    /// while a `SurfaceTexture` from it is still outstanding.
+    #[cfg(feature = "debug-overlay")]
+    fn apply_overlay_actions(&mut self, actions: super::overlay::OverlayActions) {
+        if actions.toggle_vsync {
+            self.toggle_vsync();
+        }
+        if let Some(scale) = actions.render_scale {
+            self.set_render_scale(scale);
+        }
+        if let Some(mode) = actions.aa_mode {
+            self.set_aa_mode(mode);
+        }
+        if let Some(enabled) = actions.fxaa_enabled {
+            self.set_fxaa_enabled(enabled);
+        }
+        if let Some(enabled) = actions.hdr_enabled {
+            self.set_hdr_enabled(enabled);
+        }
+        if let Some(enabled) = actions.srgb_conversion_enabled {
+            self.set_srgb_conversion_enabled(enabled);
+        }
+        if let Some(exposure) = actions.exposure {
+            self.set_exposure(exposure);
+        }
+        if let Some(operator) = actions.tonemap_operator {
+            self.set_tonemap_operator(operator);
+        }
+        if let Some((direction, color)) = actions.sun {
+            self.set_sun(direction, color);
+        }
+        if let Some(lights) = actions.lights {
+            if let Err(err) = self.set_lights(&lights) {
+                log::warn!("overlay light edit rejected: {err}");
+            }
+        }
+        if let Some(enabled) = actions.day_cycle_enabled {
+            self.set_day_cycle_enabled(enabled);
+        }
+        if let Some(paused) = actions.day_night_paused {
+            self.set_day_night_paused(paused);
+        }
+        if let Some(seconds) = actions.time_of_day {
+            self.set_time_of_day(seconds);
+        }
+        if let Some(enabled) = actions.ao_enabled {
+            self.set_ao_enabled(enabled);
+        }
+        if let Some(settings) = actions.ao_settings {
+            self.set_ao_settings(settings);
+        }
+        if let Some(enabled) = actions.accumulation_enabled {
+            self.set_accumulation_enabled(enabled);
+        }
+        if let Some(sky) = actions.sky {
+            self.set_sky(sky);
+        }
+        if let Some(enabled) = actions.debug_clear {
+            self.set_debug_clear(enabled);
+        }
+        if let Some(view) = actions.debug_view {
+            self.set_debug_view(view);
+        }
+        if let Some(far_plane) = actions.debug_far_plane {
+            self.set_debug_far_plane(far_plane);
+        }
+        if let Some(enabled) = actions.highlight_enabled {
+            self.set_highlight_enabled(enabled);
+        }
+        if actions.regenerate_chunk {
+            self.regenerate_chunk();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary(name: &str) -> AdapterSummary {
+        AdapterSummary {
+            name: name.to_string(),
+            backend: wgpu::Backend::Vulkan,
+            device_type: wgpu::DeviceType::DiscreteGpu,
+            driver: String::new(),
+            driver_info: String::new(),
+        }
+    }
+
+    #[test]
+    fn name_match_is_case_insensitive() {
+        assert!(summary("NVIDIA GeForce RTX 4070").name_matches("nvidia"));
+        assert!(summary("NVIDIA GeForce RTX 4070").name_matches("geforce"));
+    }
+
+    #[test]
+    fn name_match_is_substring() {
+        assert!(summary("AMD Radeon RX 7900 XTX").name_matches("7900"));
+    }
+
+    #[test]
+    fn name_match_rejects_unrelated_names() {
+        assert!(!summary("Intel(R) UHD Graphics 620").name_matches("nvidia"));
+    }
+
+    #[test]
+    fn scaled_size_halves_at_half_render_scale() {
+        assert_eq!(Renderer::scaled_size(1280, 720, 0.5), (640, 360));
+    }
+
+    #[test]
+    fn scaled_size_is_unchanged_at_native_scale() {
+        assert_eq!(Renderer::scaled_size(1280, 720, 1.0), (1280, 720));
+    }
+
+    #[test]
+    fn scaled_size_never_rounds_down_to_zero() {
+        assert_eq!(Renderer::scaled_size(1, 1, 0.25), (1, 1));
+    }
+
+    #[test]
+    fn native_scale_never_filters() {
+        assert_eq!(Renderer::blit_filter(1.0, AaMode::Native), BlitFilter::Nearest);
+        assert_eq!(Renderer::blit_filter(1.0, AaMode::SuperSample2x), BlitFilter::Nearest);
+    }
+
+    #[test]
+    fn supersample_only_applies_at_2x_scale() {
+        assert_eq!(Renderer::blit_filter(2.0, AaMode::SuperSample2x), BlitFilter::Box2x);
+        assert_eq!(Renderer::blit_filter(1.5, AaMode::SuperSample2x), BlitFilter::Linear);
+        assert_eq!(Renderer::blit_filter(0.5, AaMode::SuperSample2x), BlitFilter::Linear);
+    }
+
+    #[test]
+    fn non_native_scale_without_supersampling_uses_linear() {
+        assert_eq!(Renderer::blit_filter(0.5, AaMode::Native), BlitFilter::Linear);
+    }
+
+    #[test]
+    fn suspend_tears_down_a_live_surface() {
+        assert!(Renderer::should_tear_down_surface(true));
+    }
+
+    #[test]
+    fn suspend_is_a_no_op_without_a_live_surface() {
+        assert!(!Renderer::should_tear_down_surface(false));
+    }
+
+    #[test]
+    fn resume_rebuilds_a_missing_surface() {
+        assert!(Renderer::should_rebuild_surface(false));
+    }
+
+    #[test]
+    fn resume_is_a_no_op_when_the_surface_is_already_live() {
+        assert!(!Renderer::should_rebuild_surface(true));
+    }
+}
@@ -0,0 +1,166 @@
+//! Temporal reprojection: instead of resetting [`super::accumulation::AccumulationState`]
+//! to a single fresh frame on any camera motion, a real implementation would
+//! reproject last frame's shaded color using the *previous* frame's
+//! view-projection matrix and keep blending wherever the reprojected sample
+//! still lands on the same surface, only falling back to a fresh sample
+//! where it doesn't (disocclusion).
+//!
+//! Not wired into `Renderer` yet -- that needs several things this crate
+//! doesn't have:
+//! - A history color + depth texture pair with ping-pong swap management in
+//!   `Renderer`, alongside (not replacing) the existing single accumulation
+//!   buffer `AccumulationState` already tracks.
+//! - A previous-view-projection field threaded through the frame uniforms
+//!   (`Uniforms` in `voxel_renderer.rs` and its WGSL twin in
+//!   `voxel_renderer.wgsl`) so the compute shader can do this reprojection
+//!   per pixel; `Camera` itself only ever exposes the *current* matrix via
+//!   `view_proj_at`.
+//! - Resize/reset handling for the history textures alongside `RenderTargets::resize`.
+//!
+//! What's here is the reprojection math and the disocclusion test
+//! themselves: given the previous frame's view-projection matrix and a
+//! world-space position, where did that surface appear on screen last
+//! frame, and is it still close enough to trust that frame's shading.
+#![allow(dead_code)]
+
+/// Blend weight given to history when a pixel survives the disocclusion
+/// test; `depth_tolerance` is a fraction of the reprojected clip-space `w`
+/// (camera-relative distance), matching the ratio, not absolute distance
+/// this crate keeps for AO/shadow settings.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReprojectionSettings {
+    /// `0` behaves exactly like accumulation reset on every frame (no
+    /// history reuse); `1` would never take a fresh sample once history is
+    /// available, so callers should keep this below `1`.
+    pub blend_factor: f32,
+    /// How far apart (as a fraction of camera-relative distance) this
+    /// frame's depth and the reprojected sample's depth may be before the
+    /// pixel is treated as disoccluded.
+    pub depth_tolerance: f32,
+}
+
+impl Default for ReprojectionSettings {
+    fn default() -> Self {
+        Self { blend_factor: 0.9, depth_tolerance: 0.02 }
+    }
+}
+
+/// Tracks the previous frame's view-projection matrix, the one piece of
+/// bookkeeping [`reproject_to_screen_uv`] needs. Mirrors how `Renderer`
+/// already tracks `last_accumulation_camera` across frames.
+#[derive(Debug, Clone, Copy)]
+pub struct PreviousFrame {
+    view_proj: glam::Mat4,
+}
+
+impl PreviousFrame {
+    pub fn new(view_proj: glam::Mat4) -> Self {
+        Self { view_proj }
+    }
+
+    pub fn view_proj(&self) -> glam::Mat4 {
+        self.view_proj
+    }
+
+    /// Call once per rendered frame with the matrix that just rendered,
+    /// ready for next frame's reprojection.
+    pub fn advance(&mut self, view_proj: glam::Mat4) {
+        self.view_proj = view_proj;
+    }
+}
+
+/// Projects `world_pos` through last frame's view-projection matrix,
+/// returning the screen UV (`0..1`, `y` down, matching `hiz::chunk_screen_bounds`'s
+/// convention) it landed on and the camera-relative distance (clip-space
+/// `w`) it was seen at. Returns `None` when the position was behind the
+/// previous camera or fell outside the previous frame's viewport, since
+/// there's no history to reuse in either case.
+pub fn reproject_to_screen_uv(previous: &PreviousFrame, world_pos: glam::Vec3) -> Option<(glam::Vec2, f32)> {
+    let clip = previous.view_proj() * world_pos.extend(1.0);
+    if clip.w <= 0.0 {
+        return None;
+    }
+    let ndc = clip.truncate() / clip.w;
+    if ndc.x < -1.0 || ndc.x > 1.0 || ndc.y < -1.0 || ndc.y > 1.0 {
+        return None;
+    }
+    let uv = glam::Vec2::new(ndc.x * 0.5 + 0.5, 1.0 - (ndc.y * 0.5 + 0.5));
+    Some((uv, clip.w))
+}
+
+/// How much of the reprojected history sample to blend into this frame's
+/// shading for one pixel: `settings.blend_factor` if the previous frame's
+/// recorded depth at the reprojected position still agrees with this
+/// frame's fresh depth within `settings.depth_tolerance`, `0.0` (a fresh
+/// sample, no history) otherwise.
+pub fn history_weight(current_depth: f32, reprojected_depth: f32, settings: &ReprojectionSettings) -> f32 {
+    let tolerance = settings.depth_tolerance * current_depth.max(reprojected_depth).max(1.0);
+    if (current_depth - reprojected_depth).abs() <= tolerance {
+        settings.blend_factor
+    } else {
+        0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_static_camera_reprojects_a_point_back_to_the_same_screen_location() {
+        let view = glam::Mat4::look_at_rh(glam::Vec3::new(0.0, 0.0, -10.0), glam::Vec3::ZERO, glam::Vec3::Y);
+        let proj = glam::Mat4::perspective_rh(60f32.to_radians(), 1.0, 0.1, 1000.0);
+        let previous = PreviousFrame::new(proj * view);
+        let (uv, depth) = reproject_to_screen_uv(&previous, glam::Vec3::ZERO).expect("point in front of the camera");
+        assert!((uv.x - 0.5).abs() < 0.001, "centered point should reproject to the center of the screen: {uv:?}");
+        assert!((uv.y - 0.5).abs() < 0.001, "centered point should reproject to the center of the screen: {uv:?}");
+        assert!(depth > 0.0);
+    }
+
+    #[test]
+    fn a_point_behind_the_previous_camera_has_no_history() {
+        let view = glam::Mat4::look_at_rh(glam::Vec3::new(0.0, 0.0, -10.0), glam::Vec3::ZERO, glam::Vec3::Y);
+        let proj = glam::Mat4::perspective_rh(60f32.to_radians(), 1.0, 0.1, 1000.0);
+        let previous = PreviousFrame::new(proj * view);
+        let behind_camera = glam::Vec3::new(0.0, 0.0, -20.0);
+        assert!(reproject_to_screen_uv(&previous, behind_camera).is_none());
+    }
+
+    #[test]
+    fn a_point_far_outside_the_frustum_has_no_history() {
+        let view = glam::Mat4::look_at_rh(glam::Vec3::new(0.0, 0.0, -10.0), glam::Vec3::ZERO, glam::Vec3::Y);
+        let proj = glam::Mat4::perspective_rh(60f32.to_radians(), 1.0, 0.1, 1000.0);
+        let previous = PreviousFrame::new(proj * view);
+        let far_off_axis = glam::Vec3::new(1000.0, 0.0, 0.0);
+        assert!(reproject_to_screen_uv(&previous, far_off_axis).is_none());
+    }
+
+    #[test]
+    fn matching_depths_within_tolerance_reuse_history() {
+        let settings = ReprojectionSettings::default();
+        let weight = history_weight(10.0, 10.05, &settings);
+        assert_eq!(weight, settings.blend_factor);
+    }
+
+    #[test]
+    fn a_disoccluded_pixel_falls_back_to_a_fresh_sample() {
+        let settings = ReprojectionSettings::default();
+        let weight = history_weight(10.0, 50.0, &settings);
+        assert_eq!(weight, 0.0);
+    }
+
+    #[test]
+    fn zero_blend_factor_behaves_like_accumulation_always_resetting() {
+        let settings = ReprojectionSettings { blend_factor: 0.0, depth_tolerance: 0.02 };
+        assert_eq!(history_weight(10.0, 10.0, &settings), 0.0);
+    }
+
+    #[test]
+    fn previous_frame_advance_replaces_the_stored_matrix() {
+        let first = glam::Mat4::IDENTITY;
+        let second = glam::Mat4::from_translation(glam::Vec3::new(1.0, 0.0, 0.0));
+        let mut previous = PreviousFrame::new(first);
+        previous.advance(second);
+        assert_eq!(previous.view_proj(), second);
+    }
+}
@@ -0,0 +1,64 @@
+/// Coalesces back-to-back resize requests -- a window resize can fire
+/// dozens of `Resized` events per second while being dragged -- into
+/// "rebuild render targets once, at the next frame, at whatever size was
+/// last requested" instead of once per event. `Renderer::request_resize`
+/// feeds this; `Renderer::render` drains it at the start of the frame.
+#[derive(Default)]
+pub(crate) struct ResizeDebounce {
+    requested: Option<(u32, u32)>,
+}
+
+impl ResizeDebounce {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records `(width, height)` as the size to apply next frame,
+    /// overwriting whatever was requested since the last `take`.
+    pub(crate) fn request(&mut self, width: u32, height: u32) {
+        self.requested = Some((width, height));
+    }
+
+    /// Takes the latest requested size, if any, clearing it so the same
+    /// request isn't applied twice.
+    pub(crate) fn take(&mut self) -> Option<(u32, u32)> {
+        self.requested.take()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn starts_with_nothing_pending() {
+        assert_eq!(ResizeDebounce::new().take(), None);
+    }
+
+    #[test]
+    fn a_single_request_is_applied_once() {
+        let mut debounce = ResizeDebounce::new();
+        debounce.request(800, 600);
+        assert_eq!(debounce.take(), Some((800, 600)));
+        assert_eq!(debounce.take(), None);
+    }
+
+    #[test]
+    fn repeated_requests_before_a_take_collapse_to_the_latest() {
+        let mut debounce = ResizeDebounce::new();
+        debounce.request(800, 600);
+        debounce.request(801, 600);
+        debounce.request(1024, 768);
+        assert_eq!(debounce.take(), Some((1024, 768)));
+    }
+
+    #[test]
+    fn a_request_after_a_take_is_tracked_independently() {
+        let mut debounce = ResizeDebounce::new();
+        debounce.request(800, 600);
+        debounce.take();
+        assert_eq!(debounce.take(), None);
+        debounce.request(1024, 768);
+        assert_eq!(debounce.take(), Some((1024, 768)));
+    }
+}
@@ -0,0 +1,366 @@
+//! A declarative scene file (TOML, like `camera_path`'s keyframe files) so a
+//! demo can describe camera start pose, sky, fill lights, and the loaded
+//! chunk without recompiling. [`parse`]/[`load`] validate up front and name
+//! the offending entry on failure, same as [`super::camera_path::parse`].
+//!
+//! What this can't cover, and why: this engine has no procedural or
+//! multi-region world generation (`Renderer::regenerate_chunk`'s own doc
+//! comment says so) -- there's exactly one loaded [`Chunk`], filled by one
+//! of the two hardcoded test patterns, so [`ChunkGenerator`] just names
+//! those two instead of a generator+seed pair. There's also no `.vox`
+//! importer anywhere in this crate, and no editor placing materials by ID
+//! -- `material_overrides` can only retint one of the five fixed
+//! [`Voxel`](crate::voxel::material::Voxel) IDs terrain generation already
+//! produces, not register a new named material (see
+//! [`MaterialTableBuilder`](crate::voxel::material::MaterialTableBuilder)
+//! for that, once something places materials by name instead of by ID).
+//! And while [`SceneDescription::materials`] is validated on load same as
+//! everything else here, `Renderer::load_scene` can't actually push the
+//! result to the GPU yet: `VoxelRendererPass` bakes a `MaterialTable` in at
+//! construction with no update entry point, so a scene's material
+//! overrides currently only take effect on the CPU-side table this module
+//! builds, not on what the shader reads.
+//!
+//! Nothing here watches the file for changes the way `ShaderWatcher` does
+//! for `.wgsl` sources -- that watcher is feature-gated and hardcoded to
+//! `src/shaders/`, not a fit for a scene file living wherever the console's
+//! `scene` command points it. Re-running that command after an edit is the
+//! reload path for now, the same way `flypath` is for camera paths.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use super::camera_path::CameraPose;
+use crate::voxel::material::{MaterialProperties, MaterialTable, Voxel};
+use crate::voxel::{Chunk, Light, SkySettings, MAX_LIGHTS};
+
+/// Which of this crate's two hardcoded terrain generators fills the scene's
+/// chunk, and the one parameter each takes; see the module doc comment for
+/// why there isn't a third, procedural option.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ChunkGenerator {
+    TestPatternWithWater { water_depth: u32 },
+    TestPatternWithMirrorFloor { tower_height: u32 },
+}
+
+impl ChunkGenerator {
+    pub fn generate(&self, position: glam::IVec3) -> Chunk {
+        match *self {
+            Self::TestPatternWithWater { water_depth } => Chunk::filled_test_pattern_with_water(position, water_depth),
+            Self::TestPatternWithMirrorFloor { tower_height } => {
+                Chunk::filled_test_pattern_with_mirror_floor(position, tower_height)
+            }
+        }
+    }
+}
+
+/// The scene's one chunk: where it sits and which generator fills it.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SceneChunk {
+    pub position: [i32; 3],
+    pub generator: ChunkGenerator,
+}
+
+/// Camera start pose in degrees, like `CameraKeyframe` -- what a human
+/// hand-editing a TOML file reaches for, converted to radians on load.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SceneCamera {
+    pub position: [f32; 3],
+    pub yaw_degrees: f32,
+    pub pitch_degrees: f32,
+}
+
+impl SceneCamera {
+    pub fn pose(&self) -> CameraPose {
+        CameraPose {
+            position: self.position.into(),
+            yaw_radians: self.yaw_degrees.to_radians(),
+            pitch_radians: self.pitch_degrees.to_radians(),
+        }
+    }
+}
+
+/// [`SkySettings`] in plain arrays instead of `glam::Vec3` -- `glam`'s
+/// `serde` feature isn't enabled in this crate (see `CameraKeyframe`'s own
+/// `[f32; 3]` fields for the same reason), so this is the file's on-disk
+/// shape, converted with [`SceneSky::to_sky_settings`].
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SceneSky {
+    pub zenith_color: [f32; 3],
+    pub horizon_color: [f32; 3],
+    pub ground_color: [f32; 3],
+    pub sun_disc: bool,
+}
+
+impl Default for SceneSky {
+    fn default() -> Self {
+        let sky = SkySettings::default();
+        Self {
+            zenith_color: sky.zenith_color.into(),
+            horizon_color: sky.horizon_color.into(),
+            ground_color: sky.ground_color.into(),
+            sun_disc: sky.sun_disc,
+        }
+    }
+}
+
+impl SceneSky {
+    pub fn to_sky_settings(self) -> SkySettings {
+        SkySettings {
+            zenith_color: self.zenith_color.into(),
+            horizon_color: self.horizon_color.into(),
+            ground_color: self.ground_color.into(),
+            sun_disc: self.sun_disc,
+        }
+    }
+}
+
+/// A fill [`Light`] in the same array-based encoding as [`SceneSky`].
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SceneLight {
+    pub direction: [f32; 3],
+    pub color: [f32; 3],
+    pub intensity: f32,
+    #[serde(default)]
+    pub cast_shadows: bool,
+}
+
+impl SceneLight {
+    pub fn to_light(self) -> Light {
+        Light {
+            direction: glam::Vec3::from(self.direction),
+            color: glam::Vec3::from(self.color),
+            intensity: self.intensity,
+            cast_shadows: self.cast_shadows,
+        }
+    }
+}
+
+/// A parsed, validated scene file. Build one with [`load`]/[`parse`] rather
+/// than constructing it directly, so it's never seen in an unvalidated
+/// state (an unknown material override or too many lights).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SceneDescription {
+    pub camera: SceneCamera,
+    #[serde(default)]
+    pub sky: SceneSky,
+    pub chunk: SceneChunk,
+    #[serde(default)]
+    pub lights: Vec<SceneLight>,
+    /// Keyed by this engine's built-in material name (`"air"`, `"stone"`,
+    /// `"water"`, `"glowstone"`, `"mirror"`), layered onto
+    /// `MaterialTable::default()`; see the module doc comment for why an
+    /// override can't register a new material instead.
+    #[serde(default)]
+    pub material_overrides: BTreeMap<String, MaterialProperties>,
+}
+
+impl SceneDescription {
+    /// Fails on the first override naming a material this engine doesn't
+    /// have, or on more lights than `Renderer::set_lights` would accept.
+    pub fn validate(&self) -> Result<(), SceneError> {
+        self.materials()?;
+        if self.lights.len() > MAX_LIGHTS {
+            return Err(SceneError::TooManyLights { max: MAX_LIGHTS, got: self.lights.len() });
+        }
+        Ok(())
+    }
+
+    /// A fresh [`Chunk`] from [`SceneChunk::generator`], at
+    /// [`SceneChunk::position`].
+    pub fn build_chunk(&self) -> Chunk {
+        self.chunk.generator.generate(glam::IVec3::from(self.chunk.position))
+    }
+
+    pub fn camera_pose(&self) -> CameraPose {
+        self.camera.pose()
+    }
+
+    pub fn sky_settings(&self) -> SkySettings {
+        self.sky.to_sky_settings()
+    }
+
+    pub fn lights(&self) -> Vec<Light> {
+        self.lights.iter().map(|light| light.to_light()).collect()
+    }
+
+    /// [`MaterialTable::default`] with `material_overrides` layered on top,
+    /// naming the first override that doesn't match a known material.
+    pub fn materials(&self) -> Result<MaterialTable, SceneError> {
+        let mut table = MaterialTable::default();
+        for (name, properties) in &self.material_overrides {
+            let id = material_id_by_name(name).ok_or_else(|| SceneError::UnknownMaterial(name.clone()))?;
+            table.set(id, *properties);
+        }
+        Ok(table)
+    }
+}
+
+fn material_id_by_name(name: &str) -> Option<u32> {
+    match name {
+        "air" => Some(Voxel::AIR),
+        "stone" => Some(Voxel::STONE),
+        "water" => Some(Voxel::WATER),
+        "glowstone" => Some(Voxel::GLOWSTONE),
+        "mirror" => Some(Voxel::MIRROR),
+        _ => None,
+    }
+}
+
+/// Reasons parsing or validating a scene file can fail; [`std::fmt::Display`]
+/// names the offending entry rather than just "invalid scene".
+#[derive(Debug, Clone, PartialEq)]
+pub enum SceneError {
+    /// The file didn't read or didn't parse as the expected TOML shape.
+    Parse(String),
+    /// A `material_overrides` key isn't one of this engine's material
+    /// names.
+    UnknownMaterial(String),
+    /// More `lights` entries than `Renderer::set_lights` supports.
+    TooManyLights { max: usize, got: usize },
+}
+
+impl std::fmt::Display for SceneError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Parse(message) => write!(f, "{message}"),
+            Self::UnknownMaterial(name) => write!(
+                f,
+                "scene overrides unknown material {name:?} (expected one of: air, stone, water, glowstone, mirror)"
+            ),
+            Self::TooManyLights { max, got } => write!(f, "scene lists {got} lights, but at most {max} are supported"),
+        }
+    }
+}
+
+impl std::error::Error for SceneError {}
+
+/// Parses a scene TOML document and validates it, so a caller never has to
+/// check both a parse error and a validation error separately.
+pub fn parse(raw: &str) -> Result<SceneDescription, SceneError> {
+    let scene: SceneDescription = toml::from_str(raw).map_err(|err| SceneError::Parse(err.to_string()))?;
+    scene.validate()?;
+    Ok(scene)
+}
+
+/// Reads `path` and [`parse`]s it, naming `path` in the error if it doesn't
+/// even read.
+pub fn load(path: &Path) -> Result<SceneDescription, SceneError> {
+    let raw = std::fs::read_to_string(path).map_err(|err| SceneError::Parse(format!("{}: {err}", path.display())))?;
+    parse(&raw)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn representative_scene_toml() -> &'static str {
+        r#"
+            [camera]
+            position = [4.0, 20.0, 4.0]
+            yaw_degrees = 45.0
+            pitch_degrees = -30.0
+
+            [sky]
+            zenith_color = [0.1, 0.2, 0.4]
+            horizon_color = [0.6, 0.7, 0.8]
+            ground_color = [0.1, 0.1, 0.1]
+            sun_disc = false
+
+            [chunk]
+            position = [0, 0, 0]
+            generator = { kind = "test_pattern_with_water", water_depth = 6 }
+
+            [[lights]]
+            direction = [0.3, 0.5, 0.2]
+            color = [1.0, 0.9, 0.8]
+            intensity = 0.6
+            cast_shadows = true
+
+            [material_overrides.stone]
+            color = [0.6, 0.6, 0.6]
+            transparent = 0
+            absorption = 0.0
+            emissive = 0.0
+            reflectivity = 0.0
+        "#
+    }
+
+    #[test]
+    fn parses_a_representative_scene() {
+        let scene = parse(representative_scene_toml()).unwrap();
+        assert_eq!(scene.chunk.position, [0, 0, 0]);
+        assert_eq!(scene.chunk.generator, ChunkGenerator::TestPatternWithWater { water_depth: 6 });
+        assert_eq!(scene.lights.len(), 1);
+    }
+
+    #[test]
+    fn builds_a_chunk_from_the_named_generator() {
+        let scene = parse(representative_scene_toml()).unwrap();
+        let chunk = scene.build_chunk();
+        assert_eq!(chunk.position, glam::IVec3::ZERO);
+        // The water generator fills at least the requested depth with a
+        // non-air material somewhere in the column.
+        assert_ne!(chunk.tree.get(glam::UVec3::new(0, 0, 0)), Voxel::AIR);
+    }
+
+    #[test]
+    fn converts_camera_and_sky_and_lights() {
+        let scene = parse(representative_scene_toml()).unwrap();
+
+        let pose = scene.camera_pose();
+        assert_eq!(pose.position, glam::Vec3::new(4.0, 20.0, 4.0));
+        assert!((pose.yaw_radians - 45.0f32.to_radians()).abs() < 1e-6);
+
+        let sky = scene.sky_settings();
+        assert!(!sky.sun_disc);
+
+        let lights = scene.lights();
+        assert_eq!(lights.len(), 1);
+        assert!(lights[0].cast_shadows);
+    }
+
+    #[test]
+    fn applies_a_material_override_on_top_of_the_default_table() {
+        let scene = parse(representative_scene_toml()).unwrap();
+        let table = scene.materials().unwrap();
+        assert_eq!(table.get(Voxel::STONE).color, [0.6, 0.6, 0.6]);
+        // Untouched entries still come from `MaterialTable::default`.
+        assert!(table.get(Voxel::WATER).transparent == 1);
+    }
+
+    #[test]
+    fn rejects_an_override_naming_an_unknown_material() {
+        let raw = representative_scene_toml().replace("material_overrides.stone", "material_overrides.basalt");
+        let err = parse(&raw).unwrap_err();
+        assert_eq!(err, SceneError::UnknownMaterial("basalt".to_string()));
+    }
+
+    #[test]
+    fn rejects_more_lights_than_max_lights_supports() {
+        let mut scene = parse(representative_scene_toml()).unwrap();
+        scene.lights = (0..(MAX_LIGHTS + 1))
+            .map(|_| SceneLight { direction: [0.0, 1.0, 0.0], color: [1.0, 1.0, 1.0], intensity: 1.0, cast_shadows: false })
+            .collect();
+        let err = scene.validate().unwrap_err();
+        assert_eq!(err, SceneError::TooManyLights { max: MAX_LIGHTS, got: MAX_LIGHTS + 1 });
+    }
+
+    #[test]
+    fn rejects_malformed_toml_naming_the_problem() {
+        let err = parse("this is not valid toml [[[").unwrap_err();
+        assert!(matches!(err, SceneError::Parse(_)));
+    }
+
+    #[test]
+    fn rejects_a_file_that_does_not_exist() {
+        let err = load(Path::new("/nonexistent/scene.toml")).unwrap_err();
+        match err {
+            SceneError::Parse(message) => assert!(message.contains("nonexistent")),
+            other => panic!("expected a Parse error, got {other:?}"),
+        }
+    }
+}
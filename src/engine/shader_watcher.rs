@@ -0,0 +1,109 @@
+//! Dev-only shader hot reload, built only with the `shader-hot-reload`
+//! feature. See `Renderer::poll_shader_reload`.
+
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{Receiver, TryRecvError};
+
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+
+/// Watches `src/shaders/` for edits so `Renderer::render` can revalidate and
+/// rebuild the affected pipeline within a frame of saving, instead of the
+/// `include_str!` snapshot every other build embeds at compile time.
+pub struct ShaderWatcher {
+    // Never read after construction, but must outlive `events` -- dropping
+    // it tears down the OS watch.
+    _watcher: RecommendedWatcher,
+    events: Receiver<notify::Result<notify::Event>>,
+}
+
+impl ShaderWatcher {
+    pub fn new() -> notify::Result<Self> {
+        let (tx, events) = std::sync::mpsc::channel();
+        let mut watcher = notify::recommended_watcher(move |event| {
+            let _ = tx.send(event);
+        })?;
+        watcher.watch(&shaders_dir(), RecursiveMode::NonRecursive)?;
+        Ok(Self { _watcher: watcher, events })
+    }
+
+    /// File names (e.g. `"voxel_renderer.wgsl"`) touched since the last
+    /// call, deduplicated. Never blocks -- `Renderer::render` calls this
+    /// once per frame.
+    pub fn poll_changed(&self) -> Vec<String> {
+        let mut changed = Vec::new();
+        loop {
+            match self.events.try_recv() {
+                Ok(Ok(event)) => {
+                    for path in event.paths {
+                        let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+                            continue;
+                        };
+                        if name.ends_with(".wgsl") && !changed.iter().any(|c: &String| c == name) {
+                            changed.push(name.to_string());
+                        }
+                    }
+                }
+                Ok(Err(err)) => log::warn!("shader watcher error: {err}"),
+                Err(TryRecvError::Empty | TryRecvError::Disconnected) => break,
+            }
+        }
+        changed
+    }
+}
+
+fn shaders_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("src/shaders")
+}
+
+/// Reads `name` (e.g. `"voxel_renderer.wgsl"`) fresh from the source tree,
+/// for the hot-reload path; the non-hot-reload path instead embeds the same
+/// file at compile time via `include_str!`. Panics on a read failure since
+/// this only ever runs against the checkout it was compiled from.
+pub fn load(name: &str) -> String {
+    let path = shaders_dir().join(name);
+    std::fs::read_to_string(&path).unwrap_or_else(|err| panic!("failed to read shader {path:?}: {err}"))
+}
+
+/// Parses and validates `source` with naga, logging (not panicking on) any
+/// failure. `Renderer::render`'s hot-reload path keeps the last good
+/// pipeline when this returns `false`, rather than handing wgpu a broken
+/// module and losing the device.
+pub fn validate(name: &str, source: &str) -> bool {
+    let module = match naga::front::wgsl::parse_str(source) {
+        Ok(module) => module,
+        Err(err) => {
+            log::error!("{name}: {err}");
+            return false;
+        }
+    };
+    let mut validator = naga::valid::Validator::new(naga::valid::ValidationFlags::all(), naga::valid::Capabilities::all());
+    match validator.validate(&module) {
+        Ok(_) => true,
+        Err(err) => {
+            log::error!("{name}: {err}");
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_accepts_a_well_formed_module() {
+        let source = "@fragment\nfn fs_main() -> @location(0) vec4<f32> { return vec4<f32>(1.0); }";
+        assert!(validate("test.wgsl", source));
+    }
+
+    #[test]
+    fn validate_rejects_a_syntax_error() {
+        assert!(!validate("test.wgsl", "fn fs_main( {"));
+    }
+
+    #[test]
+    fn validate_rejects_a_type_error() {
+        let source = "@fragment\nfn fs_main() -> @location(0) vec4<f32> { return 1.0; }";
+        assert!(!validate("test.wgsl", source));
+    }
+}
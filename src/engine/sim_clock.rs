@@ -0,0 +1,132 @@
+/// Engine clock `Renderer::update` advances once per fixed simulation step
+/// it runs, independent of wall-clock time so it can be paused or sped up
+/// without touching real frame timing -- deliberately not derived from
+/// `Instant::now()`. Pulled out of `Renderer` so the stepping/pause/scale
+/// logic is testable without a `wgpu::Device`, the same reason `FixedTimestep`
+/// lives in its own file.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct SimClock {
+    time: f32,
+    time_scale: f32,
+    paused: bool,
+}
+
+impl Default for SimClock {
+    fn default() -> Self {
+        Self { time: 0.0, time_scale: 1.0, paused: false }
+    }
+}
+
+impl SimClock {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Advances the clock by `step_seconds * time_scale`, or not at all
+    /// while paused. Returns however much the clock actually advanced, so
+    /// `Renderer::update` can sum it into `last_update_dt` across the
+    /// several fixed steps one call might run.
+    pub(crate) fn advance(&mut self, step_seconds: f32) -> f32 {
+        if self.paused {
+            return 0.0;
+        }
+        let dt = step_seconds * self.time_scale;
+        self.time += dt;
+        dt
+    }
+
+    pub(crate) fn time(&self) -> f32 {
+        self.time
+    }
+
+    /// Negative scales are clamped to `0.0`, which freezes the clock without
+    /// `set_paused`'s "resume exactly where we left off" semantics -- a
+    /// `0.0`-scaled clock still ticks `advance` calls, just by nothing.
+    pub(crate) fn set_time_scale(&mut self, scale: f32) {
+        self.time_scale = scale.max(0.0);
+    }
+
+    pub(crate) fn time_scale(&self) -> f32 {
+        self.time_scale
+    }
+
+    pub(crate) fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+    }
+
+    pub(crate) fn toggle_paused(&mut self) {
+        self.paused = !self.paused;
+    }
+
+    pub(crate) fn paused(&self) -> bool {
+        self.paused
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn starts_at_zero_and_unscaled() {
+        let clock = SimClock::new();
+        assert_eq!(clock.time(), 0.0);
+        assert_eq!(clock.time_scale(), 1.0);
+        assert!(!clock.paused());
+    }
+
+    #[test]
+    fn advancing_accumulates_time_at_unit_scale() {
+        let mut clock = SimClock::new();
+        assert_eq!(clock.advance(0.5), 0.5);
+        assert_eq!(clock.advance(0.25), 0.25);
+        assert_eq!(clock.time(), 0.75);
+    }
+
+    #[test]
+    fn time_scale_multiplies_each_advance() {
+        let mut clock = SimClock::new();
+        clock.set_time_scale(2.0);
+        assert_eq!(clock.advance(0.5), 1.0);
+        assert_eq!(clock.time(), 1.0);
+    }
+
+    #[test]
+    fn negative_time_scale_is_clamped_to_zero() {
+        let mut clock = SimClock::new();
+        clock.set_time_scale(-3.0);
+        assert_eq!(clock.time_scale(), 0.0);
+        assert_eq!(clock.advance(1.0), 0.0);
+        assert_eq!(clock.time(), 0.0);
+    }
+
+    #[test]
+    fn pausing_freezes_the_clock_and_returns_zero_advance() {
+        let mut clock = SimClock::new();
+        clock.advance(1.0);
+        clock.set_paused(true);
+        assert_eq!(clock.advance(10.0), 0.0);
+        assert_eq!(clock.time(), 1.0);
+    }
+
+    #[test]
+    fn unpausing_resumes_from_where_it_left_off() {
+        let mut clock = SimClock::new();
+        clock.advance(1.0);
+        clock.set_paused(true);
+        clock.advance(10.0);
+        clock.set_paused(false);
+        clock.advance(0.5);
+        assert_eq!(clock.time(), 1.5);
+    }
+
+    #[test]
+    fn toggle_paused_flips_the_flag() {
+        let mut clock = SimClock::new();
+        assert!(!clock.paused());
+        clock.toggle_paused();
+        assert!(clock.paused());
+        clock.toggle_paused();
+        assert!(!clock.paused());
+    }
+}
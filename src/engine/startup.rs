@@ -0,0 +1,150 @@
+//! Progress tracking for a future asynchronous `Renderer::new`: right now
+//! `App::new` calls `pollster::block_on(Renderer::new(...))` once and only
+//! returns once adapter/device selection, shader compilation, and the
+//! initial chunk are *all* done, so the event loop can't pump events (or
+//! even show a window) until that whole chain finishes.
+//!
+//! Splitting that up for real needs things this crate doesn't have yet:
+//! - A way to hand chunk generation and pipeline creation to background
+//!   tasks and poll them from the event loop instead of `block_on`-ing them
+//!   inline; there's no thread pool or task queue anywhere in `engine`.
+//! - `Renderer::new` broken into resumable stages instead of one function
+//!   that runs start to finish, plus a `Renderer::poll_startup` entry point
+//!   `App`'s event loop would call each frame while construction is still
+//!   in flight.
+//! - A cheap "loading" clear-color frame `App` can draw before a `Renderer`
+//!   exists at all, since today there's nothing to draw *with* until
+//!   `Renderer::new` returns.
+//!
+//! What's here is the progress state machine itself: given completion
+//! signals for pipeline creation and each generated chunk, what stage
+//! startup is in, ready to drive a title or overlay string once the rest
+//! of the plumbing above exists.
+#![allow(dead_code)]
+
+/// Where a still-loading `Renderer` is in its startup sequence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StartupProgress {
+    /// Adapter/device selection and shader pipeline creation haven't
+    /// finished yet.
+    AwaitingPipelines,
+    /// Pipelines are ready; `done` of `total` chunks have finished
+    /// generating.
+    GeneratingChunks { done: u32, total: u32 },
+    /// Everything needed to render a frame is in place.
+    Ready,
+}
+
+impl std::fmt::Display for StartupProgress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StartupProgress::AwaitingPipelines => write!(f, "Loading pipelines…"),
+            StartupProgress::GeneratingChunks { done, total } => write!(f, "Generating chunks {done}/{total}…"),
+            StartupProgress::Ready => write!(f, "Ready"),
+        }
+    }
+}
+
+/// Accumulates completion signals from mocked (for now) background startup
+/// tasks and reports the resulting [`StartupProgress`].
+#[derive(Debug, Clone, Copy)]
+pub struct StartupTracker {
+    pipelines_ready: bool,
+    chunks_done: u32,
+    chunks_total: u32,
+}
+
+impl StartupTracker {
+    /// `chunks_total` is the number of chunks a fully started renderer
+    /// needs generated; `0` means startup only ever waits on pipelines.
+    pub fn new(chunks_total: u32) -> Self {
+        Self { pipelines_ready: false, chunks_done: 0, chunks_total }
+    }
+
+    /// Call once pipeline creation finishes.
+    pub fn pipelines_ready(&mut self) {
+        self.pipelines_ready = true;
+    }
+
+    /// Call once per chunk as it finishes generating; ignored past
+    /// `chunks_total` since progress can't run past `Ready`.
+    pub fn chunk_generated(&mut self) {
+        self.chunks_done = (self.chunks_done + 1).min(self.chunks_total);
+    }
+
+    pub fn progress(&self) -> StartupProgress {
+        if !self.pipelines_ready {
+            StartupProgress::AwaitingPipelines
+        } else if self.chunks_done < self.chunks_total {
+            StartupProgress::GeneratingChunks { done: self.chunks_done, total: self.chunks_total }
+        } else {
+            StartupProgress::Ready
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn starts_awaiting_pipelines() {
+        let tracker = StartupTracker::new(4);
+        assert_eq!(tracker.progress(), StartupProgress::AwaitingPipelines);
+    }
+
+    #[test]
+    fn chunk_completions_before_pipelines_are_ready_dont_advance_the_stage() {
+        let mut tracker = StartupTracker::new(4);
+        tracker.chunk_generated();
+        assert_eq!(tracker.progress(), StartupProgress::AwaitingPipelines);
+    }
+
+    #[test]
+    fn pipelines_ready_moves_to_generating_chunks() {
+        let mut tracker = StartupTracker::new(4);
+        tracker.pipelines_ready();
+        assert_eq!(tracker.progress(), StartupProgress::GeneratingChunks { done: 0, total: 4 });
+    }
+
+    #[test]
+    fn chunk_completions_increment_the_done_count() {
+        let mut tracker = StartupTracker::new(4);
+        tracker.pipelines_ready();
+        tracker.chunk_generated();
+        tracker.chunk_generated();
+        assert_eq!(tracker.progress(), StartupProgress::GeneratingChunks { done: 2, total: 4 });
+    }
+
+    #[test]
+    fn finishing_every_chunk_reaches_ready() {
+        let mut tracker = StartupTracker::new(2);
+        tracker.pipelines_ready();
+        tracker.chunk_generated();
+        tracker.chunk_generated();
+        assert_eq!(tracker.progress(), StartupProgress::Ready);
+    }
+
+    #[test]
+    fn zero_chunks_reaches_ready_as_soon_as_pipelines_are_ready() {
+        let mut tracker = StartupTracker::new(0);
+        tracker.pipelines_ready();
+        assert_eq!(tracker.progress(), StartupProgress::Ready);
+    }
+
+    #[test]
+    fn extra_chunk_completions_past_the_total_dont_overshoot() {
+        let mut tracker = StartupTracker::new(1);
+        tracker.pipelines_ready();
+        tracker.chunk_generated();
+        tracker.chunk_generated();
+        assert_eq!(tracker.progress(), StartupProgress::Ready);
+    }
+
+    #[test]
+    fn display_formats_each_stage() {
+        assert_eq!(StartupProgress::AwaitingPipelines.to_string(), "Loading pipelines…");
+        assert_eq!(StartupProgress::GeneratingChunks { done: 1, total: 3 }.to_string(), "Generating chunks 1/3…");
+        assert_eq!(StartupProgress::Ready.to_string(), "Ready");
+    }
+}
@@ -0,0 +1,191 @@
+/// Granularity `TexturePool` buckets width/height into, so a window being
+/// resized one pixel at a time hits the same bucket (and the same pooled
+/// texture) instead of missing the pool on every single event.
+const BUCKET: u32 = 64;
+
+/// Rounds `value` up to the nearest [`BUCKET`] multiple; `RenderTexture::
+/// new_pooled` uses this directly so the texture it actually creates or
+/// reuses and the key it pools under agree on size.
+pub(crate) fn bucket(value: u32) -> u32 {
+    value.max(1).div_ceil(BUCKET) * BUCKET
+}
+
+/// What a pooled texture is keyed by: close-enough size plus the exact
+/// format/usage a new texture would need to match to be a valid substitute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct PoolKey {
+    bucketed_width: u32,
+    bucketed_height: u32,
+    format: wgpu::TextureFormat,
+    usage: wgpu::TextureUsages,
+}
+
+impl PoolKey {
+    fn new(width: u32, height: u32, format: wgpu::TextureFormat, usage: wgpu::TextureUsages) -> Self {
+        Self { bucketed_width: bucket(width), bucketed_height: bucket(height), format, usage }
+    }
+}
+
+struct Entry<T> {
+    key: PoolKey,
+    value: T,
+    byte_size: u64,
+    last_used: u64,
+}
+
+/// Retains recently-freed textures (generic over `T` -- `wgpu::Texture` in
+/// production, a plain value in tests -- so the bucketing/eviction logic
+/// can be exercised without a real `wgpu::Device`, the same split
+/// `pipeline_cache.rs`'s `MemoCache` uses) keyed by bucketed size, format
+/// and usage, with an LRU cap on total retained bytes.
+///
+/// `Renderer::rebuild_render_target` wires this in via `RenderTexture::
+/// new_pooled`/`RenderTexture::release_into` for the one texture that's
+/// actually reallocated on every resize; `GBufferTextures`/`RenderTargets`
+/// in `render_texture.rs` still call `device.create_texture` directly --
+/// see their own doc comments (nothing constructs them outside tests, so
+/// there's no resize churn there to fix yet).
+pub(crate) struct TexturePool<T> {
+    max_retained_bytes: u64,
+    retained_bytes: u64,
+    clock: u64,
+    entries: Vec<Entry<T>>,
+}
+
+impl<T> TexturePool<T> {
+    pub(crate) fn new(max_retained_bytes: u64) -> Self {
+        Self { max_retained_bytes, retained_bytes: 0, clock: 0, entries: Vec::new() }
+    }
+
+    /// Removes and returns a pooled value whose bucketed size/format/usage
+    /// exactly match `width`/`height`/`format`/`usage`, if one exists.
+    pub(crate) fn acquire(
+        &mut self,
+        width: u32,
+        height: u32,
+        format: wgpu::TextureFormat,
+        usage: wgpu::TextureUsages,
+    ) -> Option<T> {
+        let key = PoolKey::new(width, height, format, usage);
+        let index = self.entries.iter().position(|entry| entry.key == key)?;
+        let entry = self.entries.remove(index);
+        self.retained_bytes -= entry.byte_size;
+        Some(entry.value)
+    }
+
+    /// Retains `value` for future reuse, evicting least-recently-used
+    /// entries first until `max_retained_bytes` is no longer exceeded.
+    pub(crate) fn release(
+        &mut self,
+        width: u32,
+        height: u32,
+        format: wgpu::TextureFormat,
+        usage: wgpu::TextureUsages,
+        value: T,
+        byte_size: u64,
+    ) {
+        self.clock += 1;
+        self.entries.push(Entry {
+            key: PoolKey::new(width, height, format, usage),
+            value,
+            byte_size,
+            last_used: self.clock,
+        });
+        self.retained_bytes += byte_size;
+        self.evict_to_cap();
+    }
+
+    fn evict_to_cap(&mut self) {
+        while self.retained_bytes > self.max_retained_bytes && !self.entries.is_empty() {
+            let lru_index = self
+                .entries
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(index, _)| index)
+                .expect("entries is non-empty");
+            let entry = self.entries.remove(lru_index);
+            self.retained_bytes -= entry.byte_size;
+        }
+    }
+
+    /// No caller outside this module's own tests yet -- `Renderer` doesn't
+    /// surface pooled-but-unused VRAM in `GpuMemoryReport`.
+    #[allow(dead_code)]
+    pub(crate) fn retained_bytes(&self) -> u64 {
+        self.retained_bytes
+    }
+
+    /// No caller outside this module's own tests yet -- see
+    /// [`TexturePool::retained_bytes`].
+    #[allow(dead_code)]
+    pub(crate) fn len(&self) -> usize {
+        self.entries.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RGBA8: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8Unorm;
+    const USAGE: wgpu::TextureUsages = wgpu::TextureUsages::STORAGE_BINDING;
+
+    #[test]
+    fn acquire_from_an_empty_pool_misses() {
+        let mut pool: TexturePool<u32> = TexturePool::new(1024);
+        assert_eq!(pool.acquire(256, 256, RGBA8, USAGE), None);
+    }
+
+    #[test]
+    fn release_then_acquire_with_the_same_key_hits() {
+        let mut pool = TexturePool::new(1024);
+        pool.release(256, 256, RGBA8, USAGE, 42u32, 100);
+        assert_eq!(pool.acquire(256, 256, RGBA8, USAGE), Some(42));
+        assert_eq!(pool.len(), 0);
+        assert_eq!(pool.retained_bytes(), 0);
+    }
+
+    #[test]
+    fn nearby_sizes_share_a_bucket() {
+        let mut pool = TexturePool::new(1024);
+        // Both round up into the same 64-wide bucket (1920x1088).
+        pool.release(1920, 1080, RGBA8, USAGE, 1u32, 100);
+        assert_eq!(pool.acquire(1900, 1070, RGBA8, USAGE), Some(1));
+    }
+
+    #[test]
+    fn a_different_format_misses_even_at_the_same_size() {
+        let mut pool = TexturePool::new(1024);
+        pool.release(256, 256, RGBA8, USAGE, 1u32, 100);
+        assert_eq!(pool.acquire(256, 256, wgpu::TextureFormat::Rgba16Float, USAGE), None);
+    }
+
+    #[test]
+    fn a_different_usage_misses_even_at_the_same_size_and_format() {
+        let mut pool = TexturePool::new(1024);
+        pool.release(256, 256, RGBA8, USAGE, 1u32, 100);
+        assert_eq!(pool.acquire(256, 256, RGBA8, wgpu::TextureUsages::TEXTURE_BINDING), None);
+    }
+
+    #[test]
+    fn eviction_removes_the_least_recently_used_entry_first() {
+        let mut pool = TexturePool::new(150);
+        pool.release(64, 64, RGBA8, USAGE, 1u32, 100);
+        pool.release(128, 128, RGBA8, USAGE, 2u32, 100);
+        // Retaining both would be 200 > 150, so the older (64x64) entry is evicted.
+        assert_eq!(pool.retained_bytes(), 100);
+        assert_eq!(pool.acquire(64, 64, RGBA8, USAGE), None);
+        assert_eq!(pool.acquire(128, 128, RGBA8, USAGE), Some(2));
+    }
+
+    #[test]
+    fn an_acquired_entry_does_not_count_against_the_cap() {
+        let mut pool = TexturePool::new(100);
+        pool.release(64, 64, RGBA8, USAGE, 1u32, 100);
+        pool.acquire(64, 64, RGBA8, USAGE);
+        pool.release(128, 128, RGBA8, USAGE, 2u32, 100);
+        assert_eq!(pool.retained_bytes(), 100);
+        assert_eq!(pool.acquire(128, 128, RGBA8, USAGE), Some(2));
+    }
+}
@@ -0,0 +1,95 @@
+use std::time::Duration;
+
+/// Accumulator-based fixed timestep: `advance` is fed the real frame delta
+/// and returns how many `step`-sized simulation steps to run. Rendering
+/// still happens once per real frame; `alpha()` gives the blend factor
+/// between the previous and current simulation state for smooth interpolation.
+pub struct FixedTimestep {
+    step: Duration,
+    accumulator: Duration,
+    /// Caps steps-per-call so a debugger pause or long hitch doesn't make the
+    /// simulation try to "catch up" forever (the classic spiral of death).
+    max_steps: u32,
+}
+
+impl FixedTimestep {
+    pub fn new(hz: f64) -> Self {
+        Self {
+            step: Duration::from_secs_f64(1.0 / hz),
+            accumulator: Duration::ZERO,
+            max_steps: 8,
+        }
+    }
+
+    /// The fixed step's duration; also used by `Renderer::step_simulation`
+    /// to advance the day-cycle clock at a rate independent of frame rate.
+    pub fn step_duration(&self) -> Duration {
+        self.step
+    }
+
+    /// Feeds a real-world frame delta and returns the number of fixed steps
+    /// that should now be run. Caller is expected to call this then run that
+    /// many steps of its own simulation logic.
+    pub fn advance(&mut self, dt: Duration) -> u32 {
+        self.accumulator += dt;
+
+        let mut steps = 0;
+        while self.accumulator >= self.step && steps < self.max_steps {
+            self.accumulator -= self.step;
+            steps += 1;
+        }
+
+        // Hit the cap: drop the rest rather than let the accumulator grow
+        // unbounded and cause every subsequent frame to also max out.
+        if steps == self.max_steps {
+            self.accumulator = Duration::ZERO;
+        }
+
+        steps
+    }
+
+    /// How far into the *next* fixed step we are, in `[0, 1)`. Used to blend
+    /// between the previous and current simulation state when rendering.
+    pub fn alpha(&self) -> f32 {
+        self.accumulator.as_secs_f32() / self.step.as_secs_f32()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exact_multiple_of_step_yields_expected_count() {
+        let mut ts = FixedTimestep::new(60.0);
+        let step = ts.step_duration();
+        let steps = ts.advance(step * 2);
+        assert_eq!(steps, 2);
+        assert!(ts.alpha() < 1e-4);
+    }
+
+    #[test]
+    fn partial_step_is_carried_in_the_accumulator() {
+        let mut ts = FixedTimestep::new(60.0);
+        let step = ts.step_duration();
+        assert_eq!(ts.advance(step - Duration::from_nanos(1)), 0);
+        let steps = ts.advance(Duration::from_nanos(1));
+        assert_eq!(steps, 1);
+    }
+
+    #[test]
+    fn large_hitch_is_clamped_to_max_steps() {
+        let mut ts = FixedTimestep::new(60.0);
+        let steps = ts.advance(Duration::from_secs(10));
+        assert_eq!(steps, 8);
+        // accumulator was reset, so the very next frame doesn't also max out
+        let steps = ts.advance(Duration::from_secs_f64(1.0 / 60.0));
+        assert_eq!(steps, 1);
+    }
+
+    #[test]
+    fn zero_dt_yields_zero_steps() {
+        let mut ts = FixedTimestep::new(60.0);
+        assert_eq!(ts.advance(Duration::ZERO), 0);
+    }
+}
@@ -0,0 +1,53 @@
+/// Batches a frame's small uniform/storage writes into the frame's own
+/// command encoder via a `wgpu::util::StagingBelt`, instead of each call
+/// going through `wgpu::Queue::write_buffer`, which allocates and uploads
+/// its own staging buffer under the hood every time it's called. One
+/// `UploadContext` lives for the whole `Renderer`, reused frame over frame;
+/// `finish` must be called once every write for the frame has been queued
+/// and before `queue.submit`, and `recall` once that submission has gone in,
+/// so the belt's chunks become available for the next frame's writes.
+pub struct UploadContext {
+    belt: wgpu::util::StagingBelt,
+}
+
+impl UploadContext {
+    /// `chunk_size` is the granularity the belt allocates staging memory in;
+    /// see `wgpu::util::StagingBelt::new`. This renderer's per-frame writes
+    /// are all small fixed-size uniform blocks, so a modest chunk comfortably
+    /// covers several of them without the belt growing a new chunk mid-frame.
+    pub fn new(chunk_size: wgpu::BufferAddress) -> Self {
+        Self {
+            belt: wgpu::util::StagingBelt::new(chunk_size),
+        }
+    }
+
+    /// Copies `data` into `target` at `offset`, recorded as a copy in
+    /// `encoder`. A no-op for empty `data` rather than a panic, since
+    /// `StagingBelt::write_buffer` requires a non-zero size.
+    pub fn write_buffer(
+        &mut self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        target: &wgpu::Buffer,
+        offset: wgpu::BufferAddress,
+        data: &[u8],
+    ) {
+        let Some(size) = wgpu::BufferSize::new(data.len() as u64) else {
+            return;
+        };
+        self.belt.write_buffer(encoder, target, offset, size, device).copy_from_slice(data);
+    }
+
+    /// Call once per frame, after every `write_buffer` for that frame has
+    /// been queued and before `queue.submit`.
+    pub fn finish(&mut self) {
+        self.belt.finish();
+    }
+
+    /// Call once per frame, after the submission carrying this frame's
+    /// writes has gone in, so chunks the GPU is done with become available
+    /// again.
+    pub fn recall(&mut self) {
+        self.belt.recall();
+    }
+}
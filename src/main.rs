@@ -0,0 +1,396 @@
+use voxel_adventure::engine;
+use voxel_adventure::voxel::chunk::depth_from_size;
+
+/// Parsed form of `main`'s CLI arguments, independent of `std::env` so
+/// parsing is testable with an arbitrary argument list. `--seed` isn't
+/// offered: this renderer has no procedural/seeded generation --
+/// `Chunk::filled_test_pattern_with_water` is a fixed deterministic
+/// pattern -- so a seed flag would just be dead CLI surface. `--chunk-size`
+/// *is* offered: `Chunk`/`Tree` are already runtime-sized (an octree depth,
+/// not a const generic), so this is just validating a voxel count into
+/// that depth; see `voxel::chunk::depth_from_size`.
+///
+/// `width`/`height`/`fullscreen`/`render_scale`/`vsync`/`backend`/`chunk_size`
+/// are `None` unless the matching flag was actually passed, so
+/// `engine::resolve_config` can tell "not on the command line" apart from
+/// "explicitly set to the default" and fall through to the config file
+/// (see `engine::Config`, loaded from `voxel-adventure.toml`) before the
+/// built-in default.
+#[derive(Debug, Default, PartialEq)]
+struct Cli {
+    list_adapters: bool,
+    write_default_config: bool,
+    bench_frames: Option<usize>,
+    bench_json_path: Option<String>,
+    bench_workgroup_sizes: Option<Vec<u32>>,
+    /// `--bench-path <file>`: a `CameraPath` TOML file sampled across a
+    /// `--bench` run instead of the static default view; see
+    /// `engine::run_bench_with_path`.
+    bench_path: Option<String>,
+    width: Option<u32>,
+    height: Option<u32>,
+    fullscreen: Option<bool>,
+    /// `--fullscreen-exclusive`, parsed eagerly so a bad `WxH@Hz` is
+    /// reported at startup rather than after the window's already up. Not
+    /// mergeable with `engine::Config` the way the other fields are -- it's
+    /// a one-off startup choice, in the same bucket as `--list-adapters`.
+    fullscreen_exclusive: Option<engine::VideoModeSpec>,
+    backend: Option<String>,
+    render_scale: Option<f32>,
+    vsync: Option<bool>,
+    background_behavior: Option<String>,
+    mouse_sensitivity: Option<f32>,
+    chunk_size: Option<u32>,
+    /// `--record <file>`: where `App` writes a `SessionRecording` of every
+    /// `Action` press, mouse-look delta, and resize once the window closes;
+    /// see `engine::input_recording`.
+    record_path: Option<String>,
+    /// `--replay <file>`: a `SessionRecording` read eagerly at startup (like
+    /// `--bench-path`'s camera path) and fed into `App` instead of live
+    /// input.
+    replay_path: Option<String>,
+    /// `--autosave-dir <dir>`: where a crash (via a panic hook installed
+    /// before `App::run`) or a clean `CloseRequested` exit writes the
+    /// loaded chunk; see `engine::autosave`.
+    autosave_dir: Option<String>,
+}
+
+/// Consumes `next` as the value for `flag`, or `Err` naming which flag was
+/// left dangling.
+fn next_value(args: &mut impl Iterator<Item = String>, flag: &str) -> Result<String, String> {
+    args.next().ok_or_else(|| format!("{flag} requires a value"))
+}
+
+/// `next_value` plus a `FromStr` parse, with an error naming both the flag
+/// and the value that didn't parse.
+fn parse_value<T: std::str::FromStr>(args: &mut impl Iterator<Item = String>, flag: &str) -> Result<T, String> {
+    let raw = next_value(args, flag)?;
+    raw.parse()
+        .map_err(|_| format!("{flag} expects a number, got {raw:?}"))
+}
+
+fn parse_args(args: impl Iterator<Item = String>) -> Result<Cli, String> {
+    let mut cli = Cli::default();
+    let mut args = args;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--list-adapters" => cli.list_adapters = true,
+            "--write-default-config" => cli.write_default_config = true,
+            "--fullscreen" => cli.fullscreen = Some(true),
+            "--fullscreen-exclusive" => {
+                let raw = next_value(&mut args, "--fullscreen-exclusive")?;
+                cli.fullscreen_exclusive = Some(engine::VideoModeSpec::parse(&raw)?);
+            }
+            "--vsync" => cli.vsync = Some(true),
+            "--no-vsync" => cli.vsync = Some(false),
+            "--bench" => cli.bench_frames = Some(parse_value(&mut args, "--bench")?),
+            "--bench-json" => cli.bench_json_path = Some(next_value(&mut args, "--bench-json")?),
+            "--bench-workgroup-sizes" => {
+                let raw = next_value(&mut args, "--bench-workgroup-sizes")?;
+                let sizes = raw
+                    .split(',')
+                    .map(|size| {
+                        size.trim()
+                            .parse::<u32>()
+                            .map_err(|_| format!("--bench-workgroup-sizes entries must be positive integers, got {size:?}"))
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                cli.bench_workgroup_sizes = Some(sizes);
+            }
+            "--bench-path" => cli.bench_path = Some(next_value(&mut args, "--bench-path")?),
+            "--width" => cli.width = Some(parse_value(&mut args, "--width")?),
+            "--height" => cli.height = Some(parse_value(&mut args, "--height")?),
+            "--render-scale" => cli.render_scale = Some(parse_value(&mut args, "--render-scale")?),
+            "--backend" => {
+                let raw = next_value(&mut args, "--backend")?;
+                engine::parse_backend(&raw).map_err(|err| format!("--backend: {err}"))?;
+                cli.backend = Some(raw);
+            }
+            "--background-behavior" => {
+                let raw = next_value(&mut args, "--background-behavior")?;
+                engine::BackgroundBehavior::parse(&raw).map_err(|err| format!("--background-behavior: {err}"))?;
+                cli.background_behavior = Some(raw);
+            }
+            "--mouse-sensitivity" => cli.mouse_sensitivity = Some(parse_value(&mut args, "--mouse-sensitivity")?),
+            "--chunk-size" => {
+                let size = parse_value(&mut args, "--chunk-size")?;
+                depth_from_size(size).map_err(|err| format!("--chunk-size: {err}"))?;
+                cli.chunk_size = Some(size);
+            }
+            "--record" => cli.record_path = Some(next_value(&mut args, "--record")?),
+            "--replay" => cli.replay_path = Some(next_value(&mut args, "--replay")?),
+            "--autosave-dir" => cli.autosave_dir = Some(next_value(&mut args, "--autosave-dir")?),
+            other => return Err(format!("unrecognized argument: {other}")),
+        }
+    }
+    Ok(cli)
+}
+
+fn print_usage_and_exit(err: &str) -> ! {
+    eprintln!("{err}");
+    eprintln!(
+        "usage: voxel-adventure [--list-adapters] [--write-default-config] [--width <u32>] [--height <u32>] \
+         [--fullscreen | --fullscreen-exclusive <WxH@Hz>] [--vsync|--no-vsync] \
+         [--backend <vulkan|metal|dx12|gl|all>] [--render-scale <f32>] [--chunk-size <u32>] \
+         [--background-behavior <pause|throttle|full>] [--mouse-sensitivity <f32>] \
+         [--record <session.bin> | --replay <session.bin>] [--autosave-dir <dir>] \
+         [--bench <n> [--bench-json <path>] [--bench-workgroup-sizes <n,n,...>] [--bench-path <path.toml>]]"
+    );
+    std::process::exit(1);
+}
+
+fn main() {
+    env_logger::init();
+
+    let cli = parse_args(std::env::args().skip(1)).unwrap_or_else(|err| print_usage_and_exit(&err));
+
+    if cli.write_default_config {
+        let path = "voxel-adventure.toml";
+        std::fs::write(path, engine::Config::defaults().to_toml()).expect("failed to write default config");
+        println!("wrote defaults to {path}");
+        return;
+    }
+
+    let config = engine::load_config()
+        .unwrap_or_else(|err| print_usage_and_exit(&err))
+        .unwrap_or_default();
+    let overrides = engine::CliOverrides {
+        width: cli.width,
+        height: cli.height,
+        fullscreen: cli.fullscreen,
+        render_scale: cli.render_scale,
+        vsync: cli.vsync,
+        backend: cli.backend.clone(),
+        background_behavior: cli.background_behavior.clone(),
+        mouse_sensitivity: cli.mouse_sensitivity,
+        chunk_size: cli.chunk_size,
+    };
+    let resolved = engine::resolve_config(&overrides, &config).unwrap_or_else(|err| print_usage_and_exit(&err));
+
+    if cli.list_adapters {
+        for adapter in engine::Renderer::enumerate_adapters(resolved.backends) {
+            println!("{adapter:?}");
+        }
+        return;
+    }
+
+    if let Some(sizes) = cli.bench_workgroup_sizes {
+        let frames = cli.bench_frames.expect("--bench-workgroup-sizes requires --bench <frame_count>");
+        for (workgroup_size, report) in engine::run_bench_sweep(frames, &sizes) {
+            println!("{}x{}: {report}", workgroup_size.x, workgroup_size.y);
+        }
+        return;
+    }
+
+    if let Some(path) = &cli.bench_path {
+        let frames = cli.bench_frames.expect("--bench-path requires --bench <frame_count>");
+        let raw = std::fs::read_to_string(path).unwrap_or_else(|err| print_usage_and_exit(&format!("--bench-path: couldn't read {path:?}: {err}")));
+        let camera_path = engine::parse_camera_path(&raw).unwrap_or_else(|err| print_usage_and_exit(&format!("--bench-path: {err}")));
+        let (report, segments) = engine::run_bench_with_path(frames, camera_path);
+        println!("{report}");
+        for segment in segments {
+            println!("{segment}");
+        }
+        if let Some(path) = cli.bench_json_path {
+            std::fs::write(&path, report.to_json()).expect("failed to write --bench-json output");
+        }
+        return;
+    }
+
+    if let Some(frames) = cli.bench_frames {
+        let report = engine::run_bench(frames);
+        println!("{report}");
+        if let Some(path) = cli.bench_json_path {
+            std::fs::write(&path, report.to_json()).expect("failed to write --bench-json output");
+        }
+        return;
+    }
+
+    let replay = cli.replay_path.as_ref().map(|path| {
+        let bytes = std::fs::read(path).unwrap_or_else(|err| print_usage_and_exit(&format!("--replay: couldn't read {path:?}: {err}")));
+        engine::SessionRecording::decode(&bytes).unwrap_or_else(|err| print_usage_and_exit(&format!("--replay: {err}")))
+    });
+
+    // Installed before `App::run` so a panic anywhere in its event loop is
+    // covered; `autosave_handle` starts empty and `App::run` fills it in
+    // once `Renderer::new` returns (see `engine::autosave`'s module doc
+    // comment for what a panic before that point misses).
+    let autosave_dir = cli.autosave_dir.map(std::path::PathBuf::from);
+    let autosave_handle = engine::AutosaveHandle::new();
+    if let Some(dir) = &autosave_dir {
+        engine::install_panic_hook(dir.clone(), autosave_handle.clone());
+    }
+
+    engine::App::run(engine::AppOptions {
+        fullscreen: resolved.fullscreen,
+        fullscreen_exclusive: cli.fullscreen_exclusive,
+        background_behavior: resolved.background_behavior,
+        input_map: resolved.input_map,
+        mouse_sensitivity: resolved.mouse_sensitivity,
+        record_path: cli.record_path.map(std::path::PathBuf::from),
+        replay,
+        autosave_dir,
+        autosave_handle: Some(autosave_handle),
+        renderer_options: engine::RendererOptions {
+            width: resolved.width,
+            height: resolved.height,
+            backends: resolved.backends,
+            render_scale: resolved.render_scale,
+            chunk_depth: resolved.chunk_depth,
+            present_mode: if resolved.vsync {
+                wgpu::PresentMode::AutoVsync
+            } else {
+                wgpu::PresentMode::AutoNoVsync
+            },
+            ..engine::RendererOptions::default()
+        },
+        ..engine::AppOptions::default()
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(raw: &[&str]) -> impl Iterator<Item = String> {
+        raw.iter().map(|s| s.to_string()).collect::<Vec<_>>().into_iter()
+    }
+
+    #[test]
+    fn defaults_with_no_arguments() {
+        assert_eq!(parse_args(args(&[])).unwrap(), Cli::default());
+    }
+
+    #[test]
+    fn parses_width_height_fullscreen_and_render_scale_together() {
+        let cli = parse_args(args(&["--width", "1920", "--height", "1080", "--fullscreen", "--render-scale", "0.5"])).unwrap();
+        assert_eq!(cli.width, Some(1920));
+        assert_eq!(cli.height, Some(1080));
+        assert_eq!(cli.fullscreen, Some(true));
+        assert_eq!(cli.render_scale, Some(0.5));
+    }
+
+    #[test]
+    fn parses_a_fullscreen_exclusive_mode_spec() {
+        let cli = parse_args(args(&["--fullscreen-exclusive", "1920x1080@60"])).unwrap();
+        assert_eq!(
+            cli.fullscreen_exclusive,
+            Some(engine::VideoModeSpec {
+                width: 1920,
+                height: 1080,
+                refresh_rate_mhz: 60_000,
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_a_malformed_fullscreen_exclusive_mode_spec() {
+        assert!(parse_args(args(&["--fullscreen-exclusive", "1920x1080"])).is_err());
+    }
+
+    #[test]
+    fn vsync_and_no_vsync_set_opposite_values() {
+        assert_eq!(parse_args(args(&["--vsync"])).unwrap().vsync, Some(true));
+        assert_eq!(parse_args(args(&["--no-vsync"])).unwrap().vsync, Some(false));
+    }
+
+    #[test]
+    fn parses_each_backend_name_case_insensitively() {
+        for name in ["Vulkan", "METAL", "dx12", "gl", "all"] {
+            assert_eq!(parse_args(args(&["--backend", name])).unwrap().backend, Some(name.to_string()));
+        }
+    }
+
+    #[test]
+    fn rejects_an_unknown_backend_name() {
+        assert!(parse_args(args(&["--backend", "directx9"])).is_err());
+    }
+
+    #[test]
+    fn parses_each_background_behavior_name() {
+        for name in ["pause", "throttle", "full"] {
+            assert_eq!(
+                parse_args(args(&["--background-behavior", name])).unwrap().background_behavior,
+                Some(name.to_string())
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_an_unknown_background_behavior_name() {
+        assert!(parse_args(args(&["--background-behavior", "nap"])).is_err());
+    }
+
+    #[test]
+    fn parses_mouse_sensitivity() {
+        let cli = parse_args(args(&["--mouse-sensitivity", "0.004"])).unwrap();
+        assert_eq!(cli.mouse_sensitivity, Some(0.004));
+    }
+
+    #[test]
+    fn rejects_a_non_numeric_width() {
+        assert!(parse_args(args(&["--width", "not-a-number"])).is_err());
+    }
+
+    #[test]
+    fn rejects_a_dangling_flag_missing_its_value() {
+        assert!(parse_args(args(&["--width"])).is_err());
+    }
+
+    #[test]
+    fn rejects_an_unrecognized_flag() {
+        assert!(parse_args(args(&["--not-a-real-flag", "64"])).is_err());
+    }
+
+    #[test]
+    fn parses_chunk_size() {
+        let cli = parse_args(args(&["--chunk-size", "64"])).unwrap();
+        assert_eq!(cli.chunk_size, Some(64));
+    }
+
+    #[test]
+    fn rejects_a_chunk_size_that_is_not_a_power_of_two() {
+        assert!(parse_args(args(&["--chunk-size", "100"])).is_err());
+    }
+
+    #[test]
+    fn write_default_config_flag_is_recognized_on_its_own() {
+        let cli = parse_args(args(&["--write-default-config"])).unwrap();
+        assert!(cli.write_default_config);
+    }
+
+    #[test]
+    fn parses_the_pre_existing_bench_flags_together() {
+        let cli = parse_args(args(&[
+            "--bench",
+            "120",
+            "--bench-json",
+            "out.json",
+            "--bench-workgroup-sizes",
+            "8,16,32",
+        ]))
+        .unwrap();
+        assert_eq!(cli.bench_frames, Some(120));
+        assert_eq!(cli.bench_json_path, Some("out.json".to_string()));
+        assert_eq!(cli.bench_workgroup_sizes, Some(vec![8, 16, 32]));
+    }
+
+    #[test]
+    fn parses_bench_path() {
+        let cli = parse_args(args(&["--bench", "60", "--bench-path", "flythrough.toml"])).unwrap();
+        assert_eq!(cli.bench_frames, Some(60));
+        assert_eq!(cli.bench_path, Some("flythrough.toml".to_string()));
+    }
+
+    #[test]
+    fn parses_record_and_replay_paths() {
+        assert_eq!(parse_args(args(&["--record", "session.bin"])).unwrap().record_path, Some("session.bin".to_string()));
+        assert_eq!(parse_args(args(&["--replay", "session.bin"])).unwrap().replay_path, Some("session.bin".to_string()));
+    }
+
+    #[test]
+    fn parses_autosave_dir() {
+        let cli = parse_args(args(&["--autosave-dir", "crash-recovery"])).unwrap();
+        assert_eq!(cli.autosave_dir, Some("crash-recovery".to_string()));
+    }
+}
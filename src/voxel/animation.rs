@@ -0,0 +1,269 @@
+//! Per-frame procedural voxel updates -- a water surface that oscillates,
+//! a beacon that blinks -- driven from Rust instead of baked into the
+//! initial `Chunk::filled_test_pattern_with_water` generation.
+//!
+//! [`ChunkAnimator::animate`] writes directly into a [`Chunk`]'s [`Tree`]
+//! and reports the local-voxel region it touched. That's as far as this
+//! module goes: there's no dirty-region GPU upload path to hand
+//! [`DirtyRegion`] to yet. `VoxelRendererPass::upload_chunk` (see
+//! `chunk.tree.to_gpu_nodes()` there) re-encodes and re-uploads the whole
+//! octree on every call, and the only thing that triggers it today is
+//! `Renderer::regenerate_chunk`'s full `rebuild_gpu_pipeline` -- expensive
+//! enough (recompiles the compute pipeline) that calling it once per frame
+//! per animator would defeat the entire point of a bounded update. So
+//! nothing here is wired into `Renderer::update` yet; [`AnimationBudget`]
+//! is scoped to what a real per-frame hook will need once the upload side
+//! catches up: running registered animators in order and skipping ones
+//! that would blow a per-frame voxel budget, cheapest-cost-first is the
+//! caller's job (this just enforces the budget it's given).
+#![allow(dead_code)]
+
+use super::chunk::Chunk;
+use super::material::Voxel;
+
+/// Inclusive local-voxel bounding box touched by one [`ChunkAnimator::animate`]
+/// call, for a future partial re-upload to size its GPU write against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct DirtyRegion {
+    pub(crate) min: glam::UVec3,
+    pub(crate) max: glam::UVec3,
+}
+
+impl DirtyRegion {
+    /// Voxel count of the bounding box, used by [`AnimationBudget`] to debit
+    /// what an `animate` call actually cost (as opposed to `voxel_cost`'s
+    /// upfront estimate).
+    fn voxel_count(&self) -> usize {
+        let extent = (self.max - self.min) + glam::UVec3::ONE;
+        (extent.x as usize) * (extent.y as usize) * (extent.z as usize)
+    }
+}
+
+/// A source of per-frame procedural voxel writes, run against the single
+/// loaded [`Chunk`] every simulation step.
+pub(crate) trait ChunkAnimator {
+    /// Advances this animator to `time` (seconds, same clock as
+    /// `Renderer::sim_clock`) and applies whatever voxel writes that
+    /// implies. Returns the region touched, or `None` if nothing changed
+    /// this call (e.g. the oscillation hasn't crossed a voxel boundary yet).
+    fn animate(&mut self, chunk: &mut Chunk, time: f32) -> Option<DirtyRegion>;
+
+    /// Upper bound on how many voxels one `animate` call could touch, used
+    /// by [`AnimationBudget`] to decide whether to run it at all *before*
+    /// paying for the call.
+    fn voxel_cost(&self) -> usize;
+}
+
+/// Oscillates a rectangular water surface's height with a sine wave,
+/// filling/draining exactly the columns whose top changed since the last
+/// call. Reference [`ChunkAnimator`] impl -- everything it needs (the
+/// footprint, the resting height, how far and fast it moves) is a
+/// constructor argument rather than global state.
+pub(crate) struct WaterOscillator {
+    /// Local-voxel XZ corner of the footprint (Y is derived from the wave).
+    origin: glam::UVec3,
+    width: u32,
+    depth: u32,
+    base_height: u32,
+    amplitude: f32,
+    /// Radians per second.
+    speed: f32,
+    /// Water-column top applied by the previous `animate` call, so only the
+    /// difference needs touching.
+    last_height: u32,
+}
+
+impl WaterOscillator {
+    pub(crate) fn new(origin: glam::UVec3, width: u32, depth: u32, base_height: u32, amplitude: f32, speed: f32) -> Self {
+        Self {
+            origin,
+            width,
+            depth,
+            base_height,
+            amplitude,
+            speed,
+            last_height: base_height,
+        }
+    }
+}
+
+impl ChunkAnimator for WaterOscillator {
+    fn animate(&mut self, chunk: &mut Chunk, time: f32) -> Option<DirtyRegion> {
+        let chunk_height = chunk.size();
+        let wave = self.amplitude * (time * self.speed).sin();
+        let new_height = (self.base_height as f32 + wave).round().clamp(0.0, (chunk_height - 1) as f32) as u32;
+        if new_height == self.last_height {
+            return None;
+        }
+
+        let (low, high, fill) = if new_height > self.last_height {
+            (self.last_height + 1, new_height, Voxel::WATER)
+        } else {
+            (new_height + 1, self.last_height, Voxel::AIR)
+        };
+        for x in self.origin.x..self.origin.x + self.width {
+            for z in self.origin.z..self.origin.z + self.depth {
+                for y in low..=high {
+                    chunk.tree.set(glam::UVec3::new(x, y, z), fill);
+                }
+            }
+        }
+        self.last_height = new_height;
+
+        Some(DirtyRegion {
+            min: glam::UVec3::new(self.origin.x, low, self.origin.z),
+            max: glam::UVec3::new(self.origin.x + self.width - 1, high, self.origin.z + self.depth - 1),
+        })
+    }
+
+    fn voxel_cost(&self) -> usize {
+        // Worst case: the wave crosses the full amplitude in one step.
+        (self.width as usize) * (self.depth as usize) * (self.amplitude.ceil().max(1.0) as usize)
+    }
+}
+
+/// Enforces a per-frame cap on how many voxels registered [`ChunkAnimator`]s
+/// may touch in total, so a burst of simultaneously-changing animators can't
+/// spike a single frame's upload cost. Animators are tried in order; one
+/// whose [`ChunkAnimator::voxel_cost`] would exceed what's left is skipped
+/// entirely (not just truncated) rather than reordering the list -- a
+/// caller that wants cheap animators prioritized should register them in
+/// that order itself.
+pub(crate) struct AnimationBudget {
+    voxels_remaining: usize,
+}
+
+impl AnimationBudget {
+    pub(crate) fn new(max_voxels_per_frame: usize) -> Self {
+        Self { voxels_remaining: max_voxels_per_frame }
+    }
+
+    /// Runs every animator in `animators` against `chunk` in order, skipping
+    /// (without calling `animate`) any whose `voxel_cost` no longer fits the
+    /// remaining budget. Returns the dirty regions from the animators that
+    /// did run and actually changed something.
+    pub(crate) fn run(&mut self, animators: &mut [Box<dyn ChunkAnimator>], chunk: &mut Chunk, time: f32) -> Vec<DirtyRegion> {
+        let mut dirty = Vec::new();
+        for animator in animators.iter_mut() {
+            if animator.voxel_cost() > self.voxels_remaining {
+                continue;
+            }
+            if let Some(region) = animator.animate(chunk, time) {
+                self.voxels_remaining = self.voxels_remaining.saturating_sub(region.voxel_count());
+                dirty.push(region);
+            }
+        }
+        dirty
+    }
+
+    pub(crate) fn voxels_remaining(&self) -> usize {
+        self.voxels_remaining
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn water_oscillator_reports_no_change_at_time_zero() {
+        let mut chunk = Chunk::empty(glam::IVec3::ZERO);
+        let mut water = WaterOscillator::new(glam::UVec3::new(0, 0, 0), 4, 4, 10, 2.0, 1.0);
+        // sin(0) == 0, so the rounded height matches `last_height` exactly.
+        assert!(water.animate(&mut chunk, 0.0).is_none());
+    }
+
+    #[test]
+    fn water_oscillator_fills_columns_when_the_wave_rises() {
+        let mut chunk = Chunk::empty(glam::IVec3::ZERO);
+        let mut water = WaterOscillator::new(glam::UVec3::new(0, 0, 0), 3, 3, 10, 2.0, 1.0);
+        // Chosen so `time * speed == pi/2`, i.e. sin == 1.0 and the surface
+        // rises by the full amplitude, from height 10 to height 12.
+        let time = std::f32::consts::FRAC_PI_2;
+        let region = water.animate(&mut chunk, time).expect("wave should have risen");
+        assert_eq!(region.min, glam::UVec3::new(0, 11, 0));
+        assert_eq!(region.max, glam::UVec3::new(2, 12, 2));
+        assert_eq!(chunk.tree.get(glam::UVec3::new(1, 11, 1)), Voxel::WATER);
+        assert_eq!(chunk.tree.get(glam::UVec3::new(1, 12, 1)), Voxel::WATER);
+        assert_eq!(chunk.tree.get(glam::UVec3::new(1, 13, 1)), Voxel::AIR);
+    }
+
+    #[test]
+    fn water_oscillator_drains_columns_when_the_wave_falls() {
+        let mut chunk = Chunk::empty(glam::IVec3::ZERO);
+        let mut water = WaterOscillator::new(glam::UVec3::new(0, 0, 0), 2, 2, 10, 2.0, 1.0);
+        water.animate(&mut chunk, std::f32::consts::FRAC_PI_2); // rises to 12
+        let region = water.animate(&mut chunk, 0.0).expect("wave should have fallen back down"); // sin(0) == 0 -> height 10
+        assert_eq!(region.min, glam::UVec3::new(0, 11, 0));
+        assert_eq!(region.max, glam::UVec3::new(1, 12, 1));
+        assert_eq!(chunk.tree.get(glam::UVec3::new(0, 11, 0)), Voxel::AIR);
+        assert_eq!(chunk.tree.get(glam::UVec3::new(0, 12, 0)), Voxel::AIR);
+    }
+
+    /// A fake animator with an independently-controllable declared
+    /// `voxel_cost` (what `AnimationBudget` checks before calling it) and
+    /// actual dirty `region` (what it debits afterward) -- real animators'
+    /// worst-case estimate and actual result usually differ too.
+    struct AlwaysDirty {
+        cost: usize,
+        region: DirtyRegion,
+    }
+
+    impl ChunkAnimator for AlwaysDirty {
+        fn animate(&mut self, _chunk: &mut Chunk, _time: f32) -> Option<DirtyRegion> {
+            Some(self.region)
+        }
+
+        fn voxel_cost(&self) -> usize {
+            self.cost
+        }
+    }
+
+    fn one_voxel() -> DirtyRegion {
+        DirtyRegion { min: glam::UVec3::ZERO, max: glam::UVec3::ZERO }
+    }
+
+    #[test]
+    fn budget_runs_every_animator_that_fits() {
+        let mut chunk = Chunk::empty(glam::IVec3::ZERO);
+        let mut animators: Vec<Box<dyn ChunkAnimator>> = vec![
+            Box::new(AlwaysDirty { cost: 4, region: one_voxel() }),
+            Box::new(AlwaysDirty { cost: 4, region: one_voxel() }),
+        ];
+        let mut budget = AnimationBudget::new(100);
+        let dirty = budget.run(&mut animators, &mut chunk, 0.0);
+        assert_eq!(dirty.len(), 2);
+    }
+
+    #[test]
+    fn budget_skips_an_animator_whose_cost_exceeds_what_remains() {
+        let mut chunk = Chunk::empty(glam::IVec3::ZERO);
+        // The first animator's actual dirty region (1 voxel) leaves 9
+        // remaining, which isn't enough for the second's declared cost.
+        let mut animators: Vec<Box<dyn ChunkAnimator>> = vec![
+            Box::new(AlwaysDirty { cost: 1, region: one_voxel() }),
+            Box::new(AlwaysDirty { cost: 20, region: one_voxel() }),
+        ];
+        let mut budget = AnimationBudget::new(10);
+        let dirty = budget.run(&mut animators, &mut chunk, 0.0);
+        assert_eq!(dirty.len(), 1, "the second animator should have been skipped");
+        assert_eq!(budget.voxels_remaining(), 9);
+    }
+
+    #[test]
+    fn budget_preserves_registration_order_rather_than_reordering_by_cost() {
+        let mut chunk = Chunk::empty(glam::IVec3::ZERO);
+        // Registered expensive-first: its actual dirty region (10 voxels)
+        // exhausts the whole budget, so the cheap one behind it never gets
+        // a turn even though it would have fit on its own.
+        let expensive_region = DirtyRegion { min: glam::UVec3::ZERO, max: glam::UVec3::new(9, 0, 0) };
+        let mut animators: Vec<Box<dyn ChunkAnimator>> = vec![
+            Box::new(AlwaysDirty { cost: 10, region: expensive_region }),
+            Box::new(AlwaysDirty { cost: 1, region: one_voxel() }),
+        ];
+        let mut budget = AnimationBudget::new(10);
+        let dirty = budget.run(&mut animators, &mut chunk, 0.0);
+        assert_eq!(dirty.len(), 1, "only the first (expensive) animator should have run");
+        assert_eq!(budget.voxels_remaining(), 0);
+    }
+}
@@ -0,0 +1,257 @@
+//! Biome classification driving which material [`super::terrain`] fills
+//! solid voxels with, so plains/desert/snow regions read as distinct
+//! materials instead of the flat `Voxel::STONE` [`super::terrain`] used to
+//! paint everything with.
+//!
+//! There's no egui panel or worker thread here for the same reason
+//! [`super::terrain`]'s module doc comment gives for skipping them there --
+//! this module doesn't add either. There's also no `.vox`-importer-driven
+//! per-biome decoration yet: [`Biome::decoration_density`] is stored in the
+//! registry below so a future pass across [`super::decorate`] can consult
+//! it per column, but [`super::decorate::decorate_chunk`]'s decorators still
+//! take a flat density today rather than looking one up here -- wiring that
+//! up is a separate, larger change to `decorate.rs`, not something to fold
+//! into adding the registry itself.
+//!
+//! Classification is temperature/humidity thresholds over two low-frequency
+//! [`super::terrain::fbm2`] fields, reusing that module's noise instead of a
+//! third hand-rolled implementation. Boundaries blend by adding a third,
+//! higher-frequency noise sample to both fields before thresholding
+//! (dithering), rather than comparing world position against a hard line --
+//! see [`BiomeMap::biome_at`].
+
+use super::material::Voxel;
+use super::terrain::{fbm2, TerrainParams};
+
+/// Identifies a [`Biome`] in the registry below; `Copy`/`Eq` so it's cheap
+/// to pass around (e.g. from the console's `biome at` command) instead of a
+/// `&'static Biome` reference.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BiomeId {
+    Plains,
+    Desert,
+    Snow,
+}
+
+/// A classification's material and decoration settings, looked up by
+/// [`BiomeId`] via [`biome`]. Material IDs index into a
+/// [`super::material::MaterialTable`] the same way [`super::terrain`]'s
+/// unconditional `Voxel::STONE` fill already did.
+pub struct Biome {
+    pub id: BiomeId,
+    pub name: &'static str,
+    /// Material for the topmost solid voxel in a column (see
+    /// [`super::terrain::Chunk::from_terrain_at_depth`] for how "topmost" is
+    /// decided).
+    pub surface_material: u32,
+    /// Material for every solid voxel that isn't the surface one.
+    pub underground_material: u32,
+    /// Fraction of surface columns a decorator should place something on,
+    /// once [`super::decorate`] is wired to read it (see this module's doc
+    /// comment).
+    pub decoration_density: f32,
+}
+
+pub const PLAINS: Biome =
+    Biome { id: BiomeId::Plains, name: "plains", surface_material: Voxel::STONE, underground_material: Voxel::STONE, decoration_density: 0.15 };
+
+pub const DESERT: Biome =
+    Biome { id: BiomeId::Desert, name: "desert", surface_material: Voxel::SAND, underground_material: Voxel::STONE, decoration_density: 0.02 };
+
+pub const SNOW: Biome =
+    Biome { id: BiomeId::Snow, name: "snow", surface_material: Voxel::SNOW, underground_material: Voxel::STONE, decoration_density: 0.05 };
+
+/// Looks `id` up in the fixed registry above.
+pub fn biome(id: BiomeId) -> &'static Biome {
+    match id {
+        BiomeId::Plains => &PLAINS,
+        BiomeId::Desert => &DESERT,
+        BiomeId::Snow => &SNOW,
+    }
+}
+
+/// Knobs [`BiomeMap`] samples noise with. Separate from [`TerrainParams`]
+/// (rather than folding `frequency` into it) since biome regions and
+/// terrain-shape features are unrelated scales -- a world's height noise
+/// frequency changing shouldn't also resize its biomes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BiomeParams {
+    /// Seeds temperature/humidity/dither noise, independently of
+    /// `TerrainParams::seed`'s own salt (see [`noise_params`]) so biome
+    /// placement isn't just terrain-height noise re-read at a lower
+    /// frequency.
+    pub seed: u32,
+    /// Sampling frequency of the temperature and humidity fields (world
+    /// units per noise cycle, inverted -- small values mean large biome
+    /// regions).
+    pub frequency: f32,
+}
+
+impl Default for BiomeParams {
+    fn default() -> Self {
+        Self { seed: 1, frequency: 0.004 }
+    }
+}
+
+const TEMPERATURE_SALT: u32 = 0x1a2b_3c4d;
+const HUMIDITY_SALT: u32 = 0x5e6f_7081;
+const DITHER_SALT: u32 = 0x9a8b_7c6d;
+
+/// The dither field samples this many times [`BiomeParams::frequency`], so
+/// it varies over a handful of voxels rather than the hundreds a biome
+/// region itself spans -- that's what makes it read as a jittered boundary
+/// rather than just a second, larger biome region.
+const DITHER_FREQUENCY_MULTIPLIER: f32 = 8.0;
+
+/// How far the dither noise can nudge a temperature/humidity sample before
+/// thresholding, in the same `[-1, 1]`-ish units [`fbm2`] returns.
+const DITHER_AMPLITUDE: f32 = 0.06;
+
+const DESERT_TEMPERATURE_THRESHOLD: f32 = 0.15;
+const DESERT_HUMIDITY_THRESHOLD: f32 = -0.1;
+const SNOW_TEMPERATURE_THRESHOLD: f32 = -0.15;
+
+/// Builds the [`TerrainParams`] one of [`BiomeMap`]'s noise fields samples
+/// through [`fbm2`] -- two octaves is enough for a smooth low-frequency
+/// field, and the other knobs ([`TerrainParams::heightmap_mode`] etc.) don't
+/// affect [`fbm2`] at all.
+fn noise_params(seed: u32, salt: u32, frequency: f32) -> TerrainParams {
+    TerrainParams {
+        seed: seed ^ salt,
+        octaves: 2,
+        lacunarity: 2.0,
+        persistence: 0.5,
+        frequency,
+        warp_amplitude: 0.0,
+        heightmap_mode: true,
+        height_scale: 1.0,
+        cave_frequency: 0.1,
+        cave_threshold: 1.0,
+    }
+}
+
+/// Classifies world-space `(x, z)` columns into a [`Biome`], per
+/// [`BiomeParams`].
+pub struct BiomeMap {
+    params: BiomeParams,
+}
+
+impl BiomeMap {
+    pub fn new(params: BiomeParams) -> Self {
+        Self { params }
+    }
+
+    /// Raw temperature field, before dithering; colder toward `-1`, hotter
+    /// toward `1`.
+    pub fn temperature_at(&self, x: f32, z: f32) -> f32 {
+        fbm2(x, z, &noise_params(self.params.seed, TEMPERATURE_SALT, self.params.frequency))
+    }
+
+    /// Raw humidity field, before dithering; drier toward `-1`, wetter
+    /// toward `1`.
+    pub fn humidity_at(&self, x: f32, z: f32) -> f32 {
+        fbm2(x, z, &noise_params(self.params.seed, HUMIDITY_SALT, self.params.frequency))
+    }
+
+    fn dither_at(&self, x: f32, z: f32) -> f32 {
+        let dither_frequency = self.params.frequency * DITHER_FREQUENCY_MULTIPLIER;
+        fbm2(x, z, &noise_params(self.params.seed, DITHER_SALT, dither_frequency)) * DITHER_AMPLITUDE
+    }
+
+    /// Classifies world-space `(x, z)` into a [`Biome`], blending boundaries
+    /// over a few voxels: the same dither sample nudges both the
+    /// temperature and humidity before they're compared against the
+    /// thresholds (in [`classify`]), so a straight line through world space
+    /// crosses a jittered band a few voxels wide instead of a hard edge.
+    pub fn biome_at(&self, x: f32, z: f32) -> &'static Biome {
+        let dither = self.dither_at(x, z);
+        classify(self.temperature_at(x, z) + dither, self.humidity_at(x, z) + dither)
+    }
+}
+
+/// The actual threshold logic [`BiomeMap::biome_at`] applies to a
+/// (possibly already-dithered) temperature/humidity pair. Pulled out as its
+/// own pure function so tests can pin the thresholds directly against
+/// hand-picked values instead of only through noise sampled at particular
+/// world coordinates.
+fn classify(temperature: f32, humidity: f32) -> &'static Biome {
+    if temperature < SNOW_TEMPERATURE_THRESHOLD {
+        biome(BiomeId::Snow)
+    } else if temperature > DESERT_TEMPERATURE_THRESHOLD && humidity < DESERT_HUMIDITY_THRESHOLD {
+        biome(BiomeId::Desert)
+    } else {
+        biome(BiomeId::Plains)
+    }
+}
+
+impl Default for BiomeMap {
+    fn default() -> Self {
+        Self::new(BiomeParams::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cold_temperature_classifies_as_snow_regardless_of_humidity() {
+        assert_eq!(classify(SNOW_TEMPERATURE_THRESHOLD - 0.01, 1.0).id, BiomeId::Snow);
+        assert_eq!(classify(SNOW_TEMPERATURE_THRESHOLD - 0.01, -1.0).id, BiomeId::Snow);
+    }
+
+    #[test]
+    fn hot_and_dry_classifies_as_desert() {
+        assert_eq!(classify(DESERT_TEMPERATURE_THRESHOLD + 0.01, DESERT_HUMIDITY_THRESHOLD - 0.01).id, BiomeId::Desert);
+    }
+
+    #[test]
+    fn hot_and_wet_classifies_as_plains_not_desert() {
+        assert_eq!(classify(DESERT_TEMPERATURE_THRESHOLD + 0.01, DESERT_HUMIDITY_THRESHOLD + 0.01).id, BiomeId::Plains);
+    }
+
+    #[test]
+    fn mild_temperature_and_humidity_classifies_as_plains() {
+        assert_eq!(classify(0.0, 0.0).id, BiomeId::Plains);
+    }
+
+    #[test]
+    fn different_seeds_produce_different_biome_layouts() {
+        let a = BiomeMap::new(BiomeParams { seed: 1, frequency: 0.004 });
+        let b = BiomeMap::new(BiomeParams { seed: 2, frequency: 0.004 });
+        let differs = (0..64).any(|i| {
+            let coord = i as f32 * 37.0;
+            a.biome_at(coord, coord).id != b.biome_at(coord, coord).id
+        });
+        assert!(differs, "expected at least one sampled column to classify differently between seeds");
+    }
+
+    #[test]
+    fn a_chunk_straddling_a_biome_boundary_contains_both_biomes_materials() {
+        // A very high biome frequency packs many boundaries into one
+        // chunk's width, so a generated chunk is virtually guaranteed to
+        // straddle at least one -- far more reliable than hunting for a
+        // specific `(x, z)` boundary by hand.
+        let params = TerrainParams { heightmap_mode: true, height_scale: 20.0, ..Default::default() };
+        let biome_params = BiomeParams { seed: 3, frequency: 0.2 };
+        let mut chunk = crate::voxel::chunk::Chunk::with_depth(glam::IVec3::ZERO, 5);
+        let size = chunk.size();
+        let map = BiomeMap::new(biome_params);
+        for x in 0..size {
+            for z in 0..size {
+                let biome = map.biome_at(x as f32, z as f32);
+                for y in 0..size {
+                    let world = glam::IVec3::new(x as i32, y as i32, z as i32);
+                    if super::super::terrain::is_solid(world.x as f32, world.y as f32, world.z as f32, &params) {
+                        chunk.tree.set(glam::UVec3::new(x, y, z), biome.surface_material);
+                    }
+                }
+            }
+        }
+        let materials: std::collections::HashSet<u32> = chunk.tree.iter_voxels().map(|(_, material)| material).collect();
+        assert!(
+            materials.len() >= 2,
+            "expected a chunk this wide relative to the biome frequency to straddle a boundary, got {materials:?}"
+        );
+    }
+}
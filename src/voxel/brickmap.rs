@@ -0,0 +1,159 @@
+//! Not yet wired into `VoxelRendererPass`; see the module docs on
+//! [`BrickMap`] for why, and `#[allow(dead_code)]` below for the same
+//! reason `Tree::get` carries one. This is the CPU-side occupancy structure
+//! only -- the GPU traversal variant, the `AccelerationStructure` selector,
+//! and benchmark mode's per-structure reporting the original request also
+//! asked for are still unstarted, not deferred-and-in-progress.
+#![allow(dead_code)]
+
+use super::chunk::Chunk;
+use super::tree::EMPTY;
+
+/// Side length (in voxels) of one brick. `Chunk::size()` must be an exact
+/// multiple of this for `BrickMap::from_chunk` to tile it evenly, which
+/// holds for every `CHUNK_DEPTH >= 3` this crate uses.
+pub(crate) const BRICK_SIZE: u32 = 8;
+
+/// Voxels in one brick (`BRICK_SIZE^3`).
+pub(crate) const BRICK_VOLUME: usize = (BRICK_SIZE * BRICK_SIZE * BRICK_SIZE) as usize;
+
+/// A flattened 8x8x8 block of material ids, local-voxel order `x + y*8 +
+/// z*64`, ready for a dense per-brick GPU read.
+pub(crate) type Brick = [u32; BRICK_VOLUME];
+
+/// Two-level acceleration structure: a coarse grid of `BRICK_SIZE`-voxel
+/// bricks, each either entirely air (no pool entry at all) or a dense block
+/// in `pool`. Compared to walking [`super::tree::Tree`]'s octree node by
+/// node, a brick hit is one pool lookup instead of `depth` pointer chases --
+/// the tradeoff this repo's octree already avoids by being sparse, so this
+/// is offered as an alternative rather than a replacement.
+///
+/// Not yet consumed by `VoxelRendererPass`: a GPU traversal variant that
+/// indexes `pool` instead of walking `node_buffer`, and the pipeline
+/// plumbing to pick between the two, are future work -- this covers the
+/// part of the request that's a well-defined, testable unit on its own.
+pub(crate) struct BrickMap {
+    /// Brick-grid dimensions, i.e. `chunk.size() / BRICK_SIZE` per axis.
+    pub(crate) dims: glam::UVec3,
+    /// One entry per brick in `dims` order (`x + y*dims.x + z*dims.x*dims.y`);
+    /// [`EMPTY`] for an all-air brick, otherwise an index into `pool`.
+    pub(crate) bricks: Vec<u32>,
+    /// Dense material blocks for every non-empty brick, in the order they
+    /// were first encountered. Air-only bricks contribute nothing here, so
+    /// `pool.len()` is exactly the number of non-empty bricks, not
+    /// `bricks.len()`.
+    pub(crate) pool: Vec<Brick>,
+}
+
+impl BrickMap {
+    /// Builds a `BrickMap` by sampling every voxel of `chunk` through its
+    /// octree. `chunk.size()` must be a multiple of `BRICK_SIZE`.
+    pub(crate) fn from_chunk(chunk: &Chunk) -> Self {
+        let size = chunk.size();
+        assert_eq!(size % BRICK_SIZE, 0, "chunk size must be a multiple of BRICK_SIZE");
+        let dims = glam::UVec3::splat(size / BRICK_SIZE);
+
+        let mut bricks = Vec::with_capacity((dims.x * dims.y * dims.z) as usize);
+        let mut pool = Vec::new();
+
+        for bz in 0..dims.z {
+            for by in 0..dims.y {
+                for bx in 0..dims.x {
+                    let origin = glam::UVec3::new(bx, by, bz) * BRICK_SIZE;
+                    let brick = sample_brick(chunk, origin);
+                    if brick.iter().all(|&material| material == 0) {
+                        bricks.push(EMPTY);
+                    } else {
+                        bricks.push(pool.len() as u32);
+                        pool.push(brick);
+                    }
+                }
+            }
+        }
+
+        Self { dims, bricks, pool }
+    }
+
+    /// Material at `pos` (in the chunk's voxel space), reconstructed by
+    /// looking the containing brick up in `pool`. `0` (air) for both an
+    /// out-of-range `pos` and an empty brick.
+    pub(crate) fn get(&self, pos: glam::UVec3) -> u32 {
+        let brick_coord = pos / BRICK_SIZE;
+        if brick_coord.x >= self.dims.x || brick_coord.y >= self.dims.y || brick_coord.z >= self.dims.z {
+            return 0;
+        }
+        let brick_index = (brick_coord.x + brick_coord.y * self.dims.x + brick_coord.z * self.dims.x * self.dims.y)
+            as usize;
+        let slot = self.bricks[brick_index];
+        if slot == EMPTY {
+            return 0;
+        }
+        let local = pos - brick_coord * BRICK_SIZE;
+        self.pool[slot as usize][(local.x + local.y * BRICK_SIZE + local.z * BRICK_SIZE * BRICK_SIZE) as usize]
+    }
+}
+
+fn sample_brick(chunk: &Chunk, origin: glam::UVec3) -> Brick {
+    let mut brick = [0u32; BRICK_VOLUME];
+    for z in 0..BRICK_SIZE {
+        for y in 0..BRICK_SIZE {
+            for x in 0..BRICK_SIZE {
+                let material = chunk.tree.get(origin + glam::UVec3::new(x, y, z));
+                brick[(x + y * BRICK_SIZE + z * BRICK_SIZE * BRICK_SIZE) as usize] = material;
+            }
+        }
+    }
+    brick
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::voxel::material::Voxel;
+
+    #[test]
+    fn empty_chunk_produces_no_pool_entries() {
+        let chunk = Chunk::empty(glam::IVec3::ZERO);
+        let brick_map = BrickMap::from_chunk(&chunk);
+        assert!(brick_map.pool.is_empty());
+        assert!(brick_map.bricks.iter().all(|&slot| slot == EMPTY));
+    }
+
+    #[test]
+    fn occupancy_matches_the_source_chunk() {
+        let chunk = Chunk::filled_test_pattern_with_water(glam::IVec3::ZERO, 4);
+        let brick_map = BrickMap::from_chunk(&chunk);
+        let size = chunk.size();
+        for z in 0..size {
+            for y in 0..size {
+                for x in 0..size {
+                    let pos = glam::UVec3::new(x, y, z);
+                    assert_eq!(brick_map.get(pos), chunk.tree.get(pos), "mismatch at {pos:?}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn empty_bricks_share_no_pool_space() {
+        let mut chunk = Chunk::empty(glam::IVec3::ZERO);
+        chunk.tree.set(glam::UVec3::new(0, 0, 0), Voxel::STONE);
+        let brick_map = BrickMap::from_chunk(&chunk);
+
+        let non_empty_bricks = brick_map.bricks.iter().filter(|&&slot| slot != EMPTY).count();
+        assert_eq!(non_empty_bricks, 1);
+        assert_eq!(brick_map.pool.len(), 1);
+    }
+
+    #[test]
+    fn pool_indices_are_compact_and_unique() {
+        let chunk = Chunk::filled_test_pattern_with_water(glam::IVec3::ZERO, 0);
+        let brick_map = BrickMap::from_chunk(&chunk);
+
+        let mut used: Vec<u32> = brick_map.bricks.iter().copied().filter(|&slot| slot != EMPTY).collect();
+        used.sort_unstable();
+        used.dedup();
+        assert_eq!(used.len(), brick_map.pool.len());
+        assert!(used.iter().enumerate().all(|(i, &slot)| slot as usize == i));
+    }
+}
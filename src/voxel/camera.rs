@@ -0,0 +1,47 @@
+/// Minimal fly camera. Look input is driven by `mouse_look::MouseLook` (see
+/// `Renderer::apply_look_delta`); planar movement input still lands in a
+/// later commit.
+pub struct Camera {
+    pub position: glam::Vec3,
+    pub yaw: f32,
+    pub pitch: f32,
+    pub fov_y_radians: f32,
+}
+
+impl Camera {
+    pub fn new(position: glam::Vec3) -> Self {
+        Self {
+            position,
+            yaw: -90f32.to_radians(),
+            pitch: -20f32.to_radians(),
+            fov_y_radians: 60f32.to_radians(),
+        }
+    }
+
+    /// Adjusts `yaw`/`pitch` by the given deltas (radians), clamping
+    /// `pitch` just short of straight up/down so `forward` never flips
+    /// past vertical. Fed from `mouse_look::MouseLook::take_delta` once
+    /// per frame while the cursor is grabbed.
+    pub fn look(&mut self, delta_yaw: f32, delta_pitch: f32) {
+        let pitch_limit = 89f32.to_radians();
+        self.yaw += delta_yaw;
+        self.pitch = (self.pitch + delta_pitch).clamp(-pitch_limit, pitch_limit);
+    }
+
+    pub fn forward(&self) -> glam::Vec3 {
+        glam::Vec3::new(
+            self.yaw.cos() * self.pitch.cos(),
+            self.pitch.sin(),
+            self.yaw.sin() * self.pitch.cos(),
+        )
+        .normalize()
+    }
+
+    /// View-projection matrix from an arbitrary (typically interpolated)
+    /// position instead of `self.position`.
+    pub fn view_proj_at(&self, aspect: f32, position: glam::Vec3) -> glam::Mat4 {
+        let view = glam::Mat4::look_to_rh(position, self.forward(), glam::Vec3::Y);
+        let proj = glam::Mat4::perspective_rh(self.fov_y_radians, aspect, 0.1, 1000.0);
+        proj * view
+    }
+}
@@ -0,0 +1,487 @@
+use super::material::{MaterialTable, Voxel};
+use super::tree::Tree;
+
+/// Default depth of a chunk's octree when nothing overrides it;
+/// `2^CHUNK_DEPTH` voxels per side. See [`Chunk::with_depth`] and
+/// `--chunk-size`/`RendererOptions::chunk_depth` for picking a different
+/// depth at runtime.
+pub const CHUNK_DEPTH: u32 = 5;
+
+/// Smallest depth [`depth_from_size`] accepts (`2^1 == 2` voxels/side);
+/// anything smaller isn't worth ray-marching.
+pub const MIN_CHUNK_DEPTH: u32 = 1;
+
+/// Largest depth [`depth_from_size`] accepts (`2^9 == 512` voxels/side),
+/// already 16x the default. Nothing bigger is rejected because it'd be
+/// wrong, but because `Tree::to_gpu_nodes` allocates its whole worst-case
+/// node buffer up front (see `Tree::estimated_size_aligned`'s doc comment
+/// on why) and this crate has no buffer-arena allocator to grow it past
+/// that instead.
+pub const MAX_CHUNK_DEPTH: u32 = 9;
+
+/// Parses a `--chunk-size`/config `chunk_size` voxel count (e.g. `32`) into
+/// the octree depth [`Chunk::with_depth`] expects, rejecting anything that
+/// isn't a power of two between `2^MIN_CHUNK_DEPTH` and `2^MAX_CHUNK_DEPTH`.
+pub fn depth_from_size(size: u32) -> Result<u32, String> {
+    if !size.is_power_of_two() {
+        return Err(format!("chunk size must be a power of two, got {size}"));
+    }
+    let depth = size.trailing_zeros();
+    if !(MIN_CHUNK_DEPTH..=MAX_CHUNK_DEPTH).contains(&depth) {
+        return Err(format!(
+            "chunk size must be between {} and {} voxels per side, got {size}",
+            1 << MIN_CHUNK_DEPTH,
+            1 << MAX_CHUNK_DEPTH
+        ));
+    }
+    Ok(depth)
+}
+
+/// A chunk's worst-case GPU node buffer wouldn't fit in a single storage
+/// binding on the device it's being uploaded to. Returned by
+/// [`validate_node_buffer_size`]; there's no `svo::Svo`/`SceneBounds` type in
+/// this codebase for a scene-wide bounds check to hang off of, so this
+/// validates the one buffer that's actually sized from chunk depth:
+/// `VoxelRendererPass`'s `node_buffer`, built from `Tree::to_gpu_nodes`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChunkBufferError {
+    pub depth: u32,
+    pub required_bytes: u64,
+    pub max_binding_size: u64,
+}
+
+impl std::fmt::Display for ChunkBufferError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "chunk depth {} needs a worst-case {}-byte node buffer, which exceeds this device's \
+             {}-byte max_storage_buffer_binding_size",
+            self.depth, self.required_bytes, self.max_binding_size
+        )
+    }
+}
+
+impl std::error::Error for ChunkBufferError {}
+
+/// Worst-case byte size of a `depth`-deep chunk's `node_buffer`, i.e.
+/// `Tree::to_gpu_nodes` output with every level fully subdivided. Used by
+/// [`validate_node_buffer_size`] and by anything that wants to reason about
+/// upload cost before a chunk is actually built.
+pub fn required_node_buffer_bytes(depth: u32) -> u64 {
+    Tree::estimated_size_aligned(depth, 1)
+}
+
+/// Checks that a `depth`-deep chunk's worst-case node buffer fits within
+/// `max_binding_size` (`wgpu::Limits::max_storage_buffer_binding_size`, as a
+/// `u64`) before `VoxelRendererPass::new` allocates it. Sized off the worst
+/// case rather than the chunk's current `to_gpu_nodes` output because a
+/// chunk that's mostly air today can still grow past this once `Tree::set`
+/// subdivides it further; failing up front here turns that into a clear
+/// error at load time instead of a wgpu validation panic mid-session.
+pub fn validate_node_buffer_size(depth: u32, max_binding_size: u64) -> Result<(), ChunkBufferError> {
+    let required_bytes = required_node_buffer_bytes(depth);
+    if required_bytes > max_binding_size {
+        return Err(ChunkBufferError { depth, required_bytes, max_binding_size });
+    }
+    Ok(())
+}
+
+/// Maximum emitters `Chunk::collect_emitters` returns, matching the fixed
+/// capacity `VoxelRendererPass` allocates for the shader's `emitters`
+/// storage buffer. Collection stops once this many are found rather than
+/// growing the buffer, so a chunk with unusually dense glowing terrain can't
+/// blow the upload budget.
+pub const MAX_EMITTERS: usize = 64;
+
+/// Radius (in voxels) every emitter lights out to. Fixed rather than
+/// per-material since tuning per-glow-strength falloff would need the
+/// golden-image harness this crate doesn't have yet.
+const EMITTER_RADIUS: f32 = 6.0;
+
+/// A single emissive voxel treated as a point light, uploaded to the
+/// shader's `emitters` storage buffer by `VoxelRendererPass`. `#[repr(C)]`
+/// matches `EmitterGpu` in `voxel_renderer.wgsl`; `color` is already scaled
+/// by the source material's emissive intensity.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct EmitterGpu {
+    pub pos: [f32; 3],
+    pub radius: f32,
+    pub color: [f32; 3],
+    _pad: f32,
+}
+
+/// A cube of voxels at a fixed world position, backed by a [`Tree`]. This is
+/// the unit the renderer uploads to the GPU and the streaming system
+/// loads/unloads.
+pub struct Chunk {
+    pub position: glam::IVec3,
+    pub tree: Tree,
+}
+
+impl Chunk {
+    pub fn empty(position: glam::IVec3) -> Self {
+        Self::with_depth(position, CHUNK_DEPTH)
+    }
+
+    /// Same as [`Self::empty`], but at an explicit octree depth instead of
+    /// the default [`CHUNK_DEPTH`]; see [`depth_from_size`] for turning a
+    /// voxel count into the `depth` this expects.
+    pub fn with_depth(position: glam::IVec3, depth: u32) -> Self {
+        Self {
+            position,
+            tree: Tree::new(depth),
+        }
+    }
+
+    pub fn size(&self) -> u32 {
+        self.tree.size()
+    }
+
+    /// Fills the chunk with a simple deterministic pattern (solid floor,
+    /// optionally topped with `water_depth` voxels of `Voxel::WATER`, air
+    /// above that) so there's something to look at before real world
+    /// generation exists.
+    pub fn filled_test_pattern_with_water(position: glam::IVec3, water_depth: u32) -> Self {
+        Self::filled_test_pattern_with_water_at_depth(position, water_depth, CHUNK_DEPTH)
+    }
+
+    /// Same as [`Self::filled_test_pattern_with_water`], but at an explicit
+    /// octree depth instead of the default [`CHUNK_DEPTH`].
+    pub fn filled_test_pattern_with_water_at_depth(position: glam::IVec3, water_depth: u32, depth: u32) -> Self {
+        let mut chunk = Self::with_depth(position, depth);
+        let size = chunk.size();
+        let floor_height = size / 2;
+        let water_top = floor_height.saturating_add(water_depth).min(size);
+        for x in 0..size {
+            for z in 0..size {
+                for y in 0..floor_height {
+                    chunk.tree.set(glam::UVec3::new(x, y, z), Voxel::STONE);
+                }
+                for y in floor_height..water_top {
+                    chunk.tree.set(glam::UVec3::new(x, y, z), Voxel::WATER);
+                }
+            }
+        }
+        chunk
+    }
+
+    /// Fills the chunk with a solid `Voxel::MIRROR` floor and a single
+    /// `Voxel::STONE` column (`tower_height` voxels tall) standing on it, so
+    /// there's a "tower" for a reflective floor to show an inverted image
+    /// of. This crate has no headless GPU readback harness to turn that into
+    /// a real pixel-level golden image (see `Renderer::new_headless`'s doc
+    /// comment) -- this is the terrain-generation half a golden test would
+    /// render, kept here so the shape is exercised without one.
+    pub fn filled_test_pattern_with_mirror_floor(position: glam::IVec3, tower_height: u32) -> Self {
+        Self::filled_test_pattern_with_mirror_floor_at_depth(position, tower_height, CHUNK_DEPTH)
+    }
+
+    /// Same as [`Self::filled_test_pattern_with_mirror_floor`], but at an
+    /// explicit octree depth instead of the default [`CHUNK_DEPTH`].
+    pub fn filled_test_pattern_with_mirror_floor_at_depth(position: glam::IVec3, tower_height: u32, depth: u32) -> Self {
+        let mut chunk = Self::with_depth(position, depth);
+        let size = chunk.size();
+        let floor_height = size / 2;
+        for x in 0..size {
+            for z in 0..size {
+                chunk.tree.set(glam::UVec3::new(x, floor_height, z), Voxel::MIRROR);
+            }
+        }
+        let tower_top = (floor_height + 1).saturating_add(tower_height).min(size);
+        let center = size / 2;
+        for y in (floor_height + 1)..tower_top {
+            chunk.tree.set(glam::UVec3::new(center, y, center), Voxel::STONE);
+        }
+        chunk
+    }
+
+    /// Walks every solid voxel and collects the emissive ones (per
+    /// `materials`) into GPU point lights, in this chunk's local voxel
+    /// space. `VoxelRendererPass` calls this once when the chunk is
+    /// (re)uploaded, not per frame.
+    pub fn collect_emitters(&self, materials: &MaterialTable) -> Vec<EmitterGpu> {
+        let mut emitters = Vec::new();
+        for (pos, material) in self.tree.iter_voxels() {
+            if emitters.len() >= MAX_EMITTERS {
+                break;
+            }
+            let props = materials.get(material);
+            if props.emissive <= 0.0 {
+                continue;
+            }
+            emitters.push(EmitterGpu {
+                pos: [pos.x as f32 + 0.5, pos.y as f32 + 0.5, pos.z as f32 + 0.5],
+                radius: EMITTER_RADIUS,
+                color: [
+                    props.color[0] * props.emissive,
+                    props.color[1] * props.emissive,
+                    props.color[2] * props.emissive,
+                ],
+                _pad: 0.0,
+            });
+        }
+        emitters
+    }
+
+    /// Order-independent content hash of every non-air voxel, `position`
+    /// folded in so two identical trees at different chunk positions still
+    /// fingerprint differently. XORs a per-voxel hash rather than hashing
+    /// the whole sequence in one pass, so the result doesn't depend on
+    /// `Tree::iter_voxels`'s traversal order -- the property a golden test
+    /// across thread counts or platforms would actually need once there's
+    /// a `TerrainGenerator` to compare serial-vs-rayon generation against.
+    /// There's no such generator yet (no RNG/noise, no rayon dependency --
+    /// `filled_test_pattern_with_water` is already a pure function of
+    /// `(position, water_depth)`), so this only adds the hashing building
+    /// block, not the audit the original ask was really about.
+    ///
+    /// Uses [`fnv1a`] rather than `std`'s `DefaultHasher`: the latter's
+    /// algorithm is explicitly unspecified and may change between Rust
+    /// versions, which would silently invalidate a pinned golden value like
+    /// the one in this module's tests.
+    pub fn fingerprint(&self) -> u64 {
+        self.tree.iter_voxels().fold(0u64, |acc, (local_pos, voxel)| {
+            let mut bytes = Vec::with_capacity(28);
+            bytes.extend_from_slice(&self.position.x.to_le_bytes());
+            bytes.extend_from_slice(&self.position.y.to_le_bytes());
+            bytes.extend_from_slice(&self.position.z.to_le_bytes());
+            bytes.extend_from_slice(&local_pos.x.to_le_bytes());
+            bytes.extend_from_slice(&local_pos.y.to_le_bytes());
+            bytes.extend_from_slice(&local_pos.z.to_le_bytes());
+            bytes.extend_from_slice(&voxel.to_le_bytes());
+            acc ^ fnv1a(&bytes)
+        })
+    }
+}
+
+/// FNV-1a over `bytes`, fixed by the algorithm rather than by any `std`
+/// type, so [`Chunk::fingerprint`]'s pinned test value stays valid across
+/// Rust versions and platforms. Also used by [`super::decorate`] to fold a
+/// chunk position into a per-chunk decoration seed.
+pub(crate) fn fnv1a(bytes: &[u8]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+    bytes.iter().fold(OFFSET_BASIS, |hash, &byte| (hash ^ byte as u64).wrapping_mul(PRIME))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_chunk_has_no_solid_voxels() {
+        let chunk = Chunk::empty(glam::IVec3::ZERO);
+        assert_eq!(chunk.tree.get(glam::UVec3::new(0, 0, 0)), 0);
+    }
+
+    #[test]
+    fn test_pattern_fills_bottom_half() {
+        let chunk = Chunk::filled_test_pattern_with_water(glam::IVec3::ZERO, 0);
+        let size = chunk.size();
+        assert_eq!(chunk.tree.get(glam::UVec3::new(0, 0, 0)), 1);
+        assert_eq!(chunk.tree.get(glam::UVec3::new(0, size - 1, 0)), 0);
+    }
+
+    #[test]
+    fn water_pattern_submerges_floor_and_leaves_air_above() {
+        let chunk = Chunk::filled_test_pattern_with_water(glam::IVec3::ZERO, 4);
+        let size = chunk.size();
+        let floor_height = size / 2;
+        assert_eq!(chunk.tree.get(glam::UVec3::new(0, 0, 0)), Voxel::STONE);
+        assert_eq!(chunk.tree.get(glam::UVec3::new(0, floor_height, 0)), Voxel::WATER);
+        assert_eq!(chunk.tree.get(glam::UVec3::new(0, floor_height + 4, 0)), Voxel::AIR);
+    }
+
+    #[test]
+    fn water_depth_is_clamped_to_chunk_height() {
+        let chunk = Chunk::filled_test_pattern_with_water(glam::IVec3::ZERO, u32::MAX);
+        let size = chunk.size();
+        assert_eq!(chunk.tree.get(glam::UVec3::new(0, size - 1, 0)), Voxel::WATER);
+    }
+
+    // A golden image of a mirror floor showing an inverted image of the
+    // tower standing on it needs the same headless GPU readback harness the
+    // other golden-image asks in this crate don't have; this instead checks
+    // the Rust-side half of that plumbing -- that the terrain helper a
+    // golden test would render from actually lays down a mirror floor with
+    // a stone tower above it.
+    #[test]
+    fn mirror_floor_pattern_places_tower_above_a_mirror_floor() {
+        let chunk = Chunk::filled_test_pattern_with_mirror_floor(glam::IVec3::ZERO, 3);
+        let size = chunk.size();
+        let floor_height = size / 2;
+        let center = size / 2;
+        assert_eq!(chunk.tree.get(glam::UVec3::new(0, floor_height, 0)), Voxel::MIRROR);
+        assert_eq!(chunk.tree.get(glam::UVec3::new(center, floor_height + 1, center)), Voxel::STONE);
+        assert_eq!(chunk.tree.get(glam::UVec3::new(center, floor_height + 3, center)), Voxel::STONE);
+        assert_eq!(chunk.tree.get(glam::UVec3::new(center, floor_height + 4, center)), Voxel::AIR);
+    }
+
+    #[test]
+    fn mirror_floor_tower_height_is_clamped_to_chunk_height() {
+        let chunk = Chunk::filled_test_pattern_with_mirror_floor(glam::IVec3::ZERO, u32::MAX);
+        let size = chunk.size();
+        let center = size / 2;
+        assert_eq!(chunk.tree.get(glam::UVec3::new(center, size - 1, center)), Voxel::STONE);
+    }
+
+    // A golden image of a lone glowstone voxel lighting a dark cave floor
+    // needs the same headless GPU readback harness the other golden-image
+    // asks in this crate don't have; this instead checks the Rust-side half
+    // of that plumbing -- that a single emissive voxel actually turns into
+    // one emitter, positioned and colored as the shader's point-light
+    // falloff expects, while a non-emissive voxel contributes nothing.
+    #[test]
+    fn collect_emitters_finds_the_one_glowing_voxel_in_a_dark_chunk() {
+        let mut chunk = Chunk::empty(glam::IVec3::ZERO);
+        chunk.tree.set(glam::UVec3::new(0, 0, 0), Voxel::STONE);
+        chunk.tree.set(glam::UVec3::new(2, 3, 4), Voxel::GLOWSTONE);
+        let materials = MaterialTable::default();
+
+        let emitters = chunk.collect_emitters(&materials);
+        assert_eq!(emitters.len(), 1);
+        assert_eq!(emitters[0].pos, [2.5, 3.5, 4.5]);
+        assert!(emitters[0].radius > 0.0);
+        assert_ne!(emitters[0].color, [0.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn emitter_gpu_pod_layout_matches_wgsl_struct() {
+        assert_eq!(std::mem::size_of::<EmitterGpu>(), 32);
+    }
+
+    #[test]
+    fn collect_emitters_caps_at_max_emitters() {
+        let mut chunk = Chunk::empty(glam::IVec3::ZERO);
+        let size = chunk.size();
+        for x in 0..size {
+            for z in 0..size {
+                chunk.tree.set(glam::UVec3::new(x, 0, z), Voxel::GLOWSTONE);
+            }
+        }
+        let emitters = chunk.collect_emitters(&MaterialTable::default());
+        assert_eq!(emitters.len(), MAX_EMITTERS);
+    }
+
+    #[test]
+    fn fingerprint_is_stable_across_repeated_generation() {
+        let a = Chunk::filled_test_pattern_with_water(glam::IVec3::new(1, 0, -1), 4);
+        let b = Chunk::filled_test_pattern_with_water(glam::IVec3::new(1, 0, -1), 4);
+        assert_eq!(a.fingerprint(), b.fingerprint());
+    }
+
+    #[test]
+    fn fingerprint_differs_for_a_different_chunk_position() {
+        let here = Chunk::filled_test_pattern_with_water(glam::IVec3::ZERO, 4);
+        let elsewhere = Chunk::filled_test_pattern_with_water(glam::IVec3::new(1, 0, 0), 4);
+        assert_ne!(here.fingerprint(), elsewhere.fingerprint());
+    }
+
+    #[test]
+    fn fingerprint_differs_for_different_water_depth() {
+        let dry = Chunk::filled_test_pattern_with_water(glam::IVec3::ZERO, 0);
+        let flooded = Chunk::filled_test_pattern_with_water(glam::IVec3::ZERO, 4);
+        assert_ne!(dry.fingerprint(), flooded.fingerprint());
+    }
+
+    #[test]
+    fn fingerprint_does_not_depend_on_voxel_set_order() {
+        let mut set_low_to_high = Chunk::empty(glam::IVec3::ZERO);
+        set_low_to_high.tree.set(glam::UVec3::new(0, 0, 0), Voxel::STONE);
+        set_low_to_high.tree.set(glam::UVec3::new(2, 3, 4), Voxel::GLOWSTONE);
+
+        let mut set_high_to_low = Chunk::empty(glam::IVec3::ZERO);
+        set_high_to_low.tree.set(glam::UVec3::new(2, 3, 4), Voxel::GLOWSTONE);
+        set_high_to_low.tree.set(glam::UVec3::new(0, 0, 0), Voxel::STONE);
+
+        assert_eq!(set_low_to_high.fingerprint(), set_high_to_low.fingerprint());
+    }
+
+    #[test]
+    fn default_seed_test_pattern_fingerprint_is_pinned() {
+        // Pins `filled_test_pattern_with_water`'s output for the world's
+        // one standard chunk (see `Renderer::new`'s `TEST_PATTERN_WATER_DEPTH`
+        // chunk at the origin) so an accidental change to the generation
+        // path shows up here instead of only as a visual diff. There's no
+        // seed parameter to vary yet -- see `main.rs`'s `Cli` doc comment
+        // -- so "default seed" is just this one deterministic chunk.
+        let chunk = Chunk::filled_test_pattern_with_water(glam::IVec3::ZERO, 4);
+        assert_eq!(chunk.fingerprint(), 9184121991081519104);
+    }
+
+    #[test]
+    fn depth_from_size_accepts_32_and_128() {
+        assert_eq!(depth_from_size(32), Ok(5));
+        assert_eq!(depth_from_size(128), Ok(7));
+    }
+
+    #[test]
+    fn depth_from_size_rejects_a_non_power_of_two() {
+        assert!(depth_from_size(96).is_err());
+    }
+
+    #[test]
+    fn depth_from_size_rejects_sizes_outside_the_supported_range() {
+        assert!(depth_from_size(1).is_err());
+        assert!(depth_from_size(1 << (MAX_CHUNK_DEPTH + 1)).is_err());
+    }
+
+    #[test]
+    fn with_depth_builds_a_chunk_of_the_requested_size_at_32_and_128() {
+        for size in [32u32, 128] {
+            let depth = depth_from_size(size).unwrap();
+            let chunk = Chunk::with_depth(glam::IVec3::ZERO, depth);
+            assert_eq!(chunk.size(), size);
+        }
+    }
+
+    #[test]
+    fn to_gpu_nodes_sizes_its_buffer_from_the_runtime_depth_at_32_and_128() {
+        for size in [32u32, 128] {
+            let depth = depth_from_size(size).unwrap();
+            let chunk = Chunk::filled_test_pattern_with_water_at_depth(glam::IVec3::ZERO, 4, depth);
+            let gpu_nodes = chunk.tree.to_gpu_nodes();
+            assert!(!gpu_nodes.is_empty());
+            assert!((gpu_nodes.len() as u64) <= Tree::estimated_size_aligned(depth, 1) / std::mem::size_of::<crate::voxel::tree::GpuNode>() as u64);
+        }
+    }
+
+    #[test]
+    fn traversal_matches_the_software_renderer_hit_mask_at_32_and_128() {
+        use crate::voxel::camera::Camera;
+        use crate::voxel::software::hit_mask;
+
+        for size in [32u32, 128] {
+            let depth = depth_from_size(size).unwrap();
+            let chunk = Chunk::filled_test_pattern_with_water_at_depth(glam::IVec3::ZERO, 4, depth);
+            let materials = MaterialTable::default();
+            let chunk_size = chunk.size() as f32;
+            let camera = Camera::new(glam::Vec3::new(chunk_size * 1.5, chunk_size, chunk_size * 1.5));
+            let mask = hit_mask(&chunk, &materials, &camera, 8, 8);
+            assert!(mask.iter().any(|&hit| hit), "expected at least one ray to hit the floor at size {size}");
+        }
+    }
+
+    #[test]
+    fn required_node_buffer_bytes_grows_with_depth() {
+        let sizes: Vec<u64> = (MIN_CHUNK_DEPTH..=MAX_CHUNK_DEPTH).map(required_node_buffer_bytes).collect();
+        assert!(sizes.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[test]
+    fn validate_node_buffer_size_accepts_a_chunk_that_fits() {
+        let required = required_node_buffer_bytes(CHUNK_DEPTH);
+        assert_eq!(validate_node_buffer_size(CHUNK_DEPTH, required), Ok(()));
+    }
+
+    #[test]
+    fn validate_node_buffer_size_rejects_a_chunk_that_does_not_fit() {
+        let required = required_node_buffer_bytes(MAX_CHUNK_DEPTH);
+        let err = validate_node_buffer_size(MAX_CHUNK_DEPTH, required - 1).unwrap_err();
+        assert_eq!(
+            err,
+            ChunkBufferError { depth: MAX_CHUNK_DEPTH, required_bytes: required, max_binding_size: required - 1 }
+        );
+    }
+}
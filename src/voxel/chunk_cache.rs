@@ -0,0 +1,144 @@
+//! Eviction policy plus a small CPU-side LRU of recently-evicted chunk
+//! data, for whenever a streaming `ChunkManager` exists to drive it -- see
+//! `chunk_priority.rs`'s doc comment, and `BufferArena`'s ("Kept as the
+//! allocator this crate will need once a streaming, multi-chunk chunk
+//! table exists"). There's no `ChunkManager`, worker thread, region-file
+//! save system, or per-chunk dirty hash to evict *from* yet, so the
+//! "write back to region files if dirty" half of the original ask has
+//! nothing real to attach to -- this only adds the self-contained part:
+//! which resident chunks fall outside the unload radius, and a bounded
+//! cache so a player oscillating near that radius gets their chunk back
+//! instead of regenerating it.
+//!
+//! **Not closed out**: with no `ChunkManager` driving it, nothing in this
+//! crate ever calls [`chunks_to_evict`] or reaches into a [`ChunkCache`] --
+//! this produces zero runtime behavior today, the same gap `BufferArena`
+//! (synth-2845) and `GpuMemoryReport::fragmentation_ratio` (synth-2896)
+//! are stuck on for the same underlying reason.
+#![allow(dead_code)]
+
+use std::collections::VecDeque;
+
+use super::chunk::Chunk;
+
+/// Positions in `resident` whose center is farther than `unload_radius`
+/// world units from `camera_position` -- what a `ChunkManager` would evict
+/// this tick.
+pub(crate) fn chunks_to_evict(
+    camera_position: glam::Vec3,
+    chunk_size: u32,
+    unload_radius: f32,
+    resident: &[glam::IVec3],
+) -> Vec<glam::IVec3> {
+    resident
+        .iter()
+        .copied()
+        .filter(|&position| {
+            let center = position.as_vec3() + glam::Vec3::splat(chunk_size as f32 * 0.5);
+            center.distance(camera_position) > unload_radius
+        })
+        .collect()
+}
+
+/// Bounded LRU of evicted [`Chunk`]s, keyed by position. A plain
+/// `VecDeque` rather than a hash map plus a separate recency list:
+/// `capacity` is expected to be small (tens of chunks near the unload
+/// boundary, not thousands), so a linear scan on `take`/`put` is cheaper
+/// than the bookkeeping a real LRU needs to justify itself.
+pub(crate) struct ChunkCache {
+    capacity: usize,
+    /// Front is least-recently-used, back is most-recently-used.
+    entries: VecDeque<Chunk>,
+}
+
+impl ChunkCache {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self { capacity, entries: VecDeque::new() }
+    }
+
+    /// Removes and returns the cached chunk at `position`, if present --
+    /// the caller is about to hand it back to residency rather than
+    /// regenerate it.
+    pub(crate) fn take(&mut self, position: glam::IVec3) -> Option<Chunk> {
+        let index = self.entries.iter().position(|chunk| chunk.position == position)?;
+        self.entries.remove(index)
+    }
+
+    /// Inserts a just-evicted chunk as the most-recently-used entry,
+    /// dropping the least-recently-used one first if already at `capacity`.
+    pub(crate) fn put(&mut self, chunk: Chunk) {
+        if self.entries.len() >= self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(chunk);
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.entries.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chunks_to_evict_returns_only_positions_beyond_the_unload_radius() {
+        let resident = vec![glam::IVec3::new(0, 0, 0), glam::IVec3::new(1000, 0, 0)];
+        let evicted = chunks_to_evict(glam::Vec3::ZERO, 32, 100.0, &resident);
+        assert_eq!(evicted, vec![glam::IVec3::new(1000, 0, 0)]);
+    }
+
+    #[test]
+    fn chunks_to_evict_is_empty_when_everything_is_in_range() {
+        let resident = vec![glam::IVec3::new(0, 0, 0), glam::IVec3::new(10, 0, 0)];
+        assert!(chunks_to_evict(glam::Vec3::ZERO, 32, 1000.0, &resident).is_empty());
+    }
+
+    #[test]
+    fn cache_returns_a_put_chunk_via_take() {
+        let mut cache = ChunkCache::new(4);
+        cache.put(Chunk::empty(glam::IVec3::new(3, 0, 0)));
+        let chunk = cache.take(glam::IVec3::new(3, 0, 0)).expect("chunk should still be cached");
+        assert_eq!(chunk.position, glam::IVec3::new(3, 0, 0));
+        assert!(cache.take(glam::IVec3::new(3, 0, 0)).is_none(), "take should remove the entry");
+    }
+
+    #[test]
+    fn cache_evicts_the_least_recently_used_entry_once_full() {
+        let mut cache = ChunkCache::new(2);
+        cache.put(Chunk::empty(glam::IVec3::new(0, 0, 0)));
+        cache.put(Chunk::empty(glam::IVec3::new(1, 0, 0)));
+        cache.put(Chunk::empty(glam::IVec3::new(2, 0, 0)));
+        assert_eq!(cache.len(), 2);
+        assert!(cache.take(glam::IVec3::new(0, 0, 0)).is_none());
+        assert!(cache.take(glam::IVec3::new(1, 0, 0)).is_some());
+        assert!(cache.take(glam::IVec3::new(2, 0, 0)).is_some());
+    }
+
+    /// Simulated camera path: a chunk leaves the unload radius, gets
+    /// evicted into the cache, and is recovered from it (rather than
+    /// regenerated) once the camera drifts back -- the oscillation case
+    /// the cache exists for. Resident count stays bounded throughout.
+    #[test]
+    fn a_camera_path_evicts_then_recovers_a_revisited_chunk() {
+        let chunk_size = 32;
+        let unload_radius = 50.0;
+        let mut resident = vec![glam::IVec3::new(0, 0, 0)];
+        let mut cache = ChunkCache::new(4);
+
+        let far_camera = glam::Vec3::new(500.0, 0.0, 0.0);
+        let evicted = chunks_to_evict(far_camera, chunk_size, unload_radius, &resident);
+        assert_eq!(evicted, vec![glam::IVec3::new(0, 0, 0)]);
+        for position in evicted {
+            let chunk = Chunk::empty(position);
+            resident.retain(|&p| p != position);
+            cache.put(chunk);
+        }
+        assert!(resident.is_empty());
+
+        let recovered = cache.take(glam::IVec3::new(0, 0, 0)).expect("should recover instead of regenerate");
+        resident.push(recovered.position);
+        assert_eq!(resident, vec![glam::IVec3::new(0, 0, 0)]);
+    }
+}
@@ -0,0 +1,158 @@
+//! Cross-chunk voxel queries: translates a world-space position or ray into
+//! chunk-local lookups that hop across chunk borders via a loaded-chunk map,
+//! instead of stopping dead at the edge of whichever [`Chunk`] happens to
+//! contain the origin -- the gap [`super::collision::solid_at`] documents by
+//! treating "outside this chunk" as empty rather than falling back to a
+//! neighbor.
+//!
+//! There's no `ChunkManager` in this crate to source that map from --
+//! `App`'s doc comment in `engine/app.rs` and [`super::collision`]'s cover
+//! why: this renderer loads exactly one `Chunk`, not a streaming
+//! multi-chunk world. The functions here take the loaded-chunk set as a
+//! plain `&HashMap<glam::IVec3, Chunk>` parameter instead, keyed by chunk
+//! position the same way [`super::chunk_priority`]'s candidate positions
+//! already are, so a future `ChunkManager` can hand its map straight to
+//! these functions without this module changing.
+//!
+//! The GPU-side half of the request -- extending the chunk-table traversal
+//! so shadow/AO rays continue into a neighboring chunk's octree -- needs a
+//! bind group that can see more than one chunk's node buffer at once, and a
+//! chunk table to look neighbors up in; `VoxelRendererPass` only ever binds
+//! a single chunk's `node_buffer` (see its own doc comments), so that half
+//! isn't done here.
+#![allow(dead_code)]
+
+use super::chunk::Chunk;
+use super::coords::world_to_chunk;
+use super::material::Voxel;
+use std::collections::HashMap;
+
+/// The loaded-chunk lookup these functions hop across, keyed by
+/// [`Chunk::position`] the way a `ChunkManager` would maintain one.
+pub(crate) type ChunkMap = HashMap<glam::IVec3, Chunk>;
+
+/// Whether the voxel containing `world_pos` is solid (anything but air),
+/// looking it up in whichever loaded chunk of `chunks` covers that
+/// position. A missing neighbor (not present in `chunks`) reads as empty,
+/// same as [`super::collision::solid_at`] treats out-of-bounds.
+pub(crate) fn get_block_state_world(chunks: &ChunkMap, chunk_size: u32, world_pos: glam::IVec3) -> bool {
+    let (chunk_key, local) = world_to_chunk(world_pos, chunk_size as i32);
+    match chunks.get(&chunk_key) {
+        Some(chunk) => chunk.tree.get(local) != Voxel::AIR,
+        None => false,
+    }
+}
+
+/// A ray hit found by [`raycast_world`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct WorldRayHit {
+    /// World-space voxel coordinate of the solid voxel that was hit.
+    pub voxel: glam::IVec3,
+    /// Material at `voxel`.
+    pub material: u32,
+    /// Distance traveled from `origin` before the hit.
+    pub distance: f32,
+}
+
+/// Steps a ray from `origin` along `dir` (need not be normalized) out to
+/// `max_dist` world units, sampling one voxel at a time via
+/// [`get_block_state_world`] so the ray keeps going once it crosses into a
+/// neighboring chunk instead of stopping at the origin chunk's border.
+/// Coarser than a real DDA voxel traversal -- see `MAX_STEP_DISTANCE` in
+/// [`super::collision`] for the same tradeoff made there -- but exact enough
+/// for a CPU raycast that only needs a hit, not a normal.
+pub(crate) fn raycast_world(
+    chunks: &ChunkMap,
+    chunk_size: u32,
+    origin: glam::Vec3,
+    dir: glam::Vec3,
+    max_dist: f32,
+) -> Option<WorldRayHit> {
+    const STEP: f32 = 0.05;
+    let dir = dir.normalize_or_zero();
+    if dir == glam::Vec3::ZERO {
+        return None;
+    }
+    let steps = (max_dist / STEP).ceil() as u32;
+    for step in 0..=steps {
+        let distance = (step as f32 * STEP).min(max_dist);
+        let pos = origin + dir * distance;
+        let voxel = pos.floor().as_ivec3();
+        let (chunk_key, local) = world_to_chunk(voxel, chunk_size as i32);
+        if let Some(chunk) = chunks.get(&chunk_key) {
+            let material = chunk.tree.get(local);
+            if material != Voxel::AIR {
+                return Some(WorldRayHit { voxel, material, distance });
+            }
+        }
+        if distance >= max_dist {
+            break;
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunk_with_one_voxel(position: glam::IVec3, local: glam::UVec3, material: u32) -> Chunk {
+        let mut chunk = Chunk::empty(position);
+        chunk.tree.set(local, material);
+        chunk
+    }
+
+    #[test]
+    fn coordinate_translation_keeps_positive_world_positions_in_their_own_chunk() {
+        let chunk_size = 32;
+        let (key, local) = world_to_chunk(glam::IVec3::new(5, 3, 40), chunk_size);
+        assert_eq!(key, glam::IVec3::new(0, 0, 32));
+        assert_eq!(local, glam::UVec3::new(5, 3, 8));
+    }
+
+    #[test]
+    fn coordinate_translation_floor_divides_negative_world_positions() {
+        let chunk_size = 32;
+        let (key, local) = world_to_chunk(glam::IVec3::new(-1, -32, -33), chunk_size);
+        assert_eq!(key, glam::IVec3::new(-32, -32, -64));
+        assert_eq!(local, glam::UVec3::new(31, 0, 31));
+    }
+
+    #[test]
+    fn get_block_state_world_reads_across_chunk_borders() {
+        let chunk_size = 32;
+        let mut chunks = ChunkMap::new();
+        chunks.insert(glam::IVec3::new(32, 0, 0), chunk_with_one_voxel(glam::IVec3::new(32, 0, 0), glam::UVec3::new(0, 0, 0), Voxel::STONE));
+        assert!(get_block_state_world(&chunks, chunk_size, glam::IVec3::new(32, 0, 0)));
+        assert!(!get_block_state_world(&chunks, chunk_size, glam::IVec3::new(31, 0, 0)));
+    }
+
+    #[test]
+    fn get_block_state_world_treats_a_missing_neighbor_as_empty() {
+        let chunks = ChunkMap::new();
+        assert!(!get_block_state_world(&chunks, 32, glam::IVec3::new(100, 0, 0)));
+    }
+
+    #[test]
+    fn raycast_world_hits_a_voxel_in_the_adjacent_chunk() {
+        let chunk_size = 32;
+        let mut chunks = ChunkMap::new();
+        chunks.insert(glam::IVec3::ZERO, Chunk::empty(glam::IVec3::ZERO));
+        chunks.insert(
+            glam::IVec3::new(32, 0, 0),
+            chunk_with_one_voxel(glam::IVec3::new(32, 0, 0), glam::UVec3::new(0, 0, 0), Voxel::STONE),
+        );
+        let origin = glam::Vec3::new(30.0, 0.5, 0.5);
+        let dir = glam::Vec3::new(1.0, 0.0, 0.0);
+        let hit = raycast_world(&chunks, chunk_size, origin, dir, 10.0).expect("should hit the neighboring chunk's voxel");
+        assert_eq!(hit.voxel, glam::IVec3::new(32, 0, 0));
+        assert_eq!(hit.material, Voxel::STONE);
+    }
+
+    #[test]
+    fn raycast_world_misses_when_nothing_is_within_max_dist() {
+        let chunks = ChunkMap::new();
+        let hit = raycast_world(&chunks, 32, glam::Vec3::ZERO, glam::Vec3::X, 5.0);
+        assert!(hit.is_none());
+    }
+}
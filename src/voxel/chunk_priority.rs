@@ -0,0 +1,98 @@
+//! Scores candidate chunk positions by distance and view angle, so whatever
+//! loads them can pop the nearest-and-in-view one first instead of grinding
+//! through a FIFO that might currently be pointed behind the camera.
+//!
+//! There's no `ChunkLoader` or streaming request queue to plug this into
+//! yet -- this renderer loads exactly one [`super::Chunk`] (see its own doc
+//! comment, and `engine::config`'s "Streaming radius isn't offered, since
+//! there's no streaming/multi-chunk system"). [`priority`] and
+//! [`sort_by_priority`] are the scoring building block a future streaming
+//! system's priority queue would re-sort by on camera movement; the queue
+//! itself, its per-frame byte cap, and the `FrameStats` fields for it all
+//! depend on that system existing first. Not yet called from anywhere,
+//! hence the `#![allow(dead_code)]` below -- same reason `brickmap.rs`
+//! carries one.
+#![allow(dead_code)]
+
+use glam::{IVec3, Vec3};
+
+/// Lower sorts first -- combines distance to `chunk_position`'s center (in
+/// world units) with the angle between `camera_forward` and the direction
+/// to it, so something far off to the side doesn't outrank something closer
+/// and dead ahead. `angle_between` is in `[0, PI]` radians; scaling distance
+/// by `1.0 + angle` keeps the unit as "world units at zero angle" while
+/// still letting a wide-angle chunk fall behind a narrower one at the same
+/// distance.
+pub(crate) fn priority(camera_position: Vec3, camera_forward: Vec3, chunk_position: IVec3, chunk_size: u32) -> f32 {
+    let center = chunk_position.as_vec3() + Vec3::splat(chunk_size as f32 * 0.5);
+    let to_chunk = center - camera_position;
+    let distance = to_chunk.length();
+    if distance < f32::EPSILON {
+        return 0.0;
+    }
+    let angle = camera_forward.normalize().angle_between(to_chunk / distance);
+    distance * (1.0 + angle)
+}
+
+/// Sorts `positions` ascending by [`priority`], i.e. into the order a
+/// priority queue would pop them in -- nearest-and-in-view first.
+pub(crate) fn sort_by_priority(camera_position: Vec3, camera_forward: Vec3, chunk_size: u32, positions: &mut [IVec3]) {
+    positions.sort_by(|a, b| {
+        let pa = priority(camera_position, camera_forward, *a, chunk_size);
+        let pb = priority(camera_position, camera_forward, *b, chunk_size);
+        pa.partial_cmp(&pb).unwrap()
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_closer_chunk_outranks_a_farther_one_at_the_same_angle() {
+        let camera_position = Vec3::ZERO;
+        let forward = Vec3::Z;
+        let near = priority(camera_position, forward, IVec3::new(0, 0, 10), 32);
+        let far = priority(camera_position, forward, IVec3::new(0, 0, 100), 32);
+        assert!(near < far);
+    }
+
+    #[test]
+    fn a_chunk_dead_ahead_outranks_one_off_to_the_side_at_the_same_distance() {
+        let camera_position = Vec3::ZERO;
+        let forward = Vec3::Z;
+        let ahead = priority(camera_position, forward, IVec3::new(0, 0, 50), 32);
+        let to_the_side = priority(camera_position, forward, IVec3::new(50, 0, 0), 32);
+        assert!(ahead < to_the_side);
+    }
+
+    #[test]
+    fn a_chunk_at_the_camera_s_own_position_has_zero_priority() {
+        let camera_position = Vec3::new(16.0, 16.0, 16.0);
+        let score = priority(camera_position, Vec3::Z, IVec3::new(0, 0, 0), 32);
+        assert_eq!(score, 0.0);
+    }
+
+    #[test]
+    fn sort_by_priority_pops_nearest_and_in_view_first() {
+        let camera_position = Vec3::ZERO;
+        let forward = Vec3::Z;
+        let mut positions = vec![
+            IVec3::new(0, 0, 200),  // far ahead
+            IVec3::new(0, 0, 20),   // near ahead
+            IVec3::new(-200, 0, 0), // far behind-ish/to the side
+        ];
+        sort_by_priority(camera_position, forward, 32, &mut positions);
+        assert_eq!(positions[0], IVec3::new(0, 0, 20));
+        assert_eq!(positions[2], IVec3::new(-200, 0, 0));
+    }
+
+    #[test]
+    fn turning_around_reprioritizes_what_was_behind_the_camera() {
+        let camera_position = Vec3::ZERO;
+        let chunk_position = IVec3::new(0, 0, -50);
+        let facing_away = priority(camera_position, Vec3::Z, chunk_position, 32);
+        let facing_toward = priority(camera_position, Vec3::NEG_Z, chunk_position, 32);
+        assert!(facing_toward < facing_away);
+    }
+}
@@ -0,0 +1,242 @@
+//! AABB-vs-voxel collision resolution, scoped to a single loaded [`Chunk`]
+//! -- this renderer "has one loaded chunk, not a streaming multi-chunk
+//! world" (`App`'s doc comment in `engine/app.rs`), and there's no
+//! `ChunkManager` to query across chunk borders (see [`super::chunk_cache`]
+//! and [`super::chunk_priority`], which hit the same gap). There's also no
+//! Fly/Walk controller mode to toggle -- `Camera` is explicitly "Minimal fly
+//! camera" (its own doc comment) with no gravity, jumping, or collision at
+//! all yet.
+//!
+//! [`integrate`] is the self-contained piece the walk controller asked for
+//! would actually call once it exists: given a body's position, extents,
+//! and velocity, resolve one step of motion against a single `Chunk`'s
+//! solid voxels, with step-up and no tunneling at high speed. Stepping past
+//! the loaded chunk's bounds just stops rather than falling through to
+//! nothing (see [`solid_at`]) -- the "falling out of loaded chunks" edge
+//! case the request raised, scoped down the same way the loaded world is.
+//!
+//! **This module is not wired up.** The request also asked for a Fly/Walk
+//! toggle `Action`, Space-to-jump, and a debug-overlay indicator -- none of
+//! that landed, because there's nothing for it to drive yet: `App` has no
+//! WASD/planar-movement input at all (see `input.rs`'s module doc comment
+//! on why `Action` has no movement variants), so there's no per-frame
+//! velocity for a walk mode to feed into [`integrate`] in the first place.
+//! Building that is a separate, larger PR than "add the collision math";
+//! until it lands, everything in this file is unused and every function
+//! here is `pub(crate)` rather than `pub` to keep it from looking like a
+//! public API anyone should be calling today.
+#![allow(dead_code)]
+
+use super::chunk::Chunk;
+use super::material::Voxel;
+
+/// An axis-aligned move or overlap test is split into steps no larger than
+/// this many world units, so a high fall speed can't skip clean over a
+/// voxel-thick floor in a single step.
+const MAX_STEP_DISTANCE: f32 = 0.25;
+
+/// Inset applied to an AABB before testing for voxel overlap, so two
+/// bodies (or a body and a wall) that are merely touching don't register
+/// as colliding.
+const SKIN: f32 = 1e-4;
+
+/// Whether the voxel containing `world_pos` is solid (anything but air),
+/// treating positions outside the chunk's bounds as empty -- there's no
+/// neighboring chunk to fall back on, so "out of the loaded chunk" reads as
+/// open air rather than a crash or a wraparound.
+pub(crate) fn solid_at(chunk: &Chunk, world_pos: glam::Vec3) -> bool {
+    let local = world_pos.floor().as_ivec3() - chunk.position;
+    let size = chunk.size() as i32;
+    if local.cmplt(glam::IVec3::ZERO).any() || local.cmpge(glam::IVec3::splat(size)).any() {
+        return false;
+    }
+    chunk.tree.get(local.as_uvec3()) != Voxel::AIR
+}
+
+/// Whether an AABB centered at `center` with `half_extents` overlaps any
+/// solid voxel in `chunk`.
+fn overlaps_solid(chunk: &Chunk, center: glam::Vec3, half_extents: glam::Vec3) -> bool {
+    let skin = glam::Vec3::splat(SKIN);
+    let min = (center - half_extents + skin).floor().as_ivec3();
+    let max = (center + half_extents - skin).floor().as_ivec3();
+    for x in min.x..=max.x {
+        for y in min.y..=max.y {
+            for z in min.z..=max.z {
+                let voxel_center = glam::IVec3::new(x, y, z).as_vec3() + glam::Vec3::splat(0.5);
+                if solid_at(chunk, voxel_center) {
+                    return true;
+                }
+            }
+        }
+    }
+    false
+}
+
+/// Moves an AABB along one `axis` (0 = X, 1 = Y, 2 = Z) by `delta`, in
+/// sub-steps no larger than [`MAX_STEP_DISTANCE`] so the sweep can't tunnel
+/// through a voxel. Returns the resolved position and whether the full
+/// `delta` was blocked before completing.
+fn move_axis(chunk: &Chunk, position: glam::Vec3, half_extents: glam::Vec3, axis: usize, delta: f32) -> (glam::Vec3, bool) {
+    if delta.abs() < SKIN {
+        return (position, false);
+    }
+    let steps = (delta.abs() / MAX_STEP_DISTANCE).ceil().max(1.0) as u32;
+    let step = delta / steps as f32;
+    let mut position = position;
+    for _ in 0..steps {
+        let mut next = position;
+        next[axis] += step;
+        if overlaps_solid(chunk, next, half_extents) {
+            return (position, true);
+        }
+        position = next;
+    }
+    (position, false)
+}
+
+/// Resolves one fixed-timestep move of a capsule-ish AABB body (approximated
+/// as a box, `half_extents` wide/tall) through `chunk`'s solid voxels.
+/// Horizontal motion (X then Z) resolves first, retrying against a position
+/// raised by `step_height` if blocked -- a one-voxel ledge becomes a step
+/// rather than a wall. Vertical motion resolves last, zeroing `velocity.y`
+/// and reporting `grounded` on any downward collision.
+///
+/// Returns `(position, velocity, grounded)`.
+pub(crate) fn integrate(
+    chunk: &Chunk,
+    position: glam::Vec3,
+    half_extents: glam::Vec3,
+    velocity: glam::Vec3,
+    dt: f32,
+    step_height: f32,
+) -> (glam::Vec3, glam::Vec3, bool) {
+    let delta = velocity * dt;
+    let mut position = position;
+    let mut velocity = velocity;
+
+    for axis in [0usize, 2usize] {
+        let (moved, blocked) = move_axis(chunk, position, half_extents, axis, delta[axis]);
+        if !blocked {
+            position = moved;
+            continue;
+        }
+        if step_height > 0.0 {
+            let (raised, raised_blocked) = move_axis(chunk, position, half_extents, 1, step_height);
+            if !raised_blocked {
+                let (stepped, stepped_blocked) = move_axis(chunk, raised, half_extents, axis, delta[axis]);
+                if !stepped_blocked {
+                    position = stepped;
+                    continue;
+                }
+            }
+        }
+        velocity[axis] = 0.0;
+        position = moved;
+    }
+
+    let (moved, blocked) = move_axis(chunk, position, half_extents, 1, delta.y);
+    let grounded = blocked && delta.y <= 0.0;
+    if blocked {
+        velocity.y = 0.0;
+    }
+    position = moved;
+
+    (position, velocity, grounded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn flat_floor_chunk(floor_height: u32) -> Chunk {
+        let mut chunk = Chunk::empty(glam::IVec3::ZERO);
+        let size = chunk.size();
+        for x in 0..size {
+            for z in 0..size {
+                for y in 0..floor_height {
+                    chunk.tree.set(glam::UVec3::new(x, y, z), Voxel::STONE);
+                }
+            }
+        }
+        chunk
+    }
+
+    #[test]
+    fn falling_body_comes_to_rest_on_the_floor() {
+        let chunk = flat_floor_chunk(4);
+        let half_extents = glam::Vec3::new(0.4, 0.9, 0.4);
+        let mut position = glam::Vec3::new(16.0, 10.0, 16.0);
+        let mut velocity = glam::Vec3::ZERO;
+        let mut grounded = false;
+        for _ in 0..200 {
+            velocity.y -= 20.0 * (1.0 / 60.0);
+            let result = integrate(&chunk, position, half_extents, velocity, 1.0 / 60.0, 0.0);
+            position = result.0;
+            velocity = result.1;
+            grounded = result.2;
+        }
+        assert!(grounded, "body should have settled onto the floor");
+        assert!((position.y - (4.0 + half_extents.y)).abs() < 1e-3, "resting height was {}", position.y);
+    }
+
+    #[test]
+    fn a_one_voxel_ledge_is_stepped_up_rather_than_blocking() {
+        let mut chunk = flat_floor_chunk(4);
+        let size = chunk.size();
+        // A one-voxel-high ledge starting at x = 20, spanning the full
+        // z range the body's extents will sweep through.
+        for x in 20..size {
+            for z in 14..18 {
+                chunk.tree.set(glam::UVec3::new(x, 4, z), Voxel::STONE);
+            }
+        }
+        let half_extents = glam::Vec3::new(0.4, 0.9, 0.4);
+        let mut position = glam::Vec3::new(18.0, 4.0 + half_extents.y, 16.0);
+        let velocity = glam::Vec3::new(2.0, 0.0, 0.0);
+        for _ in 0..120 {
+            let result = integrate(&chunk, position, half_extents, velocity, 1.0 / 60.0, 1.0);
+            position = result.0;
+        }
+        assert!(position.x > 21.0, "body should have walked onto the ledge, stopped at x={}", position.x);
+        assert!((position.y - (5.0 + half_extents.y)).abs() < 1e-3, "body should be resting on top of the ledge, y={}", position.y);
+    }
+
+    #[test]
+    fn high_fall_speed_does_not_tunnel_through_the_floor() {
+        let chunk = flat_floor_chunk(4);
+        let half_extents = glam::Vec3::new(0.4, 0.9, 0.4);
+        let position = glam::Vec3::new(16.0, 10.0, 16.0);
+        // Fast enough to cross the gap to the floor in well under one
+        // 1/60s frame if taken as a single unclamped step.
+        let velocity = glam::Vec3::new(0.0, -500.0, 0.0);
+        let (position, _velocity, grounded) = integrate(&chunk, position, half_extents, velocity, 1.0 / 60.0, 0.0);
+        assert!(grounded);
+        assert!(position.y >= 4.0 + half_extents.y - 1e-3, "body tunneled to y={}", position.y);
+    }
+
+    #[test]
+    fn a_ceiling_stops_upward_motion_without_affecting_horizontal_velocity() {
+        let mut chunk = Chunk::empty(glam::IVec3::ZERO);
+        let size = chunk.size();
+        for x in 0..size {
+            for z in 0..size {
+                chunk.tree.set(glam::UVec3::new(x, 10, z), Voxel::STONE);
+            }
+        }
+        let half_extents = glam::Vec3::new(0.4, 0.9, 0.4);
+        let position = glam::Vec3::new(16.0, 8.0, 16.0);
+        let velocity = glam::Vec3::new(1.0, 5.0, 0.0);
+        let (_position, velocity, grounded) = integrate(&chunk, position, half_extents, velocity, 1.0, 0.0);
+        assert!(!grounded, "a ceiling hit while moving up should not count as grounded");
+        assert_eq!(velocity.y, 0.0);
+        assert_eq!(velocity.x, 1.0);
+    }
+
+    #[test]
+    fn solid_at_treats_positions_outside_the_loaded_chunk_as_air() {
+        let chunk = flat_floor_chunk(4);
+        let size = chunk.size() as f32;
+        assert!(!solid_at(&chunk, glam::Vec3::new(size + 5.0, 1.0, 1.0)));
+        assert!(!solid_at(&chunk, glam::Vec3::new(1.0, -1.0, 1.0)));
+    }
+}
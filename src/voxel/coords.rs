@@ -0,0 +1,86 @@
+//! World-space to chunk-space coordinate translation, pulled out of
+//! [`super::chunk_neighbors`] into its own module so every place that needs
+//! it -- currently just `chunk_neighbors`, and whatever picking or
+//! `ChunkManager` code eventually joins it -- shares one implementation
+//! instead of each hand-rolling its own division.
+//!
+//! There's no `Point3D` type in this crate to hang `div_euclid`/`rem_euclid`
+//! off of; world and chunk positions are already `glam::IVec3` everywhere
+//! (see [`super::chunk::Chunk::position`] and [`super::chunk_priority`]),
+//! and `glam::IVec3` already provides correct `div_euclid`/`rem_euclid` --
+//! there's no naive truncating `Div<i32>` on it to fix. What was missing
+//! was a single named [`world_to_chunk`] helper instead of the translation
+//! being inlined wherever it's needed.
+
+/// Splits a world-space voxel coordinate into which `size`-voxel chunk it
+/// falls in (the chunk's [`super::chunk::Chunk::position`]) and its position
+/// local to that chunk, in `0..size` on every axis. Floor-divides
+/// (`div_euclid`/`rem_euclid`) rather than truncating, so a negative world
+/// coordinate lands in the chunk to its negative side with a non-negative
+/// local position, instead of truncating toward zero and landing in the
+/// wrong chunk -- e.g. with `size = 64`, world x = -1 maps to chunk x = -64,
+/// local x = 63, not chunk x = 0.
+pub(crate) fn world_to_chunk(world: glam::IVec3, size: i32) -> (glam::IVec3, glam::UVec3) {
+    let chunk = world.div_euclid(glam::IVec3::splat(size)) * size;
+    let local = world.rem_euclid(glam::IVec3::splat(size)).as_uvec3();
+    (chunk, local)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn positive_coordinates_stay_in_chunk_zero() {
+        assert_eq!(world_to_chunk(glam::IVec3::new(0, 0, 0), 64), (glam::IVec3::ZERO, glam::UVec3::ZERO));
+        assert_eq!(
+            world_to_chunk(glam::IVec3::new(63, 5, 40), 64),
+            (glam::IVec3::ZERO, glam::UVec3::new(63, 5, 40))
+        );
+    }
+
+    #[test]
+    fn crossing_a_positive_boundary_advances_the_chunk_and_wraps_local() {
+        assert_eq!(
+            world_to_chunk(glam::IVec3::new(64, 0, 0), 64),
+            (glam::IVec3::new(64, 0, 0), glam::UVec3::ZERO)
+        );
+        assert_eq!(
+            world_to_chunk(glam::IVec3::new(65, 128, 200), 64),
+            (glam::IVec3::new(64, 128, 192), glam::UVec3::new(1, 0, 8))
+        );
+    }
+
+    #[test]
+    fn negative_one_maps_to_the_negative_chunk_not_chunk_zero() {
+        // The exact case the request calls out: naive truncating division
+        // would give chunk 0, local -1 (or, cast to unsigned, garbage).
+        assert_eq!(
+            world_to_chunk(glam::IVec3::new(-1, -1, -1), 64),
+            (glam::IVec3::splat(-64), glam::UVec3::splat(63))
+        );
+    }
+
+    #[test]
+    fn negative_coordinates_pin_local_to_the_non_negative_range_for_several_sizes() {
+        for size in [1i32, 2, 8, 16, 32, 64, 100] {
+            for world in -3 * size..3 * size {
+                let (chunk, local) = world_to_chunk(glam::IVec3::splat(world), size);
+                assert!(chunk.x % size == 0, "chunk {chunk:?} not aligned to size {size} for world {world}");
+                assert!(local.x < size as u32, "local {local:?} out of range for size {size}, world {world}");
+                assert_eq!(chunk.x + local.x as i32, world, "round trip failed for size {size}, world {world}");
+            }
+        }
+    }
+
+    #[test]
+    fn chunk_and_local_round_trip_reconstructs_the_original_world_position() {
+        for size in [1i32, 3, 17, 64] {
+            for world in -2 * size..=2 * size {
+                let point = glam::IVec3::new(world, world + 1, world - 1);
+                let (chunk, local) = world_to_chunk(point, size);
+                assert_eq!(chunk + local.as_ivec3(), point, "round trip failed for size {size}, point {point:?}");
+            }
+        }
+    }
+}
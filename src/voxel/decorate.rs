@@ -0,0 +1,230 @@
+//! Post-generation decoration: stamps small voxel structures (trees,
+//! boulders) on top of terrain, meant to run after [`super::terrain`]
+//! builds a chunk's base shape.
+//!
+//! Two pieces of the original ask don't exist in this codebase, so they
+//! aren't in here either: a `.vox` importer (`engine::scene`'s module doc
+//! comment already covers why there's no such importer or embedded prefab
+//! file format anywhere), and the `rand` crate's `Rng` trait, which isn't a
+//! dependency -- every other pseudo-randomness in this crate is a small
+//! inline xorshift instead (see `test_util::Xorshift32`, and `tree.rs`'s
+//! own test-only copy), so [`Decorator::decorate`] takes this module's own
+//! rather than reaching for a crate this codebase doesn't otherwise use.
+//! Prefabs below are small hardcoded voxel-offset lists instead of loaded
+//! `.vox` files.
+//!
+//! [`Decorator::decorate`] also takes a `&mut Chunk` -- this crate's
+//! octree-backed, runtime-sized chunk -- not the `Chunk<SIZE>` const
+//! generic the original ask names; see `chunk::CHUNK_DEPTH`'s doc comment
+//! for why chunk size is a runtime `depth: u32` here, not a const generic.
+
+use super::chunk::Chunk;
+use super::material::Voxel;
+
+/// Xorshift32, seeded per chunk by [`decorate_chunk`]. A dedicated copy
+/// rather than reusing `test_util::Xorshift32`: that module's own doc
+/// comment scopes it to tests and `benches/`, not gameplay-facing
+/// generation code.
+pub struct Xorshift32(u32);
+
+impl Xorshift32 {
+    fn next_u32(&mut self) -> u32 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 17;
+        self.0 ^= self.0 << 5;
+        self.0
+    }
+
+    /// Uniform `[0.0, 1.0)`, for a decorator's per-column placement chance.
+    fn next_f32(&mut self) -> f32 {
+        (self.next_u32() as f64 / (u32::MAX as f64 + 1.0)) as f32
+    }
+}
+
+/// Folds `chunk_pos` into `world_seed` so every chunk gets its own
+/// reproducible decoration RNG stream: same world seed, same chunk
+/// position, same decorations every time, but unrelated-looking between
+/// neighboring chunks.
+fn chunk_seed(world_seed: u32, chunk_pos: glam::IVec3) -> u32 {
+    let mut bytes = Vec::with_capacity(12);
+    bytes.extend_from_slice(&chunk_pos.x.to_le_bytes());
+    bytes.extend_from_slice(&chunk_pos.y.to_le_bytes());
+    bytes.extend_from_slice(&chunk_pos.z.to_le_bytes());
+    world_seed ^ (super::chunk::fnv1a(&bytes) as u32)
+}
+
+/// Something that stamps structures onto a freshly generated [`Chunk`],
+/// run by [`decorate_chunk`] with a seed already derived from the chunk's
+/// position.
+pub trait Decorator {
+    fn decorate(&self, chunk_pos: glam::IVec3, chunk: &mut Chunk, rng: &mut Xorshift32);
+}
+
+/// Topmost non-air voxel in column `(x, z)`, if any -- where a decorator
+/// would stand something up.
+fn surface_height(chunk: &Chunk, x: u32, z: u32) -> Option<u32> {
+    let size = chunk.size();
+    (0..size).rev().find(|&y| chunk.tree.get(glam::UVec3::new(x, y, z)) != Voxel::AIR)
+}
+
+/// Writes `prefab`'s offsets relative to `anchor`, silently skipping any
+/// offset that lands outside `[0, size)` on any axis -- a structure stamped
+/// near a chunk border is clipped rather than wrapping or panicking, per
+/// the original ask.
+fn stamp(chunk: &mut Chunk, anchor: glam::IVec3, prefab: &[(i32, i32, i32, u32)]) {
+    let size = chunk.size() as i32;
+    for &(dx, dy, dz, material) in prefab {
+        let pos = anchor + glam::IVec3::new(dx, dy, dz);
+        if pos.x < 0 || pos.y < 0 || pos.z < 0 || pos.x >= size || pos.y >= size || pos.z >= size {
+            continue;
+        }
+        chunk.tree.set(pos.as_uvec3(), material);
+    }
+}
+
+/// A 3-voxel `Voxel::STONE` trunk topped with a small `Voxel::GLOWSTONE`
+/// leaf blob, 5 voxels tall overall. `Voxel::GLOWSTONE` stands in for a
+/// dedicated leaf material, since none exists yet (see [`Voxel`]'s doc
+/// comment for the full material list).
+const TREE_PREFAB: &[(i32, i32, i32, u32)] = &[
+    (0, 0, 0, Voxel::STONE),
+    (0, 1, 0, Voxel::STONE),
+    (0, 2, 0, Voxel::STONE),
+    (0, 3, 0, Voxel::GLOWSTONE),
+    (-1, 3, 0, Voxel::GLOWSTONE),
+    (1, 3, 0, Voxel::GLOWSTONE),
+    (0, 3, -1, Voxel::GLOWSTONE),
+    (0, 3, 1, Voxel::GLOWSTONE),
+    (0, 4, 0, Voxel::GLOWSTONE),
+];
+
+/// Places [`TREE_PREFAB`] above a fraction of surface columns, seeded by
+/// the [`Xorshift32`] `decorate_chunk` hands it.
+pub struct TreeDecorator {
+    /// Fraction of surface columns (`[0.0, 1.0]`) that get a tree.
+    pub density: f32,
+}
+
+impl Decorator for TreeDecorator {
+    fn decorate(&self, _chunk_pos: glam::IVec3, chunk: &mut Chunk, rng: &mut Xorshift32) {
+        let size = chunk.size();
+        for x in 0..size {
+            for z in 0..size {
+                if rng.next_f32() >= self.density {
+                    continue;
+                }
+                let Some(surface_y) = surface_height(chunk, x, z) else {
+                    continue;
+                };
+                let anchor = glam::IVec3::new(x as i32, surface_y as i32 + 1, z as i32);
+                stamp(chunk, anchor, TREE_PREFAB);
+            }
+        }
+    }
+}
+
+/// A small rounded `Voxel::STONE` blob, resting directly on the surface
+/// voxel rather than one above it (unlike [`TreeDecorator`], which needs
+/// clearance above the ground for its trunk).
+const BOULDER_PREFAB: &[(i32, i32, i32, u32)] = &[
+    (0, 0, 0, Voxel::STONE),
+    (1, 0, 0, Voxel::STONE),
+    (-1, 0, 0, Voxel::STONE),
+    (0, 0, 1, Voxel::STONE),
+    (0, 0, -1, Voxel::STONE),
+    (0, 1, 0, Voxel::STONE),
+];
+
+/// Places [`BOULDER_PREFAB`] on a fraction of surface columns, independent
+/// of [`TreeDecorator`] -- both can be run over the same chunk.
+pub struct BoulderDecorator {
+    /// Fraction of surface columns (`[0.0, 1.0]`) that get a boulder.
+    pub density: f32,
+}
+
+impl Decorator for BoulderDecorator {
+    fn decorate(&self, _chunk_pos: glam::IVec3, chunk: &mut Chunk, rng: &mut Xorshift32) {
+        let size = chunk.size();
+        for x in 0..size {
+            for z in 0..size {
+                if rng.next_f32() >= self.density {
+                    continue;
+                }
+                let Some(surface_y) = surface_height(chunk, x, z) else {
+                    continue;
+                };
+                let anchor = glam::IVec3::new(x as i32, surface_y as i32, z as i32);
+                stamp(chunk, anchor, BOULDER_PREFAB);
+            }
+        }
+    }
+}
+
+/// Runs every decorator in `decorators` against `chunk`, sharing one RNG
+/// stream seeded deterministically from `world_seed` and `chunk.position`
+/// via [`chunk_seed`].
+pub fn decorate_chunk(chunk: &mut Chunk, world_seed: u32, decorators: &[&dyn Decorator]) {
+    let mut rng = Xorshift32(chunk_seed(world_seed, chunk.position));
+    let chunk_pos = chunk.position;
+    for decorator in decorators {
+        decorator.decorate(chunk_pos, chunk, &mut rng);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ground_chunk() -> Chunk {
+        let params = crate::voxel::terrain::TerrainParams { heightmap_mode: true, ..Default::default() };
+        Chunk::from_terrain_at_depth(glam::IVec3::ZERO, params, 5)
+    }
+
+    #[test]
+    fn decoration_is_deterministic_for_the_same_seed_and_position() {
+        let decorators: Vec<&dyn Decorator> =
+            vec![&TreeDecorator { density: 0.2 }, &BoulderDecorator { density: 0.2 }];
+
+        let mut a = ground_chunk();
+        decorate_chunk(&mut a, 7, &decorators);
+
+        let mut b = ground_chunk();
+        decorate_chunk(&mut b, 7, &decorators);
+
+        assert_eq!(a.fingerprint(), b.fingerprint());
+    }
+
+    #[test]
+    fn a_different_world_seed_changes_the_decoration() {
+        let decorators: Vec<&dyn Decorator> = vec![&TreeDecorator { density: 0.3 }];
+
+        let mut a = ground_chunk();
+        decorate_chunk(&mut a, 7, &decorators);
+
+        let mut b = ground_chunk();
+        decorate_chunk(&mut b, 8, &decorators);
+
+        assert_ne!(a.fingerprint(), b.fingerprint());
+    }
+
+    #[test]
+    fn decorations_never_appear_in_a_column_with_no_solid_ground() {
+        // A fully empty chunk has no surface anywhere, so `surface_height`
+        // is `None` for every column -- decorators should leave it
+        // untouched rather than stamping into thin air.
+        let mut chunk = Chunk::with_depth(glam::IVec3::ZERO, 5);
+        let decorators: Vec<&dyn Decorator> = vec![&TreeDecorator { density: 1.0 }, &BoulderDecorator { density: 1.0 }];
+        decorate_chunk(&mut chunk, 42, &decorators);
+
+        assert_eq!(chunk.tree.iter_voxels().count(), 0, "expected no voxels in a groundless chunk");
+    }
+
+    #[test]
+    fn stamping_near_a_chunk_edge_clips_instead_of_panicking() {
+        let mut chunk = Chunk::with_depth(glam::IVec3::ZERO, 4);
+        let size = chunk.size();
+        chunk.tree.set(glam::UVec3::new(size - 1, 0, size - 1), Voxel::STONE);
+        stamp(&mut chunk, glam::IVec3::new(size as i32 - 1, 1, size as i32 - 1), TREE_PREFAB);
+        stamp(&mut chunk, glam::IVec3::new(0, 0, 0), BOULDER_PREFAB);
+    }
+}
@@ -0,0 +1,428 @@
+/// Material IDs this engine currently generates terrain with, as named
+/// constants instead of scattering `0`/`1`/`2` through terrain generation
+/// and tests. IDs index into a [`MaterialTable`], uploaded to the compute
+/// shader as `materials` alongside the octree nodes.
+pub struct Voxel;
+
+impl Voxel {
+    pub const AIR: u32 = 0;
+    pub const STONE: u32 = 1;
+    pub const WATER: u32 = 2;
+    pub const GLOWSTONE: u32 = 3;
+    pub const MIRROR: u32 = 4;
+    /// Desert biome surface material; see [`super::biome::DESERT`].
+    pub const SAND: u32 = 5;
+    /// Snow biome surface material; see [`super::biome::SNOW`].
+    pub const SNOW: u32 = 6;
+
+    /// Describes a transparent material tinted `(r, g, b)`. A terrain
+    /// generator installs the result into a [`MaterialTable`] slot
+    /// (typically [`Voxel::WATER`]) with [`MaterialTable::set`], then fills
+    /// voxels with that slot's ID; the compute shader's ray march
+    /// recognizes `MaterialProperties::transparent` and keeps marching
+    /// through it instead of terminating, attenuating by
+    /// [`MaterialProperties::DEFAULT_WATER_ABSORPTION`] per traveled unit.
+    pub fn water(r: f32, g: f32, b: f32) -> MaterialProperties {
+        MaterialProperties::transparent([r, g, b], MaterialProperties::DEFAULT_WATER_ABSORPTION)
+    }
+
+    /// Describes a material that glows tinted `(r, g, b)`. A terrain
+    /// generator installs the result into a [`MaterialTable`] slot
+    /// (typically [`Voxel::GLOWSTONE`]) with [`MaterialTable::set`]; the
+    /// compute shader adds the glow to a directly-hit voxel's own shading
+    /// and, via [`Chunk::collect_emitters`](super::chunk::Chunk::collect_emitters),
+    /// treats it as a point light for nearby surfaces.
+    pub fn glow(r: f32, g: f32, b: f32) -> MaterialProperties {
+        MaterialProperties::emitting([r, g, b], MaterialProperties::DEFAULT_GLOW_INTENSITY)
+    }
+
+    /// Describes a mirror-like material tinted `(r, g, b)`. A terrain
+    /// generator installs the result into a [`MaterialTable`] slot
+    /// (typically [`Voxel::MIRROR`]) with [`MaterialTable::set`]; the compute
+    /// shader's `shade_reflective` reflects the view ray about the hit
+    /// normal and re-marches it instead of shading the surface directly,
+    /// tinting the bounced result by `(r, g, b)`, up to `Uniforms::max_bounces`
+    /// bounces deep.
+    pub fn mirror(r: f32, g: f32, b: f32) -> MaterialProperties {
+        MaterialProperties::reflective([r, g, b], MaterialProperties::DEFAULT_MIRROR_REFLECTIVITY)
+    }
+}
+
+/// A material's shading/transparency properties, indexed by voxel material
+/// ID into the shader's `materials` storage buffer. `#[repr(C)]` matches
+/// `MaterialProperties` in `voxel_renderer.wgsl`; `color` doubles as the
+/// per-channel Beer-Lambert absorption tint when `transparent != 0`.
+/// `Serialize`/`Deserialize` so a [`MaterialTableBuilder`] can round-trip a
+/// named set of these through TOML.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, bytemuck::Pod, bytemuck::Zeroable, serde::Serialize, serde::Deserialize)]
+pub struct MaterialProperties {
+    pub color: [f32; 3],
+    pub transparent: u32,
+    pub absorption: f32,
+    /// Self-glow strength `shade` adds on top of a directly-hit voxel's lit
+    /// color, and the brightness [`super::chunk::Chunk::collect_emitters`]
+    /// scales `color` by for this material's point-light contribution.
+    /// `0.0` for anything that doesn't glow.
+    pub emissive: f32,
+    /// `0.0` for anything that doesn't reflect. See [`Voxel::mirror`] and
+    /// `voxel_renderer.wgsl`'s `shade_reflective`.
+    pub reflectivity: f32,
+    /// Padding only; never read. Skipped by serde so a TOML file doesn't
+    /// carry a meaningless `_pad = 0` line.
+    #[serde(skip)]
+    _pad: u32,
+}
+
+impl MaterialProperties {
+    /// Per-channel absorption strength [`Voxel::water`] uses when no other
+    /// value is given; tuned to visibly tint terrain a few voxels down
+    /// without going fully opaque within one chunk's height.
+    pub const DEFAULT_WATER_ABSORPTION: f32 = 0.3;
+
+    /// Emissive strength [`Voxel::glow`] uses when no other value is given;
+    /// tuned to read clearly as a light source without blowing out to pure
+    /// white at the source voxel itself.
+    pub const DEFAULT_GLOW_INTENSITY: f32 = 4.0;
+
+    /// Reflectivity [`Voxel::mirror`] uses when no other value is given;
+    /// short of `1.0` so a mirror surface still reads as tinted metal rather
+    /// than a perfect, colorless reflection.
+    pub const DEFAULT_MIRROR_REFLECTIVITY: f32 = 0.9;
+
+    pub fn opaque(color: [f32; 3]) -> Self {
+        Self {
+            color,
+            transparent: 0,
+            absorption: 0.0,
+            emissive: 0.0,
+            reflectivity: 0.0,
+            _pad: 0,
+        }
+    }
+
+    pub fn transparent(color: [f32; 3], absorption: f32) -> Self {
+        Self {
+            color,
+            transparent: 1,
+            absorption,
+            emissive: 0.0,
+            reflectivity: 0.0,
+            _pad: 0,
+        }
+    }
+
+    pub fn emitting(color: [f32; 3], intensity: f32) -> Self {
+        Self {
+            color,
+            transparent: 0,
+            absorption: 0.0,
+            emissive: intensity,
+            reflectivity: 0.0,
+            _pad: 0,
+        }
+    }
+
+    pub fn reflective(color: [f32; 3], reflectivity: f32) -> Self {
+        Self {
+            color,
+            transparent: 0,
+            absorption: 0.0,
+            emissive: 0.0,
+            reflectivity,
+            _pad: 0,
+        }
+    }
+}
+
+/// Number of material IDs [`MaterialTable`] holds properties for. This
+/// engine's own terrain generation only ever fills the first seven
+/// ([`Voxel::AIR`] through [`Voxel::SNOW`]); the rest of the range exists
+/// for a [`MaterialTableBuilder`] registering materials by name (a scene
+/// file, a future editor) without running into a hardcoded ceiling.
+pub const MATERIAL_COUNT: usize = 256;
+
+/// Small fixed-size palette of material properties, indexed by the same
+/// `u32` IDs `Tree`/`GpuNode` already use for a leaf's `material`. Uploaded
+/// to the shader as a single small storage buffer alongside the octree
+/// nodes, replacing the old hardcoded `material_color` switch with a real
+/// lookup that also carries transparency for [`Voxel::water`].
+#[derive(Debug, Clone, Copy)]
+pub struct MaterialTable([MaterialProperties; MATERIAL_COUNT]);
+
+impl Default for MaterialTable {
+    fn default() -> Self {
+        let mut table = Self([MaterialProperties::opaque([0.0, 0.0, 0.0]); MATERIAL_COUNT]);
+        // `Voxel::AIR`'s entry is never looked up (the shader only indexes
+        // `materials[]` for a nonzero hit material), but it's set anyway so
+        // every declared voxel ID has a defined properties entry.
+        table.set(Voxel::AIR, MaterialProperties::opaque([0.0, 0.0, 0.0]));
+        table.set(Voxel::STONE, MaterialProperties::opaque([0.35, 0.55, 0.25]));
+        table.set(Voxel::WATER, Voxel::water(0.2, 0.45, 0.65));
+        table.set(Voxel::GLOWSTONE, Voxel::glow(1.0, 0.85, 0.5));
+        table.set(Voxel::MIRROR, Voxel::mirror(0.9, 0.9, 0.95));
+        table.set(Voxel::SAND, MaterialProperties::opaque([0.85, 0.75, 0.45]));
+        table.set(Voxel::SNOW, MaterialProperties::opaque([0.9, 0.95, 0.98]));
+        table
+    }
+}
+
+impl MaterialTable {
+    pub fn set(&mut self, material: u32, properties: MaterialProperties) {
+        self.0[material as usize] = properties;
+    }
+
+    pub fn get(&self, material: u32) -> MaterialProperties {
+        self.0[material as usize]
+    }
+
+    /// Raw array in the shader's `materials[]` layout, for uploading to the
+    /// storage buffer `VoxelRendererPass` creates from it.
+    pub fn as_gpu_properties(&self) -> [MaterialProperties; MATERIAL_COUNT] {
+        self.0
+    }
+
+    /// Starts building a table by name instead of by raw ID; see
+    /// [`MaterialTableBuilder`]. [`MaterialTable::default`] is still what
+    /// terrain generation uses, since its IDs are the fixed `Voxel::*`
+    /// constants baked into `Chunk`'s test patterns and the collision/
+    /// animation code that already switches on them.
+    pub fn builder() -> MaterialTableBuilder {
+        MaterialTableBuilder::default()
+    }
+}
+
+/// Slot index into a [`MaterialTable`], returned by
+/// [`MaterialTableBuilder::register`] so a caller doesn't have to track raw
+/// IDs by hand. Interchangeable with the `u32` IDs [`Voxel`]'s constants and
+/// `Tree`/`GpuNode` leaves already use -- both index the same table the
+/// same way.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MaterialId(pub u32);
+
+/// A named entry in a [`MaterialTableBuilder`], and the on-disk shape a
+/// [`MaterialTableBuilder::to_toml_string`] file round-trips through
+/// [`MaterialTableBuilder::from_toml_str`].
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+struct NamedMaterial {
+    name: String,
+    #[serde(flatten)]
+    properties: MaterialProperties,
+}
+
+/// On-disk form of a [`MaterialTableBuilder`] -- just the ordered list of
+/// named materials; registration order is what fixes each one's
+/// [`MaterialId`] back on load.
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+struct MaterialTableFile {
+    materials: Vec<NamedMaterial>,
+}
+
+/// Reasons building or loading a [`MaterialTableBuilder`] can fail.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MaterialTableError {
+    /// `register` was called after [`MATERIAL_COUNT`] materials were
+    /// already registered.
+    Overflow { capacity: usize },
+    /// `register` (or a loaded file) named a material that was already
+    /// registered; names are how a scene file refers back to a material, so
+    /// a collision would make that reference ambiguous.
+    DuplicateName(String),
+    /// A TOML file passed to [`MaterialTableBuilder::from_toml_str`] didn't
+    /// parse, or didn't match the expected shape.
+    Parse(String),
+}
+
+impl std::fmt::Display for MaterialTableError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Overflow { capacity } => write!(f, "material table is full ({capacity} slots already registered)"),
+            Self::DuplicateName(name) => write!(f, "material {name:?} is already registered"),
+            Self::Parse(message) => write!(f, "{message}"),
+        }
+    }
+}
+
+impl std::error::Error for MaterialTableError {}
+
+/// Builds a [`MaterialTable`] by name instead of by raw ID, for a scene or
+/// editor that places materials without caring which numeric slot each one
+/// lands in. Registration order decides ID order, starting at `0`.
+#[derive(Debug, Default, Clone)]
+pub struct MaterialTableBuilder {
+    names: Vec<String>,
+    properties: Vec<MaterialProperties>,
+}
+
+impl MaterialTableBuilder {
+    /// Registers `properties` under `name`, returning the [`MaterialId`] it
+    /// was assigned.
+    pub fn register(&mut self, name: &str, properties: MaterialProperties) -> Result<MaterialId, MaterialTableError> {
+        if self.names.iter().any(|existing| existing == name) {
+            return Err(MaterialTableError::DuplicateName(name.to_string()));
+        }
+        if self.names.len() >= MATERIAL_COUNT {
+            return Err(MaterialTableError::Overflow { capacity: MATERIAL_COUNT });
+        }
+        let id = MaterialId(self.names.len() as u32);
+        self.names.push(name.to_string());
+        self.properties.push(properties);
+        Ok(id)
+    }
+
+    /// The [`MaterialId`] a previously `register`ed name was assigned, for
+    /// looking a material back up after a scene file references it by name.
+    pub fn id_of(&self, name: &str) -> Option<MaterialId> {
+        self.names.iter().position(|existing| existing == name).map(|index| MaterialId(index as u32))
+    }
+
+    /// Bakes the registered materials into a [`MaterialTable`]; unregistered
+    /// slots stay opaque black, same as [`MaterialTable::default`]'s
+    /// placeholder `AIR` entry.
+    pub fn build(&self) -> MaterialTable {
+        let mut table = MaterialTable([MaterialProperties::opaque([0.0, 0.0, 0.0]); MATERIAL_COUNT]);
+        for (index, properties) in self.properties.iter().enumerate() {
+            table.0[index] = *properties;
+        }
+        table
+    }
+
+    /// Serializes the registered materials, in registration order, as a
+    /// TOML document [`MaterialTableBuilder::from_toml_str`] can reload.
+    pub fn to_toml_string(&self) -> Result<String, MaterialTableError> {
+        let file = MaterialTableFile {
+            materials: self
+                .names
+                .iter()
+                .zip(&self.properties)
+                .map(|(name, properties)| NamedMaterial { name: name.clone(), properties: *properties })
+                .collect(),
+        };
+        toml::to_string_pretty(&file).map_err(|err| MaterialTableError::Parse(err.to_string()))
+    }
+
+    /// Rebuilds a builder from a document written by
+    /// [`MaterialTableBuilder::to_toml_string`], re-running each entry
+    /// through [`MaterialTableBuilder::register`] in file order so a
+    /// duplicated name is still rejected on load.
+    pub fn from_toml_str(raw: &str) -> Result<Self, MaterialTableError> {
+        let file: MaterialTableFile = toml::from_str(raw).map_err(|err| MaterialTableError::Parse(err.to_string()))?;
+        let mut builder = Self::default();
+        for material in file.materials {
+            builder.register(&material.name, material.properties)?;
+        }
+        Ok(builder)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A golden image showing terrain tinted under a water layer needs the
+    // same headless GPU readback harness the other golden-image asks in
+    // this crate don't have; this instead checks the Rust-side half of that
+    // plumbing -- that the table the shader reads from actually marks
+    // water transparent and stone opaque, and that its layout matches the
+    // WGSL struct the shader indexes into.
+    #[test]
+    fn material_properties_pod_layout_matches_wgsl_struct() {
+        assert_eq!(std::mem::size_of::<MaterialProperties>(), 32);
+    }
+
+    #[test]
+    fn default_table_marks_water_transparent_and_stone_opaque() {
+        let table = MaterialTable::default().as_gpu_properties();
+        assert_eq!(table[Voxel::STONE as usize].transparent, 0);
+        assert_eq!(table[Voxel::WATER as usize].transparent, 1);
+    }
+
+    #[test]
+    fn default_table_marks_glowstone_emissive_and_stone_dark() {
+        let table = MaterialTable::default();
+        assert_eq!(table.get(Voxel::STONE).emissive, 0.0);
+        assert!(table.get(Voxel::GLOWSTONE).emissive > 0.0);
+    }
+
+    #[test]
+    fn default_table_marks_mirror_reflective_and_stone_not() {
+        let table = MaterialTable::default();
+        assert_eq!(table.get(Voxel::STONE).reflectivity, 0.0);
+        assert!(table.get(Voxel::MIRROR).reflectivity > 0.0);
+    }
+
+    #[test]
+    fn builder_assigns_ids_in_registration_order() {
+        let mut builder = MaterialTable::builder();
+        let grass = builder.register("grass", MaterialProperties::opaque([0.2, 0.6, 0.2])).unwrap();
+        let sand = builder.register("sand", MaterialProperties::opaque([0.9, 0.85, 0.6])).unwrap();
+
+        assert_eq!(grass, MaterialId(0));
+        assert_eq!(sand, MaterialId(1));
+        assert_eq!(builder.id_of("grass"), Some(grass));
+        assert_eq!(builder.id_of("sand"), Some(sand));
+        assert_eq!(builder.id_of("basalt"), None);
+
+        let table = builder.build();
+        assert_eq!(table.get(grass.0).color, [0.2, 0.6, 0.2]);
+        assert_eq!(table.get(sand.0).color, [0.9, 0.85, 0.6]);
+    }
+
+    #[test]
+    fn builder_rejects_a_duplicate_name() {
+        let mut builder = MaterialTable::builder();
+        builder.register("grass", MaterialProperties::opaque([0.2, 0.6, 0.2])).unwrap();
+
+        let err = builder.register("grass", MaterialProperties::opaque([0.1, 0.1, 0.1])).unwrap_err();
+        assert_eq!(err, MaterialTableError::DuplicateName("grass".to_string()));
+    }
+
+    #[test]
+    fn builder_rejects_registration_past_material_count() {
+        let mut builder = MaterialTable::builder();
+        for i in 0..MATERIAL_COUNT {
+            builder.register(&format!("material-{i}"), MaterialProperties::opaque([0.0, 0.0, 0.0])).unwrap();
+        }
+
+        let err = builder.register("one-too-many", MaterialProperties::opaque([0.0, 0.0, 0.0])).unwrap_err();
+        assert_eq!(err, MaterialTableError::Overflow { capacity: MATERIAL_COUNT });
+    }
+
+    #[test]
+    fn builder_round_trips_through_toml() {
+        let mut builder = MaterialTable::builder();
+        builder.register("grass", MaterialProperties::opaque([0.2, 0.6, 0.2])).unwrap();
+        builder.register("glowstone", Voxel::glow(1.0, 0.85, 0.5)).unwrap();
+
+        let toml = builder.to_toml_string().unwrap();
+        let reloaded = MaterialTableBuilder::from_toml_str(&toml).unwrap();
+
+        assert_eq!(reloaded.id_of("grass"), Some(MaterialId(0)));
+        assert_eq!(reloaded.id_of("glowstone"), Some(MaterialId(1)));
+        assert_eq!(reloaded.build().as_gpu_properties(), builder.build().as_gpu_properties());
+    }
+
+    #[test]
+    fn from_toml_str_rejects_a_duplicate_name_in_the_file() {
+        let raw = r#"
+            [[materials]]
+            name = "grass"
+            color = [0.2, 0.6, 0.2]
+            transparent = 0
+            absorption = 0.0
+            emissive = 0.0
+            reflectivity = 0.0
+
+            [[materials]]
+            name = "grass"
+            color = [0.1, 0.1, 0.1]
+            transparent = 0
+            absorption = 0.0
+            emissive = 0.0
+            reflectivity = 0.0
+        "#;
+
+        let err = MaterialTableBuilder::from_toml_str(raw).unwrap_err();
+        assert_eq!(err, MaterialTableError::DuplicateName("grass".to_string()));
+    }
+}
@@ -0,0 +1,42 @@
+pub(crate) mod accel;
+pub(crate) mod animation;
+pub mod biome;
+pub(crate) mod brickmap;
+pub mod camera;
+pub mod chunk;
+pub(crate) mod chunk_cache;
+pub mod chunk_delta;
+pub(crate) mod chunk_neighbors;
+pub(crate) mod chunk_priority;
+pub(crate) mod collision;
+pub(crate) mod coords;
+pub mod decorate;
+pub mod material;
+pub mod morton;
+pub mod orientation;
+#[cfg(feature = "gpu")]
+pub mod passes;
+#[cfg(feature = "gpu")]
+pub mod render_texture;
+pub mod sky;
+pub mod software;
+pub mod terrain;
+pub mod test_util;
+pub mod tree;
+
+pub use biome::{biome, Biome, BiomeId, BiomeMap, BiomeParams};
+pub use camera::Camera;
+pub use chunk::Chunk;
+pub use chunk_delta::{ChunkDelta, ChunkSnapshot, DeltaError};
+pub use decorate::{decorate_chunk, BoulderDecorator, Decorator, TreeDecorator};
+pub use orientation::Axis90;
+#[cfg(feature = "gpu")]
+pub use passes::{
+    fit_scale, gamma_for, AoSettings, BlitFilter, CullFrameParams, CullPass, CullStats, DebugView, FitMode, FrameParams,
+    FxaaPass, GizmoPass, Light, PassCreationError, PickResult, PickTicket, TonemapOperator, VoxelImageRenderingPass,
+    VoxelRendererPass, WorkgroupSize, MAX_LIGHTS,
+};
+#[cfg(feature = "gpu")]
+pub use render_texture::{GBufferPixel, RenderTexture};
+pub use sky::SkySettings;
+pub use terrain::TerrainParams;
@@ -0,0 +1,84 @@
+//! Morton (Z-order) encoding for 3D integer coordinates.
+//!
+//! This crate's voxel storage (`Tree`) is a sparse octree, not the dense
+//! `x + y*N + z*N²` array the Morton-layout request assumed -- there's no
+//! `index_of`/`get_raw_voxels` to switch between a linear and a Morton
+//! indexing scheme, and no second layout to benchmark against. What *does*
+//! carry over honestly is the encoding itself: these are free-standing bit
+//! utilities for whatever eventually wants spatial locality over a flat
+//! index (a brickmap, a dense LOD mip, readback buffer packing), kept
+//! separate from `Tree` until something actually calls them -- `benches/voxel_benchmarks.rs`
+//! benchmarks the encoding itself, still with nothing to compare it against.
+//!
+//! Each axis must fit in 11 bits (`0..2048`); bits above that are dropped,
+//! since three 11-bit interleaved fields exactly fill a `u32`.
+
+/// Interleaves `x`, `y`, `z` into a single Morton (Z-order) code: bit `i` of
+/// each coordinate lands at bit `3*i` (x), `3*i+1` (y), `3*i+2` (z).
+///
+/// Benchmarked by `benches/voxel_benchmarks.rs` on its own (there's still no
+/// second flat-index scheme in this crate to compare it against -- see the
+/// module docs), so callers outside this crate need it `pub`.
+pub fn morton3_encode(x: u32, y: u32, z: u32) -> u32 {
+    spread_bits(x) | (spread_bits(y) << 1) | (spread_bits(z) << 2)
+}
+
+/// Inverse of [`morton3_encode`]: splits a Morton code back into its `(x,
+/// y, z)` coordinates.
+pub fn morton3_decode(code: u32) -> (u32, u32, u32) {
+    (compact_bits(code), compact_bits(code >> 1), compact_bits(code >> 2))
+}
+
+/// Spreads the low 11 bits of `v` out so each one occupies every third bit
+/// (0, 3, 6, ...), leaving the other two thirds zero.
+fn spread_bits(v: u32) -> u32 {
+    let v = v & 0x7FF;
+    let v = (v | (v << 16)) & 0x0300_00FF;
+    let v = (v | (v << 8)) & 0x0300_F00F;
+    let v = (v | (v << 4)) & 0x030C_30C3;
+    (v | (v << 2)) & 0x0924_9249
+}
+
+/// Inverse of `spread_bits`: gathers every third bit back into the low 11
+/// bits.
+fn compact_bits(v: u32) -> u32 {
+    let v = v & 0x0924_9249;
+    let v = (v | (v >> 2)) & 0x030C_30C3;
+    let v = (v | (v >> 4)) & 0x0300_F00F;
+    let v = (v | (v >> 8)) & 0x0300_00FF;
+    (v | (v >> 16)) & 0x7FF
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn origin_encodes_to_zero() {
+        assert_eq!(morton3_encode(0, 0, 0), 0);
+    }
+
+    #[test]
+    fn single_axis_steps_interleave_into_the_right_bit() {
+        assert_eq!(morton3_encode(1, 0, 0), 0b001);
+        assert_eq!(morton3_encode(0, 1, 0), 0b010);
+        assert_eq!(morton3_encode(0, 0, 1), 0b100);
+    }
+
+    #[test]
+    fn round_trips_up_to_64_cubed() {
+        for x in 0..64u32 {
+            for y in 0..64u32 {
+                for z in 0..64u32 {
+                    let code = morton3_encode(x, y, z);
+                    assert_eq!(morton3_decode(code), (x, y, z), "failed round trip for ({x}, {y}, {z})");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn bits_above_eleven_are_dropped_rather_than_panicking() {
+        assert_eq!(morton3_encode(1 << 11, 0, 0), morton3_encode(0, 0, 0));
+    }
+}
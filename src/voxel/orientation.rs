@@ -0,0 +1,193 @@
+//! The 24 axis-aligned (90°-step) rotations of a cube, a.k.a. the chiral
+//! octahedral symmetry group.
+//!
+//! This was requested as part of a per-chunk instancing layer --
+//! `Instance { chunk_ref, translation, rotation }`, a GPU chunk table with a
+//! translation/orientation per entry, and a `ChunkManager::place_instance`
+//! that reuses one uploaded chunk's buffers for many placements. None of
+//! that exists yet: there's no `ChunkManager` (see the doc comments on
+//! [`super::chunk_cache`] and [`super::chunk_neighbors`], which are staged
+//! for the same not-yet-written manager), no imported-model pipeline, and
+//! `Renderer`/`voxel_renderer.wgsl` carry exactly one [`super::Chunk`] at a
+//! time -- there's no per-entry chunk table for a ray transform to index
+//! into, and no second placement of a model to point CPU picking/collision
+//! at. Building the GPU table and the WGSL-side ray transform now would mean
+//! inventing the instancing architecture itself, not implementing a request
+//! against it.
+//!
+//! What does carry over honestly, and is exactly what the request asks
+//! tests for, is the rotation math: a compact, round-trip-safe, composable
+//! representation of the 24 orientations that preserve voxel-grid alignment,
+//! ready for `Instance::rotation` the day a `ChunkManager` exists to hold
+//! one.
+
+/// One of the 24 orientations a cube can be rotated into using only 90°
+/// steps -- the only rotations that keep a voxel grid aligned to itself.
+/// Represented as an index into [`MATRICES`] rather than, say, three stacked
+/// 90° turns, so two orientations always compose to a *third* member of the
+/// same 24 (see [`Axis90::compose`]) instead of an ever-growing turn
+/// sequence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Axis90(u8);
+
+/// The 24 proper (determinant +1) rotation matrices of a cube, i.e. every
+/// signed permutation of the identity's rows. Index 0 is the identity;
+/// `Axis90`'s other methods are agnostic to the order of the remaining 23.
+#[rustfmt::skip]
+const MATRICES: [[[i32; 3]; 3]; 24] = [
+    [[1, 0, 0], [0, 1, 0], [0, 0, 1]],
+    [[-1, 0, 0], [0, -1, 0], [0, 0, 1]],
+    [[-1, 0, 0], [0, 0, -1], [0, -1, 0]],
+    [[-1, 0, 0], [0, 0, 1], [0, 1, 0]],
+    [[-1, 0, 0], [0, 1, 0], [0, 0, -1]],
+    [[0, -1, 0], [-1, 0, 0], [0, 0, -1]],
+    [[0, -1, 0], [0, 0, -1], [1, 0, 0]],
+    [[0, -1, 0], [0, 0, 1], [-1, 0, 0]],
+    [[0, -1, 0], [1, 0, 0], [0, 0, 1]],
+    [[0, 0, -1], [-1, 0, 0], [0, 1, 0]],
+    [[0, 0, -1], [0, -1, 0], [-1, 0, 0]],
+    [[0, 0, -1], [0, 1, 0], [1, 0, 0]],
+    [[0, 0, -1], [1, 0, 0], [0, -1, 0]],
+    [[0, 0, 1], [-1, 0, 0], [0, -1, 0]],
+    [[0, 0, 1], [0, -1, 0], [1, 0, 0]],
+    [[0, 0, 1], [0, 1, 0], [-1, 0, 0]],
+    [[0, 0, 1], [1, 0, 0], [0, 1, 0]],
+    [[0, 1, 0], [-1, 0, 0], [0, 0, 1]],
+    [[0, 1, 0], [0, 0, -1], [-1, 0, 0]],
+    [[0, 1, 0], [0, 0, 1], [1, 0, 0]],
+    [[0, 1, 0], [1, 0, 0], [0, 0, -1]],
+    [[1, 0, 0], [0, -1, 0], [0, 0, -1]],
+    [[1, 0, 0], [0, 0, -1], [0, 1, 0]],
+    [[1, 0, 0], [0, 0, 1], [0, -1, 0]],
+];
+
+impl Axis90 {
+    pub const IDENTITY: Self = Self(0);
+
+    /// All 24 orientations, in [`MATRICES`] order.
+    pub const ALL: [Self; 24] = [
+        Self(0), Self(1), Self(2), Self(3), Self(4), Self(5), Self(6), Self(7), Self(8), Self(9), Self(10), Self(11),
+        Self(12), Self(13), Self(14), Self(15), Self(16), Self(17), Self(18), Self(19), Self(20), Self(21), Self(22), Self(23),
+    ];
+
+    fn matrix(self) -> [[i32; 3]; 3] {
+        MATRICES[self.0 as usize]
+    }
+
+    fn from_matrix(matrix: [[i32; 3]; 3]) -> Self {
+        let index = MATRICES
+            .iter()
+            .position(|candidate| *candidate == matrix)
+            .expect("product of two rotation matrices in MATRICES must itself be one of the 24");
+        Self(index as u8)
+    }
+
+    /// Rotates an integer voxel offset about the origin.
+    pub fn rotate(self, v: glam::IVec3) -> glam::IVec3 {
+        let m = self.matrix();
+        glam::IVec3::new(
+            m[0][0] * v.x + m[0][1] * v.y + m[0][2] * v.z,
+            m[1][0] * v.x + m[1][1] * v.y + m[1][2] * v.z,
+            m[2][0] * v.x + m[2][1] * v.y + m[2][2] * v.z,
+        )
+    }
+
+    /// Composes two orientations: `a.compose(b).rotate(v) == a.rotate(b.rotate(v))`.
+    pub fn compose(self, other: Self) -> Self {
+        let a = self.matrix();
+        let b = other.matrix();
+        let mut product = [[0i32; 3]; 3];
+        for row in 0..3 {
+            for col in 0..3 {
+                product[row][col] = (0..3).map(|k| a[row][k] * b[k][col]).sum();
+            }
+        }
+        Self::from_matrix(product)
+    }
+
+    /// The orientation that undoes this one: `self.compose(self.inverse())
+    /// == Axis90::IDENTITY`. Rotation matrices are orthogonal, so this is
+    /// just the transpose.
+    pub fn inverse(self) -> Self {
+        let m = self.matrix();
+        let mut transposed = [[0i32; 3]; 3];
+        for row in 0..3 {
+            for col in 0..3 {
+                transposed[row][col] = m[col][row];
+            }
+        }
+        Self::from_matrix(transposed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn identity_leaves_a_point_unchanged() {
+        assert_eq!(Axis90::IDENTITY.rotate(glam::IVec3::new(3, -5, 7)), glam::IVec3::new(3, -5, 7));
+    }
+
+    #[test]
+    fn all_24_orientations_are_distinct() {
+        let mut seen = std::collections::HashSet::new();
+        for axis in Axis90::ALL {
+            assert!(seen.insert(axis), "duplicate orientation at index {}", axis.0);
+        }
+        assert_eq!(seen.len(), 24);
+    }
+
+    #[test]
+    fn every_orientation_preserves_length_and_grid_alignment() {
+        let v = glam::IVec3::new(1, 2, 4);
+        for axis in Axis90::ALL {
+            let rotated = axis.rotate(v);
+            assert_eq!(rotated.x.unsigned_abs() + rotated.y.unsigned_abs() + rotated.z.unsigned_abs(), 7);
+            let magnitudes = [rotated.x.unsigned_abs(), rotated.y.unsigned_abs(), rotated.z.unsigned_abs()];
+            let mut sorted = magnitudes;
+            sorted.sort_unstable();
+            assert_eq!(sorted, [1, 2, 4], "rotation must permute axis magnitudes, not scale them");
+        }
+    }
+
+    #[test]
+    fn composing_with_the_inverse_recovers_the_identity() {
+        for axis in Axis90::ALL {
+            assert_eq!(axis.compose(axis.inverse()), Axis90::IDENTITY);
+            assert_eq!(axis.inverse().compose(axis), Axis90::IDENTITY);
+        }
+    }
+
+    #[test]
+    fn compose_matches_applying_each_rotation_in_turn() {
+        let v = glam::IVec3::new(2, -3, 5);
+        for a in Axis90::ALL {
+            for b in Axis90::ALL {
+                assert_eq!(a.compose(b).rotate(v), a.rotate(b.rotate(v)));
+            }
+        }
+    }
+
+    #[test]
+    fn composition_is_associative() {
+        let a = Axis90::ALL[5];
+        let b = Axis90::ALL[11];
+        let c = Axis90::ALL[19];
+        assert_eq!(a.compose(b).compose(c), a.compose(b.compose(c)));
+    }
+
+    #[test]
+    fn a_quarter_turn_about_y_cycles_x_and_z_and_returns_after_four_steps() {
+        let quarter_turn_about_y = Axis90::ALL
+            .into_iter()
+            .find(|axis| axis.rotate(glam::IVec3::X) == glam::IVec3::Z && axis.rotate(glam::IVec3::Y) == glam::IVec3::Y)
+            .expect("one of the 24 orientations must be a quarter turn about Y");
+
+        let mut turn = Axis90::IDENTITY;
+        for _ in 0..4 {
+            turn = turn.compose(quarter_turn_about_y);
+        }
+        assert_eq!(turn, Axis90::IDENTITY);
+    }
+}
@@ -0,0 +1,113 @@
+//! Beam optimization: a coarse pre-pass estimate of how far a primary ray
+//! can travel before it might hit anything, computed against a
+//! [`super::super::tree::Tree::lod`]-coarsened octree instead of the full
+//! one, so a full-resolution pass can start marching from that distance
+//! instead of from the camera.
+//!
+//! Not wired into `VoxelRendererPass` yet. Doing that for real needs a
+//! second compute pipeline (one ray per tile against the LOD tree, written
+//! to a small storage texture the full-resolution `render_pixel` entry
+//! point samples before starting its march), the bind groups and encoder
+//! ordering to chain the two passes, and an on/off toggle in the benchmark
+//! sweep to measure the win -- and the request's own correctness bar (“the
+//! golden images must remain identical”) needs the headless pixel-readback
+//! golden-image harness this crate doesn't have (see `Renderer::new_headless`'s
+//! doc comment); changing `voxel_renderer.wgsl`'s only compute pipeline
+//! without a way to catch a regression there is exactly the kind of change
+//! this crate's existing gaps (`chunk_cache`, `brickmap`, `animation`) all
+//! hold off on for the same reason. What's here is the conservative-distance
+//! estimate itself: a well-defined, testable unit a real pre-pass would call
+//! once per tile.
+#![allow(dead_code)]
+
+use super::super::material::Voxel;
+use super::super::tree::Tree;
+
+/// Estimates how far along `dir` from `origin` a ray could travel before
+/// possibly entering solid geometry, by stepping through `lod_tree` in
+/// `cell_size`-sized increments (the coarse cell size `lod_tree` was built
+/// with, i.e. `1 << (full_depth - target_depth)` passed to
+/// [`Tree::lod`](super::super::tree::Tree::lod)) instead of one voxel at a
+/// time. Conservative: since the true surface inside a solid coarse cell
+/// could be as close as that cell's near face, the first solid sample found
+/// backs the estimate off by one full `cell_size` rather than reporting the
+/// sample's own distance, so this never returns a distance past where real
+/// geometry starts. Returns `max_dist` if nothing solid is found in range,
+/// meaning a full-resolution pass can skip straight to the end of the beam.
+pub(crate) fn beam_min_distance(lod_tree: &Tree, cell_size: u32, origin: glam::Vec3, dir: glam::Vec3, max_dist: f32) -> f32 {
+    let dir = dir.normalize_or_zero();
+    if dir == glam::Vec3::ZERO {
+        return 0.0;
+    }
+    let step = cell_size.max(1) as f32;
+    let size = lod_tree.size() as i32;
+    let steps = (max_dist / step).ceil() as u32;
+
+    for i in 0..=steps {
+        let distance = (i as f32 * step).min(max_dist);
+        let voxel = (origin + dir * distance).floor().as_ivec3();
+        let in_bounds = !voxel.cmplt(glam::IVec3::ZERO).any() && !voxel.cmpge(glam::IVec3::splat(size)).any();
+        if in_bounds && lod_tree.get(voxel.as_uvec3()) != Voxel::AIR {
+            return (distance - step).max(0.0);
+        }
+        if distance >= max_dist {
+            break;
+        }
+    }
+    max_dist
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::voxel::chunk::Chunk;
+
+    /// One voxel at a time along `dir`, for comparing against the coarse
+    /// beam estimate -- what a full-resolution march would actually find.
+    fn brute_force_first_hit(tree: &Tree, origin: glam::Vec3, dir: glam::Vec3, max_dist: f32) -> f32 {
+        let dir = dir.normalize_or_zero();
+        let size = tree.size() as i32;
+        let mut distance = 0.0f32;
+        while distance < max_dist {
+            let voxel = (origin + dir * distance).floor().as_ivec3();
+            let in_bounds = !voxel.cmplt(glam::IVec3::ZERO).any() && !voxel.cmpge(glam::IVec3::splat(size)).any();
+            if in_bounds && tree.get(voxel.as_uvec3()) != Voxel::AIR {
+                return distance;
+            }
+            distance += 0.1;
+        }
+        max_dist
+    }
+
+    #[test]
+    fn empty_scene_returns_max_dist() {
+        let chunk = Chunk::empty(glam::IVec3::ZERO);
+        let lod = chunk.tree.lod(2);
+        let distance = beam_min_distance(&lod, 8, glam::Vec3::new(16.0, 16.0, -10.0), glam::Vec3::Z, 100.0);
+        assert_eq!(distance, 100.0);
+    }
+
+    #[test]
+    fn estimate_never_overshoots_the_true_first_hit() {
+        let chunk = Chunk::filled_test_pattern_with_water(glam::IVec3::ZERO, 0);
+        let lod = chunk.tree.lod(2);
+        let origin = glam::Vec3::new(16.0, 100.0, 16.0);
+        let dir = glam::Vec3::new(0.0, -1.0, 0.0);
+
+        let beam = beam_min_distance(&lod, 8, origin, dir, 200.0);
+        let truth = brute_force_first_hit(&chunk.tree, origin, dir, 200.0);
+        assert!(beam <= truth, "beam estimate {beam} overshot the true hit at {truth}");
+    }
+
+    #[test]
+    fn estimate_is_a_meaningful_shortcut_over_open_space() {
+        let chunk = Chunk::filled_test_pattern_with_water(glam::IVec3::ZERO, 0);
+        let lod = chunk.tree.lod(2);
+        // Looking straight down from well above the floor: most of the ray
+        // crosses open air the beam pass should let a full-res pass skip.
+        let origin = glam::Vec3::new(16.0, 100.0, 16.0);
+        let dir = glam::Vec3::new(0.0, -1.0, 0.0);
+        let beam = beam_min_distance(&lod, 8, origin, dir, 200.0);
+        assert!(beam > 50.0, "beam estimate {beam} should skip most of the empty air above the floor");
+    }
+}
@@ -0,0 +1,379 @@
+use std::num::NonZeroU64;
+
+use bytemuck::Zeroable;
+
+use crate::engine::{PipelineCache, UploadContext};
+
+use super::voxel_renderer::{tile_grid, Tile, WorkgroupSize, MAX_TILES};
+use super::PassCreationError;
+
+/// Per-frame data [`CullPass::cull`] needs to test each candidate tile
+/// against the loaded chunk's bounds; the cull-relevant subset of what
+/// `VoxelRendererPass::update_uniforms` takes as `FrameParams`. Layout must
+/// match `CullUniforms` in `shaders/cull.wgsl` exactly.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+struct CullUniforms {
+    inv_view_proj: [[f32; 4]; 4],
+    camera_pos: [f32; 3],
+    chunk_size: f32,
+    texture_width: u32,
+    texture_height: u32,
+    tile_count: u32,
+    _pad: u32,
+}
+
+/// Everything [`CullPass::cull`] needs for one frame.
+pub struct CullFrameParams {
+    pub inv_view_proj: glam::Mat4,
+    pub camera_pos: glam::Vec3,
+    pub chunk_size: f32,
+    pub texture_width: u32,
+    pub texture_height: u32,
+}
+
+/// Visible/total tile counts from the most recently resolved `cull` call,
+/// read back via [`CullPass::poll_stats`]; fed into `FrameStats` so a camera
+/// staring at empty sky can be seen dispatching far fewer tiles than one
+/// staring at the chunk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CullStats {
+    pub visible_tile_count: u32,
+    pub total_tile_count: u32,
+}
+
+/// GPU pre-pass that classifies the current frame's `tile_grid` tiles by
+/// whether a ray through any of their four corners can hit the loaded
+/// chunk's bounds (see `shaders/cull.wgsl`), compacting the survivors into
+/// `VoxelRendererPass`'s `visible_tiles_buffer` and an indirect-dispatch
+/// args buffer `VoxelRendererPass::compute_with_indirect_pass` consumes in
+/// place of its usual fixed `dispatch_workgroups` call. Single-chunk scoped
+/// like the rest of this renderer -- there's no multi-chunk "chunk table"
+/// here, just the one loaded [`crate::voxel::Chunk`]'s axis-aligned bounds.
+pub struct CullPass {
+    pipeline: std::sync::Arc<wgpu::ComputePipeline>,
+    bind_group: wgpu::BindGroup,
+    uniform_buffer: wgpu::Buffer,
+    candidate_tile_buffer: wgpu::Buffer,
+    /// `[workgroups_per_tile_x, workgroups_per_tile_y, visible_tile_count]`,
+    /// matching `wgpu::util::DispatchIndirectArgs`'s layout; `cull` resets
+    /// the first two every frame (they depend on the current frame's
+    /// largest tile) and zeroes the third for the shader's `atomicAdd` to
+    /// build back up. Also the buffer `VoxelRendererPass::compute_with_indirect_pass`
+    /// drives `dispatch_workgroups_indirect` from.
+    indirect_buffer: wgpu::Buffer,
+    readback_buffers: [wgpu::Buffer; 2],
+    /// Which `readback_buffers` slot `cull` copies into this frame; mirrors
+    /// `GpuTimer`'s `write_slot` exactly, including the flip-on-read in
+    /// `poll_stats`.
+    write_slot: usize,
+    /// Tile count `cull` dispatched against, indexed the same way as
+    /// `readback_buffers` so `poll_stats` pairs the visible count it reads
+    /// back with the total count from that same (one-frame-old) dispatch.
+    pending_total_tile_count: [u32; 2],
+    stats: CullStats,
+    /// `CullUniforms` written by the last `cull` call, so a frame where the
+    /// camera and resolution haven't moved can skip that one `write_buffer`;
+    /// see the matching `VoxelRendererPass::last_uniforms`. `candidate_tile_buffer`
+    /// and `indirect_buffer` still get rewritten every frame regardless --
+    /// `indirect_buffer`'s atomic counter needs zeroing for the shader to
+    /// build it back up, so there's nothing to skip there.
+    last_uniforms: Option<CullUniforms>,
+}
+
+impl CullPass {
+    /// `visible_tiles_buffer` is `VoxelRendererPass::visible_tiles_buffer` --
+    /// owned there (it's part of that pass's own bind group), referenced
+    /// here only to build this pass's bind group around the same buffer.
+    pub fn new(device: &wgpu::Device, visible_tiles_buffer: &wgpu::Buffer, cache: &mut PipelineCache) -> Result<Self, PassCreationError> {
+        let uniform_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("cull_uniforms"),
+            size: std::mem::size_of::<CullUniforms>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let candidate_tile_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("cull_candidate_tile_buffer"),
+            size: (MAX_TILES * std::mem::size_of::<Tile>()) as u64,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let indirect_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("cull_indirect_buffer"),
+            size: std::mem::size_of::<[u32; 3]>() as u64,
+            usage: wgpu::BufferUsages::STORAGE
+                | wgpu::BufferUsages::INDIRECT
+                | wgpu::BufferUsages::COPY_DST
+                | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+
+        let readback_buffers = std::array::from_fn(|_| {
+            device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some("cull_stats_readback_buffer"),
+                size: std::mem::size_of::<[u32; 3]>() as u64,
+                usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+                mapped_at_creation: false,
+            })
+        });
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("cull_bind_group_layout"),
+            entries: &Self::bind_group_layout_entries(),
+        });
+        let bind_group = Self::create_bind_group(
+            device,
+            &bind_group_layout,
+            &uniform_buffer,
+            &candidate_tile_buffer,
+            visible_tiles_buffer,
+            &indirect_buffer,
+        );
+
+        let source = include_str!("../../shaders/cull.wgsl");
+        device.push_error_scope(wgpu::ErrorFilter::Validation);
+        let shader = cache.shader_module("cull_shader".to_string(), || {
+            device.create_shader_module(wgpu::ShaderModuleDescriptor {
+                label: Some("cull_shader"),
+                source: wgpu::ShaderSource::Wgsl(source.into()),
+            })
+        });
+        if let Some(error) = pollster::block_on(device.pop_error_scope()) {
+            return Err(PassCreationError::shader_compile("CullPass", "cull.wgsl", error));
+        }
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("cull_pipeline_layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = cache.compute_pipeline("cull_pipeline".to_string(), || {
+            device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+                label: Some("cull_pipeline"),
+                layout: Some(&pipeline_layout),
+                module: &shader,
+                entry_point: "main",
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            })
+        });
+
+        Ok(Self {
+            pipeline,
+            bind_group,
+            uniform_buffer,
+            candidate_tile_buffer,
+            indirect_buffer,
+            readback_buffers,
+            write_slot: 0,
+            pending_total_tile_count: [0; 2],
+            stats: CullStats::default(),
+            last_uniforms: None,
+        })
+    }
+
+    /// Layout for `bind_group_layout`, pulled out of `new` so
+    /// `tests/shader_validation.rs` can cross-check it against `cull.wgsl`'s
+    /// actual bindings without needing a `wgpu::Device`.
+    pub fn bind_group_layout_entries() -> [wgpu::BindGroupLayoutEntry; 4] {
+        [
+            wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::COMPUTE,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: NonZeroU64::new(std::mem::size_of::<CullUniforms>() as u64),
+                },
+                count: None,
+            },
+            wgpu::BindGroupLayoutEntry {
+                binding: 1,
+                visibility: wgpu::ShaderStages::COMPUTE,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Storage { read_only: true },
+                    has_dynamic_offset: false,
+                    min_binding_size: NonZeroU64::new(std::mem::size_of::<Tile>() as u64),
+                },
+                count: None,
+            },
+            wgpu::BindGroupLayoutEntry {
+                binding: 2,
+                visibility: wgpu::ShaderStages::COMPUTE,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Storage { read_only: false },
+                    has_dynamic_offset: false,
+                    min_binding_size: NonZeroU64::new(std::mem::size_of::<[u32; 2]>() as u64),
+                },
+                count: None,
+            },
+            wgpu::BindGroupLayoutEntry {
+                binding: 3,
+                visibility: wgpu::ShaderStages::COMPUTE,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Storage { read_only: false },
+                    has_dynamic_offset: false,
+                    min_binding_size: NonZeroU64::new(std::mem::size_of::<[u32; 3]>() as u64),
+                },
+                count: None,
+            },
+        ]
+    }
+
+    fn create_bind_group(
+        device: &wgpu::Device,
+        layout: &wgpu::BindGroupLayout,
+        uniform_buffer: &wgpu::Buffer,
+        candidate_tile_buffer: &wgpu::Buffer,
+        visible_tiles_buffer: &wgpu::Buffer,
+        indirect_buffer: &wgpu::Buffer,
+    ) -> wgpu::BindGroup {
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("cull_bind_group"),
+            layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: uniform_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: candidate_tile_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: visible_tiles_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 3,
+                    resource: indirect_buffer.as_entire_binding(),
+                },
+            ],
+        })
+    }
+
+    /// Recomputes `tile_grid(frame.texture_width, frame.texture_height,
+    /// tile_size)` and writes this frame's candidate tiles, uniforms, and
+    /// indirect-args reset into `encoder`, ready for `cull_dispatch` to
+    /// consume. Split out of what used to be a single `cull` method so
+    /// `Renderer::render` can record these writes into a dedicated upload
+    /// encoder submitted ahead of the one `cull_dispatch` records into --
+    /// see `Renderer::render`'s `upload_encoder`. Uses exactly the same tile
+    /// grid `VoxelRendererPass::compute_with_pass` would have tiled the
+    /// non-culled dispatch into, so the indirect path covers the same
+    /// ground. `workgroup_size` must match the `VoxelRendererPass` the
+    /// indirect dispatch will drive, since `workgroups_per_tile` is sized
+    /// against it. Returns the number of bytes written, for
+    /// `Renderer::upload_stats`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn cull_upload(
+        &mut self,
+        encoder: &mut wgpu::CommandEncoder,
+        upload: &mut UploadContext,
+        device: &wgpu::Device,
+        frame: CullFrameParams,
+        tile_size: u32,
+        workgroup_size: WorkgroupSize,
+    ) -> u64 {
+        let tiles = tile_grid(frame.texture_width, frame.texture_height, tile_size);
+        // Sized against the largest tile this frame actually produced
+        // (`tile_grid` grows the last row/column past `tile_size` when the
+        // image doesn't divide evenly), not the nominal `tile_size` --
+        // otherwise a grown edge tile would be under-dispatched and miss
+        // pixels. Smaller tiles just get some invocations that return early
+        // via `render_pixel`'s own bounds check.
+        let max_tile = tiles.iter().fold([1u32; 2], |acc, t| [acc[0].max(t.size[0]), acc[1].max(t.size[1])]);
+        let workgroups_per_tile = [
+            max_tile[0].div_ceil(workgroup_size.x.max(1)),
+            max_tile[1].div_ceil(workgroup_size.y.max(1)),
+        ];
+
+        let mut padded = [Tile::zeroed(); MAX_TILES];
+        padded[..tiles.len()].copy_from_slice(&tiles);
+        let candidate_tiles = bytemuck::cast_slice(&padded);
+        upload.write_buffer(device, encoder, &self.candidate_tile_buffer, 0, candidate_tiles);
+        let mut bytes_written = candidate_tiles.len() as u64;
+
+        let uniforms = CullUniforms {
+            inv_view_proj: frame.inv_view_proj.to_cols_array_2d(),
+            camera_pos: frame.camera_pos.into(),
+            chunk_size: frame.chunk_size,
+            texture_width: frame.texture_width,
+            texture_height: frame.texture_height,
+            tile_count: tiles.len() as u32,
+            _pad: 0,
+        };
+        if self.last_uniforms != Some(uniforms) {
+            let uniform_bytes = bytemuck::bytes_of(&uniforms);
+            upload.write_buffer(device, encoder, &self.uniform_buffer, 0, uniform_bytes);
+            self.last_uniforms = Some(uniforms);
+            bytes_written += uniform_bytes.len() as u64;
+        }
+        let indirect_reset = [workgroups_per_tile[0], workgroups_per_tile[1], 0u32];
+        let indirect_reset: &[u8] = bytemuck::cast_slice(&indirect_reset);
+        upload.write_buffer(device, encoder, &self.indirect_buffer, 0, indirect_reset);
+        bytes_written += indirect_reset.len() as u64;
+
+        self.pending_total_tile_count[self.write_slot] = tiles.len() as u32;
+        bytes_written
+    }
+
+    /// Dispatches the cull shader and copies its indirect-args result into
+    /// this frame's readback slot, against whatever `cull_upload` wrote into
+    /// an earlier-submitted encoder this frame. Must run after `cull_upload`
+    /// has been called for the same frame.
+    pub fn cull_dispatch(&mut self, encoder: &mut wgpu::CommandEncoder) {
+        let tile_count = self.pending_total_tile_count[self.write_slot];
+        {
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                label: Some("cull_pass"),
+                timestamp_writes: None,
+            });
+            pass.set_pipeline(&self.pipeline);
+            pass.set_bind_group(0, &self.bind_group, &[]);
+            pass.dispatch_workgroups(tile_count.div_ceil(64), 1, 1);
+        }
+
+        encoder.copy_buffer_to_buffer(
+            &self.indirect_buffer,
+            0,
+            &self.readback_buffers[self.write_slot],
+            0,
+            self.indirect_buffer.size(),
+        );
+    }
+
+    /// The buffer `VoxelRendererPass::compute_with_indirect_pass` should
+    /// drive `dispatch_workgroups_indirect` from, once `cull` has recorded
+    /// its dispatch into the same frame's encoder.
+    pub fn indirect_buffer(&self) -> &wgpu::Buffer {
+        &self.indirect_buffer
+    }
+
+    /// Advances the stats readback and returns the latest resolved
+    /// [`CullStats`], one frame behind `cull` the same way
+    /// `GpuTimer::read_back` trails `GpuTimer::resolve` -- blocking briefly
+    /// on a copy the GPU finished a whole frame ago is cheap, and it's the
+    /// same tradeoff this renderer already makes for GPU timings.
+    pub fn poll_stats(&mut self, device: &wgpu::Device) -> CullStats {
+        let read_slot = 1 - self.write_slot;
+        self.write_slot = read_slot;
+
+        let readback = &self.readback_buffers[read_slot];
+        let slice = readback.slice(..);
+        slice.map_async(wgpu::MapMode::Read, |_| {});
+        device.poll(wgpu::Maintain::Wait);
+
+        let visible_tile_count = {
+            let data = slice.get_mapped_range();
+            bytemuck::from_bytes::<[u32; 3]>(&data)[2]
+        };
+        readback.unmap();
+
+        self.stats = CullStats {
+            visible_tile_count,
+            total_tile_count: self.pending_total_tile_count[read_slot],
+        };
+        self.stats
+    }
+}
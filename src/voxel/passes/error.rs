@@ -0,0 +1,91 @@
+use std::fmt;
+
+/// A GPU pass failed to construct. `ShaderCompile` is built from a
+/// `wgpu::Error` caught with `push_error_scope`/`pop_error_scope` around the
+/// offending `create_shader_module` call, so `pass` and `path` identify what
+/// broke even after the `wgpu::Device` that produced the error scope has
+/// gone out of reach (e.g. once it's surfaced through `RendererError`).
+/// `TreeDepthExceeded` is `VoxelRendererPass::new` rejecting a `Chunk` whose
+/// octree is deeper than `VoxelRendererPass::MAX_TREE_DEPTH` supports.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PassCreationError {
+    ShaderCompile { pass: &'static str, path: String, detail: String },
+    TreeDepthExceeded { depth: u32, max_depth: u32 },
+}
+
+impl PassCreationError {
+    /// `wgpu::Error`'s validation messages often carry a full dump of the
+    /// offending shader after the first line or two of actual explanation;
+    /// keeping only this many lines is enough context for a log line or an
+    /// error message without reprinting the whole source.
+    const MAX_DETAIL_LINES: usize = 4;
+
+    pub(crate) fn shader_compile(pass: &'static str, path: &'static str, error: wgpu::Error) -> Self {
+        Self::ShaderCompile {
+            pass,
+            path: path.to_string(),
+            detail: Self::truncate_detail(&error.to_string()),
+        }
+    }
+
+    pub(crate) fn tree_depth_exceeded(depth: u32, max_depth: u32) -> Self {
+        Self::TreeDepthExceeded { depth, max_depth }
+    }
+
+    fn truncate_detail(message: &str) -> String {
+        message.lines().take(Self::MAX_DETAIL_LINES).collect::<Vec<_>>().join("\n")
+    }
+}
+
+impl fmt::Display for PassCreationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ShaderCompile { pass, path, detail } => write!(f, "{pass} failed to compile {path}: {detail}"),
+            Self::TreeDepthExceeded { depth, max_depth } => write!(
+                f,
+                "chunk octree depth {depth} exceeds the traversal shader's supported depth of {max_depth}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PassCreationError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_includes_pass_and_path() {
+        let err = PassCreationError::ShaderCompile {
+            pass: "VoxelRendererPass",
+            path: "voxel_renderer.wgsl".to_string(),
+            detail: "expected ';', found '}'".to_string(),
+        };
+        let message = err.to_string();
+        assert!(message.contains("VoxelRendererPass"));
+        assert!(message.contains("voxel_renderer.wgsl"));
+        assert!(message.contains("expected ';'"));
+    }
+
+    #[test]
+    fn shader_compile_truncates_detail_to_a_few_lines() {
+        let message = (0..20).map(|i| format!("line {i}")).collect::<Vec<_>>().join("\n");
+        let truncated = PassCreationError::truncate_detail(&message);
+        assert_eq!(truncated.lines().count(), PassCreationError::MAX_DETAIL_LINES);
+        assert!(truncated.starts_with("line 0"));
+    }
+
+    #[test]
+    fn shader_compile_leaves_short_detail_untouched() {
+        assert_eq!(PassCreationError::truncate_detail("one line"), "one line");
+    }
+
+    #[test]
+    fn display_reports_both_depths_for_tree_depth_exceeded() {
+        let err = PassCreationError::tree_depth_exceeded(40, 31);
+        let message = err.to_string();
+        assert!(message.contains("40"));
+        assert!(message.contains("31"));
+    }
+}
@@ -0,0 +1,205 @@
+use std::sync::Arc;
+
+use crate::engine::PipelineCache;
+use crate::voxel::render_texture::RenderTexture;
+
+use super::PassCreationError;
+
+/// Post-process pass applied to `render_texture` before the final blit,
+/// gated behind `Renderer::fxaa_enabled`. Renders into its own texture
+/// (rather than in place) since `RenderTexture`'s usage flags don't include
+/// `RENDER_ATTACHMENT`.
+pub struct FxaaPass {
+    pipeline: Arc<wgpu::RenderPipeline>,
+    bind_group_layout: wgpu::BindGroupLayout,
+    bind_group: wgpu::BindGroup,
+    sampler: wgpu::Sampler,
+    output_texture: wgpu::Texture,
+    output_view: wgpu::TextureView,
+    /// Must match `render_texture.format` -- baked into `pipeline`'s color
+    /// target, so a format change (e.g. toggling HDR) needs a full `new`,
+    /// not `resize`, same as `VoxelRendererPass`.
+    format: wgpu::TextureFormat,
+}
+
+impl FxaaPass {
+    /// Layout for `bind_group_layout`, exposed so
+    /// `tests/shader_validation.rs` can cross-check it against the bind
+    /// group `fxaa.wgsl` actually declares without needing a `wgpu::Device`.
+    pub const BIND_GROUP_LAYOUT_ENTRIES: [wgpu::BindGroupLayoutEntry; 2] = [
+        wgpu::BindGroupLayoutEntry {
+            binding: 0,
+            visibility: wgpu::ShaderStages::FRAGMENT,
+            ty: wgpu::BindingType::Texture {
+                sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                view_dimension: wgpu::TextureViewDimension::D2,
+                multisampled: false,
+            },
+            count: None,
+        },
+        wgpu::BindGroupLayoutEntry {
+            binding: 1,
+            visibility: wgpu::ShaderStages::FRAGMENT,
+            ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+            count: None,
+        },
+    ];
+
+    pub fn new(device: &wgpu::Device, render_texture: &RenderTexture, cache: &mut PipelineCache) -> Result<Self, PassCreationError> {
+        #[cfg(feature = "shader-hot-reload")]
+        let source = crate::engine::shader_watcher::load("fxaa.wgsl");
+        #[cfg(not(feature = "shader-hot-reload"))]
+        let source = include_str!("../../shaders/fxaa.wgsl").to_string();
+        device.push_error_scope(wgpu::ErrorFilter::Validation);
+        let shader = cache.shader_module("fxaa_shader", || {
+            device.create_shader_module(wgpu::ShaderModuleDescriptor {
+                label: Some("fxaa_shader"),
+                source: wgpu::ShaderSource::Wgsl(source.into()),
+            })
+        });
+        if let Some(error) = pollster::block_on(device.pop_error_scope()) {
+            return Err(PassCreationError::shader_compile("FxaaPass", "fxaa.wgsl", error));
+        }
+
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("fxaa_sampler"),
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("fxaa_bind_group_layout"),
+            entries: &Self::BIND_GROUP_LAYOUT_ENTRIES,
+        });
+
+        let bind_group = Self::create_bind_group(device, &bind_group_layout, &sampler, &render_texture.view);
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("fxaa_pipeline_layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let pipeline_key = format!("fxaa_pipeline/{:?}", render_texture.format);
+        let pipeline = cache.render_pipeline(pipeline_key, || {
+            device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some("fxaa_pipeline"),
+                layout: Some(&pipeline_layout),
+                vertex: wgpu::VertexState {
+                    module: &shader,
+                    entry_point: "vs_main",
+                    buffers: &[],
+                    compilation_options: wgpu::PipelineCompilationOptions::default(),
+                },
+                fragment: Some(wgpu::FragmentState {
+                    module: &shader,
+                    entry_point: "fs_main",
+                    targets: &[Some(wgpu::ColorTargetState {
+                        format: render_texture.format,
+                        blend: None,
+                        write_mask: wgpu::ColorWrites::ALL,
+                    })],
+                    compilation_options: wgpu::PipelineCompilationOptions::default(),
+                }),
+                primitive: wgpu::PrimitiveState::default(),
+                depth_stencil: None,
+                multisample: wgpu::MultisampleState::default(),
+                multiview: None,
+            })
+        });
+
+        let (output_texture, output_view) =
+            Self::create_output(device, render_texture.width, render_texture.height, render_texture.format);
+
+        Ok(Self {
+            pipeline,
+            bind_group_layout,
+            bind_group,
+            sampler,
+            output_texture,
+            output_view,
+            format: render_texture.format,
+        })
+    }
+
+    fn create_output(
+        device: &wgpu::Device,
+        width: u32,
+        height: u32,
+        format: wgpu::TextureFormat,
+    ) -> (wgpu::Texture, wgpu::TextureView) {
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("fxaa_output_texture"),
+            size: wgpu::Extent3d {
+                width: width.max(1),
+                height: height.max(1),
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        (texture, view)
+    }
+
+    fn create_bind_group(
+        device: &wgpu::Device,
+        layout: &wgpu::BindGroupLayout,
+        sampler: &wgpu::Sampler,
+        source: &wgpu::TextureView,
+    ) -> wgpu::BindGroup {
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("fxaa_bind_group"),
+            layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(source),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(sampler),
+                },
+            ],
+        })
+    }
+
+    /// Rebuilds the source bind group and the output texture at
+    /// `render_texture`'s (possibly new) size. Called whenever
+    /// `render_texture` itself is recreated (resize, render-scale change).
+    /// `render_texture.format` must be unchanged from `new` -- see `format`.
+    pub fn resize(&mut self, device: &wgpu::Device, render_texture: &RenderTexture) {
+        debug_assert_eq!(render_texture.format, self.format);
+        self.bind_group =
+            Self::create_bind_group(device, &self.bind_group_layout, &self.sampler, &render_texture.view);
+        let (output_texture, output_view) =
+            Self::create_output(device, render_texture.width, render_texture.height, self.format);
+        self.output_texture = output_texture;
+        self.output_view = output_view;
+    }
+
+    pub fn output_view(&self) -> &wgpu::TextureView {
+        &self.output_view
+    }
+
+    /// Size of the intermediate output texture in bytes, for `GpuMemoryReport`.
+    pub fn output_byte_size(&self) -> u64 {
+        let bytes_per_texel: u64 = match self.format {
+            RenderTexture::FORMAT_HDR => 8,
+            _ => 4,
+        };
+        let size = self.output_texture.size();
+        size.width as u64 * size.height as u64 * bytes_per_texel
+    }
+
+    pub fn draw_with_pass<'a>(&'a self, pass: &mut wgpu::RenderPass<'a>) {
+        pass.set_pipeline(&self.pipeline);
+        pass.set_bind_group(0, &self.bind_group, &[]);
+        pass.draw(0..3, 0..1);
+    }
+}
@@ -0,0 +1,329 @@
+use std::sync::Arc;
+
+use wgpu::util::DeviceExt;
+
+use crate::engine::PipelineCache;
+
+use super::PassCreationError;
+
+/// Vertex attribute for `gizmo.wgsl`: a single NDC-space position per
+/// vertex, pre-baked on the CPU side (see `line_segment_quad`) rather than
+/// derived in the shader, since unlike the fullscreen `rendering.wgsl`/
+/// `fxaa.wgsl` triangle the gizmo content isn't a fixed shape -- it's
+/// wherever the crosshair strokes land in screen space.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct GizmoVertex {
+    position: [f32; 2],
+}
+
+/// Vertices the crosshair's two strokes need: two quads (one per stroke),
+/// six vertices (two triangles) each.
+const CROSSHAIR_VERTEX_COUNT: usize = 12;
+
+/// Half-length and half-thickness, in pixels, of the crosshair's two
+/// strokes; matches the values `rendering.wgsl` used to draw the crosshair
+/// itself before it moved here -- see `GizmoPass`'s doc comment.
+const CROSSHAIR_HALF_LENGTH_PX: f32 = 6.0;
+const CROSSHAIR_HALF_THICKNESS_PX: f32 = 1.0;
+
+/// Requested MSAA sample count for the gizmo pass; validated against the
+/// color target's actual capabilities in `resolve_sample_count`, with a
+/// fallback to `1` (no MSAA) rather than requesting an unsupported texture.
+const REQUESTED_SAMPLE_COUNT: u32 = 4;
+
+/// Builds the six vertices (two triangles) of a `half_thickness_px`-wide
+/// quad along the segment from `p0_px` to `p1_px`, both in pixels relative
+/// to the center of a `viewport_px`-sized surface, converted to NDC.
+/// Doesn't special-case axis-aligned segments -- the perpendicular-offset
+/// math is exact for a diagonal segment too, which is what a future
+/// wireframe-box gizmo would need as well as today's axis-aligned
+/// crosshair.
+fn line_segment_quad(p0_px: [f32; 2], p1_px: [f32; 2], half_thickness_px: f32, viewport_px: [f32; 2]) -> [[f32; 2]; 6] {
+    let dir = [p1_px[0] - p0_px[0], p1_px[1] - p0_px[1]];
+    let len = (dir[0] * dir[0] + dir[1] * dir[1]).sqrt();
+    let dir = [dir[0] / len, dir[1] / len];
+    let perp = [-dir[1] * half_thickness_px, dir[0] * half_thickness_px];
+
+    let corners_px = [
+        [p0_px[0] + perp[0], p0_px[1] + perp[1]],
+        [p0_px[0] - perp[0], p0_px[1] - perp[1]],
+        [p1_px[0] + perp[0], p1_px[1] + perp[1]],
+        [p0_px[0] - perp[0], p0_px[1] - perp[1]],
+        [p1_px[0] + perp[0], p1_px[1] + perp[1]],
+        [p1_px[0] - perp[0], p1_px[1] - perp[1]],
+    ];
+    corners_px.map(|corner| [corner[0] * 2.0 / viewport_px[0], corner[1] * 2.0 / viewport_px[1]])
+}
+
+/// The crosshair's full 12-vertex geometry (two `line_segment_quad`s) for a
+/// `viewport_px`-sized surface, with `scale_factor` keeping its physical
+/// size constant on a HiDPI display (see `Renderer::set_scale_factor`).
+fn crosshair_vertices(viewport_px: [f32; 2], scale_factor: f32) -> [GizmoVertex; CROSSHAIR_VERTEX_COUNT] {
+    let half_length = CROSSHAIR_HALF_LENGTH_PX * scale_factor;
+    let half_thickness = CROSSHAIR_HALF_THICKNESS_PX * scale_factor;
+    let horizontal = line_segment_quad([-half_length, 0.0], [half_length, 0.0], half_thickness, viewport_px);
+    let vertical = line_segment_quad([0.0, -half_length], [0.0, half_length], half_thickness, viewport_px);
+    let mut vertices = [GizmoVertex { position: [0.0; 2] }; CROSSHAIR_VERTEX_COUNT];
+    for (slot, position) in vertices.iter_mut().zip(horizontal.into_iter().chain(vertical)) {
+        slot.position = position;
+    }
+    vertices
+}
+
+/// Which `TextureFormatFeatureFlags` `resolve_sample_count` needs to grant
+/// `REQUESTED_SAMPLE_COUNT`: the format must support that sample count at
+/// all, and support resolving a multisampled attachment of it down to `1`
+/// sample (otherwise there'd be a multisampled texture with nothing able to
+/// produce the single-sample image `render`'s other passes expect).
+fn resolve_sample_count(flags: wgpu::TextureFormatFeatureFlags) -> u32 {
+    let supports_msaa =
+        flags.sample_count_supported(REQUESTED_SAMPLE_COUNT) && flags.contains(wgpu::TextureFormatFeatureFlags::MULTISAMPLE_RESOLVE);
+    if supports_msaa {
+        REQUESTED_SAMPLE_COUNT
+    } else {
+        1
+    }
+}
+
+/// Draws the crosshair reticle as real line geometry (rather than
+/// `rendering.wgsl`'s former per-pixel SDF check) into an optional
+/// multisampled intermediate, resolved onto the blit's output. Runs after
+/// `VoxelImageRenderingPass` with `LoadOp::Load`, so MSAA only costs extra
+/// work for this small amount of overlay geometry rather than the whole
+/// voxel image.
+pub struct GizmoPass {
+    pipeline: Arc<wgpu::RenderPipeline>,
+    vertex_buffer: wgpu::Buffer,
+    /// `None` when `sample_count == 1`: `draw` then renders straight into
+    /// the target view with no resolve step.
+    msaa_view: Option<wgpu::TextureView>,
+    sample_count: u32,
+    /// Must match the color target `draw` is given -- baked into
+    /// `pipeline`'s color target state and `msaa_view`'s format.
+    format: wgpu::TextureFormat,
+    width: u32,
+    height: u32,
+}
+
+impl GizmoPass {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        device: &wgpu::Device,
+        adapter: &wgpu::Adapter,
+        format: wgpu::TextureFormat,
+        width: u32,
+        height: u32,
+        scale_factor: f32,
+        cache: &mut PipelineCache,
+    ) -> Result<Self, PassCreationError> {
+        #[cfg(feature = "shader-hot-reload")]
+        let source = crate::engine::shader_watcher::load("gizmo.wgsl");
+        #[cfg(not(feature = "shader-hot-reload"))]
+        let source = include_str!("../../shaders/gizmo.wgsl").to_string();
+        device.push_error_scope(wgpu::ErrorFilter::Validation);
+        let shader = cache.shader_module("gizmo_shader", || {
+            device.create_shader_module(wgpu::ShaderModuleDescriptor {
+                label: Some("gizmo_shader"),
+                source: wgpu::ShaderSource::Wgsl(source.into()),
+            })
+        });
+        if let Some(error) = pollster::block_on(device.pop_error_scope()) {
+            return Err(PassCreationError::shader_compile("GizmoPass", "gizmo.wgsl", error));
+        }
+
+        let sample_count = resolve_sample_count(adapter.get_texture_format_features(format).flags);
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("gizmo_pipeline_layout"),
+            bind_group_layouts: &[],
+            push_constant_ranges: &[],
+        });
+        let pipeline_key = format!("gizmo_pipeline/{format:?}/{sample_count}");
+        let pipeline = cache.render_pipeline(pipeline_key, || {
+            device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some("gizmo_pipeline"),
+                layout: Some(&pipeline_layout),
+                vertex: wgpu::VertexState {
+                    module: &shader,
+                    entry_point: "vs_main",
+                    buffers: &[wgpu::VertexBufferLayout {
+                        array_stride: std::mem::size_of::<GizmoVertex>() as wgpu::BufferAddress,
+                        step_mode: wgpu::VertexStepMode::Vertex,
+                        attributes: &[wgpu::VertexAttribute {
+                            format: wgpu::VertexFormat::Float32x2,
+                            offset: 0,
+                            shader_location: 0,
+                        }],
+                    }],
+                    compilation_options: wgpu::PipelineCompilationOptions::default(),
+                },
+                fragment: Some(wgpu::FragmentState {
+                    module: &shader,
+                    entry_point: "fs_main",
+                    targets: &[Some(wgpu::ColorTargetState {
+                        format,
+                        blend: None,
+                        write_mask: wgpu::ColorWrites::ALL,
+                    })],
+                    compilation_options: wgpu::PipelineCompilationOptions::default(),
+                }),
+                primitive: wgpu::PrimitiveState::default(),
+                depth_stencil: None,
+                multisample: wgpu::MultisampleState {
+                    count: sample_count,
+                    mask: !0,
+                    alpha_to_coverage_enabled: false,
+                },
+                multiview: None,
+            })
+        });
+
+        let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("gizmo_vertex_buffer"),
+            contents: bytemuck::cast_slice(&crosshair_vertices([width as f32, height as f32], scale_factor)),
+            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+        });
+
+        let msaa_view = (sample_count > 1).then(|| Self::create_msaa_view(device, format, sample_count, width, height));
+
+        Ok(Self {
+            pipeline,
+            vertex_buffer,
+            msaa_view,
+            sample_count,
+            format,
+            width,
+            height,
+        })
+    }
+
+    fn create_msaa_view(device: &wgpu::Device, format: wgpu::TextureFormat, sample_count: u32, width: u32, height: u32) -> wgpu::TextureView {
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("gizmo_msaa_texture"),
+            size: wgpu::Extent3d {
+                width: width.max(1),
+                height: height.max(1),
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        });
+        texture.create_view(&wgpu::TextureViewDescriptor::default())
+    }
+
+    /// Recreates the MSAA intermediate (if any) at the new swapchain size
+    /// and rebuilds the crosshair geometry to match. Called by
+    /// `Renderer::resize`.
+    pub fn resize(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, width: u32, height: u32, scale_factor: f32) {
+        self.width = width;
+        self.height = height;
+        if self.sample_count > 1 {
+            self.msaa_view = Some(Self::create_msaa_view(device, self.format, self.sample_count, width, height));
+        }
+        self.write_vertices(queue, scale_factor);
+    }
+
+    /// Rewrites the crosshair geometry in place for a new `scale_factor`;
+    /// called by `Renderer::set_scale_factor`, which doesn't change the
+    /// swapchain size so the MSAA intermediate doesn't need recreating.
+    pub fn set_scale_factor(&self, queue: &wgpu::Queue, scale_factor: f32) {
+        self.write_vertices(queue, scale_factor);
+    }
+
+    fn write_vertices(&self, queue: &wgpu::Queue, scale_factor: f32) {
+        let vertices = crosshair_vertices([self.width as f32, self.height as f32], scale_factor);
+        queue.write_buffer(&self.vertex_buffer, 0, bytemuck::cast_slice(&vertices));
+    }
+
+    /// Size of the MSAA intermediate in bytes, for `GpuMemoryReport`; `0`
+    /// when `resolve_sample_count` fell back to `1` and there's no
+    /// intermediate at all.
+    pub fn msaa_byte_size(&self) -> u64 {
+        if self.msaa_view.is_none() {
+            return 0;
+        }
+        let bytes_per_texel = self.format.block_copy_size(None).unwrap_or(4) as u64;
+        self.width as u64 * self.height as u64 * bytes_per_texel * self.sample_count as u64
+    }
+
+    /// Records the crosshair draw into `encoder`, loading `target` (the
+    /// blit's already-written output) rather than clearing it, and
+    /// resolving through `msaa_view` onto `target` when MSAA is active.
+    pub fn draw(&self, encoder: &mut wgpu::CommandEncoder, target: &wgpu::TextureView) {
+        let (view, resolve_target) = match &self.msaa_view {
+            Some(msaa_view) => (msaa_view, Some(target)),
+            None => (target, None),
+        };
+        let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("gizmo_pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view,
+                resolve_target,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Load,
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        pass.set_pipeline(&self.pipeline);
+        pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
+        pass.draw(0..CROSSHAIR_VERTEX_COUNT as u32, 0..1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn line_segment_quad_on_a_diagonal_pins_its_ndc_corners() {
+        let corners = line_segment_quad([0.0, 0.0], [100.0, 100.0], 2.0, [800.0, 600.0]);
+        let expected = [
+            [-0.003_535_534, 0.004_714_045],
+            [0.003_535_534, -0.004_714_045],
+            [0.246_464_47, 0.338_047_37],
+            [0.003_535_534, -0.004_714_045],
+            [0.246_464_47, 0.338_047_37],
+            [0.253_535_53, 0.328_619_3],
+        ];
+        for (actual, expected) in corners.iter().zip(expected.iter()) {
+            assert!((actual[0] - expected[0]).abs() < 1e-5, "{actual:?} vs {expected:?}");
+            assert!((actual[1] - expected[1]).abs() < 1e-5, "{actual:?} vs {expected:?}");
+        }
+    }
+
+    #[test]
+    fn line_segment_quad_is_centered_on_an_axis_aligned_segment() {
+        let corners = line_segment_quad([-6.0, 0.0], [6.0, 0.0], 1.0, [1000.0, 1000.0]);
+        for corner in corners {
+            assert!(corner[0].abs() <= 6.0 * 2.0 / 1000.0 + 1e-6);
+            assert!(corner[1].abs() <= 1.0 * 2.0 / 1000.0 + 1e-6);
+        }
+    }
+
+    #[test]
+    fn resolve_sample_count_uses_4x_msaa_when_the_format_supports_resolve() {
+        let flags = wgpu::TextureFormatFeatureFlags::MULTISAMPLE_X4 | wgpu::TextureFormatFeatureFlags::MULTISAMPLE_RESOLVE;
+        assert_eq!(resolve_sample_count(flags), 4);
+    }
+
+    #[test]
+    fn resolve_sample_count_falls_back_to_1x_without_resolve_support() {
+        let flags = wgpu::TextureFormatFeatureFlags::MULTISAMPLE_X4;
+        assert_eq!(resolve_sample_count(flags), 1);
+    }
+
+    #[test]
+    fn resolve_sample_count_falls_back_to_1x_without_4x_support() {
+        let flags = wgpu::TextureFormatFeatureFlags::MULTISAMPLE_RESOLVE;
+        assert_eq!(resolve_sample_count(flags), 1);
+    }
+}
@@ -0,0 +1,265 @@
+//! Hierarchical-Z (HiZ) occlusion culling: downsamples a depth buffer into a
+//! min-depth mip pyramid, then tests a chunk's screen-space bounding rect
+//! against it to tell whether every pixel behind that rect already has
+//! something closer drawn in front of it.
+//!
+//! Not wired into any real pass yet, for two reasons this crate already has
+//! open gaps for:
+//! - There's no previous-frame depth buffer a CPU pass can read back from.
+//!   `render_texture::DepthTexture` only ever gets written and sampled on
+//!   the GPU; a HiZ pass needs either a compute-shader downsample reading it
+//!   directly (this module's downsample is pure Rust, so it isn't that) or
+//!   a readback path this crate doesn't have (see `VoxelRendererPass::poll_pick_result`
+//!   for the one readback path that does exist, sized for a single pixel,
+//!   not a whole depth buffer).
+//! - There's no multi-chunk "chunk table" to cull entries out of --
+//!   `CullPass`'s own doc comment covers why: this renderer loads exactly
+//!   one [`super::super::Chunk`], so there's nothing to drop from a list of
+//!   one.
+//!
+//! What's here is the part that's a well-defined, testable unit on its own:
+//! building the mip pyramid, projecting a chunk's AABB into a screen-space
+//! rect, and the occlusion test itself.
+#![allow(dead_code)]
+
+/// One mip level of a [`HiZPyramid`]: `width * height` linear depth values
+/// (see `render_texture`'s `depth_fraction`, same "larger = farther"
+/// convention), each the minimum -- i.e. the nearest surface -- over the
+/// corresponding `2x2` block one level down.
+struct HiZLevel {
+    width: u32,
+    height: u32,
+    texels: Vec<f32>,
+}
+
+/// A full mip chain built from one frame's depth buffer, coarsest level
+/// last. Querying a coarse level answers "what's the nearest surface
+/// anywhere in this (large) screen region" in one lookup instead of
+/// scanning every pixel a chunk's bounding rect covers.
+pub(crate) struct HiZPyramid {
+    levels: Vec<HiZLevel>,
+}
+
+impl HiZPyramid {
+    /// Builds the full chain from a `width * height` linear depth buffer
+    /// (row-major, `y` down), halving each axis (rounding up) until a
+    /// single texel remains.
+    pub(crate) fn build(width: u32, height: u32, depth: &[f32]) -> Self {
+        assert_eq!(depth.len(), (width * height) as usize, "depth buffer size doesn't match width * height");
+        let mut levels = vec![HiZLevel { width, height, texels: depth.to_vec() }];
+        while {
+            let last = levels.last().unwrap();
+            last.width > 1 || last.height > 1
+        } {
+            levels.push(Self::downsample(levels.last().unwrap()));
+        }
+        Self { levels }
+    }
+
+    fn downsample(level: &HiZLevel) -> HiZLevel {
+        let width = level.width.div_ceil(2).max(1);
+        let height = level.height.div_ceil(2).max(1);
+        let mut texels = Vec::with_capacity((width * height) as usize);
+        for y in 0..height {
+            for x in 0..width {
+                let mut nearest = f32::INFINITY;
+                for dy in 0..2 {
+                    for dx in 0..2 {
+                        let sx = (x * 2 + dx).min(level.width - 1);
+                        let sy = (y * 2 + dy).min(level.height - 1);
+                        nearest = nearest.min(level.texels[(sy * level.width + sx) as usize]);
+                    }
+                }
+                texels.push(nearest);
+            }
+        }
+        HiZLevel { width, height, texels }
+    }
+
+    /// Nearest recorded depth over the whole rect `[min, max]` (screen
+    /// pixels), picked from whichever mip level has texels no larger than
+    /// the rect itself -- coarser than that would blend in neighboring
+    /// geometry the rect doesn't actually cover, finer would just redo the
+    /// same min over more texels for the same answer.
+    fn nearest_depth_in_rect(&self, min: [f32; 2], max: [f32; 2]) -> f32 {
+        let rect_width = (max[0] - min[0]).max(1.0);
+        let rect_height = (max[1] - min[1]).max(1.0);
+        let base = &self.levels[0];
+        let level_index = self
+            .levels
+            .iter()
+            .enumerate()
+            .rev()
+            .find(|(_, level)| {
+                let scale_x = base.width as f32 / level.width as f32;
+                let scale_y = base.height as f32 / level.height as f32;
+                scale_x <= rect_width && scale_y <= rect_height
+            })
+            .map(|(index, _)| index)
+            .unwrap_or(0);
+        let level = &self.levels[level_index];
+        let scale_x = base.width as f32 / level.width as f32;
+        let scale_y = base.height as f32 / level.height as f32;
+        let lo_x = ((min[0] / scale_x).floor() as u32).min(level.width - 1);
+        let hi_x = ((max[0] / scale_x).ceil() as u32).min(level.width - 1);
+        let lo_y = ((min[1] / scale_y).floor() as u32).min(level.height - 1);
+        let hi_y = ((max[1] / scale_y).ceil() as u32).min(level.height - 1);
+
+        let mut nearest = f32::INFINITY;
+        for y in lo_y..=hi_y {
+            for x in lo_x..=hi_x {
+                nearest = nearest.min(level.texels[(y * level.width + x) as usize]);
+            }
+        }
+        nearest
+    }
+}
+
+/// A chunk's axis-aligned bounds, projected to screen space by
+/// [`chunk_screen_bounds`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct ScreenBounds {
+    pub min: [f32; 2],
+    pub max: [f32; 2],
+    /// Nearest of the AABB's 8 corners' linear depth -- the depth a fully
+    /// visible chunk's closest pixel could possibly report.
+    pub near_depth: f32,
+}
+
+/// Projects a `chunk_size`-voxel chunk's AABB at `chunk_position` through
+/// `view_proj`, returning its screen-space bounding rect (clamped to the
+/// `texture_width` x `texture_height` viewport) and nearest depth. Returns
+/// `None` if every corner is behind the camera or the projected rect falls
+/// entirely outside the viewport, since there's nothing meaningful to cull
+/// against in either case.
+pub(crate) fn chunk_screen_bounds(
+    view_proj: glam::Mat4,
+    chunk_position: glam::IVec3,
+    chunk_size: u32,
+    texture_width: u32,
+    texture_height: u32,
+) -> Option<ScreenBounds> {
+    let origin = chunk_position.as_vec3();
+    let size = chunk_size as f32;
+    let mut min = [f32::INFINITY; 2];
+    let mut max = [f32::NEG_INFINITY; 2];
+    let mut near_depth = f32::INFINITY;
+    let mut any_in_front = false;
+
+    for corner in 0..8u32 {
+        let offset = glam::Vec3::new(
+            if corner & 1 != 0 { size } else { 0.0 },
+            if corner & 2 != 0 { size } else { 0.0 },
+            if corner & 4 != 0 { size } else { 0.0 },
+        );
+        let world = origin + offset;
+        let clip = view_proj * world.extend(1.0);
+        if clip.w <= 0.0 {
+            continue;
+        }
+        any_in_front = true;
+        let ndc = clip.truncate() / clip.w;
+        let screen_x = (ndc.x * 0.5 + 0.5) * texture_width as f32;
+        let screen_y = (1.0 - (ndc.y * 0.5 + 0.5)) * texture_height as f32;
+        min[0] = min[0].min(screen_x);
+        min[1] = min[1].min(screen_y);
+        max[0] = max[0].max(screen_x);
+        max[1] = max[1].max(screen_y);
+        near_depth = near_depth.min(clip.w);
+    }
+
+    if !any_in_front {
+        return None;
+    }
+
+    min[0] = min[0].max(0.0);
+    min[1] = min[1].max(0.0);
+    max[0] = max[0].min(texture_width as f32);
+    max[1] = max[1].min(texture_height as f32);
+    if min[0] >= max[0] || min[1] >= max[1] {
+        return None;
+    }
+
+    Some(ScreenBounds { min, max, near_depth })
+}
+
+/// Whether `bounds` is fully hidden behind already-recorded geometry in
+/// `pyramid`: true when even the bounds' nearest corner is farther than the
+/// nearest surface anywhere in its screen footprint, plus `margin` of extra
+/// depth this frame requires before calling it occluded -- so a chunk
+/// sitting almost exactly at the occluder's depth, or one that'll swing into
+/// view next frame as the camera turns, gets one frame of slack instead of
+/// popping out and back in.
+pub(crate) fn is_occluded(pyramid: &HiZPyramid, bounds: &ScreenBounds, margin: f32) -> bool {
+    let nearest_recorded = pyramid.nearest_depth_in_rect(bounds.min, bounds.max);
+    bounds.near_depth > nearest_recorded + margin
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn downsample_keeps_the_nearest_depth_in_each_block() {
+        let depth = vec![1.0, 5.0, 5.0, 5.0];
+        let pyramid = HiZPyramid::build(2, 2, &depth);
+        assert_eq!(pyramid.levels.last().unwrap().texels, vec![1.0]);
+    }
+
+    #[test]
+    fn odd_dimensions_are_handled_by_edge_clamping() {
+        let depth = vec![3.0, 3.0, 3.0, 3.0, 3.0, 3.0, 3.0, 3.0, 3.0];
+        let pyramid = HiZPyramid::build(3, 3, &depth);
+        assert_eq!(pyramid.levels.last().unwrap().texels, vec![3.0]);
+    }
+
+    #[test]
+    fn nearest_depth_in_rect_ignores_geometry_outside_the_rect() {
+        let mut depth = vec![100.0; 64];
+        depth[0] = 1.0; // far corner, outside the rect queried below
+        let pyramid = HiZPyramid::build(8, 8, &depth);
+        let nearest = pyramid.nearest_depth_in_rect([4.0, 4.0], [8.0, 8.0]);
+        assert_eq!(nearest, 100.0);
+    }
+
+    #[test]
+    fn chunk_directly_ahead_projects_to_a_rect_within_the_viewport() {
+        let view = glam::Mat4::look_at_rh(glam::Vec3::new(16.0, 16.0, -20.0), glam::Vec3::new(16.0, 16.0, 16.0), glam::Vec3::Y);
+        let proj = glam::Mat4::perspective_rh(1.0, 1.0, 0.1, 1000.0);
+        let bounds = chunk_screen_bounds(proj * view, glam::IVec3::ZERO, 32, 256, 256).expect("chunk should project");
+        assert!(bounds.min[0] >= 0.0 && bounds.max[0] <= 256.0);
+        assert!(bounds.min[1] >= 0.0 && bounds.max[1] <= 256.0);
+        assert!(bounds.near_depth > 0.0);
+    }
+
+    #[test]
+    fn chunk_entirely_behind_the_camera_has_no_screen_bounds() {
+        let view = glam::Mat4::look_at_rh(glam::Vec3::new(16.0, 16.0, -100.0), glam::Vec3::new(16.0, 16.0, -101.0), glam::Vec3::Y);
+        let proj = glam::Mat4::perspective_rh(1.0, 1.0, 0.1, 1000.0);
+        let bounds = chunk_screen_bounds(proj * view, glam::IVec3::ZERO, 32, 256, 256);
+        assert!(bounds.is_none());
+    }
+
+    #[test]
+    fn a_chunk_behind_a_closer_wall_is_occluded() {
+        // Every pixel already has something at depth 5.0 recorded.
+        let pyramid = HiZPyramid::build(4, 4, &[5.0; 16]);
+        let bounds = ScreenBounds { min: [0.0, 0.0], max: [4.0, 4.0], near_depth: 50.0 };
+        assert!(is_occluded(&pyramid, &bounds, 0.0));
+    }
+
+    #[test]
+    fn a_chunk_closer_than_recorded_geometry_is_not_occluded() {
+        let pyramid = HiZPyramid::build(4, 4, &[5.0; 16]);
+        let bounds = ScreenBounds { min: [0.0, 0.0], max: [4.0, 4.0], near_depth: 1.0 };
+        assert!(!is_occluded(&pyramid, &bounds, 0.0));
+    }
+
+    #[test]
+    fn margin_gives_a_borderline_chunk_one_frame_of_slack() {
+        let pyramid = HiZPyramid::build(4, 4, &[10.0; 16]);
+        let bounds = ScreenBounds { min: [0.0, 0.0], max: [4.0, 4.0], near_depth: 10.5 };
+        assert!(is_occluded(&pyramid, &bounds, 0.0), "should be occluded with no margin");
+        assert!(!is_occluded(&pyramid, &bounds, 1.0), "a 1-unit margin should give it slack");
+    }
+}
@@ -0,0 +1,19 @@
+mod beam;
+mod cull_pass;
+mod error;
+mod fxaa;
+mod gizmo;
+mod hiz;
+mod voxel_image_rendering;
+mod voxel_renderer;
+
+pub use cull_pass::{CullFrameParams, CullPass, CullStats};
+pub use error::PassCreationError;
+pub use fxaa::FxaaPass;
+pub use gizmo::GizmoPass;
+pub use voxel_image_rendering::{fit_scale, gamma_for, BlitFilter, FitMode, TonemapOperator, VoxelImageRenderingPass};
+pub use voxel_renderer::{
+    AoSettings, DebugView, FrameParams, Light, NodeBufferOverflow, PickResult, PickTicket, VoxelRendererPass,
+    WorkgroupSize, MAX_LIGHTS,
+};
+pub(crate) use voxel_renderer::PUSH_CONSTANTS_SIZE;
@@ -0,0 +1,560 @@
+use std::sync::Arc;
+
+use wgpu::util::DeviceExt;
+
+use crate::engine::PipelineCache;
+
+use super::PassCreationError;
+
+/// How the blit pass samples its source texture when its size doesn't match
+/// the target 1:1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlitFilter {
+    /// The source matches the target -- a plain unfiltered sample keeps the
+    /// crisp native-resolution look.
+    Nearest,
+    /// The source is scaled relative to the target; a single bilinear
+    /// sample resizes it (looks soft when upscaling, aliased when
+    /// downscaling a non-supersampled buffer).
+    Linear,
+    /// The source is exactly 2x the target on each axis
+    /// (`AaMode::SuperSample2x`); averages the source 2x2 footprint per
+    /// output pixel instead of a single resampled tap.
+    Box2x,
+}
+
+/// Which curve `rendering.wgsl` uses to bring the (possibly HDR) source
+/// color into displayable range. `None` still clamps to `[0, 1]`, so it's a
+/// no-op rather than a pass-through when the source is already LDR.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TonemapOperator {
+    #[default]
+    None,
+    Reinhard,
+    AcesApprox,
+}
+
+impl TonemapOperator {
+    /// Matches the `operator` field values `rendering.wgsl` switches on.
+    fn as_u32(self) -> u32 {
+        match self {
+            TonemapOperator::None => 0,
+            TonemapOperator::Reinhard => 1,
+            TonemapOperator::AcesApprox => 2,
+        }
+    }
+}
+
+/// How `rendering.wgsl` maps screen UVs onto `voxel_texture` when the
+/// texture's aspect ratio (`render_scale`, or a non-uniform window resize)
+/// doesn't match the swapchain's -- see [`fit_scale`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FitMode {
+    /// Sample the texture 1:1 across the full surface, stretching it to
+    /// whatever aspect ratio the surface has. Matches this pass's behavior
+    /// before `FitMode` existed.
+    #[default]
+    Stretch,
+    /// Scale the texture down until it fits entirely within the surface,
+    /// preserving its aspect ratio; the uncovered strip (top/bottom or
+    /// left/right) is letterboxed black.
+    Contain,
+    /// Scale the texture up until it fills the surface entirely, preserving
+    /// its aspect ratio; the excess (top/bottom or left/right) is cropped.
+    Cover,
+}
+
+/// How much `rendering.wgsl` should scale a screen UV's deviation from the
+/// center before sampling `voxel_texture`, so the texture keeps its own
+/// aspect ratio instead of stretching to match `surface_size`. `texture_size`
+/// and `surface_size` are both in the same units (pixels); only their ratio
+/// matters. Either axis of `scale` being `< 1.0` widens the sampled UV range
+/// past `[0, 1]` (letterboxing, `FitMode::Contain`); `> 1.0` narrows it
+/// (cropping, `FitMode::Cover`).
+///
+/// Pulled out of `VoxelImageRenderingPass::fit_uniforms` so it can be unit
+/// tested without a `wgpu::Device`.
+pub fn fit_scale(texture_size: [f32; 2], surface_size: [f32; 2], mode: FitMode) -> [f32; 2] {
+    if mode == FitMode::Stretch {
+        return [1.0, 1.0];
+    }
+    if texture_size[0] <= 0.0 || texture_size[1] <= 0.0 || surface_size[0] <= 0.0 || surface_size[1] <= 0.0 {
+        return [1.0, 1.0];
+    }
+
+    let texture_aspect = texture_size[0] / texture_size[1];
+    let surface_aspect = surface_size[0] / surface_size[1];
+    let ratio = texture_aspect / surface_aspect;
+    let texture_relatively_wider = ratio >= 1.0;
+
+    match (mode, texture_relatively_wider) {
+        (FitMode::Contain, true) => [1.0, 1.0 / ratio],
+        (FitMode::Contain, false) => [ratio, 1.0],
+        (FitMode::Cover, true) => [ratio, 1.0],
+        (FitMode::Cover, false) => [1.0, 1.0 / ratio],
+        (FitMode::Stretch, _) => unreachable!("handled above"),
+    }
+}
+
+/// Layout must match `Tonemap` in `shaders/rendering.wgsl` exactly.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct TonemapUniforms {
+    exposure: f32,
+    gamma: f32,
+    operator: u32,
+    /// Padding to keep this a multiple of 16 bytes, matching `Tonemap` in
+    /// `rendering.wgsl`; unused. Used to carry the window scale factor for
+    /// the crosshair, which is now drawn by `GizmoPass` instead.
+    _pad: f32,
+}
+
+/// Layout must match `Fit` in `shaders/rendering.wgsl` exactly.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct FitUniforms {
+    scale: [f32; 2],
+    /// Padding to keep this a multiple of 16 bytes, matching `Fit` in
+    /// `rendering.wgsl`; unused.
+    _pad: [f32; 2],
+}
+
+/// Real sRGB isn't a pure power curve (it has a linear toe near black), but
+/// `rendering.wgsl`'s `apply_tonemap` only exposes a `pow`-based gamma knob,
+/// so this is the standard approximation applied when nothing hardware-side
+/// is already doing the real conversion; see [`gamma_for`].
+const APPROXIMATE_SRGB_GAMMA: f32 = 2.2;
+
+/// `rendering.wgsl` shades in linear space. When the format the blit
+/// actually writes into (the pipeline's color target -- see
+/// `Renderer::color_target_format`) is sRGB, the GPU already re-encodes
+/// every store to that render attachment, so the shader's own gamma step
+/// must be a no-op (`1.0`) or the image gets encoded twice and comes out
+/// washed out. `surface_format` not being sRGB -- the fallback
+/// `Renderer::new` takes when the adapter offers no sRGB-capable format and
+/// no sRGB `view_formats` companion either -- means nothing else in the
+/// pipeline ever applies that encoding, so the shader has to instead.
+/// `conversion_enabled` is a debug A/B switch (see
+/// `Renderer::set_srgb_conversion_enabled`) that forces the no-op path even
+/// on a non-sRGB surface, to compare against the double-conversion/
+/// no-conversion states this exists to prevent.
+pub fn gamma_for(surface_format: wgpu::TextureFormat, conversion_enabled: bool) -> f32 {
+    if conversion_enabled && !surface_format.is_srgb() {
+        APPROXIMATE_SRGB_GAMMA
+    } else {
+        1.0
+    }
+}
+
+/// Blits a source texture (the compute pass's `RenderTexture`, or the FXAA
+/// pass's output when enabled) to the swapchain with a fullscreen textured
+/// quad, applying exposure/tonemapping on the way.
+pub struct VoxelImageRenderingPass {
+    pipeline: Arc<wgpu::RenderPipeline>,
+    /// Used only for `BlitFilter::Box2x`; a distinct fragment shader entry
+    /// point that averages the source 2x2 footprint instead of a single tap.
+    box_pipeline: Arc<wgpu::RenderPipeline>,
+    bind_group_layout: wgpu::BindGroupLayout,
+    bind_group: wgpu::BindGroup,
+    /// Used for `BlitFilter::Nearest`.
+    sampler_nearest: wgpu::Sampler,
+    /// Used for `BlitFilter::Linear` and `BlitFilter::Box2x` (the box filter
+    /// is built out of four bilinear taps -- see `fs_main_supersample2x`).
+    sampler_linear: wgpu::Sampler,
+    tonemap_buffer: wgpu::Buffer,
+    fit_buffer: wgpu::Buffer,
+    active_filter: BlitFilter,
+}
+
+impl VoxelImageRenderingPass {
+    /// Layout for `bind_group_layout`, exposed so
+    /// `tests/shader_validation.rs` can cross-check it against the bind
+    /// group `rendering.wgsl` actually declares without needing a
+    /// `wgpu::Device`.
+    pub const BIND_GROUP_LAYOUT_ENTRIES: [wgpu::BindGroupLayoutEntry; 4] = [
+        wgpu::BindGroupLayoutEntry {
+            binding: 0,
+            visibility: wgpu::ShaderStages::FRAGMENT,
+            ty: wgpu::BindingType::Texture {
+                sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                view_dimension: wgpu::TextureViewDimension::D2,
+                multisampled: false,
+            },
+            count: None,
+        },
+        wgpu::BindGroupLayoutEntry {
+            binding: 1,
+            visibility: wgpu::ShaderStages::FRAGMENT,
+            ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+            count: None,
+        },
+        wgpu::BindGroupLayoutEntry {
+            binding: 2,
+            visibility: wgpu::ShaderStages::FRAGMENT,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Uniform,
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        },
+        wgpu::BindGroupLayoutEntry {
+            binding: 3,
+            visibility: wgpu::ShaderStages::FRAGMENT,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Uniform,
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        },
+    ];
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        device: &wgpu::Device,
+        surface_format: wgpu::TextureFormat,
+        source: &wgpu::TextureView,
+        filter: BlitFilter,
+        exposure: f32,
+        operator: TonemapOperator,
+        gamma: f32,
+        texture_size: [f32; 2],
+        surface_size: [f32; 2],
+        fit_mode: FitMode,
+        cache: &mut PipelineCache,
+    ) -> Result<Self, PassCreationError> {
+        #[cfg(feature = "shader-hot-reload")]
+        let shader_source = crate::engine::shader_watcher::load("rendering.wgsl");
+        #[cfg(not(feature = "shader-hot-reload"))]
+        let shader_source = include_str!("../../shaders/rendering.wgsl").to_string();
+        device.push_error_scope(wgpu::ErrorFilter::Validation);
+        let shader = cache.shader_module("voxel_image_shader", || {
+            device.create_shader_module(wgpu::ShaderModuleDescriptor {
+                label: Some("rendering_shader"),
+                source: wgpu::ShaderSource::Wgsl(shader_source.into()),
+            })
+        });
+        if let Some(error) = pollster::block_on(device.pop_error_scope()) {
+            return Err(PassCreationError::shader_compile("VoxelImageRenderingPass", "rendering.wgsl", error));
+        }
+
+        let tonemap_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("voxel_image_tonemap_buffer"),
+            contents: bytemuck::bytes_of(&Self::tonemap_uniforms(exposure, operator, gamma)),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+        let fit_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("voxel_image_fit_buffer"),
+            contents: bytemuck::bytes_of(&Self::fit_uniforms(texture_size, surface_size, fit_mode)),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        let sampler_nearest = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("voxel_texture_sampler_nearest"),
+            mag_filter: wgpu::FilterMode::Nearest,
+            min_filter: wgpu::FilterMode::Nearest,
+            ..Default::default()
+        });
+        let sampler_linear = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("voxel_texture_sampler_linear"),
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("voxel_image_bind_group_layout"),
+            entries: &Self::BIND_GROUP_LAYOUT_ENTRIES,
+        });
+
+        let sampler = Self::sampler_for(filter, &sampler_nearest, &sampler_linear);
+        let bind_group =
+            Self::create_bind_group(device, &bind_group_layout, sampler, source, &tonemap_buffer, &fit_buffer);
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("voxel_image_pipeline_layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let make_pipeline = |cache: &mut PipelineCache, label: &str, entry_point: &'static str| {
+            let key = format!("{label}/{surface_format:?}");
+            cache.render_pipeline(key, || {
+                device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                    label: Some(label),
+                    layout: Some(&pipeline_layout),
+                    vertex: wgpu::VertexState {
+                        module: &shader,
+                        entry_point: "vs_main",
+                        buffers: &[],
+                        compilation_options: wgpu::PipelineCompilationOptions::default(),
+                    },
+                    fragment: Some(wgpu::FragmentState {
+                        module: &shader,
+                        entry_point,
+                        targets: &[Some(wgpu::ColorTargetState {
+                            format: surface_format,
+                            blend: None,
+                            write_mask: wgpu::ColorWrites::ALL,
+                        })],
+                        compilation_options: wgpu::PipelineCompilationOptions::default(),
+                    }),
+                    primitive: wgpu::PrimitiveState::default(),
+                    depth_stencil: None,
+                    multisample: wgpu::MultisampleState::default(),
+                    multiview: None,
+                })
+            })
+        };
+
+        let pipeline = make_pipeline(cache, "voxel_image_pipeline", "fs_main");
+        let box_pipeline = make_pipeline(cache, "voxel_image_box_pipeline", "fs_main_supersample2x");
+
+        Ok(Self {
+            pipeline,
+            box_pipeline,
+            bind_group_layout,
+            bind_group,
+            sampler_nearest,
+            sampler_linear,
+            tonemap_buffer,
+            fit_buffer,
+            active_filter: filter,
+        })
+    }
+
+    fn tonemap_uniforms(exposure: f32, operator: TonemapOperator, gamma: f32) -> TonemapUniforms {
+        TonemapUniforms {
+            exposure,
+            gamma,
+            operator: operator.as_u32(),
+            _pad: 0.0,
+        }
+    }
+
+    fn fit_uniforms(texture_size: [f32; 2], surface_size: [f32; 2], mode: FitMode) -> FitUniforms {
+        FitUniforms {
+            scale: fit_scale(texture_size, surface_size, mode),
+            _pad: [0.0; 2],
+        }
+    }
+
+    fn sampler_for<'a>(
+        filter: BlitFilter,
+        sampler_nearest: &'a wgpu::Sampler,
+        sampler_linear: &'a wgpu::Sampler,
+    ) -> &'a wgpu::Sampler {
+        match filter {
+            BlitFilter::Nearest => sampler_nearest,
+            BlitFilter::Linear | BlitFilter::Box2x => sampler_linear,
+        }
+    }
+
+    fn create_bind_group(
+        device: &wgpu::Device,
+        layout: &wgpu::BindGroupLayout,
+        sampler: &wgpu::Sampler,
+        source: &wgpu::TextureView,
+        tonemap_buffer: &wgpu::Buffer,
+        fit_buffer: &wgpu::Buffer,
+    ) -> wgpu::BindGroup {
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("voxel_image_bind_group"),
+            layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(source),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(sampler),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: tonemap_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 3,
+                    resource: fit_buffer.as_entire_binding(),
+                },
+            ],
+        })
+    }
+
+    /// Rebuilds only the bind group after `source` is recreated (resize,
+    /// render-scale change, or the FXAA pass being toggled on/off); the
+    /// pipelines and the tonemap buffer's contents are untouched, and no
+    /// shader module is recompiled. `filter` picks the sampler and, on the
+    /// next `draw_with_pass`, the pipeline, as in `new`.
+    pub fn resize(&mut self, device: &wgpu::Device, source: &wgpu::TextureView, filter: BlitFilter) {
+        let sampler = Self::sampler_for(filter, &self.sampler_nearest, &self.sampler_linear);
+        self.bind_group = Self::create_bind_group(
+            device,
+            &self.bind_group_layout,
+            sampler,
+            source,
+            &self.tonemap_buffer,
+            &self.fit_buffer,
+        );
+        self.active_filter = filter;
+    }
+
+    /// Pushes new exposure/operator/gamma settings to the GPU; called by
+    /// `Renderer::set_exposure`/`set_tonemap_operator`/
+    /// `set_srgb_conversion_enabled` instead of rebuilding any pipeline or
+    /// bind group.
+    pub fn set_tonemap(&self, queue: &wgpu::Queue, exposure: f32, operator: TonemapOperator, gamma: f32) {
+        queue.write_buffer(
+            &self.tonemap_buffer,
+            0,
+            bytemuck::bytes_of(&Self::tonemap_uniforms(exposure, operator, gamma)),
+        );
+    }
+
+    /// Pushes an updated aspect-fit scale to the GPU; called by
+    /// `Renderer::rebuild_blit_source` whenever the render texture's size,
+    /// the swapchain's size, or `fit_mode` changes, instead of rebuilding
+    /// any pipeline or bind group.
+    pub fn set_fit(&self, queue: &wgpu::Queue, texture_size: [f32; 2], surface_size: [f32; 2], mode: FitMode) {
+        queue.write_buffer(&self.fit_buffer, 0, bytemuck::bytes_of(&Self::fit_uniforms(texture_size, surface_size, mode)));
+    }
+
+    pub fn draw_with_pass<'a>(&'a self, pass: &mut wgpu::RenderPass<'a>) {
+        let pipeline = match self.active_filter {
+            BlitFilter::Box2x => &self.box_pipeline,
+            BlitFilter::Nearest | BlitFilter::Linear => &self.pipeline,
+        };
+        pass.set_pipeline(pipeline);
+        pass.set_bind_group(0, &self.bind_group, &[]);
+        pass.draw(0..3, 0..1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tonemap_uniforms_size_matches_wgsl_struct() {
+        assert_eq!(std::mem::size_of::<TonemapUniforms>(), 16);
+    }
+
+    #[test]
+    fn tonemap_uniforms_round_trip_through_raw_bytes() {
+        let uniforms = VoxelImageRenderingPass::tonemap_uniforms(1.5, TonemapOperator::Reinhard, 2.2);
+        let bytes = bytemuck::bytes_of(&uniforms);
+        let restored: TonemapUniforms = *bytemuck::from_bytes(bytes);
+        assert_eq!(restored.exposure, 1.5);
+        assert_eq!(restored.gamma, 2.2);
+        assert_eq!(restored.operator, TonemapOperator::Reinhard.as_u32());
+    }
+
+    #[test]
+    fn tonemap_operator_values_are_distinct() {
+        assert_ne!(TonemapOperator::None.as_u32(), TonemapOperator::Reinhard.as_u32());
+        assert_ne!(TonemapOperator::Reinhard.as_u32(), TonemapOperator::AcesApprox.as_u32());
+    }
+
+    #[test]
+    fn gamma_for_is_a_no_op_on_an_srgb_surface() {
+        assert_eq!(gamma_for(wgpu::TextureFormat::Bgra8UnormSrgb, true), 1.0);
+        assert_eq!(gamma_for(wgpu::TextureFormat::Bgra8UnormSrgb, false), 1.0);
+    }
+
+    #[test]
+    fn gamma_for_approximates_srgb_on_a_non_srgb_surface() {
+        assert_eq!(gamma_for(wgpu::TextureFormat::Bgra8Unorm, true), 2.2);
+    }
+
+    #[test]
+    fn gamma_for_debug_toggle_disables_the_approximation() {
+        assert_eq!(gamma_for(wgpu::TextureFormat::Bgra8Unorm, false), 1.0);
+    }
+
+    /// Pins `apply_tonemap`'s midtone output so a future double-conversion
+    /// regression (e.g. `gamma_for` and `Renderer::color_target_format`
+    /// disagreeing on whether the surface already does the sRGB encode)
+    /// shows up as a changed number here instead of only as a washed-out
+    /// screenshot. Mirrors `rendering.wgsl`'s `pow(mapped, 1.0 / gamma)`
+    /// with `TonemapOperator::None`'s clamp already a no-op at this input.
+    #[test]
+    fn midtone_gray_is_unchanged_on_an_srgb_surface() {
+        let gamma = gamma_for(wgpu::TextureFormat::Bgra8UnormSrgb, true);
+        let encoded = 0.5_f32.powf(1.0 / gamma);
+        assert_eq!(encoded, 0.5);
+    }
+
+    #[test]
+    fn midtone_gray_is_brightened_on_a_non_srgb_surface() {
+        let gamma = gamma_for(wgpu::TextureFormat::Bgra8Unorm, true);
+        let encoded = 0.5_f32.powf(1.0 / gamma);
+        assert!((encoded - 0.729_740).abs() < 1e-5);
+    }
+
+    #[test]
+    fn fit_uniforms_size_matches_wgsl_struct() {
+        assert_eq!(std::mem::size_of::<FitUniforms>(), 16);
+    }
+
+    #[test]
+    fn fit_scale_stretch_always_ignores_aspect_ratio() {
+        assert_eq!(fit_scale([1920.0, 1080.0], [600.0, 800.0], FitMode::Stretch), [1.0, 1.0]);
+    }
+
+    #[test]
+    fn fit_scale_is_identity_when_aspect_ratios_already_match() {
+        assert_eq!(fit_scale([1920.0, 1080.0], [960.0, 540.0], FitMode::Contain), [1.0, 1.0]);
+        assert_eq!(fit_scale([1920.0, 1080.0], [960.0, 540.0], FitMode::Cover), [1.0, 1.0]);
+    }
+
+    #[test]
+    fn fit_scale_contain_letterboxes_a_wide_texture_on_a_narrow_surface() {
+        // A 2:1 texture on a 1:1 surface needs top/bottom bars to stay
+        // entirely visible, so the vertical axis shrinks.
+        let scale = fit_scale([1000.0, 500.0], [500.0, 500.0], FitMode::Contain);
+        assert_eq!(scale[0], 1.0);
+        assert!((scale[1] - 0.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn fit_scale_contain_pillarboxes_a_narrow_texture_on_a_wide_surface() {
+        // A 1:1 texture on a 2:1 surface needs left/right bars, so the
+        // horizontal axis shrinks.
+        let scale = fit_scale([500.0, 500.0], [1000.0, 500.0], FitMode::Contain);
+        assert!((scale[0] - 0.5).abs() < 1e-6);
+        assert_eq!(scale[1], 1.0);
+    }
+
+    #[test]
+    fn fit_scale_cover_crops_a_wide_texture_on_a_narrow_surface() {
+        // Same inputs as the letterbox case above, but `Cover` fills the
+        // surface by cropping the texture's left/right edges instead.
+        let scale = fit_scale([1000.0, 500.0], [500.0, 500.0], FitMode::Cover);
+        assert!((scale[0] - 2.0).abs() < 1e-6);
+        assert_eq!(scale[1], 1.0);
+    }
+
+    #[test]
+    fn fit_scale_cover_crops_a_narrow_texture_on_a_wide_surface() {
+        let scale = fit_scale([500.0, 500.0], [1000.0, 500.0], FitMode::Cover);
+        assert_eq!(scale[0], 1.0);
+        assert!((scale[1] - 2.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn fit_scale_falls_back_to_identity_on_degenerate_zero_sized_input() {
+        assert_eq!(fit_scale([0.0, 1080.0], [1920.0, 1080.0], FitMode::Contain), [1.0, 1.0]);
+        assert_eq!(fit_scale([1920.0, 1080.0], [1920.0, 0.0], FitMode::Cover), [1.0, 1.0]);
+    }
+
+    // A golden image at a deliberately mismatched internal resolution,
+    // showing the letterbox/pillarbox bars landing in the right place, needs
+    // the same headless GPU readback harness the other golden-image asks in
+    // this crate don't have yet (see `uniforms_size_matches_wgsl_struct`
+    // above and `VoxelRendererPass`'s equivalent tests). `fit_scale` above is
+    // exactly the fit-rect math `rendering.wgsl`'s `fit_uv` mirrors in the
+    // shader, so exhaustive coverage of it here stands in for the pixel
+    // check until that harness exists.
+}
@@ -0,0 +1,30 @@
+//! Sky gradient settings shared by the GPU renderer ([`super::passes`]) and
+//! the pure CPU reference renderer ([`super::software`]). Lives outside
+//! `passes` so `software` doesn't need to compile against wgpu just to read
+//! three colors and a bool; see `lib.rs`'s module doc comment.
+
+/// Background shown where a ray misses the chunk entirely; see
+/// `voxel_renderer.wgsl`'s `sky_color`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SkySettings {
+    /// Color straight up (`dir.y == 1`).
+    pub zenith_color: glam::Vec3,
+    /// Color at the horizon (`dir.y == 0`), both above and below.
+    pub horizon_color: glam::Vec3,
+    /// Color straight down (`dir.y == -1`).
+    pub ground_color: glam::Vec3,
+    /// Whether a small disc around `sun_direction` is drawn at `sun_color`
+    /// on top of the gradient.
+    pub sun_disc: bool,
+}
+
+impl Default for SkySettings {
+    fn default() -> Self {
+        Self {
+            zenith_color: glam::Vec3::new(0.25, 0.45, 0.75),
+            horizon_color: glam::Vec3::new(0.75, 0.8, 0.85),
+            ground_color: glam::Vec3::new(0.15, 0.13, 0.11),
+            sun_disc: true,
+        }
+    }
+}
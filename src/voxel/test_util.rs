@@ -0,0 +1,45 @@
+//! Deterministic fixtures shared between this crate's tests and
+//! `benches/voxel_benchmarks.rs`. A real (non-`cfg(test)`) module rather
+//! than a test-only one: a `benches/` binary compiles against this crate
+//! the same way an external consumer would, so it can only see `pub` items,
+//! never anything gated on `cfg(test)`.
+
+use super::chunk::Chunk;
+
+/// Xorshift32, seeded for determinism -- cheap, reproducible
+/// pseudo-randomness without a `rand` dependency, and stable across runs so
+/// two `cargo bench` invocations are actually comparable.
+pub struct Xorshift32(pub u32);
+
+impl Xorshift32 {
+    pub fn next_u32(&mut self) -> u32 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 17;
+        self.0 ^= self.0 << 5;
+        self.0
+    }
+
+    pub fn next_below(&mut self, bound: u32) -> u32 {
+        self.next_u32() % bound
+    }
+}
+
+/// `count` random in-bounds voxel coordinates for a cube of `size` per
+/// side, generated from `seed` -- the noise-like fill pattern
+/// `benches/voxel_benchmarks.rs` uses to build a `Tree` with a realistic
+/// mix of solid and air voxels instead of a fully-dense or fully-empty one.
+pub fn seeded_positions(size: u32, count: usize, seed: u32) -> Vec<glam::UVec3> {
+    let mut rng = Xorshift32(seed);
+    (0..count)
+        .map(|_| glam::UVec3::new(rng.next_below(size), rng.next_below(size), rng.next_below(size)))
+        .collect()
+}
+
+/// The chunk fixture most of this crate's tests and the new benchmarks both
+/// want: a deterministic, non-trivial scene with a solid floor, a few
+/// voxels of water, and open air above. Re-exports
+/// [`Chunk::filled_test_pattern_with_water`] under a fixed set of arguments
+/// rather than duplicating it, so it stays a single source of truth.
+pub fn standard_chunk() -> Chunk {
+    Chunk::filled_test_pattern_with_water(glam::IVec3::ZERO, 4)
+}
@@ -0,0 +1,546 @@
+/// Sentinel used in [`GpuNode::children`] for "no child here" (air).
+pub const EMPTY: u32 = u32::MAX;
+
+/// GPU-friendly flattening of a [`Tree`] node: either a branch with up to 8
+/// child indices into the same buffer, or a uniform leaf whose `material` is
+/// used directly (children left `EMPTY`).
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct GpuNode {
+    pub children: [u32; 8],
+    pub material: u32,
+    pub _pad: [u32; 3],
+}
+
+impl GpuNode {
+    fn leaf(material: u32) -> Self {
+        Self {
+            children: [EMPTY; 8],
+            material,
+            _pad: [0; 3],
+        }
+    }
+}
+
+enum Node {
+    /// Uniform region, either empty (material 0) or a solid material.
+    Leaf(u32),
+    Branch(Box<[usize; 8]>),
+}
+
+/// Every `nodes` index [`Tree::set_logged`] wrote a final value to, in the
+/// order written, so [`VoxelRendererPass::apply_tree_edits`] can mirror the
+/// edit onto the GPU's copy of [`Tree::to_gpu_nodes`] with a handful of
+/// targeted `write_buffer` calls instead of re-uploading the whole buffer.
+/// A plain `Vec` rather than a `HashMap` keyed by index: a single-voxel edit
+/// touches only the handful of nodes on the path from the root to that
+/// voxel, small enough that append-and-replay beats hashing, and duplicate
+/// entries for the same index never happen within one edit (each node on
+/// the descent path is written at most once).
+///
+/// [`VoxelRendererPass::apply_tree_edits`]: super::passes::voxel_renderer::VoxelRendererPass::apply_tree_edits
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct EditLog {
+    writes: Vec<(usize, GpuNode)>,
+}
+
+impl EditLog {
+    pub fn is_empty(&self) -> bool {
+        self.writes.is_empty()
+    }
+
+    fn push(&mut self, index: usize, node: GpuNode) {
+        self.writes.push((index, node));
+    }
+
+    /// `(node index, final value)` pairs in the order [`Tree::set_logged`]
+    /// wrote them.
+    pub fn writes(&self) -> impl Iterator<Item = (usize, GpuNode)> + '_ {
+        self.writes.iter().copied()
+    }
+}
+
+/// A sparse voxel octree covering a cube of `2^depth` voxels per side.
+/// Starts as a single empty leaf and lazily subdivides as voxels are set.
+pub struct Tree {
+    depth: u32,
+    nodes: Vec<Node>,
+}
+
+impl Tree {
+    pub fn new(depth: u32) -> Self {
+        Self {
+            depth,
+            nodes: vec![Node::Leaf(0)],
+        }
+    }
+
+    pub fn size(&self) -> u32 {
+        1 << self.depth
+    }
+
+    /// Subdivision levels below this tree's root; `size() == 1 << depth()`.
+    /// Read by `VoxelRendererPass::new` both to bounds-check `node_buffer`
+    /// before allocating it and to reject a tree too deep for the shader's
+    /// traversal loop (see `VoxelRendererPass::MAX_TREE_DEPTH`).
+    pub fn depth(&self) -> u32 {
+        self.depth
+    }
+
+    /// Worst-case byte size of a `depth`-deep tree's [`to_gpu_nodes`](Self::to_gpu_nodes)
+    /// output -- every level fully subdivided -- rounded up to `alignment`.
+    /// Used by [`super::chunk::validate_node_buffer_size`] to bounds-check a
+    /// chunk's node buffer before `VoxelRendererPass::new` allocates it.
+    pub(crate) fn estimated_size_aligned(depth: u32, alignment: u64) -> u64 {
+        let node_count: u64 = (0..=depth).map(|level| 8u64.pow(level)).sum();
+        let bytes = node_count * std::mem::size_of::<GpuNode>() as u64;
+        bytes.div_ceil(alignment) * alignment
+    }
+
+    /// Point sample at a voxel coordinate; the traversal primitive
+    /// [`super::software::trace`] ray-marches with instead of the GPU
+    /// shader's `sample_octree`.
+    pub fn get(&self, pos: glam::UVec3) -> u32 {
+        self.get_from(0, self.depth, pos)
+    }
+
+    fn get_from(&self, node: usize, depth: u32, pos: glam::UVec3) -> u32 {
+        match &self.nodes[node] {
+            Node::Leaf(material) => *material,
+            Node::Branch(children) => {
+                let half = 1u32 << (depth - 1);
+                let octant = octant_of(pos, half);
+                let child_pos = pos - octant_origin(octant, half);
+                self.get_from(children[octant as usize], depth - 1, child_pos)
+            }
+        }
+    }
+
+    pub fn set(&mut self, pos: glam::UVec3, material: u32) {
+        self.set_from(0, self.depth, pos, material, None);
+    }
+
+    /// Same as [`Self::set`], but also appends every `nodes` index this edit
+    /// writes a final value to onto `log`, for `VoxelRendererPass::apply_tree_edits`
+    /// to replay directly onto the GPU's node buffer. No caller yet -- see
+    /// `apply_tree_edits`'s doc comment.
+    pub fn set_logged(&mut self, pos: glam::UVec3, material: u32, log: &mut EditLog) {
+        self.set_from(0, self.depth, pos, material, Some(log));
+    }
+
+    fn set_from(&mut self, node: usize, depth: u32, pos: glam::UVec3, material: u32, mut log: Option<&mut EditLog>) {
+        if depth == 0 {
+            self.nodes[node] = Node::Leaf(material);
+            if let Some(log) = log {
+                log.push(node, GpuNode::leaf(material));
+            }
+            return;
+        }
+
+        if let Node::Leaf(existing) = self.nodes[node] {
+            if existing == material {
+                return;
+            }
+            self.subdivide(node, existing, log.as_deref_mut());
+        }
+
+        let half = 1u32 << (depth - 1);
+        let octant = octant_of(pos, half);
+        let child_pos = pos - octant_origin(octant, half);
+        let child = match &self.nodes[node] {
+            Node::Branch(children) => children[octant as usize],
+            Node::Leaf(_) => unreachable!("subdivided above"),
+        };
+        self.set_from(child, depth - 1, child_pos, material, log);
+    }
+
+    /// Replaces a leaf with a branch of 8 leaves carrying the same material,
+    /// so a single voxel edit inside a uniform region doesn't lose the rest.
+    fn subdivide(&mut self, node: usize, material: u32, mut log: Option<&mut EditLog>) {
+        let mut children = [0usize; 8];
+        for child in children.iter_mut() {
+            *child = self.nodes.len();
+            self.nodes.push(Node::Leaf(material));
+            if let Some(log) = log.as_deref_mut() {
+                log.push(*child, GpuNode::leaf(material));
+            }
+        }
+        self.nodes[node] = Node::Branch(Box::new(children));
+        if let Some(log) = log {
+            log.push(node, GpuNode { children: children.map(|c| c as u32), material: 0, _pad: [0; 3] });
+        }
+    }
+
+    /// Every solid (non-air) voxel's position and material, for callers like
+    /// [`super::chunk::Chunk::collect_emitters`] that need to walk actual
+    /// voxels rather than the octree's leaf regions. Visits a uniform leaf's
+    /// whole region in one push each rather than one recursive call per
+    /// voxel, but still expands to per-voxel positions since that's what
+    /// those callers need.
+    pub fn iter_voxels(&self) -> impl Iterator<Item = (glam::UVec3, u32)> + '_ {
+        let mut voxels = Vec::new();
+        self.collect_voxels(0, self.depth, glam::UVec3::ZERO, &mut voxels);
+        voxels.into_iter()
+    }
+
+    fn collect_voxels(&self, node: usize, depth: u32, origin: glam::UVec3, out: &mut Vec<(glam::UVec3, u32)>) {
+        match &self.nodes[node] {
+            Node::Leaf(material) => {
+                if *material == 0 {
+                    return;
+                }
+                let size = 1u32 << depth;
+                for x in 0..size {
+                    for y in 0..size {
+                        for z in 0..size {
+                            out.push((origin + glam::UVec3::new(x, y, z), *material));
+                        }
+                    }
+                }
+            }
+            Node::Branch(children) => {
+                let half = 1u32 << (depth - 1);
+                for (octant, &child) in children.iter().enumerate() {
+                    let child_origin = origin + octant_origin(octant as u32, half);
+                    self.collect_voxels(child, depth - 1, child_origin, out);
+                }
+            }
+        }
+    }
+
+    /// Coarsens the tree to `target_depth` levels (clamped to `self.depth`)
+    /// by merging every subtree below that depth into a single leaf: solid
+    /// (carrying whichever material is found first) if any voxel in that
+    /// subtree was solid, air otherwise. Still covers the same [`Self::size`]
+    /// cube -- [`Self::get`] on the result answers for whichever coarse cell
+    /// a position falls in, not the same value the original tree would give
+    /// -- so a caller that only needs a conservative "is anything solid
+    /// here" answer over a coarse grid, like a beam pre-pass's per-tile
+    /// entry-distance estimate (see `passes::beam`), can march through this
+    /// far more cheaply than the full-resolution tree without ever missing a
+    /// solid voxel the full-resolution tree has.
+    /// Not yet called by production code outside `passes::beam`'s own
+    /// tests; wired into a real pass once that pre-pass has a GPU pipeline
+    /// to feed. `pub` (rather than `pub(crate)`) so `benches/voxel_benchmarks.rs`,
+    /// which builds against this crate the same way an external consumer
+    /// would, can benchmark it.
+    pub fn lod(&self, target_depth: u32) -> Self {
+        let target_depth = target_depth.min(self.depth);
+        let stop_at = self.depth - target_depth;
+        let mut nodes = Vec::new();
+        self.lod_from(0, self.depth, stop_at, &mut nodes);
+        Self { depth: self.depth, nodes }
+    }
+
+    /// Appends the coarsened subtree rooted at `node` to `out` and returns
+    /// its index. Reserves that index up front (rather than pushing after
+    /// recursing into children) so the very first call -- for the tree's
+    /// root -- lands at index `0`, matching every other `Tree` invariant
+    /// that the root is `nodes[0]`.
+    fn lod_from(&self, node: usize, depth: u32, stop_at: u32, out: &mut Vec<Node>) -> usize {
+        let index = out.len();
+        out.push(Node::Leaf(0));
+        out[index] = if depth <= stop_at {
+            Node::Leaf(self.first_solid_material(node))
+        } else {
+            match &self.nodes[node] {
+                Node::Leaf(material) => Node::Leaf(*material),
+                Node::Branch(children) => {
+                    let new_children = children.map(|child| self.lod_from(child, depth - 1, stop_at, out));
+                    Node::Branch(Box::new(new_children))
+                }
+            }
+        };
+        index
+    }
+
+    /// First non-air material found under `node`, air (`0`) if the whole
+    /// subtree is empty. Traversal order is fixed (octant order), so the
+    /// choice among multiple solid materials in the same merged cell is at
+    /// least deterministic, even if arbitrary.
+    fn first_solid_material(&self, node: usize) -> u32 {
+        match &self.nodes[node] {
+            Node::Leaf(material) => *material,
+            Node::Branch(children) => children
+                .iter()
+                .map(|&child| self.first_solid_material(child))
+                .find(|&material| material != 0)
+                .unwrap_or(0),
+        }
+    }
+
+    /// Flattens the arena into the layout the compute shader traverses:
+    /// node 0 is the root, `EMPTY` children mean "not present".
+    pub fn to_gpu_nodes(&self) -> Vec<GpuNode> {
+        self.nodes
+            .iter()
+            .map(|node| match node {
+                Node::Leaf(material) => GpuNode::leaf(*material),
+                Node::Branch(children) => GpuNode {
+                    children: children.map(|c| c as u32),
+                    material: 0,
+                    _pad: [0; 3],
+                },
+            })
+            .collect()
+    }
+}
+
+fn octant_of(pos: glam::UVec3, half: u32) -> u32 {
+    ((pos.x >= half) as u32) | (((pos.y >= half) as u32) << 1) | (((pos.z >= half) as u32) << 2)
+}
+
+fn octant_origin(octant: u32, half: u32) -> glam::UVec3 {
+    glam::UVec3::new(
+        if octant & 1 != 0 { half } else { 0 },
+        if octant & 2 != 0 { half } else { 0 },
+        if octant & 4 != 0 { half } else { 0 },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fresh_tree_is_all_air() {
+        let tree = Tree::new(3);
+        assert_eq!(tree.get(glam::UVec3::new(1, 2, 3)), 0);
+    }
+
+    #[test]
+    fn set_then_get_round_trips() {
+        let mut tree = Tree::new(3);
+        tree.set(glam::UVec3::new(1, 2, 3), 5);
+        assert_eq!(tree.get(glam::UVec3::new(1, 2, 3)), 5);
+        // neighboring voxel is untouched
+        assert_eq!(tree.get(glam::UVec3::new(1, 2, 4)), 0);
+    }
+
+    #[test]
+    fn setting_a_second_voxel_preserves_the_first() {
+        let mut tree = Tree::new(2);
+        tree.set(glam::UVec3::new(0, 0, 0), 1);
+        tree.set(glam::UVec3::new(3, 3, 3), 2);
+        assert_eq!(tree.get(glam::UVec3::new(0, 0, 0)), 1);
+        assert_eq!(tree.get(glam::UVec3::new(3, 3, 3)), 2);
+    }
+
+    /// Mirrors what `VoxelRendererPass::apply_tree_edits` does to a real GPU
+    /// buffer: overwrite an existing index in place, append past the end.
+    /// `apply_tree_edits` itself additionally refuses to do the latter past
+    /// the buffer's allocated capacity (see its own doc comment); this test
+    /// helper has no such limit since it's reproducing the full byte stream,
+    /// not bounds-checking a fixed-size buffer.
+    fn replay_edit_log(mut nodes: Vec<GpuNode>, log: &EditLog) -> Vec<GpuNode> {
+        for (index, node) in log.writes() {
+            if index == nodes.len() {
+                nodes.push(node);
+            } else {
+                nodes[index] = node;
+            }
+        }
+        nodes
+    }
+
+    #[test]
+    fn set_logged_reproduces_a_full_reserialization_when_subdividing() {
+        let mut tree = Tree::new(2);
+        let before = tree.to_gpu_nodes();
+        let mut log = EditLog::default();
+        tree.set_logged(glam::UVec3::new(3, 3, 3), 5, &mut log);
+        assert!(!log.is_empty());
+        assert_eq!(replay_edit_log(before, &log), tree.to_gpu_nodes());
+    }
+
+    #[test]
+    fn set_logged_reproduces_a_full_reserialization_for_a_value_only_edit() {
+        let mut tree = Tree::new(2);
+        tree.set(glam::UVec3::new(3, 3, 3), 5);
+        let before = tree.to_gpu_nodes();
+        let mut log = EditLog::default();
+        tree.set_logged(glam::UVec3::new(3, 3, 3), 9, &mut log);
+        assert!(!log.is_empty());
+        assert_eq!(replay_edit_log(before, &log), tree.to_gpu_nodes());
+    }
+
+    #[test]
+    fn set_logged_records_nothing_when_the_material_is_unchanged() {
+        let mut tree = Tree::new(2);
+        let mut log = EditLog::default();
+        tree.set_logged(glam::UVec3::new(1, 1, 1), 0, &mut log);
+        assert!(log.is_empty());
+    }
+
+    #[test]
+    fn gpu_nodes_mark_leaves_with_empty_children() {
+        let tree = Tree::new(1);
+        let nodes = tree.to_gpu_nodes();
+        assert_eq!(nodes[0].children, [EMPTY; 8]);
+    }
+
+    #[test]
+    fn iter_voxels_skips_air_and_reports_set_positions() {
+        let mut tree = Tree::new(2);
+        tree.set(glam::UVec3::new(1, 2, 3), 5);
+        let voxels: Vec<_> = tree.iter_voxels().collect();
+        assert_eq!(voxels, vec![(glam::UVec3::new(1, 2, 3), 5)]);
+    }
+
+    #[test]
+    fn lod_at_full_depth_is_unchanged() {
+        let mut tree = Tree::new(3);
+        tree.set(glam::UVec3::new(1, 2, 3), 5);
+        let lod = tree.lod(3);
+        for pos in [glam::UVec3::new(1, 2, 3), glam::UVec3::new(0, 0, 0), glam::UVec3::new(7, 7, 7)] {
+            assert_eq!(lod.get(pos), tree.get(pos));
+        }
+    }
+
+    #[test]
+    fn lod_at_depth_zero_collapses_to_a_single_solid_leaf_if_anything_is_solid() {
+        let mut tree = Tree::new(3);
+        tree.set(glam::UVec3::new(7, 7, 7), 5);
+        let lod = tree.lod(0);
+        assert_eq!(lod.get(glam::UVec3::new(0, 0, 0)), 5);
+        assert_eq!(lod.get(glam::UVec3::new(3, 3, 3)), 5);
+    }
+
+    #[test]
+    fn lod_of_an_empty_tree_stays_air_everywhere() {
+        let tree = Tree::new(3);
+        let lod = tree.lod(0);
+        assert_eq!(lod.get(glam::UVec3::new(4, 4, 4)), 0);
+    }
+
+    #[test]
+    fn lod_never_hides_a_solid_voxel_as_air() {
+        let mut tree = Tree::new(4);
+        tree.set(glam::UVec3::new(9, 2, 5), 7);
+        let lod = tree.lod(2);
+        let coarse_cell = glam::UVec3::new(9, 2, 5) / 4; // 2^(4 - 2) per coarse cell
+        let coarse_origin = coarse_cell * 4;
+        assert_ne!(lod.get(coarse_origin), 0, "the coarse cell containing a solid voxel must not read as air");
+    }
+
+    #[test]
+    fn estimated_size_aligned_covers_a_fully_subdivided_tree() {
+        let depth = 3;
+        let worst_case_nodes: u64 = (0..=depth).map(|level| 8u64.pow(level)).sum();
+        let exact = worst_case_nodes * std::mem::size_of::<GpuNode>() as u64;
+        assert_eq!(Tree::estimated_size_aligned(depth, 1), exact);
+    }
+
+    #[test]
+    fn estimated_size_aligned_rounds_up_to_alignment() {
+        let size = Tree::estimated_size_aligned(1, 256);
+        assert_eq!(size % 256, 0);
+        assert!(size >= Tree::estimated_size_aligned(1, 1));
+    }
+
+    // synth-2884 asked for proptest-based fuzzing of `Tree::set_block_state`/
+    // `get_block_state` against a dense bitset model, plus a cargo-fuzz
+    // target for `Tree::from_bytes`. None of that exists to fuzz: `Tree`
+    // never grew bit-level block-state accessors or a byte serialization
+    // format (it's a material-valued sparse octree, not a bitset), and this
+    // crate has neither a `proptest`/`cargo-fuzz` dependency nor a `fuzz/`
+    // directory to add a target to -- not worth pulling either in as a side
+    // effect of one request (see `software.rs`'s own call not to add
+    // `rayon` for the same reason). What's here instead covers the same bug
+    // surface with what this crate actually has: `set`/`get` recursing
+    // through `subdivide`/`set_from`/`get_from` at arbitrary coordinates,
+    // checked with the same shape of property test -- random write
+    // sequences compared against a dense model -- using a tiny inline PRNG
+    // instead of a new dependency.
+
+    /// Xorshift32, seeded per test for determinism. Not for security, just
+    /// cheap, reproducible pseudo-randomness without a `rand` dependency.
+    struct Xorshift32(u32);
+
+    impl Xorshift32 {
+        fn next_u32(&mut self) -> u32 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 17;
+            self.0 ^= self.0 << 5;
+            self.0
+        }
+
+        fn next_below(&mut self, bound: u32) -> u32 {
+            self.next_u32() % bound
+        }
+    }
+
+    /// Applies `operations` random writes (material `0`, `1` or `2`, i.e.
+    /// air or one of two "solid" IDs) at random in-bounds coordinates to
+    /// both a `Tree` and a dense `Vec<u32>` model of the same size,
+    /// asserting `Tree::get` agrees with the model at several random
+    /// coordinates after every single write -- so a write that silently
+    /// clobbers an unrelated voxel is caught the same batch it happens in.
+    fn check_set_get_matches_dense_model(depth: u32, operations: u32, seed: u32) {
+        let size = 1u32 << depth;
+        let mut tree = Tree::new(depth);
+        let mut model = vec![0u32; (size * size * size) as usize];
+        let mut rng = Xorshift32(seed);
+        let index_of = |pos: glam::UVec3| (pos.x + pos.y * size + pos.z * size * size) as usize;
+
+        for _ in 0..operations {
+            let pos = glam::UVec3::new(rng.next_below(size), rng.next_below(size), rng.next_below(size));
+            let material = rng.next_below(3);
+            tree.set(pos, material);
+            model[index_of(pos)] = material;
+
+            for _ in 0..8 {
+                let sample = glam::UVec3::new(rng.next_below(size), rng.next_below(size), rng.next_below(size));
+                assert_eq!(
+                    tree.get(sample),
+                    model[index_of(sample)],
+                    "tree/model diverged at {sample:?} after setting {pos:?} to {material} (seed {seed})"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn random_set_sequences_match_a_dense_model_at_size_8() {
+        check_set_get_matches_dense_model(3, 200, 0x1234_5678);
+    }
+
+    #[test]
+    fn random_set_sequences_match_a_dense_model_at_size_16() {
+        check_set_get_matches_dense_model(4, 200, 0x9abc_def0);
+    }
+
+    #[test]
+    fn random_set_sequences_match_a_dense_model_at_size_32() {
+        check_set_get_matches_dense_model(5, 200, 0x0f0f_f0f0);
+    }
+
+    /// The structural invariant synth-2884 asked for ("a parent bit is set
+    /// iff at least one child byte is non-zero") doesn't translate to this
+    /// octree, but the same spirit applies to `iter_voxels`: after any
+    /// sequence of random writes, it must report exactly the solid voxels a
+    /// dense model has, no more and no fewer.
+    #[test]
+    fn iter_voxels_matches_a_dense_model_after_random_writes() {
+        let depth = 3;
+        let size = 1u32 << depth;
+        let mut tree = Tree::new(depth);
+        let mut model = std::collections::HashMap::new();
+        let mut rng = Xorshift32(0xdead_beef);
+
+        for _ in 0..100 {
+            let pos = glam::UVec3::new(rng.next_below(size), rng.next_below(size), rng.next_below(size));
+            let material = rng.next_below(3);
+            tree.set(pos, material);
+            if material == 0 {
+                model.remove(&(pos.x, pos.y, pos.z));
+            } else {
+                model.insert((pos.x, pos.y, pos.z), material);
+            }
+        }
+
+        let from_tree: std::collections::HashMap<(u32, u32, u32), u32> =
+            tree.iter_voxels().map(|(pos, material)| ((pos.x, pos.y, pos.z), material)).collect();
+        assert_eq!(from_tree, model, "iter_voxels should report exactly the model's solid voxels after random writes");
+    }
+}
@@ -0,0 +1,205 @@
+//! Parses and validates every shader under `src/shaders/` with naga, the
+//! same front end wgpu itself uses, so a syntax or type error is caught by
+//! `cargo test` instead of only showing up the next time someone runs the
+//! app with a GPU. Also cross-checks each pass's reflected bind group
+//! layout against its Rust-side `BindGroupLayoutEntry` definitions, so a
+//! binding that drifts out of sync between the `.wgsl` and the pass module
+//! fails here rather than as a cryptic wgpu validation panic at runtime.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use voxel_adventure::voxel::{CullPass, FxaaPass, VoxelImageRenderingPass, VoxelRendererPass};
+
+fn shaders_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("src/shaders")
+}
+
+fn parse_and_validate(name: &str) -> naga::Module {
+    let path = shaders_dir().join(name);
+    let source = fs::read_to_string(&path).unwrap_or_else(|err| panic!("failed to read {path:?}: {err}"));
+    let module = naga::front::wgsl::parse_str(&source).unwrap_or_else(|err| panic!("{name} failed to parse: {err}"));
+    let mut validator = naga::valid::Validator::new(naga::valid::ValidationFlags::all(), naga::valid::Capabilities::all());
+    validator
+        .validate(&module)
+        .unwrap_or_else(|err| panic!("{name} failed validation: {err}"));
+    module
+}
+
+fn has_entry_point(module: &naga::Module, name: &str) -> bool {
+    module.entry_points.iter().any(|entry_point| entry_point.name == name)
+}
+
+/// What a binding is, independent of whether it came from naga reflection or
+/// a `wgpu::BindGroupLayoutEntry` -- the common ground the two sides are
+/// compared on. Storage texture formats are deliberately not part of this:
+/// `voxel_renderer.wgsl`'s `output` binding is declared against a fixed
+/// WGSL format while the Rust side varies it with `RenderTexture::format`
+/// (HDR toggle), so format is not a meaningful cross-check here.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BindingKind {
+    UniformBuffer,
+    StorageBuffer { read_only: bool },
+    Texture,
+    StorageTexture { access: StorageAccess },
+    Sampler,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum StorageAccess {
+    Read,
+    Write,
+    ReadWrite,
+}
+
+fn naga_bindings(module: &naga::Module) -> Vec<(u32, BindingKind)> {
+    module
+        .global_variables
+        .iter()
+        .filter_map(|(_, var)| {
+            let binding = var.binding.as_ref()?;
+            Some((binding.binding, naga_binding_kind(module, var)))
+        })
+        .collect()
+}
+
+fn naga_binding_kind(module: &naga::Module, var: &naga::GlobalVariable) -> BindingKind {
+    match &module.types[var.ty].inner {
+        naga::TypeInner::Image { class, .. } => match class {
+            naga::ImageClass::Sampled { .. } | naga::ImageClass::Depth { .. } => BindingKind::Texture,
+            naga::ImageClass::Storage { access, .. } => BindingKind::StorageTexture {
+                access: storage_access_from_naga(*access),
+            },
+        },
+        naga::TypeInner::Sampler { .. } => BindingKind::Sampler,
+        _ => match var.space {
+            naga::AddressSpace::Uniform => BindingKind::UniformBuffer,
+            naga::AddressSpace::Storage { access } => BindingKind::StorageBuffer {
+                read_only: !access.contains(naga::StorageAccess::STORE),
+            },
+            other => panic!("{:?} is bound but lives in unexpected address space {other:?}", var.name),
+        },
+    }
+}
+
+fn storage_access_from_naga(access: naga::StorageAccess) -> StorageAccess {
+    match (
+        access.contains(naga::StorageAccess::LOAD),
+        access.contains(naga::StorageAccess::STORE),
+    ) {
+        (true, true) => StorageAccess::ReadWrite,
+        (true, false) => StorageAccess::Read,
+        (false, true) => StorageAccess::Write,
+        (false, false) => panic!("storage binding declares neither load nor store access"),
+    }
+}
+
+fn wgpu_bindings(entries: &[wgpu::BindGroupLayoutEntry]) -> Vec<(u32, BindingKind)> {
+    entries.iter().map(|entry| (entry.binding, wgpu_binding_kind(entry))).collect()
+}
+
+fn wgpu_binding_kind(entry: &wgpu::BindGroupLayoutEntry) -> BindingKind {
+    match entry.ty {
+        wgpu::BindingType::Buffer {
+            ty: wgpu::BufferBindingType::Uniform,
+            ..
+        } => BindingKind::UniformBuffer,
+        wgpu::BindingType::Buffer {
+            ty: wgpu::BufferBindingType::Storage { read_only },
+            ..
+        } => BindingKind::StorageBuffer { read_only },
+        wgpu::BindingType::Texture { .. } => BindingKind::Texture,
+        wgpu::BindingType::StorageTexture { access, .. } => BindingKind::StorageTexture {
+            access: match access {
+                wgpu::StorageTextureAccess::ReadOnly => StorageAccess::Read,
+                wgpu::StorageTextureAccess::WriteOnly => StorageAccess::Write,
+                wgpu::StorageTextureAccess::ReadWrite => StorageAccess::ReadWrite,
+            },
+        },
+        wgpu::BindingType::Sampler(_) => BindingKind::Sampler,
+        other => panic!("unexpected wgpu::BindingType in a pass layout: {other:?}"),
+    }
+}
+
+/// Asserts every `(binding, BindingKind)` the shader declares is also what
+/// the Rust-side layout declares for that binding number, and vice versa.
+fn assert_bind_groups_match(shader_name: &str, module: &naga::Module, rust_entries: &[wgpu::BindGroupLayoutEntry]) {
+    let mut shader_side = naga_bindings(module);
+    let mut rust_side = wgpu_bindings(rust_entries);
+    shader_side.sort_by_key(|(binding, _)| *binding);
+    rust_side.sort_by_key(|(binding, _)| *binding);
+    assert_eq!(
+        shader_side, rust_side,
+        "{shader_name}'s bind group layout doesn't match its Rust-side BindGroupLayoutEntry definitions"
+    );
+}
+
+#[test]
+fn every_shader_parses_and_validates() {
+    for name in ["voxel_renderer.wgsl", "cull.wgsl", "fxaa.wgsl", "rendering.wgsl", "gizmo.wgsl"] {
+        parse_and_validate(name);
+    }
+}
+
+#[test]
+fn voxel_renderer_shader_has_its_compute_entry_points() {
+    let module = parse_and_validate("voxel_renderer.wgsl");
+    assert!(has_entry_point(&module, "main"), "voxel_renderer.wgsl is missing its `main` entry point");
+    assert!(
+        has_entry_point(&module, "main_indirect"),
+        "voxel_renderer.wgsl is missing its `main_indirect` entry point"
+    );
+}
+
+#[test]
+fn voxel_renderer_bind_group_layout_matches_shader() {
+    let module = parse_and_validate("voxel_renderer.wgsl");
+    let entries = VoxelRendererPass::bind_group_layout_entries(wgpu::TextureFormat::Rgba8Unorm);
+    assert_bind_groups_match("voxel_renderer.wgsl", &module, &entries);
+}
+
+#[test]
+fn cull_shader_has_its_compute_entry_point() {
+    let module = parse_and_validate("cull.wgsl");
+    assert!(has_entry_point(&module, "main"), "cull.wgsl is missing its `main` entry point");
+}
+
+#[test]
+fn cull_bind_group_layout_matches_shader() {
+    let module = parse_and_validate("cull.wgsl");
+    assert_bind_groups_match("cull.wgsl", &module, &CullPass::bind_group_layout_entries());
+}
+
+#[test]
+fn fxaa_shader_has_its_vertex_and_fragment_entry_points() {
+    let module = parse_and_validate("fxaa.wgsl");
+    assert!(has_entry_point(&module, "vs_main"), "fxaa.wgsl is missing its `vs_main` entry point");
+    assert!(has_entry_point(&module, "fs_main"), "fxaa.wgsl is missing its `fs_main` entry point");
+}
+
+#[test]
+fn fxaa_bind_group_layout_matches_shader() {
+    let module = parse_and_validate("fxaa.wgsl");
+    assert_bind_groups_match("fxaa.wgsl", &module, &FxaaPass::BIND_GROUP_LAYOUT_ENTRIES);
+}
+
+#[test]
+fn rendering_shader_has_its_vertex_and_fragment_entry_points() {
+    let module = parse_and_validate("rendering.wgsl");
+    for entry_point in ["vs_main", "fs_main", "fs_main_supersample2x"] {
+        assert!(has_entry_point(&module, entry_point), "rendering.wgsl is missing its `{entry_point}` entry point");
+    }
+}
+
+#[test]
+fn rendering_bind_group_layout_matches_shader() {
+    let module = parse_and_validate("rendering.wgsl");
+    assert_bind_groups_match("rendering.wgsl", &module, &VoxelImageRenderingPass::BIND_GROUP_LAYOUT_ENTRIES);
+}
+
+#[test]
+fn gizmo_shader_has_its_vertex_and_fragment_entry_points() {
+    let module = parse_and_validate("gizmo.wgsl");
+    assert!(has_entry_point(&module, "vs_main"), "gizmo.wgsl is missing its `vs_main` entry point");
+    assert!(has_entry_point(&module, "fs_main"), "gizmo.wgsl is missing its `fs_main` entry point");
+}